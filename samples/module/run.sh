@@ -0,0 +1,16 @@
+#!/usr/bin/env bash
+
+src_files=`ls *.pho`
+
+for f in ${src_files}
+do
+  cargo run --release -- -f $f &>/dev/null
+done
+
+mkdir -p mods/com.example.app/com/example/app
+mv module-info.class mods/com.example.app
+mv Main.class Greeter.class mods/com.example.app/com/example/app
+
+java -p mods -m com.example.app
+
+rm -rf mods
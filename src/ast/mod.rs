@@ -31,6 +31,12 @@ pub trait PhoronAstVisitor<'a> {
         input: Self::Input,
     ) -> Self::Result;
 
+    fn visit_module_def(
+        &mut self,
+        module_def: &PhoronModuleDef,
+        input: Self::Input,
+    ) -> Self::Result;
+
     fn visit_super_def(&mut self, super_def: &PhoronSuperDef, input: Self::Input) -> Self::Result;
 
     fn visit_implements_def(
@@ -39,6 +45,12 @@ pub trait PhoronAstVisitor<'a> {
         input: Self::Input,
     ) -> Self::Result;
 
+    fn visit_class_directive(
+        &mut self,
+        class_directive: &PhoronClassDirective,
+        input: Self::Input,
+    ) -> Self::Result;
+
     fn visit_body(&mut self, body: &PhoronBody, input: Self::Input) -> Self::Result;
     fn visit_field_def(&mut self, field_def: &PhoronFieldDef, input: Self::Input) -> Self::Result;
 
@@ -61,6 +73,45 @@ pub struct PhoronProgram {
 
 // header
 
+/// The class file version, as specified by the `.version` (or `.bytecode`) directive.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct PhoronVersionDef {
+    pub major_version: u16,
+    pub minor_version: u16,
+}
+
+impl PhoronVersionDef {
+    pub const DEFAULT_MAJOR_VERSION: u16 = 45;
+    pub const DEFAULT_MINOR_VERSION: u16 = 3;
+
+    /// The minor version marking a class file as depending on preview features (Java 12+).
+    pub const PREVIEW_MINOR_VERSION: u16 = 65535;
+
+    /// Check whether the version is one that the JVM can load. From Java 12 (56.0) onwards, the
+    /// minor version must either be 0, or 65535 to indicate that the class uses preview features.
+    pub fn is_valid(&self) -> bool {
+        self.major_version >= PhoronVersionDef::DEFAULT_MAJOR_VERSION
+            && (self.major_version < 56
+                || self.minor_version == 0
+                || self.minor_version == PhoronVersionDef::PREVIEW_MINOR_VERSION)
+    }
+}
+
+impl Default for PhoronVersionDef {
+    fn default() -> Self {
+        PhoronVersionDef {
+            major_version: PhoronVersionDef::DEFAULT_MAJOR_VERSION,
+            minor_version: PhoronVersionDef::DEFAULT_MINOR_VERSION,
+        }
+    }
+}
+
+impl fmt::Display for PhoronVersionDef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}", self.major_version, self.minor_version)
+    }
+}
+
 #[derive(Debug, PartialEq, Default)]
 pub struct PhoronSourceFileDef {
     pub source_file: String,
@@ -94,10 +145,77 @@ pub struct PhoronInterfaceDef {
     pub access_flags: Vec<PhoronClassOrInterfaceAccessFlag>,
 }
 
+// modules
+
+/// The name of the class holding a module declaration.
+pub const MODULE_INFO_CLASS_NAME: &str = "module-info";
+
+#[derive(Debug, PartialEq)]
+pub enum PhoronModuleAccessFlag {
+    AccOpen,
+    AccSynthetic,
+    AccMandated,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum PhoronRequiresAccessFlag {
+    AccTransitive,
+    AccStaticPhase,
+    AccSynthetic,
+    AccMandated,
+}
+
+/// The flags of an `exports` or `opens` statement.
+#[derive(Debug, PartialEq)]
+pub enum PhoronExportsAccessFlag {
+    AccSynthetic,
+    AccMandated,
+}
+
+/// `requires <flags> <module> [version]`
+#[derive(Default, PartialEq, Debug)]
+pub struct PhoronRequiresDef {
+    pub module_name: String,
+    pub access_flags: Vec<PhoronRequiresAccessFlag>,
+    pub version: Option<String>,
+}
+
+/// `exports <flags> <package> [to <module>*]`, or the same for `opens`.
+#[derive(Default, PartialEq, Debug)]
+pub struct PhoronExportsDef {
+    pub package_name: String,
+    pub access_flags: Vec<PhoronExportsAccessFlag>,
+    pub to_modules: Vec<String>,
+}
+
+/// `provides <service> with <class>*`
+#[derive(Default, PartialEq, Debug)]
+pub struct PhoronProvidesDef {
+    pub service_name: String,
+    pub with_classes: Vec<String>,
+}
+
+/// A module declaration, which generates the `module-info` class. The `packages` and `main_class`
+/// go into the `ModulePackages` and `ModuleMainClass` attributes respectively.
+#[derive(Default, PartialEq, Debug)]
+pub struct PhoronModuleDef {
+    pub name: String,
+    pub access_flags: Vec<PhoronModuleAccessFlag>,
+    pub version: Option<String>,
+    pub requires: Vec<PhoronRequiresDef>,
+    pub exports: Vec<PhoronExportsDef>,
+    pub opens: Vec<PhoronExportsDef>,
+    pub uses: Vec<String>,
+    pub provides: Vec<PhoronProvidesDef>,
+    pub packages: Vec<String>,
+    pub main_class: Option<String>,
+}
+
 #[derive(PartialEq, Debug)]
 pub enum PhoronClassOrInterface {
     Class(PhoronClassDef),
     Interface(PhoronInterfaceDef),
+    Module(PhoronModuleDef),
 }
 
 impl Default for PhoronClassOrInterface {
@@ -116,19 +234,140 @@ pub struct PhoronImplementsDef {
     pub class_name: String,
 }
 
+#[derive(Default, Debug, PartialEq)]
+pub enum PhoronInnerClassAccessFlag {
+    #[default]
+    AccPublic,
+    AccPrivate,
+    AccProtected,
+    AccStatic,
+    AccFinal,
+    AccInterface,
+    AccAbstract,
+    AccSynthetic,
+    AccAnnotation,
+    AccEnum,
+}
+
+/// An entry in the `InnerClasses` attribute. Anonymous classes have no `inner_name`, and only
+/// member classes have an `outer_class_name`.
+#[derive(Default, PartialEq, Debug)]
+pub struct PhoronInnerClassDef {
+    pub inner_class_name: String,
+    pub outer_class_name: Option<String>,
+    pub inner_name: Option<String>,
+    pub access_flags: Vec<PhoronInnerClassAccessFlag>,
+}
+
+/// A component of a record class, along with the `.signature` and `.annotation` directives which
+/// follow it.
+#[derive(Default, PartialEq, Debug)]
+pub struct PhoronRecordComponentDef {
+    pub name: String,
+    pub field_descriptor: PhoronFieldDescriptor,
+    pub signature: Option<String>,
+    pub annotations: Vec<PhoronAnnotationDef>,
+    pub type_annotations: Vec<PhoronTypeAnnotationDef>,
+}
+
+/// A raw attribute, given by `.attribute` (or `.codeattribute`), for attributes which are not
+/// otherwise modelled by Phoron. Its contents are emitted verbatim.
+#[derive(Default, PartialEq, Debug)]
+pub struct PhoronAttributeDef {
+    pub name: String,
+    pub info: Vec<u8>,
+}
+
+/// The class (and, unless the class is enclosed by an initializer, the method) enclosing a local
+/// or anonymous class.
+#[derive(Default, PartialEq, Debug)]
+pub struct PhoronEnclosingMethodDef {
+    pub class_name: String,
+    pub method: Option<(String, PhoronMethodDescriptor)>,
+}
+
+/// Directives which apply to the class (or interface) as a whole, and which follow the `.super`
+/// and `.implements` directives.
+#[derive(PartialEq, Debug)]
+pub enum PhoronClassDirective {
+    /// `.bootstrap <name> <method-handle> [<static-argument> ...]` declares a bootstrap method
+    /// which may then be referred to by name by `invokedynamic` instructions.
+    Bootstrap {
+        name: String,
+        method_handle: PhoronMethodHandle,
+        arguments: Vec<BootstrapArgument>,
+    },
+
+    /// `.signature <signature>` gives the generic signature of the class.
+    Signature(String),
+
+    /// `.annotation visible|invisible <type> ... .end annotation` annotates the class.
+    Annotation(PhoronAnnotationDef),
+
+    /// `.annotation type <target> ...` annotates a type used in the class.
+    TypeAnnotation(PhoronTypeAnnotationDef),
+
+    /// `.inner class|interface <access> <inner> [outer <outer>] [name <simple-name>]` records a
+    /// nested class in the `InnerClasses` attribute.
+    InnerClass(PhoronInnerClassDef),
+
+    /// `.enclosing method <class> [<name><descriptor>]` gives the immediately enclosing method of
+    /// a local or anonymous class.
+    EnclosingMethod(PhoronEnclosingMethodDef),
+
+    /// `.nesthost <class>` gives the host of the nest which the class is a member of.
+    NestHost(String),
+
+    /// `.nestmember <class>` adds a class to the nest hosted by the class.
+    NestMember(String),
+
+    /// `.component <name> <descriptor>` declares a component of a record class, in the `Record`
+    /// attribute.
+    RecordComponent(PhoronRecordComponentDef),
+
+    /// `.permits <class>` allows a class to directly extend (or implement) the sealed class.
+    Permits(String),
+
+    /// `.debug "<string>"` appends to the `SourceDebugExtension` attribute of the class, typically
+    /// a JSR-45 SMAP mapping the class back to its original source.
+    Debug(String),
+
+    /// `.attribute <name> "<file>"|hex "<hex-digits>"` adds a raw attribute to the class.
+    Attribute(PhoronAttributeDef),
+
+    /// `.deprecated` marks the class with the `Deprecated` attribute.
+    Deprecated,
+
+    /// `.synthetic [flag]` marks the class with the `Synthetic` attribute, and with the
+    /// `ACC_SYNTHETIC` access flag as well if `flag` is set.
+    Synthetic { flag: bool },
+}
+
+impl Default for PhoronClassDirective {
+    fn default() -> Self {
+        PhoronClassDirective::Bootstrap {
+            name: String::default(),
+            method_handle: PhoronMethodHandle::default(),
+            arguments: Vec::default(),
+        }
+    }
+}
+
 #[derive(Default, PartialEq, Debug)]
 pub struct PhoronHeader {
+    pub version_def: PhoronVersionDef,
     pub sourcefile_def: PhoronSourceFileDef,
     pub class_or_interface_def: PhoronClassOrInterface,
     pub super_def: PhoronSuperDef,
     pub implements_defs: Vec<PhoronImplementsDef>,
+    pub class_directives: Vec<PhoronClassDirective>,
 }
 
 // Descriptors
 
 use std::fmt;
 
-#[derive(PartialEq, Debug)]
+#[derive(PartialEq, Debug, Clone)]
 pub enum PhoronFieldDescriptor {
     BaseType(PhoronBaseType),
     ObjectType {
@@ -145,6 +384,18 @@ impl Default for PhoronFieldDescriptor {
     }
 }
 
+impl PhoronFieldDescriptor {
+    /// Whether values of this type belong to computational type category 2 (`long` and `double`),
+    /// and so take up two slots in the operand stack and local variables.
+    pub fn is_category2(&self) -> bool {
+        matches!(
+            self,
+            PhoronFieldDescriptor::BaseType(PhoronBaseType::Long)
+                | PhoronFieldDescriptor::BaseType(PhoronBaseType::Double)
+        )
+    }
+}
+
 impl fmt::Display for PhoronFieldDescriptor {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         use PhoronFieldDescriptor::*;
@@ -161,7 +412,7 @@ impl fmt::Display for PhoronFieldDescriptor {
     }
 }
 
-#[derive(Default, PartialEq, Debug)]
+#[derive(Default, PartialEq, Debug, Clone)]
 pub enum PhoronBaseType {
     #[default]
     Byte,
@@ -241,6 +492,245 @@ impl fmt::Display for PhoronReturnDescriptor {
     }
 }
 
+// annotations
+
+/// Whether an annotation is retained at runtime (`visible`), or only in the class file
+/// (`invisible`).
+#[derive(Default, Debug, PartialEq, Clone, Copy)]
+pub enum PhoronAnnotationVisibility {
+    #[default]
+    Visible,
+    Invisible,
+}
+
+/// An annotation - its type, and the values of its elements.
+#[derive(Default, Debug, PartialEq)]
+pub struct PhoronAnnotation {
+    pub annotation_type: PhoronFieldDescriptor,
+    pub element_value_pairs: Vec<PhoronElementValuePair>,
+}
+
+/// An `.annotation visible|invisible <type> ... .end annotation` block applied to a class, field,
+/// or method.
+#[derive(Default, Debug, PartialEq)]
+pub struct PhoronAnnotationDef {
+    pub visibility: PhoronAnnotationVisibility,
+    pub annotation: PhoronAnnotation,
+}
+
+#[derive(Default, Debug, PartialEq)]
+pub struct PhoronElementValuePair {
+    pub name: String,
+    pub value: PhoronElementValue,
+}
+
+/// The value of an annotation element, one variant per `element_value` tag.
+#[derive(Debug, PartialEq)]
+pub enum PhoronElementValue {
+    Byte(i8),
+    Char(u16),
+    Double(f64),
+    Float(f32),
+    Integer(i32),
+    Long(i64),
+    Short(i16),
+    Boolean(bool),
+    QuotedString(String),
+    Enum {
+        enum_type: PhoronFieldDescriptor,
+        const_name: String,
+    },
+    Class(PhoronReturnDescriptor),
+    Annotation(PhoronAnnotation),
+    Array(Vec<PhoronElementValue>),
+}
+
+impl Default for PhoronElementValue {
+    fn default() -> Self {
+        PhoronElementValue::Integer(i32::default())
+    }
+}
+
+impl PhoronElementValue {
+    /// The `element_value` tag of the value, as per the JVM specification.
+    pub fn tag(&self) -> u8 {
+        use PhoronElementValue::*;
+
+        match *self {
+            Byte(..) => b'B',
+            Char(..) => b'C',
+            Double(..) => b'D',
+            Float(..) => b'F',
+            Integer(..) => b'I',
+            Long(..) => b'J',
+            Short(..) => b'S',
+            Boolean(..) => b'Z',
+            QuotedString(..) => b's',
+            Enum { .. } => b'e',
+            Class(..) => b'c',
+            Annotation(..) => b'@',
+            Array(..) => b'[',
+        }
+    }
+}
+
+/// A range of code in which a local variable holds the annotated type.
+#[derive(Default, Debug, PartialEq)]
+pub struct PhoronLocalVarRange {
+    pub varnum: u16,
+    pub from_label: String,
+    pub to_label: String,
+}
+
+/// The type annotated by a type annotation, one variant per `target_type`. Labels refer to the
+/// instruction whose type is annotated.
+#[derive(Default, Debug, PartialEq)]
+pub enum PhoronTypeAnnotationTarget {
+    ClassTypeParameter {
+        type_parameter_index: u8,
+    },
+    MethodTypeParameter {
+        type_parameter_index: u8,
+    },
+    /// `supertype_index` is the index into the `.implements` directives, or 65535 for `.super`.
+    Supertype {
+        supertype_index: u16,
+    },
+    ClassTypeParameterBound {
+        type_parameter_index: u8,
+        bound_index: u8,
+    },
+    MethodTypeParameterBound {
+        type_parameter_index: u8,
+        bound_index: u8,
+    },
+    #[default]
+    Field,
+    Return,
+    Receiver,
+    FormalParameter {
+        formal_parameter_index: u8,
+    },
+    Throws {
+        throws_type_index: u16,
+    },
+    LocalVar {
+        ranges: Vec<PhoronLocalVarRange>,
+    },
+    ResourceVar {
+        ranges: Vec<PhoronLocalVarRange>,
+    },
+    Catch {
+        exception_table_index: u16,
+    },
+    Instanceof {
+        label: String,
+    },
+    New {
+        label: String,
+    },
+    ConstructorReference {
+        label: String,
+    },
+    MethodReference {
+        label: String,
+    },
+    Cast {
+        label: String,
+        type_argument_index: u8,
+    },
+    ConstructorInvocationTypeArgument {
+        label: String,
+        type_argument_index: u8,
+    },
+    MethodInvocationTypeArgument {
+        label: String,
+        type_argument_index: u8,
+    },
+    ConstructorReferenceTypeArgument {
+        label: String,
+        type_argument_index: u8,
+    },
+    MethodReferenceTypeArgument {
+        label: String,
+        type_argument_index: u8,
+    },
+}
+
+impl PhoronTypeAnnotationTarget {
+    /// The `target_type` of the target, as per the JVM specification.
+    pub fn target_type(&self) -> u8 {
+        use PhoronTypeAnnotationTarget::*;
+
+        match *self {
+            ClassTypeParameter { .. } => 0x00,
+            MethodTypeParameter { .. } => 0x01,
+            Supertype { .. } => 0x10,
+            ClassTypeParameterBound { .. } => 0x11,
+            MethodTypeParameterBound { .. } => 0x12,
+            Field => 0x13,
+            Return => 0x14,
+            Receiver => 0x15,
+            FormalParameter { .. } => 0x16,
+            Throws { .. } => 0x17,
+            LocalVar { .. } => 0x40,
+            ResourceVar { .. } => 0x41,
+            Catch { .. } => 0x42,
+            Instanceof { .. } => 0x43,
+            New { .. } => 0x44,
+            ConstructorReference { .. } => 0x45,
+            MethodReference { .. } => 0x46,
+            Cast { .. } => 0x47,
+            ConstructorInvocationTypeArgument { .. } => 0x48,
+            MethodInvocationTypeArgument { .. } => 0x49,
+            ConstructorReferenceTypeArgument { .. } => 0x4a,
+            MethodReferenceTypeArgument { .. } => 0x4b,
+        }
+    }
+
+    /// Whether the annotated type appears in the code of a method, in which case the annotation
+    /// goes in the `Code` attribute rather than the method itself.
+    pub fn is_code_target(&self) -> bool {
+        self.target_type() >= 0x40
+    }
+}
+
+/// A step in the path to the annotated part of a type.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum PhoronTypePathEntry {
+    /// `[` - deeper in an array type
+    Array,
+    /// `.` - deeper in a nested type
+    Nested,
+    /// `*` - on the bound of a wildcard type argument
+    WildcardBound,
+    /// `<n>;` - on the n-th type argument of a parameterized type
+    TypeArgument(u8),
+}
+
+impl PhoronTypePathEntry {
+    /// The `type_path_kind` of the entry, as per the JVM specification.
+    pub fn type_path_kind(&self) -> u8 {
+        use PhoronTypePathEntry::*;
+
+        match *self {
+            Array => 0,
+            Nested => 1,
+            WildcardBound => 2,
+            TypeArgument(..) => 3,
+        }
+    }
+}
+
+/// An `.annotation type <target> [path <type-path>] visible|invisible <type> ... .end annotation`
+/// block, annotating a type used in a class, field, or method.
+#[derive(Default, Debug, PartialEq)]
+pub struct PhoronTypeAnnotationDef {
+    pub target: PhoronTypeAnnotationTarget,
+    pub type_path: Vec<PhoronTypePathEntry>,
+    pub annotation_def: PhoronAnnotationDef,
+}
+
 // body
 
 // Fields
@@ -277,7 +767,13 @@ pub struct PhoronFieldDef {
     pub name: String,
     pub access_flags: Vec<PhoronFieldAccessFlag>,
     pub field_descriptor: PhoronFieldDescriptor,
+    pub signature: Option<String>,
     pub init_val: Option<PhoronFieldInitValue>,
+    pub annotations: Vec<PhoronAnnotationDef>,
+    pub type_annotations: Vec<PhoronTypeAnnotationDef>,
+    pub attributes: Vec<PhoronAttributeDef>,
+    pub deprecated: bool,
+    pub synthetic: bool,
 }
 
 // methods
@@ -299,6 +795,13 @@ pub enum PhoronMethodAccessFlag {
     AccSynthetic,
 }
 
+#[derive(Debug, PartialEq)]
+pub enum PhoronParameterAccessFlag {
+    AccFinal,
+    AccSynthetic,
+    AccMandated,
+}
+
 #[derive(PartialEq, Debug)]
 pub enum PhoronDirective {
     LimitStack(u16),
@@ -315,6 +818,7 @@ pub enum PhoronDirective {
         varnum: u16,
         name: String,
         field_descriptor: PhoronFieldDescriptor,
+        signature: Option<String>,
         from_label: String,
         to_label: String,
     },
@@ -325,6 +829,37 @@ pub enum PhoronDirective {
         to_label: String,
         handler_label: String,
     },
+
+    Signature(String),
+
+    Annotation(PhoronAnnotationDef),
+
+    ParameterAnnotation {
+        parameter_index: u8,
+        annotation_def: PhoronAnnotationDef,
+    },
+
+    TypeAnnotation(PhoronTypeAnnotationDef),
+
+    AnnotationDefault(PhoronElementValue),
+
+    Parameter {
+        parameter_index: u8,
+        name: String,
+        access_flags: Vec<PhoronParameterAccessFlag>,
+    },
+
+    Attribute(PhoronAttributeDef),
+
+    CodeAttribute(PhoronAttributeDef),
+
+    Deprecated,
+
+    /// `.synthetic [flag]` marks the method with the `Synthetic` attribute, and with the
+    /// `ACC_SYNTHETIC` access flag as well if `flag` is set.
+    Synthetic {
+        flag: bool,
+    },
 }
 
 impl Default for PhoronDirective {
@@ -338,6 +873,10 @@ pub enum LdcValue {
     Float(f32),
     Integer(i32),
     QuotedString(String),
+    Class(String),
+    MethodType(PhoronMethodDescriptor),
+    MethodHandle(PhoronMethodHandle),
+    Dynamic(PhoronDynamicConstant),
 }
 
 impl Default for LdcValue {
@@ -351,6 +890,10 @@ pub enum LdcwValue {
     Float(f32),
     Integer(i32),
     QuotedString(String),
+    Class(String),
+    MethodType(PhoronMethodDescriptor),
+    MethodHandle(PhoronMethodHandle),
+    Dynamic(PhoronDynamicConstant),
 }
 
 impl Default for LdcwValue {
@@ -363,6 +906,7 @@ impl Default for LdcwValue {
 pub enum Ldc2wValue {
     Double(f64),
     Long(i64),
+    Dynamic(PhoronDynamicConstant),
 }
 
 impl Default for Ldc2wValue {
@@ -371,6 +915,117 @@ impl Default for Ldc2wValue {
     }
 }
 
+/// A method handle, as used by `.bootstrap` directives and `ldc`. Each kind of handle is named
+/// after the JVM instruction whose behaviour it encapsulates (the `reference_kind` in JVM parlance).
+#[derive(Debug, PartialEq)]
+pub enum PhoronMethodHandle {
+    Getfield {
+        class_name: String,
+        field_name: String,
+        field_descriptor: PhoronFieldDescriptor,
+    },
+    Getstatic {
+        class_name: String,
+        field_name: String,
+        field_descriptor: PhoronFieldDescriptor,
+    },
+    Putfield {
+        class_name: String,
+        field_name: String,
+        field_descriptor: PhoronFieldDescriptor,
+    },
+    Putstatic {
+        class_name: String,
+        field_name: String,
+        field_descriptor: PhoronFieldDescriptor,
+    },
+    Invokevirtual {
+        class_name: String,
+        method_name: String,
+        method_descriptor: PhoronMethodDescriptor,
+    },
+    Invokestatic {
+        class_name: String,
+        method_name: String,
+        method_descriptor: PhoronMethodDescriptor,
+        is_interface: bool,
+    },
+    Invokespecial {
+        class_name: String,
+        method_name: String,
+        method_descriptor: PhoronMethodDescriptor,
+        is_interface: bool,
+    },
+    Newinvokespecial {
+        class_name: String,
+        method_descriptor: PhoronMethodDescriptor,
+    },
+    Invokeinterface {
+        interface_name: String,
+        method_name: String,
+        method_descriptor: PhoronMethodDescriptor,
+    },
+}
+
+impl PhoronMethodHandle {
+    /// The `reference_kind` of the `CONSTANT_MethodHandle` for this method handle.
+    pub fn reference_kind(&self) -> u8 {
+        use PhoronMethodHandle::*;
+
+        match *self {
+            Getfield { .. } => 1,
+            Getstatic { .. } => 2,
+            Putfield { .. } => 3,
+            Putstatic { .. } => 4,
+            Invokevirtual { .. } => 5,
+            Invokestatic { .. } => 6,
+            Invokespecial { .. } => 7,
+            Newinvokespecial { .. } => 8,
+            Invokeinterface { .. } => 9,
+        }
+    }
+}
+
+impl Default for PhoronMethodHandle {
+    fn default() -> Self {
+        PhoronMethodHandle::Invokestatic {
+            class_name: String::default(),
+            method_name: String::default(),
+            method_descriptor: PhoronMethodDescriptor::default(),
+            is_interface: false,
+        }
+    }
+}
+
+/// A static argument to a bootstrap method.
+#[derive(Debug, PartialEq)]
+pub enum BootstrapArgument {
+    Integer(i32),
+    Float(f32),
+    Long(i64),
+    Double(f64),
+    QuotedString(String),
+    Class(String),
+    MethodType(PhoronMethodDescriptor),
+    MethodHandle(PhoronMethodHandle),
+    Dynamic(PhoronDynamicConstant),
+}
+
+impl Default for BootstrapArgument {
+    fn default() -> Self {
+        BootstrapArgument::Integer(i32::default())
+    }
+}
+
+/// A dynamically-computed constant (`CONSTANT_Dynamic`), whose value is produced on first use by
+/// invoking the named bootstrap method.
+#[derive(Default, Debug, PartialEq)]
+pub struct PhoronDynamicConstant {
+    pub name: String,
+    pub field_descriptor: PhoronFieldDescriptor,
+    pub bootstrap_method: String,
+}
+
 #[derive(Default, Debug, PartialEq)]
 pub struct LookupSwitchPair {
     pub key: i32,
@@ -600,6 +1255,11 @@ pub enum JvmInstruction {
     Instanceof {
         check_type: PhoronFieldDescriptor,
     },
+    Invokedynamic {
+        method_name: String,
+        method_descriptor: PhoronMethodDescriptor,
+        bootstrap_method: String,
+    },
     Invokeinterface {
         interface_name: String,
         method_name: String,
@@ -727,6 +1387,132 @@ pub enum JvmInstruction {
     Wide(WideInstruction),
 }
 
+impl JvmInstruction {
+    /// The number of bytes taken up by the instruction in the `code` array when it starts at
+    /// `offset`, which determines the padding of `tableswitch` and `lookupswitch`.
+    pub fn code_length(&self, offset: u32) -> u32 {
+        use JvmInstruction::*;
+
+        match self {
+            // 1-byte instructions
+            Aaload | Aastore | Aconstnull | Aload0 | Aload1 | Aload2 | Aload3 | Areturn
+            | Arraylength | Astore0 | Astore1 | Astore2 | Astore3 | Athrow | Baload | Bastore
+            | Caload | Castore | D2f | D2i | D2l | Dadd | Daload | Dastore | Dcmpg | Dcmpl
+            | Dconst0 | Dconst1 | Ddiv | Dload0 | Dload1 | Dload2 | Dload3 | Dmul | Dneg | Drem
+            | Dreturn | Dstore0 | Dstore1 | Dstore2 | Dstore3 | Dsub | Dup | Dupx1 | Dupx2
+            | Dup2 | Dup2x1 | Dup2x2 | F2d | F2i | F2l | Fadd | Faload | Fastore | Fcmpg
+            | Fcmpl | Fconst0 | Fconst1 | Fconst2 | Fdiv | Fload0 | Fload1 | Fload2 | Fload3
+            | Fmul | Fneg | Frem | Freturn | Fstore0 | Fstore1 | Fstore2 | Fstore3 | Fsub | I2b
+            | I2c | I2d | I2f | I2l | I2s | Iadd | Iaload | Iand | Iastore | Iconstm1 | Iconst0
+            | Iconst1 | Iconst2 | Iconst3 | Iconst4 | Iconst5 | Idiv | Iload0 | Iload1 | Iload2
+            | Iload3 | Imul | Ineg | Ior | Irem | Ireturn | Ishl | Ishr | Istore0 | Istore1
+            | Istore2 | Istore3 | Isub | Iushr | Ixor | L2d | L2f | L2i | Ladd | Laload | Land
+            | Lastore | Lcmp | Lconst0 | Lconst1 | Ldiv | Lload0 | Lload1 | Lload2 | Lload3
+            | Lmul | Lneg | Lor | Lrem | Lreturn | Lshl | Lshr | Lstore0 | Lstore1 | Lstore2
+            | Lstore3 | Lsub | Lushr | Lxor | Monitorenter | Monitorexit | Nop | Pop | Pop2
+            | Return | Saload | Sastore | Swap => 1,
+
+            Bipush(..)
+            | Newarray { .. }
+            | Iload { .. }
+            | Fload { .. }
+            | Aload { .. }
+            | Lload { .. }
+            | Dload { .. }
+            | Istore { .. }
+            | Fstore { .. }
+            | Astore { .. }
+            | Lstore { .. }
+            | Dstore { .. }
+            | Ldc(..)
+            | Ret { .. } => 2,
+
+            Sipush(..)
+            | Anewarray { .. }
+            | Checkcast { .. }
+            | Getstatic { .. }
+            | Getfield { .. }
+            | Iinc { .. }
+            | Invokespecial { .. }
+            | Invokestatic { .. }
+            | Invokevirtual { .. }
+            | Putfield { .. }
+            | Putstatic { .. }
+            | Goto { .. }
+            | Ifacmpeq { .. }
+            | Ifacmpne { .. }
+            | Ificmpeq { .. }
+            | Ificmpge { .. }
+            | Ificmpgt { .. }
+            | Ificmple { .. }
+            | Ificmplt { .. }
+            | Ificmpne { .. }
+            | Ifeq { .. }
+            | Ifge { .. }
+            | Ifgt { .. }
+            | Ifle { .. }
+            | Iflt { .. }
+            | Ifne { .. }
+            | Ifnonnull { .. }
+            | Ifnull { .. }
+            | Instanceof { .. }
+            | Ldcw(..)
+            | Ldc2w(..)
+            | New { .. }
+            | Jsr { .. } => 3,
+
+            Invokeinterface { .. } | Multianewarray { .. } => 4,
+
+            Invokedynamic { .. } | Jsrw { .. } | Gotow { .. } => 5,
+
+            // the opcode, the padding up to the next multiple of 4 bytes, the default offset, the
+            // number of pairs, and the match-offset pairs
+            Lookupswitch { ref switches, .. } => {
+                1 + (4 - (offset + 1) % 4) % 4 + 4 + 4 + 8 * switches.len() as u32
+            }
+
+            // the opcode, the padding up to the next multiple of 4 bytes, the default offset,
+            // `low` and `high`, and the jump offsets
+            Tableswitch { ref switches, .. } => {
+                1 + (4 - (offset + 1) % 4) % 4 + 4 + 8 + 4 * switches.len() as u32
+            }
+
+            Wide(ref wide_instr) => match wide_instr {
+                WideInstruction::IInc { .. } => 6,
+                _ => 4,
+            },
+        }
+    }
+
+    /// The label branched to by a `goto`, `jsr`, or conditional branch instruction - those which
+    /// take a 16-bit branch offset.
+    pub fn short_branch_target(&self) -> Option<&str> {
+        use JvmInstruction::*;
+
+        match self {
+            Goto { ref label }
+            | Jsr { ref label }
+            | Ifacmpeq { ref label }
+            | Ifacmpne { ref label }
+            | Ificmpeq { ref label }
+            | Ificmpge { ref label }
+            | Ificmpgt { ref label }
+            | Ificmple { ref label }
+            | Ificmplt { ref label }
+            | Ificmpne { ref label }
+            | Ifeq { ref label }
+            | Ifge { ref label }
+            | Ifgt { ref label }
+            | Ifle { ref label }
+            | Iflt { ref label }
+            | Ifne { ref label }
+            | Ifnonnull { ref label }
+            | Ifnull { ref label } => Some(label),
+            _ => None,
+        }
+    }
+}
+
 #[derive(PartialEq, Debug)]
 pub enum PhoronInstruction {
     PhoronDirective(PhoronDirective),
@@ -754,6 +1540,47 @@ pub struct PhoronBody {
     pub method_defs: Vec<PhoronMethodDef>,
 }
 
+#[cfg(test)]
+impl PhoronProgram {
+    /// A public class `Test`, whose only method is `public static void run`, with the given
+    /// parameters and code, for testing the later passes without going through a source file.
+    pub(crate) fn with_method(
+        param_descriptor: Vec<PhoronFieldDescriptor>,
+        instructions: Vec<PhoronInstruction>,
+    ) -> Self {
+        PhoronProgram {
+            header: PhoronHeader {
+                sourcefile_def: PhoronSourceFileDef {
+                    source_file: "Test.pho".to_string(),
+                },
+                class_or_interface_def: PhoronClassOrInterface::Class(PhoronClassDef {
+                    name: "Test".to_string(),
+                    access_flags: vec![PhoronClassOrInterfaceAccessFlag::AccPublic],
+                }),
+                super_def: PhoronSuperDef {
+                    super_class_name: "java/lang/Object".to_string(),
+                },
+                ..Default::default()
+            },
+            body: PhoronBody {
+                field_defs: vec![],
+                method_defs: vec![PhoronMethodDef {
+                    name: "run".to_string(),
+                    access_flags: vec![
+                        PhoronMethodAccessFlag::AccPublic,
+                        PhoronMethodAccessFlag::AccStatic,
+                    ],
+                    method_descriptor: PhoronMethodDescriptor {
+                        param_descriptor,
+                        return_descriptor: PhoronReturnDescriptor::VoidDescriptor,
+                    },
+                    instructions,
+                }],
+            },
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::{PhoronBaseType::*, PhoronFieldDescriptor::*, PhoronReturnDescriptor::*, *};
@@ -822,4 +1649,28 @@ mod tests {
         };
         assert_eq!("[Ljava/lang/String;", array_type.to_string());
     }
+
+    #[test]
+    fn test_switch_code_length() {
+        let tableswitch = JvmInstruction::Tableswitch {
+            low: 0,
+            high: 1,
+            switches: vec!["Zero".to_string(), "One".to_string()],
+            default: "Default".to_string(),
+        };
+
+        // the padding brings the default offset to a multiple of 4 bytes
+        assert_eq!(1 + 3 + 12 + 8, tableswitch.code_length(0));
+        assert_eq!(1 + 2 + 12 + 8, tableswitch.code_length(1));
+        assert_eq!(1 + 12 + 8, tableswitch.code_length(3));
+
+        let lookupswitch = JvmInstruction::Lookupswitch {
+            switches: vec![LookupSwitchPair {
+                key: 42,
+                label: "Answer".to_string(),
+            }],
+            default: "Default".to_string(),
+        };
+        assert_eq!(1 + 8 + 8, lookupswitch.code_length(7));
+    }
 }
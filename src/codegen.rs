@@ -3,6 +3,7 @@
 use crate::{
     ast::{attributes::*, *},
     cp_analyzer::constant_pool::*,
+    stack_map::{self, class_hierarchy::*, Frame, StackMapError, VerificationType},
 };
 use phoron_core::{
     error::SerializeError,
@@ -16,7 +17,13 @@ use phoron_core::{
     serializer::Serializer,
 };
 
-use std::{collections::HashMap, error::Error, fmt, io::Write};
+use std::{
+    collections::{HashMap, HashSet},
+    error::Error,
+    fmt,
+    io::Write,
+    rc::Rc,
+};
 
 #[derive(Debug)]
 pub enum CodegenError {
@@ -43,8 +50,30 @@ pub enum CodegenError {
         opcode: &'static str,
         details: &'static str,
     },
+    /// The code of the method is longer than the JVM allows.
+    CodeTooLong {
+        method: String,
+        code_length: u32,
+    },
+
+    /// A code offset referred to by a directive does not fit in the `u16` field it is stored in.
+    CodeOffsetTooLarge {
+        method: String,
+        directive: String,
+        offset: u32,
+    },
+
+    /// The code range of a directive ends before it starts.
+    CodeRangeReversed {
+        method: String,
+        directive: String,
+        start_pc: u16,
+        end_pc: u16,
+    },
+
     Unknown,
     SerializeError(SerializeError),
+    StackMapError(StackMapError),
 }
 
 impl Error for CodegenError {}
@@ -74,8 +103,31 @@ impl fmt::Display for CodegenError {
                     ref opcode,
                     ref details,
                 } => format!("malformed or invalid opcode {opcode} : {details}"),
+                CodeTooLong {
+                    ref method,
+                    code_length,
+                } => format!(
+                    "the code of method `{method}` is {code_length} bytes long, but at most {MAX_CODE_LENGTH} bytes are allowed"
+                ),
+                CodeOffsetTooLarge {
+                    ref method,
+                    ref directive,
+                    offset,
+                } => format!(
+                    "{directive} in method `{method}` refers to code offset {offset}, which is beyond the maximum of {}",
+                    u16::MAX
+                ),
+                CodeRangeReversed {
+                    ref method,
+                    ref directive,
+                    start_pc,
+                    end_pc,
+                } => format!(
+                    "{directive} in method `{method}` covers the code range {start_pc} to {end_pc}, which ends before it starts"
+                ),
                 Unknown => "an unknown error occurred during code generation".into(),
                 SerializeError(ref ser_err) => ser_err.to_string(),
+                StackMapError(ref stack_map_err) => stack_map_err.to_string(),
             }
         )
     }
@@ -87,8 +139,68 @@ impl From<SerializeError> for CodegenError {
     }
 }
 
+impl From<StackMapError> for CodegenError {
+    fn from(stack_map_err: StackMapError) -> Self {
+        CodegenError::StackMapError(stack_map_err)
+    }
+}
+
 pub type CodegenResult<T> = Result<T, CodegenError>;
 
+/// Problems which do not prevent the class file from being generated, but which are likely to
+/// make it fail verification, or which make it larger than it would need to be.
+#[derive(Debug, PartialEq)]
+pub enum CodegenWarning {
+    LimitTooSmall {
+        method: String,
+        limit: &'static str,
+        declared: u16,
+        computed: u16,
+    },
+
+    /// The limits of a method without a `.limit` directive for each could not be computed from its
+    /// code, and so the missing ones default to 1.
+    LimitsNotComputed { method: String, details: String },
+
+    /// An `ldc` whose constant is beyond the first 255 Constant Pool entries, and which is
+    /// therefore generated as `ldc_w`. Only reported if enabled through
+    /// `Codegen::warn_ldc_promotion`.
+    LdcPromoted { method: String, cp_index: u16 },
+}
+
+impl fmt::Display for CodegenWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use CodegenWarning::*;
+
+        write!(
+            f,
+            "{}",
+            match *self {
+                LimitTooSmall {
+                    ref method,
+                    limit,
+                    declared,
+                    computed,
+                } => format!(
+                    "`.limit {limit} {declared}` in method `{method}` is smaller than the {computed} required by its code"
+                ),
+                LimitsNotComputed {
+                    ref method,
+                    ref details,
+                } => format!(
+                    "the limits of method `{method}` could not be computed ({details}), so those without a `.limit` default to {DEFAULT_CODE_LIMIT}"
+                ),
+                LdcPromoted {
+                    ref method,
+                    cp_index,
+                } => format!(
+                    "`ldc` of Constant Pool entry {cp_index} in method `{method}` is generated as `ldc_w`, since the index does not fit in a byte"
+                ),
+            }
+        )
+    }
+}
+
 impl PhoronClassOrInterfaceAccessFlag {
     fn to_u16(&self) -> u16 {
         match self {
@@ -104,6 +216,24 @@ impl PhoronClassOrInterfaceAccessFlag {
         }
     }
 }
+
+impl PhoronInnerClassAccessFlag {
+    fn to_u16(&self) -> u16 {
+        match self {
+            PhoronInnerClassAccessFlag::AccPublic => ACC_PUBLIC,
+            PhoronInnerClassAccessFlag::AccPrivate => ACC_PRIVATE,
+            PhoronInnerClassAccessFlag::AccProtected => ACC_PROTECTED,
+            PhoronInnerClassAccessFlag::AccStatic => ACC_STATIC,
+            PhoronInnerClassAccessFlag::AccFinal => ACC_FINAL,
+            PhoronInnerClassAccessFlag::AccInterface => ACC_INTERFACE,
+            PhoronInnerClassAccessFlag::AccAbstract => ACC_ABSTRACT,
+            PhoronInnerClassAccessFlag::AccSynthetic => ACC_SYNTHETIC,
+            PhoronInnerClassAccessFlag::AccAnnotation => ACC_ANNOTATION,
+            PhoronInnerClassAccessFlag::AccEnum => ACC_ENUM,
+        }
+    }
+}
+
 impl PhoronFieldAccessFlag {
     fn to_u16(&self) -> u16 {
         match self {
@@ -139,9 +269,77 @@ impl PhoronMethodAccessFlag {
     }
 }
 
+impl PhoronParameterAccessFlag {
+    fn to_u16(&self) -> u16 {
+        match self {
+            PhoronParameterAccessFlag::AccFinal => ACC_FINAL,
+            PhoronParameterAccessFlag::AccSynthetic => ACC_SYNTHETIC,
+            PhoronParameterAccessFlag::AccMandated => ACC_MANDATED,
+        }
+    }
+}
+
+impl PhoronModuleAccessFlag {
+    fn to_u16(&self) -> u16 {
+        match self {
+            PhoronModuleAccessFlag::AccOpen => ACC_OPEN,
+            PhoronModuleAccessFlag::AccSynthetic => ACC_SYNTHETIC,
+            PhoronModuleAccessFlag::AccMandated => ACC_MANDATED,
+        }
+    }
+}
+
+impl PhoronRequiresAccessFlag {
+    fn to_u16(&self) -> u16 {
+        match self {
+            PhoronRequiresAccessFlag::AccTransitive => ACC_TRANSITIVE,
+            PhoronRequiresAccessFlag::AccStaticPhase => ACC_STATIC_PHASE,
+            PhoronRequiresAccessFlag::AccSynthetic => ACC_SYNTHETIC,
+            PhoronRequiresAccessFlag::AccMandated => ACC_MANDATED,
+        }
+    }
+}
+
+impl PhoronExportsAccessFlag {
+    fn to_u16(&self) -> u16 {
+        match self {
+            PhoronExportsAccessFlag::AccSynthetic => ACC_SYNTHETIC,
+            PhoronExportsAccessFlag::AccMandated => ACC_MANDATED,
+        }
+    }
+}
+
 const MAGIC: u32 = 0xcafebabe;
-const MAJOR_VERSION: u16 = 45;
-const MINOR_VERSION: u16 = 3;
+
+/// The `max_stack` and `max_locals` of a method whose limits are neither declared nor computed.
+const DEFAULT_CODE_LIMIT: u16 = 1;
+
+// flags of the `Module` (and `MethodParameters`) attribute, which `phoron_core` does not define
+const ACC_OPEN: u16 = 0x0020;
+const ACC_TRANSITIVE: u16 = 0x0020;
+const ACC_STATIC_PHASE: u16 = 0x0040;
+const ACC_MANDATED: u16 = 0x8000;
+
+// verification type tags for the `StackMapTable` attribute
+const ITEM_TOP: u8 = 0;
+const ITEM_INTEGER: u8 = 1;
+const ITEM_FLOAT: u8 = 2;
+const ITEM_DOUBLE: u8 = 3;
+const ITEM_LONG: u8 = 4;
+const ITEM_NULL: u8 = 5;
+const ITEM_UNINITIALIZED_THIS: u8 = 6;
+const ITEM_OBJECT: u8 = 7;
+const ITEM_UNINITIALIZED: u8 = 8;
+
+/// The maximum length of the code of a method, in bytes.
+const MAX_CODE_LENGTH: u32 = 65535;
+
+// `StackMapTable` frame types (or the first of their range)
+const SAME_FRAME: u8 = 0;
+const SAME_LOCALS_1_STACK_ITEM_FRAME: u8 = 64;
+const SAME_LOCALS_1_STACK_ITEM_FRAME_EXTENDED: u8 = 247;
+const SAME_FRAME_EXTENDED: u8 = 251;
+const FULL_FRAME: u8 = 255;
 
 pub struct Codegen<'c, W>
 where
@@ -149,8 +347,17 @@ where
 {
     outfile: Serializer<'c, W>,
     classfile: ClassFile,
-    label_mapping: HashMap<String, i16>,
-    curr_code_offset: i16,
+    label_mapping: HashMap<String, u32>,
+    /// the code offsets of the branches of the current method which are widened, since their
+    /// targets are out of range of a 16-bit branch offset.
+    far_branches: HashSet<u32>,
+    curr_code_offset: u32,
+    warn_ldc_promotion: bool,
+    bootstrap_methods: HashMap<String, u16>,
+    class_name: String,
+    super_class_name: String,
+    class_hierarchy: Rc<dyn ClassHierarchyResolver>,
+    warnings: Vec<CodegenWarning>,
 }
 
 impl<'c, W> Codegen<'c, W>
@@ -158,14 +365,44 @@ where
     W: Write,
 {
     pub fn new(outfile: &'c mut W) -> Self {
+        Codegen::with_class_hierarchy_resolver(
+            outfile,
+            Rc::new(DefaultClassHierarchyResolver::new()),
+        )
+    }
+
+    /// Create a code generator which uses the given resolver when merging reference types for the
+    /// stack map frames. This should be the same resolver as used by `ConstantPoolAnalyzer`.
+    pub fn with_class_hierarchy_resolver(
+        outfile: &'c mut W,
+        class_hierarchy: Rc<dyn ClassHierarchyResolver>,
+    ) -> Self {
         Codegen {
             outfile: Serializer::new(Writer::new(outfile)),
             classfile: ClassFile::default(),
             label_mapping: HashMap::new(),
+            far_branches: HashSet::new(),
             curr_code_offset: 0,
+            warn_ldc_promotion: false,
+            bootstrap_methods: HashMap::new(),
+            class_name: String::default(),
+            super_class_name: String::default(),
+            class_hierarchy,
+            warnings: Vec::new(),
         }
     }
 
+    /// The warnings reported while generating the class file.
+    pub fn warnings(&self) -> &[CodegenWarning] {
+        &self.warnings
+    }
+
+    /// Report every `ldc` instruction which is promoted to `ldc_w` since the index of its constant
+    /// does not fit in a byte (see `CodegenWarning::LdcPromoted`). This is off by default.
+    pub fn warn_ldc_promotion(&mut self, warn: bool) {
+        self.warn_ldc_promotion = warn;
+    }
+
     fn gen_constant_pool(&mut self, cp: &PhoronConstantPool) -> CodegenResult<()> {
         let constant_pool_count = cp.len();
 
@@ -216,6 +453,48 @@ where
                     })
                 }
 
+                PhoronConstantPoolKind::MethodHandle {
+                    ref reference_kind,
+                    ref reference_index,
+                } => {
+                    constant_pool[cp_idx] = Some(CpInfo::ConstantMethodHandleInfo {
+                        tag: CONSTANT_METHOD_HANDLE,
+                        reference_kind: *reference_kind,
+                        reference_index: *reference_index,
+                    })
+                }
+
+                PhoronConstantPoolKind::MethodType {
+                    ref descriptor_index,
+                } => {
+                    constant_pool[cp_idx] = Some(CpInfo::ConstantMethodTypeInfo {
+                        tag: CONSTANT_METHOD_TYPE,
+                        descriptor_index: *descriptor_index,
+                    })
+                }
+
+                PhoronConstantPoolKind::Dynamic {
+                    ref bootstrap_method_attr_index,
+                    ref name_and_type_index,
+                } => {
+                    constant_pool[cp_idx] = Some(CpInfo::ConstantDynamicInfo {
+                        tag: CONSTANT_DYNAMIC,
+                        bootstrap_method_attr_index: *bootstrap_method_attr_index,
+                        name_and_type_index: *name_and_type_index,
+                    })
+                }
+
+                PhoronConstantPoolKind::InvokeDynamic {
+                    ref bootstrap_method_attr_index,
+                    ref name_and_type_index,
+                } => {
+                    constant_pool[cp_idx] = Some(CpInfo::ConstantInvokeDynamicInfo {
+                        tag: CONSTANT_INVOKE_DYNAMIC,
+                        bootstrap_method_attr_index: *bootstrap_method_attr_index,
+                        name_and_type_index: *name_and_type_index,
+                    })
+                }
+
                 PhoronConstantPoolKind::String { ref string_index } => {
                     constant_pool[cp_idx] = Some(CpInfo::ConstantStringInfo {
                         tag: CONSTANT_STRING,
@@ -267,181 +546,1763 @@ where
                         }
                     })?;
 
-                    let low_bytes: [u8; 4] = double_bytes[4..].try_into().map_err(|_| {
-                        CodegenError::ConstantPoolError {
-                            cp_entry: "double",
-                            details: " failed to get low bytes",
-                        }
-                    })?;
+                    let low_bytes: [u8; 4] = double_bytes[4..].try_into().map_err(|_| {
+                        CodegenError::ConstantPoolError {
+                            cp_entry: "double",
+                            details: " failed to get low bytes",
+                        }
+                    })?;
+
+                    constant_pool[cp_idx] = Some(CpInfo::ConstantDoubleInfo {
+                        tag: CONSTANT_DOUBLE,
+                        high_bytes: u32::from_be_bytes(high_bytes),
+                        low_bytes: u32::from_be_bytes(low_bytes),
+                    })
+                }
+
+                PhoronConstantPoolKind::Module { ref name_index } => {
+                    constant_pool[cp_idx] = Some(CpInfo::ConstantModuleInfo {
+                        tag: CONSTANT_MODULE,
+                        name_index: *name_index,
+                    })
+                }
+
+                PhoronConstantPoolKind::Package { ref name_index } => {
+                    constant_pool[cp_idx] = Some(CpInfo::ConstantPackageInfo {
+                        tag: CONSTANT_PACKAGE,
+                        name_index: *name_index,
+                    })
+                }
+
+                PhoronConstantPoolKind::NameAndType {
+                    ref name_index,
+                    ref descriptor_index,
+                } => {
+                    constant_pool[cp_idx] = Some(CpInfo::ConstantNameAndTypeInfo {
+                        tag: CONSTANT_NAME_AND_TYPE,
+                        name_index: *name_index,
+                        descriptor_index: *descriptor_index,
+                    })
+                }
+
+                PhoronConstantPoolKind::Utf8(ref s) => {
+                    constant_pool[cp_idx] = Some(CpInfo::ConstantUtf8Info {
+                        tag: CONSTANT_UTF8,
+                        length: s.len() as u16,
+                        bytes: s.bytes().collect::<_>(),
+                    })
+                }
+            }
+        }
+
+        self.classfile.constant_pool = constant_pool;
+
+        Ok(())
+    }
+
+    /// Map the labels of the method to their code offsets. Branches with a 16-bit offset whose
+    /// target is out of range are widened, which moves the code following them (and possibly
+    /// changes the padding of any `tableswitch` or `lookupswitch` after them), so this is repeated
+    /// until no more branches need to be widened. Since branches are only ever widened, this always
+    /// terminates.
+    fn gen_label_mappings(
+        &mut self,
+        method_def: &PhoronMethodDef,
+        cp: &PhoronConstantPool,
+    ) -> CodegenResult<()> {
+        let instructions = &method_def.instructions;
+
+        // `ldc` instructions whose constant is beyond the reach of a single-byte index are
+        // promoted to `ldc_w`, taking up an extra byte
+        let mut wide_ldcs = HashSet::new();
+        for (index, jvm_instr) in instructions
+            .iter()
+            .filter_map(|instr| match instr {
+                PhoronInstruction::JvmInstruction(ref jvm_instr) => Some(jvm_instr),
+                _ => None,
+            })
+            .enumerate()
+        {
+            if let JvmInstruction::Ldc(ref ldc_val) = jvm_instr {
+                let cp_index = self.get_ldc_index(ldc_val, cp)?;
+
+                if cp_index > u8::MAX as u16 {
+                    wide_ldcs.insert(index);
+
+                    if self.warn_ldc_promotion {
+                        self.warnings.push(CodegenWarning::LdcPromoted {
+                            method: method_def.name.to_owned(),
+                            cp_index,
+                        });
+                    }
+                }
+            }
+        }
+
+        // the positions of the widened branches among the JVM instructions of the method
+        let mut far_instructions = HashSet::new();
+
+        loop {
+            self.label_mapping.clear();
+            let mut instruction_offsets = Vec::new();
+            let mut curr_code_offset = 0u32;
+
+            for instr in instructions {
+                match instr {
+                    PhoronInstruction::PhoronDirective(ref _directive) => {}
+
+                    PhoronInstruction::PhoronLabel(ref label) => {
+                        self.label_mapping
+                            .insert(label.to_string(), curr_code_offset);
+                    }
+
+                    PhoronInstruction::JvmInstruction(ref jvm_instr) => {
+                        let index = instruction_offsets.len();
+                        instruction_offsets.push((curr_code_offset, jvm_instr));
+
+                        curr_code_offset += jvm_instr.code_length(curr_code_offset);
+                        if far_instructions.contains(&index) {
+                            curr_code_offset += Self::far_branch_extra_length(jvm_instr);
+                        }
+                        if wide_ldcs.contains(&index) {
+                            curr_code_offset += 1;
+                        }
+                    }
+                }
+            }
+
+            let far_count = far_instructions.len();
+            for (index, (offset, jvm_instr)) in instruction_offsets.iter().enumerate() {
+                // undefined labels are reported when generating the instruction itself
+                if let Some(label_offset) = jvm_instr
+                    .short_branch_target()
+                    .and_then(|label| self.label_mapping.get(label))
+                {
+                    if i16::try_from(*label_offset as i64 - *offset as i64).is_err() {
+                        far_instructions.insert(index);
+                    }
+                }
+            }
+
+            if far_instructions.len() == far_count {
+                self.far_branches = far_instructions
+                    .into_iter()
+                    .map(|index| instruction_offsets[index].0)
+                    .collect();
+                break;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Convert a code offset referred to by the directive described by `directive` to the `u16`
+    /// used by the exception table and the attributes of the `Code` attribute.
+    fn gen_code_offset(
+        offset: u32,
+        method_def: &PhoronMethodDef,
+        directive: impl FnOnce() -> String,
+    ) -> CodegenResult<u16> {
+        u16::try_from(offset).map_err(|_| CodegenError::CodeOffsetTooLarge {
+            method: method_def.name.to_owned(),
+            directive: directive(),
+            offset,
+        })
+    }
+
+    /// The number of bytes added by widening the branch - `goto` and `jsr` become `goto_w` and
+    /// `jsr_w`, while a conditional branch is followed by a `goto_w` (see `gen_branch`).
+    fn far_branch_extra_length(jvm_instr: &JvmInstruction) -> u32 {
+        match jvm_instr {
+            JvmInstruction::Goto { .. } | JvmInstruction::Jsr { .. } => 2,
+            _ => 5,
+        }
+    }
+
+    /// Generate a branch instruction with a 16-bit offset to `label`. Branches found to be out of
+    /// range by `gen_label_mappings` are widened instead - `goto` and `jsr` into `goto_w` and
+    /// `jsr_w`, and a conditional branch into the inverse condition skipping over a `goto_w` to
+    /// `label`.
+    fn gen_branch(
+        &self,
+        opcode: u8,
+        mnemonic: &'static str,
+        label: &str,
+    ) -> CodegenResult<Vec<u8>> {
+        let label_offset = self
+            .label_mapping
+            .get(label)
+            .ok_or(CodegenError::OpcodeError {
+                opcode: mnemonic,
+                details: "invalid label",
+            })?;
+
+        let offset = *label_offset as i32 - self.curr_code_offset as i32;
+        let mut opcodes = Vec::new();
+
+        if !self.far_branches.contains(&self.curr_code_offset) {
+            opcodes.push(opcode);
+            opcodes.extend_from_slice(&(offset as i16).to_be_bytes());
+            return Ok(opcodes);
+        }
+
+        match opcode {
+            // goto
+            0xa7 => {
+                opcodes.push(0xc8);
+                opcodes.extend_from_slice(&offset.to_be_bytes());
+            }
+
+            // jsr
+            0xa8 => {
+                opcodes.push(0xc9);
+                opcodes.extend_from_slice(&offset.to_be_bytes());
+            }
+
+            _ => {
+                let inverse_opcode = match opcode {
+                    0xc6 => 0xc7, // ifnull -> ifnonnull
+                    0xc7 => 0xc6, // ifnonnull -> ifnull
+
+                    // the rest come in pairs, from ifeq/ifne to if_acmpeq/if_acmpne
+                    _ if opcode % 2 == 1 => opcode + 1,
+                    _ => opcode - 1,
+                };
+
+                // the inverse branch skips over itself and the `goto_w`, to the next instruction
+                opcodes.extend_from_slice(&[inverse_opcode, 0x00, 0x08]);
+
+                // the `goto_w` follows the 3-byte inverse branch
+                opcodes.push(0xc8);
+                opcodes.extend_from_slice(&(offset - 3).to_be_bytes());
+            }
+        }
+
+        Ok(opcodes)
+    }
+
+    /// Retrieve the Constant Pool index of the constant loaded by an `ldc` instruction.
+    fn get_ldc_index(&self, ldc_val: &LdcValue, cp: &PhoronConstantPool) -> CodegenResult<u16> {
+        Ok(match ldc_val {
+            LdcValue::QuotedString(ref string) => {
+                *cp.get_string(string).ok_or(CodegenError::OpcodeError {
+                    opcode: "ldc",
+                    details: "missing quoted string",
+                })?
+            }
+
+            LdcValue::Integer(int) => *cp.get_integer(*int).ok_or(CodegenError::OpcodeError {
+                opcode: "ldc",
+                details: "missing integer",
+            })?,
+
+            LdcValue::Float(float) => *cp.get_float(*float).ok_or(CodegenError::OpcodeError {
+                opcode: "ldc",
+                details: "missing float",
+            })?,
+
+            LdcValue::Class(ref class_name) => {
+                *cp.get_class(class_name).ok_or(CodegenError::OpcodeError {
+                    opcode: "ldc",
+                    details: "missing class",
+                })?
+            }
+
+            LdcValue::MethodType(ref method_descriptor) => *cp
+                .get_method_type(&method_descriptor.to_string())
+                .ok_or(CodegenError::OpcodeError {
+                    opcode: "ldc",
+                    details: "missing method type",
+                })?,
+
+            LdcValue::MethodHandle(ref method_handle) => *cp
+                .get_method_handle(method_handle)
+                .ok_or(CodegenError::OpcodeError {
+                    opcode: "ldc",
+                    details: "missing method handle",
+                })?,
+
+            LdcValue::Dynamic(ref dynamic_constant) => *self
+                .get_dynamic_constant_index(dynamic_constant, cp)
+                .ok_or(CodegenError::OpcodeError {
+                    opcode: "ldc",
+                    details: "missing dynamic constant",
+                })?,
+        })
+    }
+
+    /// Retrieve the Constant Pool index, if present, of a dynamically-computed constant, resolving
+    /// its bootstrap method by name.
+    fn get_dynamic_constant_index<'p>(
+        &self,
+        dynamic_constant: &PhoronDynamicConstant,
+        cp: &'p PhoronConstantPool,
+    ) -> Option<&'p u16> {
+        self.bootstrap_methods
+            .get(&dynamic_constant.bootstrap_method)
+            .and_then(|bootstrap_method_attr_index| {
+                cp.get_dynamic(
+                    *bootstrap_method_attr_index,
+                    &dynamic_constant.name,
+                    &dynamic_constant.field_descriptor.to_string(),
+                )
+            })
+    }
+
+    /// Retrieve the Constant Pool index of the static argument to a bootstrap method.
+    fn gen_bootstrap_argument_index(
+        &self,
+        argument: &BootstrapArgument,
+        cp: &PhoronConstantPool,
+    ) -> CodegenResult<u16> {
+        let argument_index = match argument {
+            BootstrapArgument::Integer(int) => cp.get_integer(*int),
+            BootstrapArgument::Float(float) => cp.get_float(*float),
+            BootstrapArgument::Long(long) => cp.get_long(*long),
+            BootstrapArgument::Double(double) => cp.get_double(*double),
+            BootstrapArgument::QuotedString(ref string) => cp.get_string(string),
+            BootstrapArgument::Class(ref class_name) => cp.get_class(class_name),
+            BootstrapArgument::MethodType(ref method_descriptor) => {
+                cp.get_method_type(&method_descriptor.to_string())
+            }
+            BootstrapArgument::MethodHandle(ref method_handle) => {
+                cp.get_method_handle(method_handle)
+            }
+            BootstrapArgument::Dynamic(ref dynamic_constant) => {
+                self.get_dynamic_constant_index(dynamic_constant, cp)
+            }
+        };
+
+        Ok(*argument_index.ok_or(CodegenError::AttributeError {
+            attr: "BootstrapMethods",
+            details: "missing bootstrap argument",
+        })?)
+    }
+
+    fn gen_verification_type_info(
+        &self,
+        vtype: &VerificationType,
+        instruction_offsets: &[u16],
+        cp: &PhoronConstantPool,
+    ) -> CodegenResult<VerificationTypeInfo> {
+        Ok(match vtype {
+            // return addresses only arise in subroutines, for which no frames are generated
+            VerificationType::Top | VerificationType::ReturnAddress(..) => {
+                VerificationTypeInfo::TopVariableInfo { tag: ITEM_TOP }
+            }
+            VerificationType::Integer => {
+                VerificationTypeInfo::IntegerVariableInfo { tag: ITEM_INTEGER }
+            }
+            VerificationType::Float => VerificationTypeInfo::FloatVariableInfo { tag: ITEM_FLOAT },
+            VerificationType::Double => {
+                VerificationTypeInfo::DoubleVariableInfo { tag: ITEM_DOUBLE }
+            }
+            VerificationType::Long => VerificationTypeInfo::LongVariableInfo { tag: ITEM_LONG },
+            VerificationType::Null => VerificationTypeInfo::NullVariableInfo { tag: ITEM_NULL },
+            VerificationType::UninitializedThis => {
+                VerificationTypeInfo::UninitializedThisVariableInfo {
+                    tag: ITEM_UNINITIALIZED_THIS,
+                }
+            }
+            VerificationType::Object(ref class_name) => VerificationTypeInfo::ObjectVariableInfo {
+                tag: ITEM_OBJECT,
+                cpool_index: *cp
+                    .get_class(class_name)
+                    .ok_or(CodegenError::AttributeError {
+                        attr: "StackMapTable",
+                        details: "missing class reference for verification type",
+                    })?,
+            },
+            VerificationType::Uninitialized(index) => {
+                VerificationTypeInfo::UninitializedVariableInfo {
+                    tag: ITEM_UNINITIALIZED,
+                    offset: instruction_offsets[*index],
+                }
+            }
+        })
+    }
+
+    fn gen_verification_type_infos(
+        &self,
+        vtypes: &[VerificationType],
+        instruction_offsets: &[u16],
+        cp: &PhoronConstantPool,
+    ) -> CodegenResult<(Vec<VerificationTypeInfo>, u32)> {
+        let mut length = 0;
+        let mut infos = Vec::new();
+
+        for vtype in vtypes {
+            length += match vtype {
+                VerificationType::Object(..) | VerificationType::Uninitialized(..) => 3,
+                _ => 1,
+            };
+            infos.push(self.gen_verification_type_info(vtype, instruction_offsets, cp)?);
+        }
+
+        Ok((infos, length))
+    }
+
+    /// Generate the most compact stack map frame describing `frame`, given the locals of the
+    /// previous frame. Return the frame along with its length in bytes.
+    fn gen_stack_map_frame(
+        &self,
+        offset_delta: u16,
+        prev_locals: &[VerificationType],
+        frame: &Frame,
+        instruction_offsets: &[u16],
+        cp: &PhoronConstantPool,
+    ) -> CodegenResult<(StackMapFrame, u32)> {
+        let locals = frame.frame_locals();
+        let stack = frame.frame_stack();
+
+        let (stack_infos, stack_length) =
+            self.gen_verification_type_infos(&stack, instruction_offsets, cp)?;
+
+        Ok(if locals == prev_locals && stack.is_empty() {
+            if offset_delta < 64 {
+                (
+                    StackMapFrame::SameFrame {
+                        frame_type: SAME_FRAME + offset_delta as u8,
+                    },
+                    1,
+                )
+            } else {
+                (
+                    StackMapFrame::SameFrameExtended {
+                        frame_type: SAME_FRAME_EXTENDED,
+                        offset_delta,
+                    },
+                    3,
+                )
+            }
+        } else if locals == prev_locals && stack.len() == 1 {
+            if offset_delta < 64 {
+                (
+                    StackMapFrame::SameLocals1StackItemFrame {
+                        frame_type: SAME_LOCALS_1_STACK_ITEM_FRAME + offset_delta as u8,
+                        stack: stack_infos,
+                    },
+                    1 + stack_length,
+                )
+            } else {
+                (
+                    StackMapFrame::SameLocals1StackItemFrameExtended {
+                        frame_type: SAME_LOCALS_1_STACK_ITEM_FRAME_EXTENDED,
+                        offset_delta,
+                        stack: stack_infos,
+                    },
+                    3 + stack_length,
+                )
+            }
+        } else if stack.is_empty()
+            && locals.len() < prev_locals.len()
+            && prev_locals.len() - locals.len() <= 3
+            && prev_locals.starts_with(&locals)
+        {
+            (
+                StackMapFrame::ChopFrame {
+                    frame_type: SAME_FRAME_EXTENDED - (prev_locals.len() - locals.len()) as u8,
+                    offset_delta,
+                },
+                3,
+            )
+        } else if stack.is_empty()
+            && locals.len() > prev_locals.len()
+            && locals.len() - prev_locals.len() <= 3
+            && locals.starts_with(prev_locals)
+        {
+            let (locals_infos, locals_length) = self.gen_verification_type_infos(
+                &locals[prev_locals.len()..],
+                instruction_offsets,
+                cp,
+            )?;
+
+            (
+                StackMapFrame::AppendFrame {
+                    frame_type: SAME_FRAME_EXTENDED + (locals.len() - prev_locals.len()) as u8,
+                    offset_delta,
+                    locals: locals_infos,
+                },
+                3 + locals_length,
+            )
+        } else {
+            let (locals_infos, locals_length) =
+                self.gen_verification_type_infos(&locals, instruction_offsets, cp)?;
+
+            (
+                StackMapFrame::FullFrame {
+                    frame_type: FULL_FRAME,
+                    offset_delta,
+                    number_of_locals: locals.len() as u16,
+                    locals: locals_infos,
+                    number_of_stack_items: stack.len() as u16,
+                    stack: stack_infos,
+                },
+                7 + locals_length + stack_length,
+            )
+        })
+    }
+
+    /// Determine the `max_stack` and `max_locals` of the method's `Code` attribute. Limits without a
+    /// `.limit` directive are computed from the code of the method, and explicit limits which are
+    /// smaller than the computed ones are reported as warnings. If the code cannot be analyzed, that
+    /// is reported as a warning as well, and the missing limits default to `DEFAULT_CODE_LIMIT`.
+    fn gen_code_limits(
+        &mut self,
+        method_def: &PhoronMethodDef,
+        limit_stack: Option<u16>,
+        limit_locals: Option<u16>,
+    ) -> CodegenResult<(u16, u16)> {
+        let limits = match stack_map::compute_limits(
+            method_def,
+            &self.class_name,
+            &self.super_class_name,
+            self.class_hierarchy.as_ref(),
+        ) {
+            Ok(limits) => limits,
+
+            // the limits are only being checked, so leave it to the verifier to reject the code
+            Err(..) if limit_stack.is_some() && limit_locals.is_some() => {
+                return Ok((limit_stack.unwrap(), limit_locals.unwrap()))
+            }
+
+            Err(err) => {
+                self.warnings.push(CodegenWarning::LimitsNotComputed {
+                    method: method_def.name.to_owned(),
+                    details: err.to_string(),
+                });
+
+                return Ok((
+                    limit_stack.unwrap_or(DEFAULT_CODE_LIMIT),
+                    limit_locals.unwrap_or(DEFAULT_CODE_LIMIT),
+                ));
+            }
+        };
+
+        for (limit, declared, computed) in [
+            ("stack", limit_stack, limits.max_stack),
+            ("locals", limit_locals, limits.max_locals),
+        ] {
+            if let Some(declared) = declared {
+                if declared < computed {
+                    self.warnings.push(CodegenWarning::LimitTooSmall {
+                        method: method_def.name.to_owned(),
+                        limit,
+                        declared,
+                        computed,
+                    });
+                }
+            }
+        }
+
+        Ok((
+            limit_stack.unwrap_or(limits.max_stack),
+            limit_locals.unwrap_or(limits.max_locals),
+        ))
+    }
+
+    /// Generate the `Signature` attribute for the generic signature of a class, field, or method.
+    fn gen_signature_attribute(
+        &self,
+        signature: &str,
+        cp: &PhoronConstantPool,
+    ) -> CodegenResult<AttributeInfo> {
+        let attribute_name_index =
+            *cp.get_name(PHORON_SIGNATURE)
+                .ok_or(CodegenError::AttributeError {
+                    attr: "Signature",
+                    details: "missing attribute name index for `Signature` attribute",
+                })?;
+
+        let signature_index = *cp.get_name(signature).ok_or(CodegenError::AttributeError {
+            attr: "Signature",
+            details: "missing signature index for `Signature` attribute",
+        })?;
+
+        Ok(AttributeInfo::Signature {
+            attribute_name_index,
+            attribute_length: 2, // excluding the initial 6 bytes, as per the spec
+            signature_index,
+        })
+    }
+
+    fn gen_deprecated_attribute(&self, cp: &PhoronConstantPool) -> CodegenResult<AttributeInfo> {
+        let attribute_name_index =
+            *cp.get_name(PHORON_DEPRECATED)
+                .ok_or(CodegenError::AttributeError {
+                    attr: "Deprecated",
+                    details: "missing attribute name index for `Deprecated` attribute",
+                })?;
+
+        Ok(AttributeInfo::Deprecated {
+            attribute_name_index,
+            attribute_length: 0, // excluding the initial 6 bytes, as per the spec
+        })
+    }
+
+    fn gen_synthetic_attribute(&self, cp: &PhoronConstantPool) -> CodegenResult<AttributeInfo> {
+        let attribute_name_index =
+            *cp.get_name(PHORON_SYNTHETIC)
+                .ok_or(CodegenError::AttributeError {
+                    attr: "Synthetic",
+                    details: "missing attribute name index for `Synthetic` attribute",
+                })?;
+
+        Ok(AttributeInfo::Synthetic {
+            attribute_name_index,
+            attribute_length: 0, // excluding the initial 6 bytes, as per the spec
+        })
+    }
+
+    /// Add the annotation to the `RuntimeVisibleAnnotations` or `RuntimeInvisibleAnnotations`
+    /// attribute in `attributes`, depending on its visibility, creating the attribute if it is not
+    /// there yet. Returns whether the attribute was created.
+    fn gen_annotation_def(
+        attributes: &mut Vec<AttributeInfo>,
+        annotation_def: &PhoronAnnotationDef,
+        cp: &PhoronConstantPool,
+    ) -> CodegenResult<bool> {
+        let visible = annotation_def.visibility == PhoronAnnotationVisibility::Visible;
+        let attr = if visible {
+            PHORON_RUNTIME_VISIBLE_ANNOTATIONS
+        } else {
+            PHORON_RUNTIME_INVISIBLE_ANNOTATIONS
+        };
+
+        let (annotation, annotation_length) =
+            Self::gen_annotation(&annotation_def.annotation, attr, cp)?;
+
+        let (annotations_index, created) = match attributes.iter().position(|attribute| {
+            if visible {
+                matches!(attribute, AttributeInfo::RuntimeVisibleAnnotations { .. })
+            } else {
+                matches!(attribute, AttributeInfo::RuntimeInvisibleAnnotations { .. })
+            }
+        }) {
+            Some(annotations_index) => (annotations_index, false),
+            None => {
+                let attribute_name_index =
+                    *cp.get_name(attr).ok_or(CodegenError::AttributeError {
+                        attr,
+                        details: "missing attribute name index for annotations attribute",
+                    })?;
+
+                let attribute_length = 2; // excluding the initial 6 bytes, as per the spec
+                let num_annotations = 0;
+                let annotations = Vec::new();
+
+                attributes.push(if visible {
+                    AttributeInfo::RuntimeVisibleAnnotations {
+                        attribute_name_index,
+                        attribute_length,
+                        num_annotations,
+                        annotations,
+                    }
+                } else {
+                    AttributeInfo::RuntimeInvisibleAnnotations {
+                        attribute_name_index,
+                        attribute_length,
+                        num_annotations,
+                        annotations,
+                    }
+                });
+
+                (attributes.len() - 1, true)
+            }
+        };
+
+        if let AttributeInfo::RuntimeVisibleAnnotations {
+            ref mut attribute_length,
+            ref mut num_annotations,
+            ref mut annotations,
+            ..
+        }
+        | AttributeInfo::RuntimeInvisibleAnnotations {
+            ref mut attribute_length,
+            ref mut num_annotations,
+            ref mut annotations,
+            ..
+        } = attributes[annotations_index]
+        {
+            *attribute_length += annotation_length;
+            *num_annotations += 1;
+            annotations.push(annotation);
+        }
+
+        Ok(created)
+    }
+
+    /// Add the annotation for the parameter at `parameter_index` to the
+    /// `RuntimeVisibleParameterAnnotations` or `RuntimeInvisibleParameterAnnotations` attribute in
+    /// `attributes`, depending on its visibility, creating the attribute (with an entry for each
+    /// of the `num_parameters` parameters) if it is not there yet. Returns whether the attribute
+    /// was created.
+    fn gen_parameter_annotation_def(
+        attributes: &mut Vec<AttributeInfo>,
+        num_parameters: u8,
+        parameter_index: u8,
+        annotation_def: &PhoronAnnotationDef,
+        cp: &PhoronConstantPool,
+    ) -> CodegenResult<bool> {
+        let visible = annotation_def.visibility == PhoronAnnotationVisibility::Visible;
+        let attr = if visible {
+            PHORON_RUNTIME_VISIBLE_PARAMETER_ANNOTATIONS
+        } else {
+            PHORON_RUNTIME_INVISIBLE_PARAMETER_ANNOTATIONS
+        };
+
+        if parameter_index >= num_parameters {
+            return Err(CodegenError::AttributeError {
+                attr,
+                details: "invalid parameter index for parameter annotation",
+            });
+        }
+
+        let (annotation, annotation_length) =
+            Self::gen_annotation(&annotation_def.annotation, attr, cp)?;
+
+        let (annotations_index, created) = match attributes.iter().position(|attribute| {
+            if visible {
+                matches!(
+                    attribute,
+                    AttributeInfo::RuntimeVisibleParameterAnnotations { .. }
+                )
+            } else {
+                matches!(
+                    attribute,
+                    AttributeInfo::RuntimeInvisibleParameterAnnotations { .. }
+                )
+            }
+        }) {
+            Some(annotations_index) => (annotations_index, false),
+            None => {
+                let attribute_name_index =
+                    *cp.get_name(attr).ok_or(CodegenError::AttributeError {
+                        attr,
+                        details: "missing attribute name index for parameter annotations attribute",
+                    })?;
+
+                // num_parameters, and num_annotations for each parameter
+                let attribute_length = 1 + 2 * num_parameters as u32;
+                let parameter_annotations = (0..num_parameters)
+                    .map(|_| ParameterAnnotation::default())
+                    .collect();
+
+                attributes.push(if visible {
+                    AttributeInfo::RuntimeVisibleParameterAnnotations {
+                        attribute_name_index,
+                        attribute_length,
+                        num_parameters,
+                        parameter_annotations,
+                    }
+                } else {
+                    AttributeInfo::RuntimeInvisibleParameterAnnotations {
+                        attribute_name_index,
+                        attribute_length,
+                        num_parameters,
+                        parameter_annotations,
+                    }
+                });
+
+                (attributes.len() - 1, true)
+            }
+        };
+
+        if let AttributeInfo::RuntimeVisibleParameterAnnotations {
+            ref mut attribute_length,
+            ref mut parameter_annotations,
+            ..
+        }
+        | AttributeInfo::RuntimeInvisibleParameterAnnotations {
+            ref mut attribute_length,
+            ref mut parameter_annotations,
+            ..
+        } = attributes[annotations_index]
+        {
+            let parameter_annotation = &mut parameter_annotations[parameter_index as usize];
+
+            *attribute_length += annotation_length;
+            parameter_annotation.num_annotations += 1;
+            parameter_annotation.annotations.push(annotation);
+        }
+
+        Ok(created)
+    }
+
+    /// Add the type annotation to the `RuntimeVisibleTypeAnnotations` or
+    /// `RuntimeInvisibleTypeAnnotations` attribute in `attributes`, depending on its visibility,
+    /// creating the attribute if it is not there yet. Labels in the target are resolved using
+    /// `label_mapping`, and `method` names the method whose code they are in, if any. Returns
+    /// whether the attribute was created.
+    fn gen_type_annotation_def(
+        attributes: &mut Vec<AttributeInfo>,
+        type_annotation_def: &PhoronTypeAnnotationDef,
+        label_mapping: &HashMap<String, u32>,
+        method: Option<&str>,
+        cp: &PhoronConstantPool,
+    ) -> CodegenResult<bool> {
+        let annotation_def = &type_annotation_def.annotation_def;
+
+        let visible = annotation_def.visibility == PhoronAnnotationVisibility::Visible;
+        let attr = if visible {
+            PHORON_RUNTIME_VISIBLE_TYPE_ANNOTATIONS
+        } else {
+            PHORON_RUNTIME_INVISIBLE_TYPE_ANNOTATIONS
+        };
+
+        let (target_info, target_info_length) =
+            Self::gen_target_info(&type_annotation_def.target, label_mapping, method, attr)?;
+
+        let target_path = TypePath {
+            path_length: type_annotation_def.type_path.len() as u8,
+            path: type_annotation_def
+                .type_path
+                .iter()
+                .map(|entry| Path {
+                    type_path_kind: entry.type_path_kind(),
+                    type_argument_index: match *entry {
+                        PhoronTypePathEntry::TypeArgument(type_argument_index) => {
+                            type_argument_index
+                        }
+                        _ => 0,
+                    },
+                })
+                .collect(),
+        };
+
+        let (
+            Annotation {
+                type_index,
+                num_element_value_pairs,
+                element_value_pairs,
+            },
+            annotation_length,
+        ) = Self::gen_annotation(&annotation_def.annotation, attr, cp)?;
+
+        // target_type, target_info, type_path, and the annotation itself
+        let type_annotation_length =
+            1 + target_info_length + 1 + 2 * target_path.path_length as u32 + annotation_length;
+
+        let type_annotation = TypeAnnotation {
+            target_type: type_annotation_def.target.target_type(),
+            target_info,
+            target_path,
+            type_index,
+            num_element_value_pairs,
+            element_value_pairs,
+        };
+
+        let (annotations_index, created) = match attributes.iter().position(|attribute| {
+            if visible {
+                matches!(
+                    attribute,
+                    AttributeInfo::RuntimeVisibleTypeAnnotations { .. }
+                )
+            } else {
+                matches!(
+                    attribute,
+                    AttributeInfo::RuntimeInvisibleTypeAnnotations { .. }
+                )
+            }
+        }) {
+            Some(annotations_index) => (annotations_index, false),
+            None => {
+                let attribute_name_index =
+                    *cp.get_name(attr).ok_or(CodegenError::AttributeError {
+                        attr,
+                        details: "missing attribute name index for type annotations attribute",
+                    })?;
+
+                let attribute_length = 2; // excluding the initial 6 bytes, as per the spec
+                let num_annotations = 0;
+                let annotations = Vec::new();
+
+                attributes.push(if visible {
+                    AttributeInfo::RuntimeVisibleTypeAnnotations {
+                        attribute_name_index,
+                        attribute_length,
+                        num_annotations,
+                        annotations,
+                    }
+                } else {
+                    AttributeInfo::RuntimeInvisibleTypeAnnotations {
+                        attribute_name_index,
+                        attribute_length,
+                        num_annotations,
+                        annotations,
+                    }
+                });
+
+                (attributes.len() - 1, true)
+            }
+        };
+
+        if let AttributeInfo::RuntimeVisibleTypeAnnotations {
+            ref mut attribute_length,
+            ref mut num_annotations,
+            ref mut annotations,
+            ..
+        }
+        | AttributeInfo::RuntimeInvisibleTypeAnnotations {
+            ref mut attribute_length,
+            ref mut num_annotations,
+            ref mut annotations,
+            ..
+        } = attributes[annotations_index]
+        {
+            *attribute_length += type_annotation_length;
+            *num_annotations += 1;
+            annotations.push(type_annotation);
+        }
+
+        Ok(created)
+    }
+
+    /// Generate the `target_info` structure for the type annotation target, along with its length
+    /// in bytes.
+    fn gen_target_info(
+        target: &PhoronTypeAnnotationTarget,
+        label_mapping: &HashMap<String, u32>,
+        method: Option<&str>,
+        attr: &'static str,
+    ) -> CodegenResult<(TargetInfo, u32)> {
+        use PhoronTypeAnnotationTarget::*;
+
+        let get_offset = |label: &str| {
+            label_mapping.get(label).map(|offset| *offset as u16).ok_or(
+                CodegenError::AttributeError {
+                    attr,
+                    details: "invalid label for type annotation target",
+                },
+            )
+        };
+
+        Ok(match *target {
+            ClassTypeParameter {
+                type_parameter_index,
+            }
+            | MethodTypeParameter {
+                type_parameter_index,
+            } => (
+                TargetInfo::TypeParameterTarget {
+                    type_parameter_index,
+                },
+                1,
+            ),
+
+            Supertype { supertype_index } => (TargetInfo::SuperTypeTarget { supertype_index }, 2),
+
+            ClassTypeParameterBound {
+                type_parameter_index,
+                bound_index,
+            }
+            | MethodTypeParameterBound {
+                type_parameter_index,
+                bound_index,
+            } => (
+                TargetInfo::TypeParameterBoundTarget {
+                    type_parameter_index,
+                    bound_index,
+                },
+                2,
+            ),
+
+            Field | Return | Receiver => (TargetInfo::EmptyTarget, 0),
+
+            FormalParameter {
+                formal_parameter_index,
+            } => (
+                TargetInfo::FormalParameterTarget {
+                    formal_parameter_index,
+                },
+                1,
+            ),
+
+            Throws { throws_type_index } => (TargetInfo::ThrowsTarget { throws_type_index }, 2),
+
+            LocalVar { ref ranges } | ResourceVar { ref ranges } => {
+                let target = if matches!(*target, LocalVar { .. }) {
+                    "localvar"
+                } else {
+                    "resourcevar"
+                };
+
+                let table = ranges
+                    .iter()
+                    .map(|range| {
+                        let start_pc = get_offset(&range.from_label)?;
+                        let end_pc = get_offset(&range.to_label)?;
+
+                        let length = end_pc.checked_sub(start_pc).ok_or_else(|| {
+                            CodegenError::CodeRangeReversed {
+                                method: method.unwrap_or_default().to_owned(),
+                                directive: format!("`.annotation type {target} {}`", range.varnum),
+                                start_pc,
+                                end_pc,
+                            }
+                        })?;
+
+                        Ok(LocalVarEntry {
+                            start_pc,
+                            length,
+                            index: range.varnum,
+                        })
+                    })
+                    .collect::<CodegenResult<Vec<_>>>()?;
+
+                let table_length = table.len() as u16;
+                (
+                    TargetInfo::LocalVarTarget {
+                        table_length,
+                        table,
+                    },
+                    2 + 6 * table_length as u32,
+                )
+            }
+
+            Catch {
+                exception_table_index,
+            } => (
+                TargetInfo::CatchTarget {
+                    exception_table_index,
+                },
+                2,
+            ),
+
+            Instanceof { ref label }
+            | New { ref label }
+            | ConstructorReference { ref label }
+            | MethodReference { ref label } => (
+                TargetInfo::OffsetTarget {
+                    offset: get_offset(label)?,
+                },
+                2,
+            ),
+
+            Cast {
+                ref label,
+                type_argument_index,
+            }
+            | ConstructorInvocationTypeArgument {
+                ref label,
+                type_argument_index,
+            }
+            | MethodInvocationTypeArgument {
+                ref label,
+                type_argument_index,
+            }
+            | ConstructorReferenceTypeArgument {
+                ref label,
+                type_argument_index,
+            }
+            | MethodReferenceTypeArgument {
+                ref label,
+                type_argument_index,
+            } => (
+                TargetInfo::TypeArgumentTarget {
+                    offset: get_offset(label)?,
+                    type_argument_index,
+                },
+                3,
+            ),
+        })
+    }
+
+    /// Generate the `annotation` structure for the annotation, along with its length in bytes.
+    /// `attr` is the attribute that the annotation belongs to, for error reporting.
+    fn gen_annotation(
+        annotation: &PhoronAnnotation,
+        attr: &'static str,
+        cp: &PhoronConstantPool,
+    ) -> CodegenResult<(Annotation, u32)> {
+        let type_index = *cp.get_name(&annotation.annotation_type.to_string()).ok_or(
+            CodegenError::AttributeError {
+                attr,
+                details: "missing type index for annotation",
+            },
+        )?;
+
+        let mut annotation_length = 4; // type_index and num_element_value_pairs
+
+        let element_value_pairs = annotation
+            .element_value_pairs
+            .iter()
+            .map(|element_value_pair| {
+                let element_name_index =
+                    *cp.get_name(&element_value_pair.name)
+                        .ok_or(CodegenError::AttributeError {
+                            attr,
+                            details: "missing element name index for annotation",
+                        })?;
+
+                let (value, value_length) =
+                    Self::gen_element_value(&element_value_pair.value, attr, cp)?;
+                annotation_length += 2 + value_length;
+
+                Ok(ElementValuePair {
+                    element_name_index,
+                    value,
+                })
+            })
+            .collect::<CodegenResult<Vec<_>>>()?;
+
+        Ok((
+            Annotation {
+                type_index,
+                num_element_value_pairs: element_value_pairs.len() as u16,
+                element_value_pairs,
+            },
+            annotation_length,
+        ))
+    }
+
+    /// Generate the `element_value` structure for the value, along with its length in bytes.
+    fn gen_element_value(
+        value: &PhoronElementValue,
+        attr: &'static str,
+        cp: &PhoronConstantPool,
+    ) -> CodegenResult<(ElementValue, u32)> {
+        let tag = value.tag();
+
+        let get_name = |name: &str, details: &'static str| {
+            cp.get_name(name)
+                .copied()
+                .ok_or(CodegenError::AttributeError { attr, details })
+        };
+
+        let const_value = |const_value_index: Option<&u16>| {
+            Ok((
+                ElementValue::ConstValueIndex {
+                    tag,
+                    const_value_index: *const_value_index.ok_or(CodegenError::AttributeError {
+                        attr,
+                        details: "missing constant value index for annotation element",
+                    })?,
+                },
+                3,
+            ))
+        };
+
+        match value {
+            PhoronElementValue::Byte(byte) => const_value(cp.get_integer(*byte as i32)),
+            PhoronElementValue::Char(c) => const_value(cp.get_integer(*c as i32)),
+            PhoronElementValue::Double(double) => const_value(cp.get_double(*double)),
+            PhoronElementValue::Float(float) => const_value(cp.get_float(*float)),
+            PhoronElementValue::Integer(int) => const_value(cp.get_integer(*int)),
+            PhoronElementValue::Long(long) => const_value(cp.get_long(*long)),
+            PhoronElementValue::Short(short) => const_value(cp.get_integer(*short as i32)),
+            PhoronElementValue::Boolean(boolean) => const_value(cp.get_integer(*boolean as i32)),
+            PhoronElementValue::QuotedString(ref string) => const_value(cp.get_name(string)),
+
+            PhoronElementValue::Enum {
+                ref enum_type,
+                ref const_name,
+            } => Ok((
+                ElementValue::EnumConstValue {
+                    tag,
+                    type_name_index: get_name(
+                        &enum_type.to_string(),
+                        "missing enum type index for annotation element",
+                    )?,
+                    const_name_index: get_name(
+                        const_name,
+                        "missing enum constant name index for annotation element",
+                    )?,
+                },
+                5,
+            )),
+
+            PhoronElementValue::Class(ref return_descriptor) => Ok((
+                ElementValue::ClassInfoIndex {
+                    tag,
+                    class_info_index: get_name(
+                        &return_descriptor.to_string(),
+                        "missing class index for annotation element",
+                    )?,
+                },
+                3,
+            )),
+
+            PhoronElementValue::Annotation(ref annotation) => {
+                let (annotation, annotation_length) = Self::gen_annotation(annotation, attr, cp)?;
+
+                Ok((
+                    ElementValue::AnnotationValue { tag, annotation },
+                    1 + annotation_length,
+                ))
+            }
+
+            PhoronElementValue::Array(ref values) => {
+                let mut array_length = 3; // tag and num_values
+
+                let values = values
+                    .iter()
+                    .map(|value| {
+                        let (value, value_length) = Self::gen_element_value(value, attr, cp)?;
+                        array_length += value_length;
+                        Ok(value)
+                    })
+                    .collect::<CodegenResult<Vec<_>>>()?;
+
+                Ok((
+                    ElementValue::ArrayValue {
+                        tag,
+                        num_values: values.len() as u16,
+                        values,
+                    },
+                    array_length,
+                ))
+            }
+        }
+    }
+
+    /// Generate the `StackMapTable` attribute for the method, if it needs one. `instruction_offsets`
+    /// holds the bytecode offset of each JVM instruction of the method.
+    fn gen_stack_map_table(
+        &self,
+        method_def: &PhoronMethodDef,
+        instruction_offsets: &[u16],
+        cp: &PhoronConstantPool,
+    ) -> CodegenResult<Option<AttributeInfo>> {
+        // a widened conditional branch skips over its `goto_w` to the next instruction, which
+        // therefore needs a frame of its own
+        let far_conditional_targets = method_def
+            .instructions
+            .iter()
+            .filter_map(|instr| match instr {
+                PhoronInstruction::JvmInstruction(ref jvm_instr) => Some(jvm_instr),
+                _ => None,
+            })
+            .enumerate()
+            .filter(|(index, jvm_instr)| {
+                !matches!(
+                    jvm_instr,
+                    JvmInstruction::Goto { .. } | JvmInstruction::Jsr { .. }
+                ) && jvm_instr.short_branch_target().is_some()
+                    && self
+                        .far_branches
+                        .contains(&(instruction_offsets[*index] as u32))
+            })
+            .map(|(index, _)| index + 1)
+            .collect::<Vec<_>>();
+
+        let method_frames = match stack_map::compute_frames(
+            method_def,
+            &self.class_name,
+            &self.super_class_name,
+            self.class_hierarchy.as_ref(),
+            &far_conditional_targets,
+        ) {
+            Ok(method_frames) => method_frames,
+
+            // subroutines are only forbidden from version 51.0 onwards, and the JVM falls back to
+            // the type inferencing verifier for version 50.0 class files without stack map frames
+            Err(StackMapError::Subroutine { .. })
+                if self.classfile.major_version == stack_map::STACK_MAP_MAJOR_VERSION =>
+            {
+                return Ok(None)
+            }
+
+            Err(err) => return Err(err.into()),
+        };
+
+        if method_frames.frames.is_empty() {
+            return Ok(None);
+        }
+
+        let attribute_name_index =
+            *cp.get_name(PHORON_STACK_MAP_TABLE)
+                .ok_or(CodegenError::AttributeError {
+                    attr: "StackMapTable",
+                    details: "missing attribute name index for `StackMapTable` attribute",
+                })?;
+
+        let mut attribute_length = 2; // for number_of_entries
+        let mut entries = Vec::new();
+
+        let mut prev_locals = method_frames.initial_frame.frame_locals();
+        let mut prev_offset = None;
+
+        for (index, frame) in &method_frames.frames {
+            let offset = instruction_offsets[*index];
+
+            // the offset of the first frame is absolute, but subsequent ones are relative to the
+            // previous frame (less one)
+            let offset_delta = match prev_offset {
+                None => offset,
+                Some(prev_offset) => offset - prev_offset - 1,
+            };
+
+            let (entry, entry_length) = self.gen_stack_map_frame(
+                offset_delta,
+                &prev_locals,
+                frame,
+                instruction_offsets,
+                cp,
+            )?;
+
+            entries.push(entry);
+            attribute_length += entry_length;
+
+            prev_locals = frame.frame_locals();
+            prev_offset = Some(offset);
+        }
+
+        Ok(Some(AttributeInfo::StackMapTable {
+            attribute_name_index,
+            attribute_length,
+            number_of_entries: entries.len() as u16,
+            entries,
+        }))
+    }
+
+    /// Generate a `record_component_info` structure for the component, along with its attributes.
+    fn gen_record_component(
+        &self,
+        component: &PhoronRecordComponentDef,
+        cp: &PhoronConstantPool,
+    ) -> CodegenResult<RecordComponentInfo> {
+        let mut component_info = RecordComponentInfo {
+            name_index: *cp
+                .get_name(&component.name)
+                .ok_or(CodegenError::AttributeError {
+                    attr: "Record",
+                    details: "missing name index for record component",
+                })?,
+            descriptor_index: *cp.get_name(&component.field_descriptor.to_string()).ok_or(
+                CodegenError::AttributeError {
+                    attr: "Record",
+                    details: "missing descriptor index for record component",
+                },
+            )?,
+            attributes_count: 0,
+            attributes: Vec::new(),
+        };
+
+        if let Some(ref signature) = component.signature {
+            component_info.attributes_count += 1;
+            component_info
+                .attributes
+                .push(self.gen_signature_attribute(signature, cp)?);
+        }
+
+        for annotation_def in &component.annotations {
+            if Self::gen_annotation_def(&mut component_info.attributes, annotation_def, cp)? {
+                component_info.attributes_count += 1;
+            }
+        }
 
-                    constant_pool[cp_idx] = Some(CpInfo::ConstantDoubleInfo {
-                        tag: CONSTANT_DOUBLE,
-                        high_bytes: u32::from_be_bytes(high_bytes),
-                        low_bytes: u32::from_be_bytes(low_bytes),
-                    })
-                }
+        for type_annotation_def in &component.type_annotations {
+            if Self::gen_type_annotation_def(
+                &mut component_info.attributes,
+                type_annotation_def,
+                &self.label_mapping,
+                None,
+                cp,
+            )? {
+                component_info.attributes_count += 1;
+            }
+        }
 
-                PhoronConstantPoolKind::NameAndType {
-                    ref name_index,
-                    ref descriptor_index,
-                } => {
-                    constant_pool[cp_idx] = Some(CpInfo::ConstantNameAndTypeInfo {
-                        tag: CONSTANT_NAME_AND_TYPE,
-                        name_index: *name_index,
-                        descriptor_index: *descriptor_index,
-                    })
-                }
+        Ok(component_info)
+    }
 
-                PhoronConstantPoolKind::Utf8(ref s) => {
-                    constant_pool[cp_idx] = Some(CpInfo::ConstantUtf8Info {
-                        tag: CONSTANT_UTF8,
-                        length: s.len() as u16,
-                        bytes: s.bytes().collect::<_>(),
-                    })
-                }
+    /// The length in bytes of an attribute of a record component, including the initial 6 bytes.
+    fn record_component_attribute_length(attr: &AttributeInfo) -> u32 {
+        6 + match attr {
+            AttributeInfo::Signature {
+                attribute_length, ..
+            }
+            | AttributeInfo::RuntimeVisibleAnnotations {
+                attribute_length, ..
             }
+            | AttributeInfo::RuntimeInvisibleAnnotations {
+                attribute_length, ..
+            }
+            | AttributeInfo::RuntimeVisibleTypeAnnotations {
+                attribute_length, ..
+            }
+            | AttributeInfo::RuntimeInvisibleTypeAnnotations {
+                attribute_length, ..
+            } => *attribute_length,
+            _ => unreachable!("unexpected record component attribute"),
         }
+    }
 
-        self.classfile.constant_pool = constant_pool;
+    /// Generate the `MethodParameters` attribute for the method, if it has any `.parameter`
+    /// directives. There is an entry for every parameter in the method descriptor, and the
+    /// parameters without a `.parameter` directive have no name and no flags.
+    fn gen_method_parameters(
+        method_def: &PhoronMethodDef,
+        cp: &PhoronConstantPool,
+    ) -> CodegenResult<Option<AttributeInfo>> {
+        let parameters_count = method_def.method_descriptor.param_descriptor.len() as u8;
+        let mut parameters = (0..parameters_count)
+            .map(|_| Parameter::default())
+            .collect::<Vec<_>>();
+        let mut has_parameters = false;
+
+        for instr in &method_def.instructions {
+            if let PhoronInstruction::PhoronDirective(PhoronDirective::Parameter {
+                parameter_index,
+                ref name,
+                ref access_flags,
+            }) = instr
+            {
+                let parameter = parameters.get_mut(*parameter_index as usize).ok_or(
+                    CodegenError::AttributeError {
+                        attr: "MethodParameters",
+                        details: "invalid parameter index for method parameter",
+                    },
+                )?;
 
-        Ok(())
+                parameter.name_index = *cp.get_name(name).ok_or(CodegenError::AttributeError {
+                    attr: "MethodParameters",
+                    details: "missing name index for method parameter",
+                })?;
+                parameter.access_flags =
+                    access_flags.iter().fold(0u16, |acc, af| acc | af.to_u16());
+                has_parameters = true;
+            }
+        }
+
+        if !has_parameters {
+            return Ok(None);
+        }
+
+        Ok(Some(AttributeInfo::MethodParameters {
+            attribute_name_index: *cp.get_name(PHORON_METHOD_PARAMETERS).ok_or(
+                CodegenError::AttributeError {
+                    attr: "MethodParameters",
+                    details: "missing attribute name index for `MethodParameters` attribute",
+                },
+            )?,
+            // parameters_count, and the name and flags of each parameter
+            attribute_length: 1 + 4 * parameters_count as u32,
+            parameters_count,
+            parameters,
+        }))
     }
 
-    fn gen_label_mappings(&mut self, instructions: &[PhoronInstruction]) -> CodegenResult<()> {
-        use JvmInstruction::*;
+    /// Generate an entry in the `InnerClasses` attribute. The outer class and the simple name are
+    /// zero for the classes which do not have them.
+    fn gen_inner_class(
+        &self,
+        inner_class_def: &PhoronInnerClassDef,
+        cp: &PhoronConstantPool,
+    ) -> CodegenResult<Class> {
+        let inner_class_info_index = *cp.get_class(&inner_class_def.inner_class_name).ok_or(
+            CodegenError::AttributeError {
+                attr: "InnerClasses",
+                details: "missing class index for inner class",
+            },
+        )?;
+
+        let outer_class_info_index = match inner_class_def.outer_class_name {
+            Some(ref outer_class_name) => {
+                *cp.get_class(outer_class_name)
+                    .ok_or(CodegenError::AttributeError {
+                        attr: "InnerClasses",
+                        details: "missing class index for outer class",
+                    })?
+            }
+            None => 0,
+        };
 
-        self.label_mapping.clear();
-        let mut curr_code_offset = 0i16;
+        let inner_name_index = match inner_class_def.inner_name {
+            Some(ref inner_name) => {
+                *cp.get_name(inner_name)
+                    .ok_or(CodegenError::AttributeError {
+                        attr: "InnerClasses",
+                        details: "missing name index for inner class name",
+                    })?
+            }
+            None => 0,
+        };
 
-        for instr in instructions {
-            match instr {
-                PhoronInstruction::PhoronDirective(ref _directive) => {}
+        Ok(Class {
+            inner_class_info_index,
+            outer_class_info_index,
+            inner_name_index,
+            inner_class_access_flags: inner_class_def
+                .access_flags
+                .iter()
+                .fold(0u16, |acc, af| acc | af.to_u16()),
+        })
+    }
 
-                PhoronInstruction::PhoronLabel(ref label) => {
-                    self.label_mapping
-                        .insert(label.to_string(), curr_code_offset);
-                }
+    /// Generate the `Module` attribute for a module declaration, as well as the `ModulePackages`
+    /// and `ModuleMainClass` attributes if the module has packages or a main class.
+    fn gen_module_attributes(
+        &mut self,
+        module_def: &PhoronModuleDef,
+        cp: &PhoronConstantPool,
+    ) -> CodegenResult<()> {
+        let module_index = |module_name: &str| {
+            cp.get_module(module_name)
+                .copied()
+                .ok_or(CodegenError::AttributeError {
+                    attr: "Module",
+                    details: "missing module index",
+                })
+        };
 
-                PhoronInstruction::JvmInstruction(ref jvm_instr) => {
-                    curr_code_offset += match jvm_instr {
-                        // 1-byte instructions
-                        Aaload | Aastore | Aconstnull | Aload0 | Aload1 | Aload2 | Aload3
-                        | Areturn | Arraylength | Astore0 | Astore1 | Astore2 | Astore3
-                        | Athrow | Baload | Bastore | Caload | Castore | D2f | D2i | D2l | Dadd
-                        | Daload | Dastore | Dcmpg | Dcmpl | Dconst0 | Dconst1 | Ddiv | Dload0
-                        | Dload1 | Dload2 | Dload3 | Dmul | Dneg | Drem | Dreturn | Dstore0
-                        | Dstore1 | Dstore2 | Dstore3 | Dsub | Dup | Dupx1 | Dupx2 | Dup2
-                        | Dup2x1 | Dup2x2 | F2d | F2i | F2l | Fadd | Faload | Fastore | Fcmpg
-                        | Fcmpl | Fconst0 | Fconst1 | Fconst2 | Fdiv | Fload0 | Fload1 | Fload2
-                        | Fload3 | Fmul | Fneg | Frem | Freturn | Fstore0 | Fstore1 | Fstore2
-                        | Fstore3 | Fsub | I2b | I2c | I2d | I2f | I2l | I2s | Iadd | Iaload
-                        | Iand | Iastore | Iconstm1 | Iconst0 | Iconst1 | Iconst2 | Iconst3
-                        | Iconst4 | Iconst5 | Idiv | Iload0 | Iload1 | Iload2 | Iload3 | Imul
-                        | Ineg | Ior | Irem | Ireturn | Ishl | Ishr | Istore0 | Istore1
-                        | Istore2 | Istore3 | Isub | Iushr | Ixor | L2d | L2f | L2i | Ladd
-                        | Laload | Land | Lastore | Lcmp | Lconst0 | Lconst1 | Ldiv | Lload0
-                        | Lload1 | Lload2 | Lload3 | Lmul | Lneg | Lor | Lrem | Lreturn | Lshl
-                        | Lshr | Lstore0 | Lstore1 | Lstore2 | Lstore3 | Lsub | Lushr | Lxor
-                        | Monitorenter | Monitorexit | Nop | Pop | Pop2 | Return | Saload
-                        | Sastore | Swap => 1,
-
-                        Bipush(..)
-                        | Newarray { .. }
-                        | Iload { .. }
-                        | Fload { .. }
-                        | Aload { .. }
-                        | Lload { .. }
-                        | Dload { .. }
-                        | Istore { .. }
-                        | Fstore { .. }
-                        | Astore { .. }
-                        | Lstore { .. }
-                        | Dstore { .. }
-                        | Ldc(..)
-                        | Ret { .. } => 2,
-
-                        Sipush(..)
-                        | Anewarray { .. }
-                        | Checkcast { .. }
-                        | Getstatic { .. }
-                        | Getfield { .. }
-                        | Iinc { .. }
-                        | Invokespecial { .. }
-                        | Invokestatic { .. }
-                        | Invokevirtual { .. }
-                        | Putfield { .. }
-                        | Putstatic { .. }
-                        | Goto { .. }
-                        | Ifacmpeq { .. }
-                        | Ifacmpne { .. }
-                        | Ificmpeq { .. }
-                        | Ificmpge { .. }
-                        | Ificmpgt { .. }
-                        | Ificmple { .. }
-                        | Ificmplt { .. }
-                        | Ificmpne { .. }
-                        | Ifeq { .. }
-                        | Ifge { .. }
-                        | Ifgt { .. }
-                        | Ifle { .. }
-                        | Iflt { .. }
-                        | Ifne { .. }
-                        | Ifnonnull { .. }
-                        | Ifnull { .. }
-                        | Instanceof { .. }
-                        | Ldcw(..)
-                        | Ldc2w(..)
-                        | New { .. }
-                        | Jsr { .. } => 3,
-
-                        Invokeinterface { .. } | Multianewarray { .. } => 4,
-
-                        Jsrw { .. } | Gotow { .. } => 5,
-
-                        Lookupswitch { ref switches, .. } => {
-                            let mut opcode_len = 1i16; // for the opcode
-
-                            let default_offset_padding = (4 - (curr_code_offset + 1) % 4) % 4;
-                            opcode_len += default_offset_padding;
-
-                            opcode_len += std::mem::size_of::<i32>() as i16; // for the number of switch pairs
-
-                            opcode_len +=
-                                switches.len() as i16 * 2i16 * std::mem::size_of::<u32>() as i16; // for the switch pairs
-
-                            opcode_len += std::mem::size_of::<u32>() as i16; // for the default case
-                            opcode_len
-                        }
+        let package_index = |package_name: &str| {
+            cp.get_package(package_name)
+                .copied()
+                .ok_or(CodegenError::AttributeError {
+                    attr: "Module",
+                    details: "missing package index",
+                })
+        };
 
-                        Tableswitch {
-                            ref low, ref high, ..
-                        } => {
-                            let mut opcode_len = 1i16; // for the opcode
+        let class_index = |class_name: &str| {
+            cp.get_class(class_name)
+                .copied()
+                .ok_or(CodegenError::AttributeError {
+                    attr: "Module",
+                    details: "missing class index",
+                })
+        };
+
+        // an absent version is represented by a zero index
+        let version_index = |version: &Option<String>| match version {
+            Some(ref version) => {
+                cp.get_name(version)
+                    .copied()
+                    .ok_or(CodegenError::AttributeError {
+                        attr: "Module",
+                        details: "missing name index for module version",
+                    })
+            }
+            None => Ok(0),
+        };
 
-                            let default_offset_padding = (4 - (curr_code_offset + 1) % 4) % 4;
-                            opcode_len += default_offset_padding;
+        // `exports` and `opens` share the same layout
+        let gen_exports = |exports_def: &PhoronExportsDef| -> CodegenResult<(u16, u16, Vec<u16>)> {
+            Ok((
+                package_index(&exports_def.package_name)?,
+                exports_def
+                    .access_flags
+                    .iter()
+                    .fold(0u16, |acc, af| acc | af.to_u16()),
+                exports_def
+                    .to_modules
+                    .iter()
+                    .map(|module_name| module_index(module_name))
+                    .collect::<CodegenResult<Vec<_>>>()?,
+            ))
+        };
 
-                            // for `low` and `high`
-                            opcode_len += 2i16 * std::mem::size_of::<i32>() as i16;
-                            // for the switch offsets
-                            opcode_len +=
-                                (high - low + 1) as i16 * std::mem::size_of::<i32>() as i16;
+        let requires = module_def
+            .requires
+            .iter()
+            .map(|requires_def| {
+                Ok(Require {
+                    requires_index: module_index(&requires_def.module_name)?,
+                    requires_flags: requires_def
+                        .access_flags
+                        .iter()
+                        .fold(0u16, |acc, af| acc | af.to_u16()),
+                    requires_version_index: version_index(&requires_def.version)?,
+                })
+            })
+            .collect::<CodegenResult<Vec<_>>>()?;
+
+        let exports = module_def
+            .exports
+            .iter()
+            .map(|exports_def| {
+                let (exports_index, exports_flags, exports_to_index) = gen_exports(exports_def)?;
+
+                Ok(Export {
+                    exports_index,
+                    exports_flags,
+                    exports_to_count: exports_to_index.len() as u16,
+                    exports_to_index,
+                })
+            })
+            .collect::<CodegenResult<Vec<_>>>()?;
+
+        let opens = module_def
+            .opens
+            .iter()
+            .map(|opens_def| {
+                let (opens_index, opens_flags, opens_to_index) = gen_exports(opens_def)?;
+
+                Ok(Open {
+                    opens_index,
+                    opens_flags,
+                    opens_to_count: opens_to_index.len() as u16,
+                    opens_to_index,
+                })
+            })
+            .collect::<CodegenResult<Vec<_>>>()?;
+
+        let uses_index = module_def
+            .uses
+            .iter()
+            .map(|service_name| class_index(service_name))
+            .collect::<CodegenResult<Vec<_>>>()?;
+
+        let provides = module_def
+            .provides
+            .iter()
+            .map(|provides_def| {
+                let provides_with_index = provides_def
+                    .with_classes
+                    .iter()
+                    .map(|class_name| class_index(class_name))
+                    .collect::<CodegenResult<Vec<_>>>()?;
+
+                Ok(Provide {
+                    provides_index: class_index(&provides_def.service_name)?,
+                    provides_with_count: provides_with_index.len() as u16,
+                    provides_with_index,
+                })
+            })
+            .collect::<CodegenResult<Vec<_>>>()?;
+
+        // the module name, flags, and version, followed by the five tables and their counts
+        let attribute_length = 6
+            + 2
+            + 6 * requires.len()
+            + 2
+            + exports
+                .iter()
+                .map(|export| 6 + 2 * export.exports_to_index.len())
+                .sum::<usize>()
+            + 2
+            + opens
+                .iter()
+                .map(|open| 6 + 2 * open.opens_to_index.len())
+                .sum::<usize>()
+            + 2
+            + 2 * uses_index.len()
+            + 2
+            + provides
+                .iter()
+                .map(|provide| 4 + 2 * provide.provides_with_index.len())
+                .sum::<usize>();
 
-                            // for the default pair
-                            opcode_len += 2i16 & std::mem::size_of::<i32>() as i16;
-                            opcode_len
-                        }
+        self.classfile.attributes_count += 1;
+        self.classfile.attributes.push(AttributeInfo::Module {
+            attribute_name_index: *cp.get_name(PHORON_MODULE).ok_or(
+                CodegenError::AttributeError {
+                    attr: "Module",
+                    details: "missing attribute name index for `Module` attribute",
+                },
+            )?,
+            attribute_length: attribute_length as u32,
+            module_name_index: module_index(&module_def.name)?,
+            module_flags: module_def
+                .access_flags
+                .iter()
+                .fold(0u16, |acc, af| acc | af.to_u16()),
+            module_version_index: version_index(&module_def.version)?,
+            requires_count: requires.len() as u16,
+            requires,
+            exports_count: exports.len() as u16,
+            exports,
+            opens_count: opens.len() as u16,
+            opens,
+            uses_count: uses_index.len() as u16,
+            uses_index,
+            provides_count: provides.len() as u16,
+            provides,
+        });
+
+        if !module_def.packages.is_empty() {
+            let package_index = module_def
+                .packages
+                .iter()
+                .map(|package_name| package_index(package_name))
+                .collect::<CodegenResult<Vec<_>>>()?;
+
+            self.classfile.attributes_count += 1;
+            self.classfile
+                .attributes
+                .push(AttributeInfo::ModulePackages {
+                    attribute_name_index: *cp.get_name(PHORON_MODULE_PACKAGES).ok_or(
+                        CodegenError::AttributeError {
+                            attr: "ModulePackages",
+                            details: "missing attribute name index for `ModulePackages` attribute",
+                        },
+                    )?,
+                    attribute_length: 2 + 2 * package_index.len() as u32,
+                    package_count: package_index.len() as u16,
+                    package_index,
+                });
+        }
 
-                        Wide(ref wide_instr) => match wide_instr {
-                            WideInstruction::IInc { .. } => 6,
-                            _ => 4,
+        if let Some(ref main_class) = module_def.main_class {
+            self.classfile.attributes_count += 1;
+            self.classfile
+                .attributes
+                .push(AttributeInfo::ModuleMainClass {
+                    attribute_name_index: *cp.get_name(PHORON_MODULE_MAIN_CLASS).ok_or(
+                        CodegenError::AttributeError {
+                            attr: "ModuleMainClass",
+                            details: "missing attribute name index for `ModuleMainClass` attribute",
                         },
-                    } as i16;
+                    )?,
+                    attribute_length: 2, // as per the spec
+                    main_class_index: class_index(main_class)?,
+                });
+        }
+
+        Ok(())
+    }
+
+    /// `phoron_core` has no variant for arbitrary attributes, but a `SourceDebugExtension` is laid
+    /// out as just the attribute name index, the attribute length, and the bytes of the attribute,
+    /// so it is used to emit raw attributes verbatim.
+    fn gen_raw_attribute(
+        attribute_def: &PhoronAttributeDef,
+        cp: &PhoronConstantPool,
+    ) -> CodegenResult<AttributeInfo> {
+        let attribute_name_index =
+            *cp.get_name(&attribute_def.name)
+                .ok_or(CodegenError::AttributeError {
+                    attr: "raw",
+                    details: "missing attribute name index for raw attribute",
+                })?;
+
+        let attribute_length =
+            u32::try_from(attribute_def.info.len()).map_err(|_| CodegenError::AttributeError {
+                attr: "raw",
+                details: "raw attribute exceeds the maximum attribute length",
+            })?;
+
+        Ok(AttributeInfo::SourceDebugExtension {
+            attribute_name_index,
+            attribute_length,
+            debug_extension: attribute_def.info.clone(),
+        })
+    }
+
+    /// Encode the string in the "modified UTF-8" format used by the class file - the null character
+    /// takes two bytes, and supplementary characters are encoded as surrogate pairs of three bytes
+    /// each.
+    fn gen_modified_utf8(s: &str) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(s.len());
+
+        for c in s.encode_utf16() {
+            match c {
+                0x0001..=0x007f => bytes.push(c as u8),
+                0x0000 | 0x0080..=0x07ff => {
+                    bytes.push(0xc0 | (c >> 6) as u8);
+                    bytes.push(0x80 | (c & 0x3f) as u8);
+                }
+                _ => {
+                    bytes.push(0xe0 | (c >> 12) as u8);
+                    bytes.push(0x80 | ((c >> 6) & 0x3f) as u8);
+                    bytes.push(0x80 | (c & 0x3f) as u8);
                 }
             }
         }
 
-        Ok(())
+        bytes
     }
 
     fn gen_class_or_interface_access_flags(
@@ -470,10 +2331,10 @@ where
         Ok(())
     }
 
-    fn gen_classfile_headers(&mut self) -> CodegenResult<()> {
+    fn gen_classfile_headers(&mut self, version_def: &PhoronVersionDef) -> CodegenResult<()> {
         self.classfile.magic = MAGIC;
-        self.classfile.minor_version = MINOR_VERSION;
-        self.classfile.major_version = MAJOR_VERSION;
+        self.classfile.minor_version = version_def.minor_version;
+        self.classfile.major_version = version_def.major_version;
 
         Ok(())
     }
@@ -483,7 +2344,7 @@ where
         program: &PhoronProgram,
         cp: &PhoronConstantPool,
     ) -> CodegenResult<()> {
-        self.gen_classfile_headers()?;
+        self.gen_classfile_headers(&program.header.version_def)?;
         self.gen_constant_pool(&cp)?;
         self.visit_program(&program, cp)?;
 
@@ -521,14 +2382,28 @@ where
             PhoronClassOrInterface::Interface(ref interface_def) => {
                 self.visit_interface_def(interface_def, cp)?
             }
+            PhoronClassOrInterface::Module(ref module_def) => {
+                self.visit_module_def(module_def, cp)?
+            }
         };
-        self.visit_super_def(&header.super_def, cp)?;
+
+        // `super_class` is zero for the `module-info` class
+        if !matches!(
+            header.class_or_interface_def,
+            PhoronClassOrInterface::Module(..)
+        ) {
+            self.visit_super_def(&header.super_def, cp)?;
+        }
 
         self.classfile.interfaces_count = header.implements_defs.len() as u16;
         for impl_def in &header.implements_defs {
             self.visit_implements_def(impl_def, cp)?;
         }
 
+        for class_directive in &header.class_directives {
+            self.visit_class_directive(class_directive, cp)?;
+        }
+
         Ok(CodegenResultType::Empty)
     }
 
@@ -560,6 +2435,7 @@ where
     }
 
     fn visit_class_def(&mut self, class_def: &PhoronClassDef, cp: Self::Input) -> Self::Result {
+        self.class_name = class_def.name.to_owned();
         self.gen_class_or_interface_access_flags(&class_def.access_flags)?;
 
         self.classfile.this_class =
@@ -575,6 +2451,7 @@ where
         class_def: &PhoronInterfaceDef,
         cp: Self::Input,
     ) -> Self::Result {
+        self.class_name = class_def.name.to_owned();
         self.gen_class_or_interface_access_flags(&class_def.access_flags)?;
 
         self.classfile.this_class =
@@ -585,7 +2462,23 @@ where
         Ok(CodegenResultType::Empty)
     }
 
+    fn visit_module_def(&mut self, module_def: &PhoronModuleDef, cp: Self::Input) -> Self::Result {
+        self.class_name = MODULE_INFO_CLASS_NAME.to_owned();
+        self.gen_class_or_interface_access_flags(&[PhoronClassOrInterfaceAccessFlag::AccModule])?;
+
+        self.classfile.this_class =
+            *cp.get_class(MODULE_INFO_CLASS_NAME)
+                .ok_or(CodegenError::Missing {
+                    component: "`this` class",
+                })?;
+
+        self.gen_module_attributes(module_def, cp)?;
+
+        Ok(CodegenResultType::Empty)
+    }
+
     fn visit_super_def(&mut self, super_def: &PhoronSuperDef, cp: Self::Input) -> Self::Result {
+        self.super_class_name = super_def.super_class_name.to_owned();
         self.classfile.super_class =
             *cp.get_class(&super_def.super_class_name)
                 .ok_or(CodegenError::Missing {
@@ -610,6 +2503,449 @@ where
         Ok(CodegenResultType::Empty)
     }
 
+    fn visit_class_directive(
+        &mut self,
+        class_directive: &PhoronClassDirective,
+        cp: Self::Input,
+    ) -> Self::Result {
+        match class_directive {
+            // all the bootstrap methods go into a single `BootstrapMethods` attribute, indexed
+            // in the order in which they are declared.
+            PhoronClassDirective::Bootstrap {
+                ref name,
+                ref method_handle,
+                ref arguments,
+            } => {
+                let bootstrap_methods_index = match self
+                    .classfile
+                    .attributes
+                    .iter()
+                    .position(|attr| matches!(attr, AttributeInfo::BootstrapMethods { .. }))
+                {
+                    Some(bootstrap_methods_index) => bootstrap_methods_index,
+                    None => {
+                        self.classfile.attributes_count += 1;
+
+                        let attribute_name_index =
+                            *cp.get_name(PHORON_BOOTSTRAP_METHODS)
+                                .ok_or(CodegenError::AttributeError {
+                                attr: "BootstrapMethods",
+                                details:
+                                    "missing attribute name index for `BootstrapMethods` attribute",
+                            })?;
+
+                        self.classfile
+                            .attributes
+                            .push(AttributeInfo::BootstrapMethods {
+                                attribute_name_index,
+                                attribute_length: 2, // excluding the initial 6 bytes, as per the spec
+                                num_bootstrap_methods: 0,
+                                bootstrap_methods: Vec::new(),
+                            });
+
+                        self.classfile.attributes.len() - 1
+                    }
+                };
+
+                let bootstrap_method_ref =
+                    *cp.get_method_handle(method_handle)
+                        .ok_or(CodegenError::AttributeError {
+                            attr: "BootstrapMethods",
+                            details: "missing method handle for bootstrap method",
+                        })?;
+
+                let bootstrap_arguments = arguments
+                    .iter()
+                    .map(|argument| self.gen_bootstrap_argument_index(argument, cp))
+                    .collect::<CodegenResult<Vec<_>>>()?;
+
+                if let AttributeInfo::BootstrapMethods {
+                    ref mut attribute_length,
+                    ref mut num_bootstrap_methods,
+                    ref mut bootstrap_methods,
+                    ..
+                } = self.classfile.attributes[bootstrap_methods_index]
+                {
+                    self.bootstrap_methods
+                        .insert(name.to_owned(), *num_bootstrap_methods);
+
+                    *attribute_length += 4 + 2 * bootstrap_arguments.len() as u32;
+                    *num_bootstrap_methods += 1;
+
+                    bootstrap_methods.push(BootstrapMethod {
+                        bootstrap_method_ref,
+                        num_bootstrap_arguments: bootstrap_arguments.len() as u16,
+                        bootstrap_arguments,
+                    });
+                }
+            }
+
+            PhoronClassDirective::Signature(ref signature) => {
+                self.classfile.attributes_count += 1;
+                self.classfile
+                    .attributes
+                    .push(self.gen_signature_attribute(signature, cp)?);
+            }
+
+            PhoronClassDirective::Annotation(ref annotation_def) => {
+                if Self::gen_annotation_def(&mut self.classfile.attributes, annotation_def, cp)? {
+                    self.classfile.attributes_count += 1;
+                }
+            }
+
+            PhoronClassDirective::TypeAnnotation(ref type_annotation_def) => {
+                if Self::gen_type_annotation_def(
+                    &mut self.classfile.attributes,
+                    type_annotation_def,
+                    &self.label_mapping,
+                    None,
+                    cp,
+                )? {
+                    self.classfile.attributes_count += 1;
+                }
+            }
+
+            // all the inner classes go into a single `InnerClasses` attribute.
+            PhoronClassDirective::InnerClass(ref inner_class_def) => {
+                let inner_classes_index = match self
+                    .classfile
+                    .attributes
+                    .iter()
+                    .position(|attr| matches!(attr, AttributeInfo::InnerClasses { .. }))
+                {
+                    Some(inner_classes_index) => inner_classes_index,
+                    None => {
+                        self.classfile.attributes_count += 1;
+
+                        let attribute_name_index = *cp.get_name(PHORON_INNER_CLASSES).ok_or(
+                            CodegenError::AttributeError {
+                                attr: "InnerClasses",
+                                details:
+                                    "missing attribute name index for `InnerClasses` attribute",
+                            },
+                        )?;
+
+                        self.classfile.attributes.push(AttributeInfo::InnerClasses {
+                            attribute_name_index,
+                            attribute_length: 2, // excluding the initial 6 bytes, as per the spec
+                            number_of_classes: 0,
+                            classes: Vec::new(),
+                        });
+
+                        self.classfile.attributes.len() - 1
+                    }
+                };
+
+                let class = self.gen_inner_class(inner_class_def, cp)?;
+
+                if let AttributeInfo::InnerClasses {
+                    ref mut attribute_length,
+                    ref mut number_of_classes,
+                    ref mut classes,
+                    ..
+                } = self.classfile.attributes[inner_classes_index]
+                {
+                    *attribute_length += 8;
+                    *number_of_classes += 1;
+                    classes.push(class);
+                }
+            }
+
+            PhoronClassDirective::EnclosingMethod(PhoronEnclosingMethodDef {
+                ref class_name,
+                ref method,
+            }) => {
+                let attribute_name_index =
+                    *cp.get_name(PHORON_ENCLOSING_METHOD)
+                        .ok_or(CodegenError::AttributeError {
+                            attr: "EnclosingMethod",
+                            details: "missing attribute name index for `EnclosingMethod` attribute",
+                        })?;
+
+                let class_index =
+                    *cp.get_class(class_name)
+                        .ok_or(CodegenError::AttributeError {
+                            attr: "EnclosingMethod",
+                            details: "missing class index for enclosing class",
+                        })?;
+
+                // zero when the class is not enclosed by a method
+                let method_index = match method {
+                    Some((method_name, method_descriptor)) => *cp
+                        .get_name_and_type(method_name, &method_descriptor.to_string())
+                        .ok_or(CodegenError::AttributeError {
+                            attr: "EnclosingMethod",
+                            details: "missing name and type index for enclosing method",
+                        })?,
+                    None => 0,
+                };
+
+                self.classfile.attributes_count += 1;
+                self.classfile
+                    .attributes
+                    .push(AttributeInfo::EnclosingMethod {
+                        attribute_name_index,
+                        attribute_length: 4,
+                        class_index,
+                        method_index,
+                    });
+            }
+
+            PhoronClassDirective::NestHost(ref class_name) => {
+                let attribute_name_index =
+                    *cp.get_name(PHORON_NEST_HOST)
+                        .ok_or(CodegenError::AttributeError {
+                            attr: "NestHost",
+                            details: "missing attribute name index for `NestHost` attribute",
+                        })?;
+
+                let host_class_index =
+                    *cp.get_class(class_name)
+                        .ok_or(CodegenError::AttributeError {
+                            attr: "NestHost",
+                            details: "missing class index for nest host",
+                        })?;
+
+                self.classfile.attributes_count += 1;
+                self.classfile.attributes.push(AttributeInfo::NestHost {
+                    attribute_name_index,
+                    attribute_length: 2,
+                    host_class_index,
+                });
+            }
+
+            // all the nest members go into a single `NestMembers` attribute.
+            PhoronClassDirective::NestMember(ref class_name) => {
+                let nest_members_index = match self
+                    .classfile
+                    .attributes
+                    .iter()
+                    .position(|attr| matches!(attr, AttributeInfo::NestMembers { .. }))
+                {
+                    Some(nest_members_index) => nest_members_index,
+                    None => {
+                        self.classfile.attributes_count += 1;
+
+                        let attribute_name_index = *cp.get_name(PHORON_NEST_MEMBERS).ok_or(
+                            CodegenError::AttributeError {
+                                attr: "NestMembers",
+                                details: "missing attribute name index for `NestMembers` attribute",
+                            },
+                        )?;
+
+                        self.classfile.attributes.push(AttributeInfo::NestMembers {
+                            attribute_name_index,
+                            attribute_length: 2, // excluding the initial 6 bytes, as per the spec
+                            number_of_classes: 0,
+                            classes: Vec::new(),
+                        });
+
+                        self.classfile.attributes.len() - 1
+                    }
+                };
+
+                let member_class_index =
+                    *cp.get_class(class_name)
+                        .ok_or(CodegenError::AttributeError {
+                            attr: "NestMembers",
+                            details: "missing class index for nest member",
+                        })?;
+
+                if let AttributeInfo::NestMembers {
+                    ref mut attribute_length,
+                    ref mut number_of_classes,
+                    ref mut classes,
+                    ..
+                } = self.classfile.attributes[nest_members_index]
+                {
+                    *attribute_length += 2;
+                    *number_of_classes += 1;
+                    classes.push(member_class_index);
+                }
+            }
+
+            // all the permitted subclasses go into a single `PermittedSubclasses` attribute.
+            PhoronClassDirective::Permits(ref class_name) => {
+                let permitted_subclasses_index = match self
+                    .classfile
+                    .attributes
+                    .iter()
+                    .position(|attr| matches!(attr, AttributeInfo::PermittedSubclasses { .. }))
+                {
+                    Some(permitted_subclasses_index) => permitted_subclasses_index,
+                    None => {
+                        self.classfile.attributes_count += 1;
+
+                        let attribute_name_index = *cp
+                            .get_name(PHORON_PERMITTED_SUBCLASSES)
+                            .ok_or(CodegenError::AttributeError {
+                            attr: "PermittedSubclasses",
+                            details:
+                                "missing attribute name index for `PermittedSubclasses` attribute",
+                        })?;
+
+                        self.classfile
+                            .attributes
+                            .push(AttributeInfo::PermittedSubclasses {
+                                attribute_name_index,
+                                attribute_length: 2, // excluding the initial 6 bytes, as per the spec
+                                number_of_classes: 0,
+                                classes: Vec::new(),
+                            });
+
+                        self.classfile.attributes.len() - 1
+                    }
+                };
+
+                let subclass_index =
+                    *cp.get_class(class_name)
+                        .ok_or(CodegenError::AttributeError {
+                            attr: "PermittedSubclasses",
+                            details: "missing class index for permitted subclass",
+                        })?;
+
+                if let AttributeInfo::PermittedSubclasses {
+                    ref mut attribute_length,
+                    ref mut number_of_classes,
+                    ref mut classes,
+                    ..
+                } = self.classfile.attributes[permitted_subclasses_index]
+                {
+                    *attribute_length += 2;
+                    *number_of_classes += 1;
+                    classes.push(subclass_index);
+                }
+            }
+
+            // there can be at most one `SourceDebugExtension` attribute, so the strings of all
+            // the `.debug` directives are concatenated, in the order in which they are declared.
+            // raw attributes are also generated as `SourceDebugExtension` entries (see
+            // `gen_raw_attribute`), so the attribute name has to be checked as well.
+            PhoronClassDirective::Debug(ref debug_extension) => {
+                let attribute_name_index = *cp.get_name(PHORON_SOURCE_DEBUG_EXTENSION).ok_or(
+                    CodegenError::AttributeError {
+                        attr: "SourceDebugExtension",
+                        details:
+                            "missing attribute name index for `SourceDebugExtension` attribute",
+                    },
+                )?;
+
+                let debug_extension_index = match self.classfile.attributes.iter().position(
+                    |attr| matches!(attr, AttributeInfo::SourceDebugExtension { attribute_name_index: name_index, .. } if *name_index == attribute_name_index),
+                ) {
+                    Some(debug_extension_index) => debug_extension_index,
+                    None => {
+                        self.classfile.attributes_count += 1;
+
+                        self.classfile
+                            .attributes
+                            .push(AttributeInfo::SourceDebugExtension {
+                                attribute_name_index,
+                                attribute_length: 0, // excluding the initial 6 bytes, as per the spec
+                                debug_extension: Vec::new(),
+                            });
+
+                        self.classfile.attributes.len() - 1
+                    }
+                };
+
+                let bytes = Self::gen_modified_utf8(debug_extension);
+
+                if let AttributeInfo::SourceDebugExtension {
+                    ref mut attribute_length,
+                    ref mut debug_extension,
+                    ..
+                } = self.classfile.attributes[debug_extension_index]
+                {
+                    *attribute_length = u32::try_from(bytes.len())
+                        .ok()
+                        .and_then(|len| attribute_length.checked_add(len))
+                        .ok_or(CodegenError::AttributeError {
+                            attr: "SourceDebugExtension",
+                            details: "debug extension exceeds the maximum attribute length",
+                        })?;
+                    debug_extension.extend(bytes);
+                }
+            }
+
+            PhoronClassDirective::Attribute(ref attribute_def) => {
+                self.classfile.attributes_count += 1;
+                self.classfile
+                    .attributes
+                    .push(Self::gen_raw_attribute(attribute_def, cp)?);
+            }
+
+            PhoronClassDirective::Deprecated => {
+                let deprecated = self.gen_deprecated_attribute(cp)?;
+                self.classfile.attributes_count += 1;
+                self.classfile.attributes.push(deprecated);
+            }
+
+            PhoronClassDirective::Synthetic { flag } => {
+                if *flag {
+                    self.classfile.access_flags |= ACC_SYNTHETIC;
+                }
+
+                let synthetic = self.gen_synthetic_attribute(cp)?;
+                self.classfile.attributes_count += 1;
+                self.classfile.attributes.push(synthetic);
+            }
+
+            // all the components go into a single `Record` attribute, in the order in which they
+            // are declared.
+            PhoronClassDirective::RecordComponent(ref component) => {
+                let record_index = match self
+                    .classfile
+                    .attributes
+                    .iter()
+                    .position(|attr| matches!(attr, AttributeInfo::Record { .. }))
+                {
+                    Some(record_index) => record_index,
+                    None => {
+                        self.classfile.attributes_count += 1;
+
+                        let attribute_name_index =
+                            *cp.get_name(PHORON_RECORD)
+                                .ok_or(CodegenError::AttributeError {
+                                    attr: "Record",
+                                    details: "missing attribute name index for `Record` attribute",
+                                })?;
+
+                        self.classfile.attributes.push(AttributeInfo::Record {
+                            attribute_name_index,
+                            attribute_length: 2, // excluding the initial 6 bytes, as per the spec
+                            components_count: 0,
+                            components: Vec::new(),
+                        });
+
+                        self.classfile.attributes.len() - 1
+                    }
+                };
+
+                let component_info = self.gen_record_component(component, cp)?;
+                let component_length = 6 + component_info
+                    .attributes
+                    .iter()
+                    .map(Self::record_component_attribute_length)
+                    .sum::<u32>();
+
+                if let AttributeInfo::Record {
+                    ref mut attribute_length,
+                    ref mut components_count,
+                    ref mut components,
+                    ..
+                } = self.classfile.attributes[record_index]
+                {
+                    *attribute_length += component_length;
+                    *components_count += 1;
+                    components.push(component_info);
+                }
+            }
+        }
+
+        Ok(CodegenResultType::Empty)
+    }
+
     fn visit_body(&mut self, body: &PhoronBody, cp: Self::Input) -> Self::Result {
         self.classfile.fields_count = body.field_defs.len() as u16;
 
@@ -648,6 +2984,52 @@ where
 
         field_info.attributes_count = 0;
 
+        if let Some(ref signature) = field_def.signature {
+            field_info.attributes_count += 1;
+            field_info
+                .attributes
+                .push(self.gen_signature_attribute(signature, cp)?);
+        }
+
+        for annotation_def in &field_def.annotations {
+            if Self::gen_annotation_def(&mut field_info.attributes, annotation_def, cp)? {
+                field_info.attributes_count += 1;
+            }
+        }
+
+        for type_annotation_def in &field_def.type_annotations {
+            if Self::gen_type_annotation_def(
+                &mut field_info.attributes,
+                type_annotation_def,
+                &self.label_mapping,
+                None,
+                cp,
+            )? {
+                field_info.attributes_count += 1;
+            }
+        }
+
+        for attribute_def in &field_def.attributes {
+            field_info.attributes_count += 1;
+            field_info
+                .attributes
+                .push(Self::gen_raw_attribute(attribute_def, cp)?);
+        }
+
+        if field_def.deprecated {
+            field_info.attributes_count += 1;
+            field_info
+                .attributes
+                .push(self.gen_deprecated_attribute(cp)?);
+        }
+
+        if field_def.synthetic {
+            field_info.attributes_count += 1;
+            field_info
+                .attributes
+                .push(self.gen_synthetic_attribute(cp)?);
+        }
+
         self.classfile.fields.push(field_info);
 
         Ok(CodegenResultType::Empty)
@@ -733,6 +3115,27 @@ where
                                 }
                             }
 
+                            PhoronDirective::TypeAnnotation(ref type_annotation_def)
+                                if type_annotation_def.target.is_code_target() =>
+                            {
+                                return Err(CodegenError::Invalid {
+                                    component: "interface",
+                                    details:
+                                        "type annotations on code are not supported for interfaces",
+                                })
+                            }
+
+                            // generated after the other directives, as top-level attributes
+                            PhoronDirective::Signature(..)
+                            | PhoronDirective::Annotation(..)
+                            | PhoronDirective::ParameterAnnotation { .. }
+                            | PhoronDirective::TypeAnnotation(..)
+                            | PhoronDirective::AnnotationDefault(..)
+                            | PhoronDirective::Parameter { .. }
+                            | PhoronDirective::Attribute(..)
+                            | PhoronDirective::Deprecated
+                            | PhoronDirective::Synthetic { .. } => {}
+
                             _ => {
                                 return Err(CodegenError::Invalid {
                                     component: "interface",
@@ -772,12 +3175,12 @@ where
                 // offsets after the Code vector/array has already beem generated (as would have
                 // been the case in a more traditional compiler).
 
-                self.gen_label_mappings(&method_def.instructions)?;
+                self.gen_label_mappings(method_def, cp)?;
                 method_info.attributes_count += 1; // for the Code attribute
 
                 let mut code_attributes_length = 12; // default minimum (as per the spec)
-                let mut code_max_stack = 1; // default
-                let mut code_max_locals = 1; // default
+                let mut code_max_stack = None;
+                let mut code_max_locals = None;
 
                 let mut code = Vec::new();
 
@@ -786,16 +3189,18 @@ where
                 let mut code_attributes_count = 0;
                 let mut code_attributes = vec![];
 
+                let mut instruction_offsets = Vec::new();
+
                 self.curr_code_offset = 0;
                 for instr in &method_def.instructions {
                     match instr {
                         PhoronInstruction::PhoronDirective(ref dir) => match dir {
                             PhoronDirective::LimitStack(max_stack) => {
-                                code_max_stack = *max_stack;
+                                code_max_stack = Some(*max_stack);
                             }
 
                             PhoronDirective::LimitLocals(max_locals) => {
-                                code_max_locals = *max_locals;
+                                code_max_locals = Some(*max_locals);
                             }
 
                             // this is a top-level attribute inside Methhodnfo, ot the same level as the
@@ -887,7 +3292,11 @@ where
                                     lntindex
                                 };
 
-                                let start_pc = self.curr_code_offset as u16;
+                                let start_pc = Self::gen_code_offset(
+                                    self.curr_code_offset,
+                                    method_def,
+                                    || format!("`.line {line_number}`"),
+                                )?;
 
                                 if let AttributeInfo::LineNumberTable {
                                     ref mut attribute_length,
@@ -915,13 +3324,53 @@ where
                             // If not empty, find the index of the vector which contains the
                             // LocalVariableTable, and enter the local vars there.
                             PhoronDirective::Var {
+                                varnum,
                                 ref name,
                                 ref field_descriptor,
+                                ref signature,
                                 ref from_label,
                                 ref to_label,
-                                ..
                             } => {
-                                let local_var_table_index = if code_attributes.is_empty() {
+                                // from_label and to_label are optional. If they are missing
+                                // their range extends from the start, or to the end, of the
+                                // code vector. A missing end is backpatched once the size of the
+                                // code vector is available.
+                                let start_pc = Self::gen_code_offset(
+                                    self.label_mapping.get(from_label).map_or(0, |spc| *spc),
+                                    method_def,
+                                    || format!("`.var {varnum}`"),
+                                )?;
+
+                                let length = match self.label_mapping.get(to_label) {
+                                    Some(epc) => {
+                                        let end_pc =
+                                            Self::gen_code_offset(*epc, method_def, || {
+                                                format!("`.var {varnum}`")
+                                            })?;
+
+                                        end_pc.checked_sub(start_pc).ok_or_else(|| {
+                                            CodegenError::CodeRangeReversed {
+                                                method: method_def.name.to_owned(),
+                                                directive: format!("`.var {varnum}`"),
+                                                start_pc,
+                                                end_pc,
+                                            }
+                                        })?
+                                    }
+                                    None => 0,
+                                };
+
+                                let name_index = *cp.get_name(&name).ok_or(CodegenError::AttributeError {
+                                attr: "Code",
+                                details: "missing name index for local var for local variable table for Code attribute",
+                            })?;
+
+                                let local_var_table_index = if let Some(lvtindex) =
+                                    code_attributes.iter().position(|attr| {
+                                        matches!(attr, AttributeInfo::LocalVariableTable { .. })
+                                    }) {
+                                    lvtindex
+                                } else {
                                     code_attributes_count += 1;
                                     code_attributes_length += 8;
 
@@ -941,68 +3390,92 @@ where
                                         local_variable_table_length,
                                         local_variable_table,
                                     });
-                                    0
-                                } else {
-                                    let mut lvtindex = 0;
-                                    for (index, attr) in code_attributes.iter().enumerate() {
-                                        if let AttributeInfo::LocalVariableTable { .. } = attr {
-                                            lvtindex = index;
-                                            break;
-                                        }
-                                    }
-                                    lvtindex
+                                    code_attributes.len() - 1
                                 };
 
                                 if let AttributeInfo::LocalVariableTable {
                                     ref mut attribute_length,
                                     ref mut local_variable_table_length,
-                                    ref mut local_variable_table,
-                                    ..
-                                } = code_attributes[local_var_table_index]
-                                {
-                                    *local_variable_table_length += 1;
-
-                                    // from_label and to_label are optional. If they are missing
-                                    // their range extends from across the entire code vector.
-                                    // This possibly backpatching once the size of the code vector
-                                    // is available.
-                                    let start_pc =
-                                        self.label_mapping.get(from_label).map_or(0, |spc| *spc)
-                                            as u16;
-
-                                    let end_pc =
-                                        self.label_mapping.get(to_label).map_or(0, |epc| *epc)
-                                            as u16;
-
-                                    let length = end_pc - start_pc;
-
-                                    let name_index = *cp.get_name(&name).ok_or(CodegenError::AttributeError {
-                                attr: "Code",
-                                details: "missing name index for local var for local variable table for Code attribute",
-                            })?;
+                                    ref mut local_variable_table,
+                                    ..
+                                } = code_attributes[local_var_table_index]
+                                {
+                                    *local_variable_table_length += 1;
 
                                     let descriptor_index = *cp.get_name(&field_descriptor.to_string()).ok_or(CodegenError::AttributeError {
                                 attr: "Code",
                                 details: "missing descriptor index for local var for local variable table for Code attribute",
                             })?;
 
-                                    let index = if local_variable_table.is_empty() {
-                                        0
-                                    } else {
-                                        local_variable_table.len() as u16
-                                    };
-
                                     local_variable_table.push(LocalVariable {
                                         start_pc,
                                         length,
                                         name_index,
                                         descriptor_index,
-                                        index,
+                                        index: *varnum,
                                     });
 
                                     *attribute_length += 10; // 10 bytes per LocalVariable struct
                                     code_attributes_length += 10;
                                 }
+
+                                // a generic local var also gets an entry in the LocalVariableTypeTable
+                                // attribute, which must match its entry in the LocalVariableTable above.
+                                if let Some(ref signature) = signature {
+                                    let local_var_type_table_index = if let Some(lvttindex) =
+                                        code_attributes.iter().position(|attr| {
+                                            matches!(
+                                                attr,
+                                                AttributeInfo::LocalVariableTypeTable { .. }
+                                            )
+                                        }) {
+                                        lvttindex
+                                    } else {
+                                        code_attributes_count += 1;
+                                        code_attributes_length += 8;
+
+                                        let attribute_name_index = *cp.get_name(PHORON_LOCAL_VARIABLE_TYPE_TABLE).ok_or(CodegenError::AttributeError {
+                                attr: "Code",
+                                details: "missing attribute name index for local variable type table in Code attribute"
+                            })?;
+
+                                        code_attributes.push(
+                                            AttributeInfo::LocalVariableTypeTable {
+                                                attribute_name_index,
+                                                attribute_length: 2, // excluding the initial 6 bytes, as per the spec
+                                                local_variable_type_table_length: 0,
+                                                local_variable_type_table: Vec::new(),
+                                            },
+                                        );
+                                        code_attributes.len() - 1
+                                    };
+
+                                    let signature_index = *cp.get_name(signature).ok_or(CodegenError::AttributeError {
+                                attr: "Code",
+                                details: "missing signature index for local var for local variable type table for Code attribute",
+                            })?;
+
+                                    if let AttributeInfo::LocalVariableTypeTable {
+                                        ref mut attribute_length,
+                                        ref mut local_variable_type_table_length,
+                                        ref mut local_variable_type_table,
+                                        ..
+                                    } = code_attributes[local_var_type_table_index]
+                                    {
+                                        *local_variable_type_table_length += 1;
+
+                                        local_variable_type_table.push(LocalVariableType {
+                                            start_pc,
+                                            length,
+                                            name_index,
+                                            signature_index,
+                                            index: *varnum,
+                                        });
+
+                                        *attribute_length += 10; // 10 bytes per LocalVariableType struct
+                                        code_attributes_length += 10;
+                                    }
+                                }
                             }
 
                             // this goes in the exception_table field of the Code attribute
@@ -1015,28 +3488,43 @@ where
                             } => {
                                 exception_table_length += 1;
 
+                                let catch_directive = || {
+                                    format!("`.catch {class_name} from {from_label} to {to_label} using {handler_label}`")
+                                };
+
                                 let mut exc_handler = ExceptionHandler::default();
-                                exc_handler.start_pc = *self.label_mapping.get(from_label).ok_or(
-                                    CodegenError::AttributeError {
-                                        attr: "Code",
-                                        details: "missing start_pc for exception handler",
-                                    },
-                                )? as u16;
-
-                                exc_handler.end_pc = *self.label_mapping.get(to_label).ok_or(
-                                    CodegenError::AttributeError {
-                                        attr: "Code",
-                                        details: "missing end_pc for exception handler",
-                                    },
-                                )? as u16;
-
-                                exc_handler.handler_pc =
+                                exc_handler.start_pc = Self::gen_code_offset(
+                                    *self.label_mapping.get(from_label).ok_or(
+                                        CodegenError::AttributeError {
+                                            attr: "Code",
+                                            details: "missing start_pc for exception handler",
+                                        },
+                                    )?,
+                                    method_def,
+                                    catch_directive,
+                                )?;
+
+                                exc_handler.end_pc = Self::gen_code_offset(
+                                    *self.label_mapping.get(to_label).ok_or(
+                                        CodegenError::AttributeError {
+                                            attr: "Code",
+                                            details: "missing end_pc for exception handler",
+                                        },
+                                    )?,
+                                    method_def,
+                                    catch_directive,
+                                )?;
+
+                                exc_handler.handler_pc = Self::gen_code_offset(
                                     *self.label_mapping.get(handler_label).ok_or(
                                         CodegenError::AttributeError {
                                             attr: "Code",
                                             details: "missing handler_pc for exception handler",
                                         },
-                                    )? as u16;
+                                    )?,
+                                    method_def,
+                                    catch_directive,
+                                )?;
 
                                 exc_handler.catch_type = if class_name == "all" {
                                     0 // catch any exception
@@ -1054,6 +3542,28 @@ where
                                 // update attribute length for `Code` attribute
                                 code_attributes_length += 4 * std::mem::size_of::<u16>() as u32;
                             }
+
+                            // this goes in the `code_attributes` field of the `Code` attribute
+                            PhoronDirective::CodeAttribute(ref attribute_def) => {
+                                code_attributes_count += 1;
+                                code_attributes.push(Self::gen_raw_attribute(attribute_def, cp)?);
+
+                                // the length has already been checked to fit in a u32 above
+                                code_attributes_length += 6 + attribute_def.info.len() as u32;
+                            }
+
+                            // these are top-level attributes inside MethodInfo (or, for type
+                            // annotations on code, in the `Code` attribute), generated once the
+                            // code is in place.
+                            PhoronDirective::Signature(..)
+                            | PhoronDirective::Annotation(..)
+                            | PhoronDirective::ParameterAnnotation { .. }
+                            | PhoronDirective::TypeAnnotation(..)
+                            | PhoronDirective::AnnotationDefault(..)
+                            | PhoronDirective::Parameter { .. }
+                            | PhoronDirective::Attribute(..)
+                            | PhoronDirective::Deprecated
+                            | PhoronDirective::Synthetic { .. } => {}
                         },
 
                         PhoronInstruction::PhoronLabel(..) => {}
@@ -1062,7 +3572,9 @@ where
                             let opcodes = self.visit_jvm_instruction(jvm_instr, cp)?;
 
                             if let CodegenResultType::ByteVec(instr_opcodes) = opcodes {
-                                let opcode_len = instr_opcodes.len() as i16;
+                                instruction_offsets.push(self.curr_code_offset as u16);
+
+                                let opcode_len = instr_opcodes.len() as u32;
                                 code.extend_from_slice(&instr_opcodes);
                                 self.curr_code_offset += opcode_len;
                             } else {
@@ -1073,23 +3585,99 @@ where
                 }
 
                 let code_length = code.len() as u32;
+                if code_length > MAX_CODE_LENGTH {
+                    return Err(CodegenError::CodeTooLong {
+                        method: method_def.name.to_owned(),
+                        code_length,
+                    });
+                }
+
                 code_attributes_length += code_length; // need to add sizes of all attributes
 
                 // backpatch the LocalVarTable, if applicable - in case the length of
-                // the local var's range is 0, assume that `from` and/or `to` quantifiers
-                // were not provided, simply mark the range of the local var as the entire
-                // code vector.
+                // the local var's range is 0, assume that the `to` quantifier was not
+                // provided, and extend the range of the local var to the end of the code
+                // vector.
                 for attr in code_attributes.iter_mut() {
-                    if let AttributeInfo::LocalVariableTable {
-                        ref mut local_variable_table,
-                        ..
+                    match attr {
+                        AttributeInfo::LocalVariableTable {
+                            ref mut local_variable_table,
+                            ..
+                        } => {
+                            for local_var in local_variable_table {
+                                if local_var.length == 0 {
+                                    local_var.length = code_length as u16 - local_var.start_pc;
+                                }
+                            }
+                        }
+
+                        AttributeInfo::LocalVariableTypeTable {
+                            ref mut local_variable_type_table,
+                            ..
+                        } => {
+                            for local_var_type in local_variable_type_table {
+                                if local_var_type.length == 0 {
+                                    local_var_type.length =
+                                        code_length as u16 - local_var_type.start_pc;
+                                }
+                            }
+                        }
+
+                        _ => {}
+                    }
+                }
+
+                // type annotations on code refer to the offsets of the instructions, so they
+                // can only be generated after the code
+                let mut type_annotation_attributes = vec![];
+                for instr in &method_def.instructions {
+                    if let PhoronInstruction::PhoronDirective(PhoronDirective::TypeAnnotation(
+                        ref type_annotation_def,
+                    )) = instr
+                    {
+                        if type_annotation_def.target.is_code_target() {
+                            Self::gen_type_annotation_def(
+                                &mut type_annotation_attributes,
+                                type_annotation_def,
+                                &self.label_mapping,
+                                Some(&method_def.name),
+                                cp,
+                            )?;
+                        }
+                    }
+                }
+
+                for attr in type_annotation_attributes {
+                    if let AttributeInfo::RuntimeVisibleTypeAnnotations {
+                        attribute_length, ..
+                    }
+                    | AttributeInfo::RuntimeInvisibleTypeAnnotations {
+                        attribute_length, ..
                     } = attr
                     {
-                        for local_var in local_variable_table {
-                            if local_var.length == 0 {
-                                local_var.length = code_length as u16;
-                            }
+                        code_attributes_length += 6 + attribute_length;
+                    }
+
+                    code_attributes_count += 1;
+                    code_attributes.push(attr);
+                }
+
+                let (code_max_stack, code_max_locals) =
+                    self.gen_code_limits(method_def, code_max_stack, code_max_locals)?;
+
+                if self.classfile.major_version >= stack_map::STACK_MAP_MAJOR_VERSION {
+                    if let Some(stack_map_table) =
+                        self.gen_stack_map_table(method_def, &instruction_offsets, cp)?
+                    {
+                        if let AttributeInfo::StackMapTable {
+                            attribute_length, ..
+                        } = stack_map_table
+                        {
+                            code_attributes_length += 6 + attribute_length;
                         }
+
+                        code_attributes_count += 1;
+                        code_attributes.push(stack_map_table);
                     }
                 }
 
@@ -1108,6 +3696,116 @@ where
             }
         }
 
+        for instr in &method_def.instructions {
+            match instr {
+                PhoronInstruction::PhoronDirective(PhoronDirective::Signature(ref signature)) => {
+                    method_info.attributes_count += 1;
+                    method_info
+                        .attributes
+                        .push(self.gen_signature_attribute(signature, cp)?);
+                }
+
+                PhoronInstruction::PhoronDirective(PhoronDirective::Annotation(
+                    ref annotation_def,
+                )) => {
+                    let created =
+                        Self::gen_annotation_def(&mut method_info.attributes, annotation_def, cp)?;
+                    if created {
+                        method_info.attributes_count += 1;
+                    }
+                }
+
+                PhoronInstruction::PhoronDirective(PhoronDirective::ParameterAnnotation {
+                    parameter_index,
+                    ref annotation_def,
+                }) => {
+                    let created = Self::gen_parameter_annotation_def(
+                        &mut method_info.attributes,
+                        method_def.method_descriptor.param_descriptor.len() as u8,
+                        *parameter_index,
+                        annotation_def,
+                        cp,
+                    )?;
+                    if created {
+                        method_info.attributes_count += 1;
+                    }
+                }
+
+                // type annotations on code have already been added to the `Code` attribute
+                PhoronInstruction::PhoronDirective(PhoronDirective::TypeAnnotation(
+                    ref type_annotation_def,
+                )) if !type_annotation_def.target.is_code_target() => {
+                    let created = Self::gen_type_annotation_def(
+                        &mut method_info.attributes,
+                        type_annotation_def,
+                        &self.label_mapping,
+                        Some(&method_def.name),
+                        cp,
+                    )?;
+                    if created {
+                        method_info.attributes_count += 1;
+                    }
+                }
+
+                PhoronInstruction::PhoronDirective(PhoronDirective::AnnotationDefault(
+                    ref default_value,
+                )) => {
+                    let attribute_name_index = *cp.get_name(PHORON_ANNOTATION_DEFAULT).ok_or(
+                        CodegenError::AttributeError {
+                            attr: "AnnotationDefault",
+                            details: "missing attribute name index for `AnnotationDefault` attribute in method info",
+                        },
+                    )?;
+
+                    let (default_value, attribute_length) =
+                        Self::gen_element_value(default_value, "AnnotationDefault", cp)?;
+
+                    method_info.attributes_count += 1;
+                    method_info
+                        .attributes
+                        .push(AttributeInfo::AnnotationDefault {
+                            attribute_name_index,
+                            attribute_length,
+                            default_value,
+                        });
+                }
+
+                PhoronInstruction::PhoronDirective(PhoronDirective::Attribute(
+                    ref attribute_def,
+                )) => {
+                    method_info.attributes_count += 1;
+                    method_info
+                        .attributes
+                        .push(Self::gen_raw_attribute(attribute_def, cp)?);
+                }
+
+                PhoronInstruction::PhoronDirective(PhoronDirective::Deprecated) => {
+                    method_info.attributes_count += 1;
+                    method_info
+                        .attributes
+                        .push(self.gen_deprecated_attribute(cp)?);
+                }
+
+                PhoronInstruction::PhoronDirective(PhoronDirective::Synthetic { flag }) => {
+                    if *flag {
+                        method_info.access_flags |= ACC_SYNTHETIC;
+                    }
+
+                    method_info.attributes_count += 1;
+                    method_info
+                        .attributes
+                        .push(self.gen_synthetic_attribute(cp)?);
+                }
+
+                _ => {}
+            }
+        }
+
+        if let Some(method_parameters) = Self::gen_method_parameters(method_def, cp)? {
+            method_info.attributes_count += 1;
+            method_info.attributes.push(method_parameters);
+        }
+
         self.classfile.methods.push(method_info);
 
         Ok(CodegenResultType::Empty)
@@ -1355,22 +4053,7 @@ where
                 CodegenResultType::ByteVec(opcodes)
             }
 
-            Goto { ref label } => {
-                let mut opcodes = vec![0xa7];
-
-                let label_offset =
-                    self.label_mapping
-                        .get(label)
-                        .ok_or(CodegenError::OpcodeError {
-                            opcode: "goto",
-                            details: "invalid label",
-                        })?;
-
-                let offset = label_offset - self.curr_code_offset;
-                opcodes.extend_from_slice(&offset.to_be_bytes());
-
-                CodegenResultType::ByteVec(opcodes)
-            }
+            Goto { ref label } => CodegenResultType::ByteVec(self.gen_branch(0xa7, "goto", label)?),
 
             Gotow { ref label } => {
                 let mut opcodes = vec![0xc8];
@@ -1383,7 +4066,7 @@ where
                             details: "invalid label",
                         })?;
 
-                let offset = (label_offset - self.curr_code_offset) as i32;
+                let offset = *label_offset as i32 - self.curr_code_offset as i32;
                 opcodes.extend_from_slice(&offset.to_be_bytes());
 
                 CodegenResultType::ByteVec(opcodes)
@@ -1409,275 +4092,55 @@ where
             Idiv => CodegenResultType::ByteVec(vec![0x6c]),
 
             Ifacmpeq { ref label } => {
-                let mut opcodes = vec![0xa5];
-
-                let label_offset =
-                    self.label_mapping
-                        .get(label)
-                        .ok_or(CodegenError::OpcodeError {
-                            opcode: "if_acmpeq",
-                            details: "invalid label ",
-                        })?;
-
-                let offset = label_offset - self.curr_code_offset;
-                opcodes.extend_from_slice(&offset.to_be_bytes());
-
-                CodegenResultType::ByteVec(opcodes)
-            }
-
-            Ifacmpne { ref label } => {
-                let mut opcodes = vec![0xa6];
-
-                let label_offset =
-                    self.label_mapping
-                        .get(label)
-                        .ok_or(CodegenError::OpcodeError {
-                            opcode: "if_acmpne",
-                            details: "invalid label ",
-                        })?;
-
-                let offset = label_offset - self.curr_code_offset;
-                opcodes.extend_from_slice(&offset.to_be_bytes());
-
-                CodegenResultType::ByteVec(opcodes)
-            }
-
-            Ificmpeq { ref label } => {
-                let mut opcodes = vec![0x9f];
-
-                let label_offset =
-                    self.label_mapping
-                        .get(label)
-                        .ok_or(CodegenError::OpcodeError {
-                            opcode: "if_icmpeq",
-                            details: "invalid label ",
-                        })?;
-
-                let offset = label_offset - self.curr_code_offset;
-                opcodes.extend_from_slice(&offset.to_be_bytes());
-
-                CodegenResultType::ByteVec(opcodes)
-            }
-
-            Ificmpne { ref label } => {
-                let mut opcodes = vec![0xa0];
-
-                let label_offset =
-                    self.label_mapping
-                        .get(label)
-                        .ok_or(CodegenError::OpcodeError {
-                            opcode: "if_icmpne",
-                            details: "invalid label ",
-                        })?;
-
-                let offset = label_offset - self.curr_code_offset;
-                opcodes.extend_from_slice(&offset.to_be_bytes());
-
-                CodegenResultType::ByteVec(opcodes)
-            }
-
-            Ificmplt { ref label } => {
-                let mut opcodes = vec![0xa1];
-
-                let label_offset =
-                    self.label_mapping
-                        .get(label)
-                        .ok_or(CodegenError::OpcodeError {
-                            opcode: "if_icmplt",
-                            details: "invalid label ",
-                        })?;
-
-                let offset = label_offset - self.curr_code_offset;
-                opcodes.extend_from_slice(&offset.to_be_bytes());
-
-                CodegenResultType::ByteVec(opcodes)
-            }
-
-            Ificmpge { ref label } => {
-                let mut opcodes = vec![0xa2];
-
-                let label_offset =
-                    self.label_mapping
-                        .get(label)
-                        .ok_or(CodegenError::OpcodeError {
-                            opcode: "if_icmpge",
-                            details: "invalid label ",
-                        })?;
-
-                let offset = label_offset - self.curr_code_offset;
-                opcodes.extend_from_slice(&offset.to_be_bytes());
-
-                CodegenResultType::ByteVec(opcodes)
-            }
-
-            Ificmpgt { ref label } => {
-                let mut opcodes = vec![0xa3];
-
-                let label_offset =
-                    self.label_mapping
-                        .get(label)
-                        .ok_or(CodegenError::OpcodeError {
-                            opcode: "if_icmpgt",
-                            details: "invalid label",
-                        })?;
-
-                let offset = label_offset - self.curr_code_offset;
-                opcodes.extend_from_slice(&offset.to_be_bytes());
-
-                CodegenResultType::ByteVec(opcodes)
-            }
-
-            Ificmple { ref label } => {
-                let mut opcodes = vec![0xa4];
-
-                let label_offset =
-                    self.label_mapping
-                        .get(label)
-                        .ok_or(CodegenError::OpcodeError {
-                            opcode: "if_icmple",
-                            details: "invalid label ",
-                        })?;
-
-                let offset = label_offset - self.curr_code_offset;
-                opcodes.extend_from_slice(&offset.to_be_bytes());
-
-                CodegenResultType::ByteVec(opcodes)
-            }
-
-            Ifeq { ref label } => {
-                let mut opcodes = vec![0x99];
-
-                let label_offset =
-                    self.label_mapping
-                        .get(label)
-                        .ok_or(CodegenError::OpcodeError {
-                            opcode: "ifeq",
-                            details: "invalid label",
-                        })?;
-
-                let offset = label_offset - self.curr_code_offset;
-                opcodes.extend_from_slice(&offset.to_be_bytes());
-
-                CodegenResultType::ByteVec(opcodes)
-            }
-
-            Ifne { ref label } => {
-                let mut opcodes = vec![0x9a];
-
-                let label_offset =
-                    self.label_mapping
-                        .get(label)
-                        .ok_or(CodegenError::OpcodeError {
-                            opcode: "ifne",
-                            details: "invalid label",
-                        })?;
-
-                let offset = label_offset - self.curr_code_offset;
-                opcodes.extend_from_slice(&offset.to_be_bytes());
-
-                CodegenResultType::ByteVec(opcodes)
-            }
-
-            Iflt { ref label } => {
-                let mut opcodes = vec![0x9b];
-
-                let label_offset =
-                    self.label_mapping
-                        .get(label)
-                        .ok_or(CodegenError::OpcodeError {
-                            opcode: "iflt",
-                            details: "invalid label",
-                        })?;
-
-                let offset = label_offset - self.curr_code_offset;
-                opcodes.extend_from_slice(&offset.to_be_bytes());
-
-                CodegenResultType::ByteVec(opcodes)
+                CodegenResultType::ByteVec(self.gen_branch(0xa5, "if_acmpeq", label)?)
             }
 
-            Ifge { ref label } => {
-                let mut opcodes = vec![0x9c];
-
-                let label_offset =
-                    self.label_mapping
-                        .get(label)
-                        .ok_or(CodegenError::OpcodeError {
-                            opcode: "ifge",
-                            details: "invalid label",
-                        })?;
-
-                let offset = label_offset - self.curr_code_offset;
-                opcodes.extend_from_slice(&offset.to_be_bytes());
+            Ifacmpne { ref label } => {
+                CodegenResultType::ByteVec(self.gen_branch(0xa6, "if_acmpne", label)?)
+            }
 
-                CodegenResultType::ByteVec(opcodes)
+            Ificmpeq { ref label } => {
+                CodegenResultType::ByteVec(self.gen_branch(0x9f, "if_icmpeq", label)?)
             }
 
-            Ifgt { ref label } => {
-                let mut opcodes = vec![0x9d];
+            Ificmpne { ref label } => {
+                CodegenResultType::ByteVec(self.gen_branch(0xa0, "if_icmpne", label)?)
+            }
 
-                let label_offset =
-                    self.label_mapping
-                        .get(label)
-                        .ok_or(CodegenError::OpcodeError {
-                            opcode: "ifgt",
-                            details: "invalid label",
-                        })?;
+            Ificmplt { ref label } => {
+                CodegenResultType::ByteVec(self.gen_branch(0xa1, "if_icmplt", label)?)
+            }
 
-                let offset = label_offset - self.curr_code_offset;
-                opcodes.extend_from_slice(&offset.to_be_bytes());
+            Ificmpge { ref label } => {
+                CodegenResultType::ByteVec(self.gen_branch(0xa2, "if_icmpge", label)?)
+            }
 
-                CodegenResultType::ByteVec(opcodes)
+            Ificmpgt { ref label } => {
+                CodegenResultType::ByteVec(self.gen_branch(0xa3, "if_icmpgt", label)?)
             }
 
-            Ifle { ref label } => {
-                let mut opcodes = vec![0x9e];
+            Ificmple { ref label } => {
+                CodegenResultType::ByteVec(self.gen_branch(0xa4, "if_icmple", label)?)
+            }
 
-                let label_offset =
-                    self.label_mapping
-                        .get(label)
-                        .ok_or(CodegenError::OpcodeError {
-                            opcode: "ifle",
-                            details: "invalid label",
-                        })?;
+            Ifeq { ref label } => CodegenResultType::ByteVec(self.gen_branch(0x99, "ifeq", label)?),
 
-                let offset = label_offset - self.curr_code_offset;
-                opcodes.extend_from_slice(&offset.to_be_bytes());
+            Ifne { ref label } => CodegenResultType::ByteVec(self.gen_branch(0x9a, "ifne", label)?),
 
-                CodegenResultType::ByteVec(opcodes)
-            }
+            Iflt { ref label } => CodegenResultType::ByteVec(self.gen_branch(0x9b, "iflt", label)?),
 
-            Ifnull { ref label } => {
-                let mut opcodes = vec![0xc6];
+            Ifge { ref label } => CodegenResultType::ByteVec(self.gen_branch(0x9c, "ifge", label)?),
 
-                let label_offset =
-                    self.label_mapping
-                        .get(label)
-                        .ok_or(CodegenError::OpcodeError {
-                            opcode: "ifnull",
-                            details: "invalid label",
-                        })?;
+            Ifgt { ref label } => CodegenResultType::ByteVec(self.gen_branch(0x9d, "ifgt", label)?),
 
-                let offset = label_offset - self.curr_code_offset;
-                opcodes.extend_from_slice(&offset.to_be_bytes());
+            Ifle { ref label } => CodegenResultType::ByteVec(self.gen_branch(0x9e, "ifle", label)?),
 
-                CodegenResultType::ByteVec(opcodes)
+            Ifnull { ref label } => {
+                CodegenResultType::ByteVec(self.gen_branch(0xc6, "ifnull", label)?)
             }
 
             Ifnonnull { ref label } => {
-                let mut opcodes = vec![0xc7];
-
-                let label_offset =
-                    self.label_mapping
-                        .get(label)
-                        .ok_or(CodegenError::OpcodeError {
-                            opcode: "ifnull",
-                            details: "invalid label",
-                        })?;
-
-                let offset = label_offset - self.curr_code_offset;
-                opcodes.extend_from_slice(&offset.to_be_bytes());
-
-                CodegenResultType::ByteVec(opcodes)
+                CodegenResultType::ByteVec(self.gen_branch(0xc7, "ifnonnull", label)?)
             }
 
             // check: wide
@@ -1739,6 +4202,38 @@ where
                 CodegenResultType::ByteVec(opcodes)
             }
 
+            Invokedynamic {
+                ref method_name,
+                ref method_descriptor,
+                ref bootstrap_method,
+            } => {
+                let mut opcodes = vec![0xba];
+
+                let bootstrap_method_attr_index = *self
+                    .bootstrap_methods
+                    .get(bootstrap_method)
+                    .ok_or(CodegenError::OpcodeError {
+                        opcode: "invokedynamic",
+                        details: "unknown bootstrap method",
+                    })?;
+
+                let invoke_dynamic_index = *cp
+                    .get_invoke_dynamic(
+                        bootstrap_method_attr_index,
+                        method_name,
+                        &method_descriptor.to_string(),
+                    )
+                    .ok_or(CodegenError::OpcodeError {
+                        opcode: "invokedynamic",
+                        details: "missing invokedynamic call site specifier",
+                    })?;
+
+                opcodes.extend_from_slice(&invoke_dynamic_index.to_be_bytes());
+                opcodes.extend_from_slice(&[0, 0]); // as per the spec
+
+                CodegenResultType::ByteVec(opcodes)
+            }
+
             Invokeinterface {
                 ref interface_name,
                 ref method_name,
@@ -1840,22 +4335,7 @@ where
             Iushr => CodegenResultType::ByteVec(vec![0x7c]),
             Ixor => CodegenResultType::ByteVec(vec![0x82]),
 
-            Jsr { ref label } => {
-                let mut opcodes = vec![0xa8];
-
-                let label_offset =
-                    self.label_mapping
-                        .get(label)
-                        .ok_or(CodegenError::OpcodeError {
-                            opcode: "jsr",
-                            details: "missing label",
-                        })?;
-
-                let offset = label_offset - self.curr_code_offset;
-                opcodes.extend_from_slice(&offset.to_be_bytes());
-
-                CodegenResultType::ByteVec(opcodes)
-            }
+            Jsr { ref label } => CodegenResultType::ByteVec(self.gen_branch(0xa8, "jsr", label)?),
 
             Jsrw { ref label } => {
                 let mut opcodes = vec![0xc9];
@@ -1868,7 +4348,7 @@ where
                             details: "missing label",
                         })?;
 
-                let offset = (label_offset - self.curr_code_offset) as i32;
+                let offset = *label_offset as i32 - self.curr_code_offset as i32;
                 opcodes.extend_from_slice(&offset.to_be_bytes());
 
                 CodegenResultType::ByteVec(opcodes)
@@ -1886,38 +4366,18 @@ where
             Lconst1 => CodegenResultType::ByteVec(vec![0x0a]),
 
             Ldc(ref ldc_val) => {
-                let mut opcodes = vec![0x12];
-
-                match ldc_val {
-                    LdcValue::QuotedString(ref string) => {
-                        let string_index =
-                            *cp.get_string(string).ok_or(CodegenError::OpcodeError {
-                                opcode: "ldc",
-                                details: "missing quoted string",
-                            })?;
-
-                        opcodes.extend_from_slice(&string_index.to_be_bytes()[1..])
-                    }
-
-                    LdcValue::Integer(int) => {
-                        let int_index = *cp.get_integer(*int).ok_or(CodegenError::OpcodeError {
-                            opcode: "ldc",
-                            details: "missing integer",
-                        })?;
-                        opcodes.push(int_index.to_be_bytes()[1]);
-                    }
-
-                    LdcValue::Float(float) => {
-                        let float_index =
-                            *cp.get_float(*float).ok_or(CodegenError::OpcodeError {
-                                opcode: "lcd",
-                                details: "missing float",
-                            })?;
-                        opcodes.push(float_index.to_be_bytes()[1]);
+                let cp_index = self.get_ldc_index(ldc_val, cp)?;
+
+                // ldc only has room for a single-byte index - anything beyond that needs ldc_w,
+                // which `gen_label_mappings` has already accounted for
+                CodegenResultType::ByteVec(match u8::try_from(cp_index) {
+                    Ok(cp_index) => vec![0x12, cp_index],
+                    Err(_) => {
+                        let mut opcodes = vec![0x13];
+                        opcodes.extend_from_slice(&cp_index.to_be_bytes());
+                        opcodes
                     }
-                }
-
-                CodegenResultType::ByteVec(opcodes)
+                })
             }
 
             Ldcw(ref ldcw_val) => {
@@ -1950,6 +4410,45 @@ where
                             })?;
                         opcodes.extend_from_slice(&float_index.to_be_bytes());
                     }
+
+                    LdcwValue::Class(ref class_name) => {
+                        let class_index =
+                            *cp.get_class(class_name).ok_or(CodegenError::OpcodeError {
+                                opcode: "ldcw",
+                                details: "missing class",
+                            })?;
+                        opcodes.extend_from_slice(&class_index.to_be_bytes());
+                    }
+
+                    LdcwValue::MethodType(ref method_descriptor) => {
+                        let method_type_index = *cp
+                            .get_method_type(&method_descriptor.to_string())
+                            .ok_or(CodegenError::OpcodeError {
+                                opcode: "ldcw",
+                                details: "missing method type",
+                            })?;
+                        opcodes.extend_from_slice(&method_type_index.to_be_bytes());
+                    }
+
+                    LdcwValue::MethodHandle(ref method_handle) => {
+                        let method_handle_index = *cp.get_method_handle(method_handle).ok_or(
+                            CodegenError::OpcodeError {
+                                opcode: "ldcw",
+                                details: "missing method handle",
+                            },
+                        )?;
+                        opcodes.extend_from_slice(&method_handle_index.to_be_bytes());
+                    }
+
+                    LdcwValue::Dynamic(ref dynamic_constant) => {
+                        let dynamic_index = *self
+                            .get_dynamic_constant_index(dynamic_constant, cp)
+                            .ok_or(CodegenError::OpcodeError {
+                                opcode: "ldcw",
+                                details: "missing dynamic constant",
+                            })?;
+                        opcodes.extend_from_slice(&dynamic_index.to_be_bytes());
+                    }
                 }
 
                 CodegenResultType::ByteVec(opcodes)
@@ -1975,6 +4474,16 @@ where
                             })?;
                         opcodes.extend_from_slice(&double_index.to_be_bytes());
                     }
+
+                    Ldc2wValue::Dynamic(ref dynamic_constant) => {
+                        let dynamic_index = *self
+                            .get_dynamic_constant_index(dynamic_constant, cp)
+                            .ok_or(CodegenError::OpcodeError {
+                                opcode: "ldc2w",
+                                details: "missing dynamic constant",
+                            })?;
+                        opcodes.extend_from_slice(&dynamic_index.to_be_bytes());
+                    }
                 }
 
                 CodegenResultType::ByteVec(opcodes)
@@ -2017,7 +4526,8 @@ where
                             details: "missing default label",
                         })?;
 
-                let default_label_offset = (default_label_offset - self.curr_code_offset) as i32;
+                let default_label_offset =
+                    *default_label_offset as i32 - self.curr_code_offset as i32;
                 opcodes.extend_from_slice(&default_label_offset.to_be_bytes());
 
                 // number of match pairs
@@ -2035,7 +4545,7 @@ where
                                 details: "missing label for switch pair",
                             })?;
 
-                    let offset = (label_offset - self.curr_code_offset) as i32;
+                    let offset = *label_offset as i32 - self.curr_code_offset as i32;
                     opcodes.extend_from_slice(&offset.to_be_bytes());
                 }
 
@@ -2197,7 +4707,7 @@ where
                         })?;
 
                 let default_label_offset =
-                    (4 + default_label_offset - self.curr_code_offset) as i32;
+                    *default_label_offset as i32 - self.curr_code_offset as i32;
                 opcodes.extend_from_slice(&default_label_offset.to_be_bytes());
 
                 // low and high
@@ -2214,7 +4724,7 @@ where
                                 details: "missing label for switch offset",
                             })?;
 
-                    let offset = (4 + label_offset - self.curr_code_offset) as i32;
+                    let offset = *label_offset as i32 - self.curr_code_offset as i32;
                     opcodes.extend_from_slice(&offset.to_be_bytes());
                 }
 
@@ -2300,3 +4810,632 @@ where
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        cp_analyzer::ConstantPoolAnalyzer, lexer::Lexer, parser::Parser, sourcefile::SourceFile,
+    };
+
+    use std::path::Path;
+
+    /// Generate the class file for the program, and return it along with the warnings.
+    fn assemble(
+        program: &PhoronProgram,
+        warn_ldc_promotion: bool,
+    ) -> CodegenResult<(ClassFile, Vec<CodegenWarning>)> {
+        let cp = ConstantPoolAnalyzer::new().analyze(program).unwrap();
+
+        let mut bytes = Vec::new();
+        let mut codegen = Codegen::new(&mut bytes);
+        codegen.warn_ldc_promotion(warn_ldc_promotion);
+        codegen.gen_bytecode(program, &cp)?;
+
+        Ok((
+            std::mem::take(&mut codegen.classfile),
+            std::mem::take(&mut codegen.warnings),
+        ))
+    }
+
+    /// Parse the sample, which must be free of syntax errors, and generate its class file.
+    fn assemble_sample<P: AsRef<Path>>(
+        testfile: P,
+    ) -> CodegenResult<(ClassFile, Vec<CodegenWarning>)> {
+        let source_file = SourceFile::new(testfile.as_ref()).unwrap();
+        let mut parser = Parser::new(Lexer::new(&source_file));
+        let program = parser.parse().unwrap();
+        assert!(!parser.errored());
+
+        assemble(&program, false)
+    }
+
+    /// The program whose `run` method declares a stack and locals of 2 each, and has the given
+    /// parameters and code.
+    fn run_method(
+        param_descriptor: Vec<PhoronFieldDescriptor>,
+        code: Vec<PhoronInstruction>,
+    ) -> PhoronProgram {
+        let mut instructions = vec![
+            directive(PhoronDirective::LimitStack(2)),
+            directive(PhoronDirective::LimitLocals(2)),
+        ];
+        instructions.extend(code);
+
+        PhoronProgram::with_method(param_descriptor, instructions)
+    }
+
+    fn jvm(instr: JvmInstruction) -> PhoronInstruction {
+        PhoronInstruction::JvmInstruction(instr)
+    }
+
+    fn label(name: &str) -> PhoronInstruction {
+        PhoronInstruction::PhoronLabel(name.to_string())
+    }
+
+    fn directive(directive: PhoronDirective) -> PhoronInstruction {
+        PhoronInstruction::PhoronDirective(directive)
+    }
+
+    /// `nop` instructions taking up `length` bytes, which is enough to put branches out of range
+    /// of a 16-bit offset when `length` is above 32767.
+    fn padding(length: usize) -> impl Iterator<Item = PhoronInstruction> {
+        (0..length).map(|_| jvm(JvmInstruction::Nop))
+    }
+
+    fn var(varnum: u16, from_label: &str, to_label: &str) -> PhoronInstruction {
+        directive(PhoronDirective::Var {
+            varnum,
+            name: format!("var{varnum}"),
+            field_descriptor: PhoronFieldDescriptor::BaseType(PhoronBaseType::Integer),
+            signature: None,
+            from_label: from_label.to_string(),
+            to_label: to_label.to_string(),
+        })
+    }
+
+    /// The `Code` attribute of the first method of the class file.
+    fn method_code(classfile: &ClassFile) -> &AttributeInfo {
+        classfile.methods[0]
+            .attributes
+            .iter()
+            .find(|attr| matches!(attr, AttributeInfo::Code { .. }))
+            .unwrap()
+    }
+
+    /// The code of the first method of the class file.
+    fn code(classfile: &ClassFile) -> &[u8] {
+        match method_code(classfile) {
+            AttributeInfo::Code { ref code, .. } => code,
+            _ => unreachable!(),
+        }
+    }
+
+    /// The `start_pc`, `length`, and `index` of the entries of the `LocalVariableTable` of the first
+    /// method of the class file.
+    fn local_variable_table(classfile: &ClassFile) -> Vec<(u16, u16, u16)> {
+        match method_code(classfile) {
+            AttributeInfo::Code {
+                ref code_attributes,
+                ..
+            } => code_attributes
+                .iter()
+                .find_map(|attr| match attr {
+                    AttributeInfo::LocalVariableTable {
+                        ref local_variable_table,
+                        ..
+                    } => Some(
+                        local_variable_table
+                            .iter()
+                            .map(|local_var| {
+                                (local_var.start_pc, local_var.length, local_var.index)
+                            })
+                            .collect(),
+                    ),
+                    _ => None,
+                })
+                .unwrap(),
+            _ => unreachable!(),
+        }
+    }
+
+    fn read_i32(code: &[u8], offset: usize) -> i32 {
+        i32::from_be_bytes(code[offset..offset + 4].try_into().unwrap())
+    }
+
+    #[test]
+    fn test_local_variable_table_index() -> CodegenResult<()> {
+        use JvmInstruction::*;
+
+        let (classfile, _) = assemble(
+            &run_method(
+                vec![
+                    PhoronFieldDescriptor::BaseType(PhoronBaseType::Long),
+                    PhoronFieldDescriptor::BaseType(PhoronBaseType::Integer),
+                ],
+                vec![
+                    var(3, "Label1", "Label2"),
+                    var(0, "Label1", "Label2"),
+                    label("Label1"),
+                    jvm(Iload2),
+                    jvm(Istore3),
+                    label("Label2"),
+                    jvm(Return),
+                ],
+            ),
+            false,
+        )?;
+
+        // the index of an entry is the local variable slot, not the position of the entry
+        assert_eq!(vec![(0, 2, 3), (0, 2, 0)], local_variable_table(&classfile));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_local_variable_range_to_end() -> CodegenResult<()> {
+        use JvmInstruction::*;
+
+        let (classfile, _) = assemble(
+            &run_method(
+                vec![],
+                vec![
+                    var(0, "Start", ""),
+                    var(1, "", ""),
+                    jvm(Iconst0),
+                    jvm(Istore0),
+                    label("Start"),
+                    jvm(Iconst1),
+                    jvm(Istore1),
+                    jvm(Return),
+                ],
+            ),
+            false,
+        )?;
+
+        // without a `to` label, the range extends to the end of the code
+        assert_eq!(vec![(2, 3, 0), (0, 5, 1)], local_variable_table(&classfile));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_widen_goto_and_jsr() -> CodegenResult<()> {
+        use JvmInstruction::*;
+
+        let mut instructions = vec![
+            jvm(Goto {
+                label: "Far".to_string(),
+            }),
+            jvm(Jsr {
+                label: "Far".to_string(),
+            }),
+        ];
+        instructions.extend(padding(40000));
+        instructions.extend([label("Far"), jvm(Return)]);
+
+        let (classfile, _) = assemble(&run_method(vec![], instructions), false)?;
+        let code = code(&classfile);
+        let far = 5 + 5 + 40000;
+
+        assert_eq!(0xc8, code[0]); // goto_w
+        assert_eq!(far as i32, read_i32(code, 1));
+        assert_eq!(0xc9, code[5]); // jsr_w
+        assert_eq!(far as i32 - 5, read_i32(code, 6));
+        assert_eq!(far + 1, code.len());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_widen_conditional_branches() -> CodegenResult<()> {
+        use JvmInstruction::*;
+
+        let far = || "Far".to_string();
+        let mut instructions = vec![
+            jvm(Iload0),
+            jvm(Ifeq { label: far() }),
+            jvm(Aload1),
+            jvm(Ifnonnull { label: far() }),
+            jvm(Iload0),
+            jvm(Iload0),
+            jvm(Ificmpgt { label: far() }),
+        ];
+        instructions.extend(padding(40000));
+        instructions.extend([label("Far"), jvm(Return)]);
+
+        let (classfile, _) = assemble(
+            &run_method(
+                vec![
+                    PhoronFieldDescriptor::BaseType(PhoronBaseType::Integer),
+                    PhoronFieldDescriptor::ObjectType {
+                        class_name: "java/lang/Object".to_string(),
+                    },
+                ],
+                instructions,
+            ),
+            false,
+        )?;
+        let code = code(&classfile);
+        let far = 3 * (1 + 8) + 1 + 40000;
+
+        // each branch jumps over a `goto_w` to the target on the inverse condition, and the
+        // offset of the `goto_w` is relative to itself, 3 bytes past the branch
+        for (branch, inverse) in [(1, 0x9a), (10, 0xc6), (20, 0xa4)] {
+            assert_eq!([inverse, 0x00, 0x08, 0xc8], code[branch..branch + 4]);
+            assert_eq!((far - branch - 3) as i32, read_i32(code, branch + 4));
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_widen_before_switches() -> CodegenResult<()> {
+        use JvmInstruction::*;
+
+        let far = || "Far".to_string();
+        let mut instructions = vec![
+            jvm(Goto { label: far() }),
+            label("Switches"),
+            jvm(Iload0),
+            jvm(Tableswitch {
+                low: 0,
+                high: 1,
+                switches: vec!["Switches".to_string(), far()],
+                default: far(),
+            }),
+            jvm(Goto { label: far() }),
+            jvm(Iload0),
+            jvm(Lookupswitch {
+                switches: vec![LookupSwitchPair {
+                    key: 1,
+                    label: "Switches".to_string(),
+                }],
+                default: far(),
+            }),
+        ];
+        instructions.extend(padding(40000));
+        instructions.extend([label("Far"), jvm(Return)]);
+
+        let (classfile, _) = assemble(
+            &run_method(
+                vec![PhoronFieldDescriptor::BaseType(PhoronBaseType::Integer)],
+                instructions,
+            ),
+            false,
+        )?;
+        let code = code(&classfile);
+
+        // widening each `goto` moves the switch following it by 2 bytes, from offset 4 to 6 (and
+        // from 32 to 34), and so shrinks its padding from 3 bytes to 1
+        let (switches, tableswitch, lookupswitch) = (5, 6, 34);
+        let far = 52 + 40000;
+
+        assert_eq!(0xc8, code[0]);
+        assert_eq!(far as i32, read_i32(code, 1));
+
+        assert_eq!([0xaa, 0x00], code[tableswitch..tableswitch + 2]);
+        assert_eq!((far - tableswitch) as i32, read_i32(code, 8)); // default
+        assert_eq!([0, 1], [read_i32(code, 12), read_i32(code, 16)]);
+        assert_eq!(
+            [
+                switches as i32 - tableswitch as i32,
+                (far - tableswitch) as i32
+            ],
+            [read_i32(code, 20), read_i32(code, 24)]
+        );
+
+        assert_eq!(0xc8, code[28]);
+        assert_eq!((far - 28) as i32, read_i32(code, 29));
+
+        assert_eq!([0xab, 0x00], code[lookupswitch..lookupswitch + 2]);
+        assert_eq!((far - lookupswitch) as i32, read_i32(code, 36)); // default
+        assert_eq!(1, read_i32(code, 40)); // npairs
+        assert_eq!(
+            [1, switches as i32 - lookupswitch as i32],
+            [read_i32(code, 44), read_i32(code, 48)]
+        );
+        assert_eq!(far + 1, code.len());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_limit_too_small() -> CodegenResult<()> {
+        use JvmInstruction::*;
+
+        let (classfile, warnings) = assemble(
+            &PhoronProgram::with_method(
+                vec![],
+                vec![
+                    directive(PhoronDirective::LimitStack(1)),
+                    jvm(Lconst0),
+                    jvm(Lstore0),
+                    jvm(Return),
+                ],
+            ),
+            false,
+        )?;
+
+        // the declared limit is kept, and the computed one is used for the missing limit
+        assert!(matches!(
+            method_code(&classfile),
+            AttributeInfo::Code {
+                max_stack: 1,
+                max_locals: 2,
+                ..
+            }
+        ));
+        assert_eq!(
+            vec![CodegenWarning::LimitTooSmall {
+                method: "run".to_string(),
+                limit: "stack",
+                declared: 1,
+                computed: 2,
+            }],
+            warnings
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_limits_not_computed() -> CodegenResult<()> {
+        use JvmInstruction::*;
+
+        // the code underflows the operand stack, so its limits cannot be computed
+        let code = || vec![jvm(Pop), jvm(Return)];
+
+        let mut instructions = vec![directive(PhoronDirective::LimitStack(3))];
+        instructions.extend(code());
+        let (classfile, warnings) =
+            assemble(&PhoronProgram::with_method(vec![], instructions), false)?;
+
+        assert!(matches!(
+            method_code(&classfile),
+            AttributeInfo::Code {
+                max_stack: 3,
+                max_locals: 1,
+                ..
+            }
+        ));
+        assert!(matches!(
+            warnings[..],
+            [CodegenWarning::LimitsNotComputed { ref method, .. }] if method == "run"
+        ));
+
+        // with both limits declared, there is nothing to compute, and it is up to the verifier to
+        // reject the code
+        let (classfile, warnings) = assemble(&run_method(vec![], code()), false)?;
+
+        assert!(matches!(
+            method_code(&classfile),
+            AttributeInfo::Code {
+                max_stack: 2,
+                max_locals: 2,
+                ..
+            }
+        ));
+        assert!(warnings.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_code_too_long() {
+        let mut instructions = padding(70000).collect::<Vec<_>>();
+        instructions.push(jvm(JvmInstruction::Return));
+
+        let err = assemble(&run_method(vec![], instructions), false).unwrap_err();
+
+        assert!(matches!(
+            err,
+            CodegenError::CodeTooLong {
+                ref method,
+                code_length: 70001,
+            } if method == "run"
+        ));
+    }
+
+    #[test]
+    fn test_line_number_offset_too_large() {
+        let mut instructions = padding(65536).collect::<Vec<_>>();
+        instructions.extend([
+            directive(PhoronDirective::LineNumber(42)),
+            jvm(JvmInstruction::Return),
+        ]);
+
+        let err = assemble(&run_method(vec![], instructions), false).unwrap_err();
+
+        assert!(matches!(
+            err,
+            CodegenError::CodeOffsetTooLarge {
+                ref method,
+                offset: 65536,
+                ..
+            } if method == "run"
+        ));
+    }
+
+    #[test]
+    fn test_local_variable_offset_too_large() {
+        let mut instructions = vec![var(0, "Start", "End"), label("Start")];
+        instructions.extend(padding(65536));
+        instructions.extend([label("End"), jvm(JvmInstruction::Return)]);
+
+        let err = assemble(&run_method(vec![], instructions), false).unwrap_err();
+
+        assert!(matches!(
+            err,
+            CodegenError::CodeOffsetTooLarge {
+                ref method,
+                offset: 65536,
+                ..
+            } if method == "run"
+        ));
+    }
+
+    #[test]
+    fn test_local_variable_range_reversed() {
+        use JvmInstruction::*;
+
+        let err = assemble(
+            &run_method(
+                vec![],
+                vec![
+                    var(0, "End", "Start"),
+                    label("Start"),
+                    jvm(Iconst0),
+                    jvm(Istore0),
+                    label("End"),
+                    jvm(Return),
+                ],
+            ),
+            false,
+        )
+        .unwrap_err();
+
+        assert!(matches!(
+            err,
+            CodegenError::CodeRangeReversed {
+                ref method,
+                start_pc: 2,
+                end_pc: 0,
+                ..
+            } if method == "run"
+        ));
+    }
+
+    #[test]
+    fn test_local_variable_type_annotation_range_reversed() {
+        let err =
+            assemble_sample("samples/invalid/ReversedLocalVarTypeAnnotation.pho").unwrap_err();
+
+        assert!(matches!(
+            err,
+            CodegenError::CodeRangeReversed {
+                ref method,
+                ref directive,
+                start_pc: 2,
+                end_pc: 0,
+            } if method == "greet" && directive == "`.annotation type localvar 0`"
+        ));
+    }
+
+    #[test]
+    fn test_exception_handler_offset_too_large() {
+        let mut instructions = vec![
+            directive(PhoronDirective::Catch {
+                class_name: "all".to_string(),
+                from_label: "Start".to_string(),
+                to_label: "End".to_string(),
+                handler_label: "Handler".to_string(),
+            }),
+            label("Start"),
+            jvm(JvmInstruction::Nop),
+            label("End"),
+        ];
+        instructions.extend(padding(65535));
+        instructions.extend([label("Handler"), jvm(JvmInstruction::Athrow)]);
+
+        let err = assemble(&run_method(vec![], instructions), false).unwrap_err();
+
+        assert!(matches!(
+            err,
+            CodegenError::CodeOffsetTooLarge {
+                ref method,
+                offset: 65536,
+                ..
+            } if method == "run"
+        ));
+    }
+
+    /// The program whose method loads 300 distinct `int` constants - enough for the later ones to
+    /// be beyond a single-byte index - between two branches over them.
+    fn ldc_program() -> PhoronProgram {
+        use JvmInstruction::*;
+
+        let mut instructions = vec![
+            jvm(Goto {
+                label: "End".to_string(),
+            }),
+            label("Loads"),
+        ];
+        for int in 0..300 {
+            instructions.extend([jvm(Ldc(LdcValue::Integer(100_000 + int))), jvm(Pop)]);
+        }
+        instructions.extend([
+            jvm(Goto {
+                label: "Loads".to_string(),
+            }),
+            label("End"),
+            jvm(Return),
+        ]);
+
+        run_method(vec![], instructions)
+    }
+
+    #[test]
+    fn test_ldc_promotion() -> CodegenResult<()> {
+        let (classfile, _) = assemble(&ldc_program(), false)?;
+        let code = code(&classfile);
+
+        // walk the loads, which start right after the first `goto`
+        let loads = 3;
+        let mut offset = loads;
+        let (mut narrow, mut wide) = (0, 0);
+        while code[offset] != 0xa7 {
+            match code[offset] {
+                0x12 => {
+                    narrow += 1;
+                    offset += 2;
+                }
+                0x13 => {
+                    assert!(u16::from_be_bytes([code[offset + 1], code[offset + 2]]) > 255);
+                    wide += 1;
+                    offset += 3;
+                }
+                opcode => panic!("unexpected opcode {opcode:#x} at offset {offset}"),
+            }
+            assert_eq!(0x57, code[offset]); // pop
+            offset += 1;
+        }
+
+        assert_eq!(300, narrow + wide);
+        assert!(narrow > 0 && wide > 0);
+        assert_eq!(loads + 300 * 3 + wide, offset);
+
+        // both branches account for the extra byte of each promoted `ldc`
+        let end = offset + 3;
+        assert_eq!([0xa7], code[..1]);
+        assert_eq!(end as i16, i16::from_be_bytes([code[1], code[2]]));
+        assert_eq!(
+            loads as i16 - offset as i16,
+            i16::from_be_bytes([code[offset + 1], code[offset + 2]])
+        );
+        assert_eq!([0xb1], code[end..]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_ldc_promotion_warnings() -> CodegenResult<()> {
+        let ldc_promotions = |warnings: &[CodegenWarning]| {
+            warnings
+                .iter()
+                .filter(|warning| matches!(warning, CodegenWarning::LdcPromoted { method, .. } if method == "run"))
+                .count()
+        };
+
+        let (classfile, warnings) = assemble(&ldc_program(), true)?;
+        // each promoted `ldc` is 3 bytes long rather than 2
+        let wide = code(&classfile).len() - (3 + 300 * 3 + 3 + 1);
+        assert!(wide > 0);
+        assert_eq!(wide, ldc_promotions(&warnings));
+
+        let (_, warnings) = assemble(&ldc_program(), false)?;
+        assert_eq!(0, ldc_promotions(&warnings));
+
+        Ok(())
+    }
+}
@@ -1,3 +1,5 @@
+use crate::ast::PhoronMethodHandle;
+
 use std::collections::{hash_map::Iter, HashMap};
 
 #[derive(Debug, PartialEq, Hash, Eq)]
@@ -6,6 +8,10 @@ pub enum PhoronConstantPoolKind {
         name_index: u16,
     },
     Double([u8; 8]),
+    Dynamic {
+        bootstrap_method_attr_index: u16,
+        name_and_type_index: u16,
+    },
     Fieldref {
         class_index: u16,
         name_and_type_index: u16,
@@ -16,21 +22,121 @@ pub enum PhoronConstantPoolKind {
         class_index: u16,
         name_and_type_index: u16,
     },
+    InvokeDynamic {
+        bootstrap_method_attr_index: u16,
+        name_and_type_index: u16,
+    },
     Long([u8; 8]),
+    MethodHandle {
+        reference_kind: u8,
+        reference_index: u16,
+    },
+    MethodType {
+        descriptor_index: u16,
+    },
     Methodref {
         class_index: u16,
         name_and_type_index: u16,
     },
+    Module {
+        name_index: u16,
+    },
     NameAndType {
         name_index: u16,
         descriptor_index: u16,
     },
+    Package {
+        name_index: u16,
+    },
     String {
         string_index: u16,
     },
     Utf8(String),
 }
 
+impl PhoronConstantPoolKind {
+    /// The number of Constant Pool slots taken up by the entry.
+    pub fn slots(&self) -> u16 {
+        match self {
+            PhoronConstantPoolKind::Long(_) | PhoronConstantPoolKind::Double(_) => 2,
+            _ => 1,
+        }
+    }
+
+    /// Replace the Constant Pool indices that the entry refers to, using the given mapping.
+    fn map_indices(self, f: impl Fn(u16) -> u16) -> Self {
+        use PhoronConstantPoolKind::*;
+
+        match self {
+            Class { name_index } => Class {
+                name_index: f(name_index),
+            },
+            Dynamic {
+                bootstrap_method_attr_index,
+                name_and_type_index,
+            } => Dynamic {
+                bootstrap_method_attr_index,
+                name_and_type_index: f(name_and_type_index),
+            },
+            Fieldref {
+                class_index,
+                name_and_type_index,
+            } => Fieldref {
+                class_index: f(class_index),
+                name_and_type_index: f(name_and_type_index),
+            },
+            InterfaceMethodref {
+                class_index,
+                name_and_type_index,
+            } => InterfaceMethodref {
+                class_index: f(class_index),
+                name_and_type_index: f(name_and_type_index),
+            },
+            InvokeDynamic {
+                bootstrap_method_attr_index,
+                name_and_type_index,
+            } => InvokeDynamic {
+                bootstrap_method_attr_index,
+                name_and_type_index: f(name_and_type_index),
+            },
+            MethodHandle {
+                reference_kind,
+                reference_index,
+            } => MethodHandle {
+                reference_kind,
+                reference_index: f(reference_index),
+            },
+            MethodType { descriptor_index } => MethodType {
+                descriptor_index: f(descriptor_index),
+            },
+            Methodref {
+                class_index,
+                name_and_type_index,
+            } => Methodref {
+                class_index: f(class_index),
+                name_and_type_index: f(name_and_type_index),
+            },
+            Module { name_index } => Module {
+                name_index: f(name_index),
+            },
+            NameAndType {
+                name_index,
+                descriptor_index,
+            } => NameAndType {
+                name_index: f(name_index),
+                descriptor_index: f(descriptor_index),
+            },
+            Package { name_index } => Package {
+                name_index: f(name_index),
+            },
+            String { string_index } => String {
+                string_index: f(string_index),
+            },
+            Double(_) | Float(_) | Integer(_) | Long(_) | Utf8(_) => self,
+        }
+    }
+}
+
 // Map from Constant Pool entries to indices
 #[derive(Debug, PartialEq)]
 pub struct PhoronConstantPool(pub(super) HashMap<PhoronConstantPoolKind, u16>);
@@ -49,14 +155,38 @@ impl PhoronConstantPool {
         let mut len = 0;
 
         for (cp_kind, _) in &self.0 {
-            match cp_kind {
-                PhoronConstantPoolKind::Long(_) | PhoronConstantPoolKind::Double(_) => len += 2,
-                _ => len += 1,
-            }
+            len += cp_kind.slots() as usize;
         }
         len
     }
 
+    /// Reassign the indices of the Constant Pool in increasing order of the given key (and of the
+    /// current index, for entries with the same key), rewriting the references between entries to
+    /// match. The Constant Pool keeps its size, since only the order of the entries changes.
+    pub fn relayout<K: Ord>(self, sort_key: impl Fn(&PhoronConstantPoolKind, u16) -> K) -> Self {
+        let mut entries = self.0.into_iter().collect::<Vec<_>>();
+        entries.sort_by_cached_key(|(cp_kind, cp_index)| (sort_key(cp_kind, *cp_index), *cp_index));
+
+        let mut new_indices = HashMap::new();
+        let mut next_cp_index = 1;
+        for (cp_kind, cp_index) in &entries {
+            new_indices.insert(*cp_index, next_cp_index);
+            next_cp_index += cp_kind.slots();
+        }
+
+        PhoronConstantPool(
+            entries
+                .into_iter()
+                .map(|(cp_kind, cp_index)| {
+                    (
+                        cp_kind.map_indices(|index| new_indices[&index]),
+                        new_indices[&cp_index],
+                    )
+                })
+                .collect(),
+        )
+    }
+
     /// Return an iterator over the contents of the Constant Pool.
     pub fn iter(&self) -> Iter<'_, PhoronConstantPoolKind, u16> {
         self.0.iter()
@@ -115,6 +245,24 @@ impl PhoronConstantPool {
         })
     }
 
+    /// Retrieve the index in the Constant Pool, if present, of the given module.
+    pub fn get_module(&self, module_name: &str) -> Option<&u16> {
+        self.get_name(module_name).and_then(|name_index| {
+            self.0.get(&PhoronConstantPoolKind::Module {
+                name_index: *name_index,
+            })
+        })
+    }
+
+    /// Retrieve the index in the Constant Pool, if present, of the given package.
+    pub fn get_package(&self, package_name: &str) -> Option<&u16> {
+        self.get_name(package_name).and_then(|name_index| {
+            self.0.get(&PhoronConstantPoolKind::Package {
+                name_index: *name_index,
+            })
+        })
+    }
+
     /// Retrieve the index in the Constant Pool, if present, of the given NamdAndType.
     pub fn get_name_and_type(&self, name: &str, descriptor: &str) -> Option<&u16> {
         self.get_name(name).and_then(|name_index| {
@@ -162,4 +310,173 @@ impl PhoronConstantPool {
                 })
         })
     }
+
+    /// Retrieve the index in the Constant Pool, if present, of the given InterfaceMethodref.
+    pub fn get_interface_methodref(
+        &self,
+        interface_name: &str,
+        method_name: &str,
+        method_descriptor: &str,
+    ) -> Option<&u16> {
+        self.get_class(interface_name).and_then(|class_index| {
+            self.get_name_and_type(method_name, method_descriptor)
+                .and_then(|name_and_type_index| {
+                    self.0.get(&PhoronConstantPoolKind::InterfaceMethodref {
+                        class_index: *class_index,
+                        name_and_type_index: *name_and_type_index,
+                    })
+                })
+        })
+    }
+
+    /// Retrieve the index in the Constant Pool, if present, of the given MethodType.
+    pub fn get_method_type(&self, method_descriptor: &str) -> Option<&u16> {
+        self.get_name(method_descriptor)
+            .and_then(|descriptor_index| {
+                self.0.get(&PhoronConstantPoolKind::MethodType {
+                    descriptor_index: *descriptor_index,
+                })
+            })
+    }
+
+    /// Retrieve the index in the Constant Pool, if present, of the given MethodHandle.
+    pub fn get_method_handle(&self, method_handle: &PhoronMethodHandle) -> Option<&u16> {
+        use PhoronMethodHandle::*;
+
+        let reference_index = match method_handle {
+            Getfield {
+                ref class_name,
+                ref field_name,
+                ref field_descriptor,
+            }
+            | Getstatic {
+                ref class_name,
+                ref field_name,
+                ref field_descriptor,
+            }
+            | Putfield {
+                ref class_name,
+                ref field_name,
+                ref field_descriptor,
+            }
+            | Putstatic {
+                ref class_name,
+                ref field_name,
+                ref field_descriptor,
+            } => self.get_fieldref(class_name, field_name, &field_descriptor.to_string()),
+
+            Invokestatic {
+                ref class_name,
+                ref method_name,
+                ref method_descriptor,
+                is_interface: true,
+            }
+            | Invokespecial {
+                ref class_name,
+                ref method_name,
+                ref method_descriptor,
+                is_interface: true,
+            }
+            | Invokeinterface {
+                interface_name: ref class_name,
+                ref method_name,
+                ref method_descriptor,
+            } => self.get_interface_methodref(
+                class_name,
+                method_name,
+                &method_descriptor.to_string(),
+            ),
+
+            Invokevirtual {
+                ref class_name,
+                ref method_name,
+                ref method_descriptor,
+            }
+            | Invokestatic {
+                ref class_name,
+                ref method_name,
+                ref method_descriptor,
+                ..
+            }
+            | Invokespecial {
+                ref class_name,
+                ref method_name,
+                ref method_descriptor,
+                ..
+            } => self.get_methodref(class_name, method_name, &method_descriptor.to_string()),
+
+            Newinvokespecial {
+                ref class_name,
+                ref method_descriptor,
+            } => self.get_methodref(class_name, "<init>", &method_descriptor.to_string()),
+        };
+
+        reference_index.and_then(|reference_index| {
+            self.0.get(&PhoronConstantPoolKind::MethodHandle {
+                reference_kind: method_handle.reference_kind(),
+                reference_index: *reference_index,
+            })
+        })
+    }
+
+    /// Retrieve the index in the Constant Pool, if present, of the given InvokeDynamic.
+    pub fn get_invoke_dynamic(
+        &self,
+        bootstrap_method_attr_index: u16,
+        method_name: &str,
+        method_descriptor: &str,
+    ) -> Option<&u16> {
+        self.get_name_and_type(method_name, method_descriptor)
+            .and_then(|name_and_type_index| {
+                self.0.get(&PhoronConstantPoolKind::InvokeDynamic {
+                    bootstrap_method_attr_index,
+                    name_and_type_index: *name_and_type_index,
+                })
+            })
+    }
+
+    /// Retrieve the index in the Constant Pool, if present, of the given Dynamic.
+    pub fn get_dynamic(
+        &self,
+        bootstrap_method_attr_index: u16,
+        name: &str,
+        field_descriptor: &str,
+    ) -> Option<&u16> {
+        self.get_name_and_type(name, field_descriptor)
+            .and_then(|name_and_type_index| {
+                self.0.get(&PhoronConstantPoolKind::Dynamic {
+                    bootstrap_method_attr_index,
+                    name_and_type_index: *name_and_type_index,
+                })
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_relayout() {
+        let mut cp = PhoronConstantPool::new();
+        cp.insert(
+            PhoronConstantPoolKind::Utf8("java/lang/Object".to_string()),
+            1,
+        );
+        cp.insert(PhoronConstantPoolKind::Class { name_index: 1 }, 2);
+        cp.insert(PhoronConstantPoolKind::Long(42i64.to_be_bytes()), 3);
+        cp.insert(PhoronConstantPoolKind::Utf8("hot".to_string()), 5);
+        cp.insert(PhoronConstantPoolKind::String { string_index: 5 }, 6);
+
+        // move the String to the front, and keep the rest in their current order
+        let cp =
+            cp.relayout(|cp_kind, _| !matches!(cp_kind, PhoronConstantPoolKind::String { .. }));
+
+        assert_eq!(6, cp.len());
+        assert_eq!(Some(&1), cp.get_string("hot"));
+        assert_eq!(Some(&2), cp.get_name("java/lang/Object"));
+        assert_eq!(Some(&3), cp.get_class("java/lang/Object"));
+        assert_eq!(Some(&4), cp.get_long(42));
+        assert_eq!(Some(&6), cp.get_name("hot"));
+    }
 }
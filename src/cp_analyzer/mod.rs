@@ -2,19 +2,30 @@
 //! bytecode format).
 //!
 //! The indxeing of the Constant Pool elements is deterministic (the ordering is left unspecified
-//! in the JVM specification) and follows a top-down recursive approach.
+//! in the JVM specification) and follows a top-down recursive approach. Optionally, the entries are
+//! then laid out again so that those loaded by `ldc` get the lowest indices (see
+//! `ConstantPoolAnalyzer::optimize_layout`).
 //!
-use crate::ast::{attributes::*, *};
+use crate::{
+    ast::{attributes::*, *},
+    stack_map::{self, class_hierarchy::*, VerificationType},
+};
 
 pub mod constant_pool;
 
 use constant_pool::*;
 
-use std::fmt;
+use std::{
+    cmp::Reverse,
+    collections::{HashMap, HashSet},
+    fmt,
+    rc::Rc,
+};
 
 #[derive(Debug)]
 pub enum ConstantPoolAnalyzerError {
     IndexNotAvailable { component: &'static str },
+    ConstantPoolOverflow { slots: u32 },
 }
 
 impl std::error::Error for ConstantPoolAnalyzerError {}
@@ -30,6 +41,10 @@ impl fmt::Display for ConstantPoolAnalyzerError {
                 IndexNotAvailable { ref component } => {
                     format!("Constant Pool index not available for {component}")
                 }
+                ConstantPoolOverflow { slots } => format!(
+                    "the Constant Pool needs {slots} slots (`long` and `double` constants take up two), but at most {} are allowed",
+                    u16::MAX - 1
+                ),
             },
         )
     }
@@ -38,12 +53,76 @@ impl fmt::Display for ConstantPoolAnalyzerError {
 pub type ConstantPoolAnalyzerResult<T> = Result<T, ConstantPoolAnalyzerError>;
 
 pub struct ConstantPoolAnalyzer {
-    cp_index: u16,
+    /// the next free Constant Pool index. This may run past the `u16` indices of the class file,
+    /// which is reported once the analysis is done.
+    cp_index: u32,
+    /// the number of references to each Constant Pool entry, keyed by its index
+    use_counts: HashMap<u16, u32>,
+    /// the indices of the Constant Pool entries loaded by `ldc` instructions
+    ldc_indices: HashSet<u16>,
+    optimize_layout: bool,
+    bootstrap_methods: HashMap<String, u16>,
+    major_version: u16,
+    class_name: String,
+    super_class_name: String,
+    class_hierarchy: Rc<dyn ClassHierarchyResolver>,
 }
 
 impl ConstantPoolAnalyzer {
     pub fn new() -> Self {
-        ConstantPoolAnalyzer { cp_index: 1 } // index 0 is not allowed
+        ConstantPoolAnalyzer::with_class_hierarchy_resolver(Rc::new(
+            DefaultClassHierarchyResolver::new(),
+        ))
+    }
+
+    /// Create an analyzer which uses the given resolver when merging reference types for the stack
+    /// map frames. This should be the same resolver as used by `Codegen`.
+    pub fn with_class_hierarchy_resolver(class_hierarchy: Rc<dyn ClassHierarchyResolver>) -> Self {
+        ConstantPoolAnalyzer {
+            cp_index: 1, // index 0 is not allowed
+            use_counts: HashMap::new(),
+            ldc_indices: HashSet::new(),
+            optimize_layout: false,
+            bootstrap_methods: HashMap::new(),
+            major_version: PhoronVersionDef::default().major_version,
+            class_name: String::default(),
+            super_class_name: String::default(),
+            class_hierarchy,
+        }
+    }
+
+    /// Lay out the Constant Pool so that the entries loaded by `ldc` come first, followed by the
+    /// rest in decreasing order of use, rather than in the order of visitation. This lets more
+    /// `ldc` instructions use a one-byte index. This is off by default.
+    pub fn optimize_layout(&mut self, optimize: bool) {
+        self.optimize_layout = optimize;
+    }
+
+    /// Take up the next `slots` Constant Pool slots (two for `long` and `double` constants, one
+    /// otherwise), and return the index of the first.
+    fn next_cp_index(&mut self, slots: u32) -> u16 {
+        let curr_cp_index = self.cp_index;
+        self.cp_index += slots;
+
+        // indices past the end of the Constant Pool are never used, since `analyze` fails
+        curr_cp_index as u16
+    }
+
+    /// Return the index of the given entry, inserting it into the Constant Pool (taking up `slots`
+    /// slots) if not already present, and count the reference to it.
+    fn intern(
+        &mut self,
+        cp_kind: PhoronConstantPoolKind,
+        slots: u32,
+        cp: &mut PhoronConstantPool,
+    ) -> u16 {
+        let cp_index = *cp
+            .0
+            .entry(cp_kind)
+            .or_insert_with(|| self.next_cp_index(slots));
+        *self.use_counts.entry(cp_index).or_insert(0) += 1;
+
+        cp_index
     }
 
     /// check if the name (Utf8) is already in the constant pool, and if not, insert it.
@@ -53,14 +132,7 @@ impl ConstantPoolAnalyzer {
         name: &str,
         cp: &mut PhoronConstantPool,
     ) -> ConstantPoolAnalyzerResult<u16> {
-        Ok(*cp
-            .0
-            .entry(PhoronConstantPoolKind::Utf8(name.to_owned()))
-            .or_insert_with(|| {
-                let curr_cp_index = self.cp_index;
-                self.cp_index += 1;
-                curr_cp_index
-            }))
+        Ok(self.intern(PhoronConstantPoolKind::Utf8(name.to_owned()), 1, cp))
     }
 
     /// check if the name (Utf8) is already in the constant pool, and if not, insert it.
@@ -70,14 +142,7 @@ impl ConstantPoolAnalyzer {
         string_index: u16,
         cp: &mut PhoronConstantPool,
     ) -> ConstantPoolAnalyzerResult<u16> {
-        Ok(*cp
-            .0
-            .entry(PhoronConstantPoolKind::String { string_index })
-            .or_insert_with(|| {
-                let curr_cp_index = self.cp_index;
-                self.cp_index += 1;
-                curr_cp_index
-            }))
+        Ok(self.intern(PhoronConstantPoolKind::String { string_index }, 1, cp))
     }
 
     /// check if the int (i32) is already in the constant pool, and if not, insert it.
@@ -87,14 +152,7 @@ impl ConstantPoolAnalyzer {
         int: i32,
         cp: &mut PhoronConstantPool,
     ) -> ConstantPoolAnalyzerResult<u16> {
-        Ok(*cp
-            .0
-            .entry(PhoronConstantPoolKind::Integer(int.to_be_bytes()))
-            .or_insert_with(|| {
-                let curr_cp_index = self.cp_index;
-                self.cp_index += 1;
-                curr_cp_index
-            }))
+        Ok(self.intern(PhoronConstantPoolKind::Integer(int.to_be_bytes()), 1, cp))
     }
 
     /// check if the long (i64) is already in the constant pool, and if not, insert it.
@@ -104,14 +162,8 @@ impl ConstantPoolAnalyzer {
         long: i64,
         cp: &mut PhoronConstantPool,
     ) -> ConstantPoolAnalyzerResult<u16> {
-        Ok(*cp
-            .0
-            .entry(PhoronConstantPoolKind::Long(long.to_be_bytes()))
-            .or_insert_with(|| {
-                let curr_cp_index = self.cp_index;
-                self.cp_index += 2; // extra index slot for LongInfo
-                curr_cp_index
-            }))
+        // extra index slot for LongInfo
+        Ok(self.intern(PhoronConstantPoolKind::Long(long.to_be_bytes()), 2, cp))
     }
 
     /// check if the float (f32) is already in the constant pool, and if not, insert it.
@@ -121,14 +173,7 @@ impl ConstantPoolAnalyzer {
         float: f32,
         cp: &mut PhoronConstantPool,
     ) -> ConstantPoolAnalyzerResult<u16> {
-        Ok(*cp
-            .0
-            .entry(PhoronConstantPoolKind::Float(float.to_be_bytes()))
-            .or_insert_with(|| {
-                let curr_cp_index = self.cp_index;
-                self.cp_index += 1;
-                curr_cp_index
-            }))
+        Ok(self.intern(PhoronConstantPoolKind::Float(float.to_be_bytes()), 1, cp))
     }
     /// check if the double (f64) is already in the constant pool, and if not, insert it.
     /// Update the Constant Pool index accordingly.
@@ -137,14 +182,8 @@ impl ConstantPoolAnalyzer {
         double: f64,
         cp: &mut PhoronConstantPool,
     ) -> ConstantPoolAnalyzerResult<u16> {
-        Ok(*cp
-            .0
-            .entry(PhoronConstantPoolKind::Double(double.to_be_bytes()))
-            .or_insert_with(|| {
-                let curr_cp_index = self.cp_index;
-                self.cp_index += 2; // extra index slot for DoubleInfo
-                curr_cp_index
-            }))
+        // extra index slot for DoubleInfo
+        Ok(self.intern(PhoronConstantPoolKind::Double(double.to_be_bytes()), 2, cp))
     }
 
     /// check if the NameAndType is already in the constant pool, and if not, insert it.
@@ -155,17 +194,14 @@ impl ConstantPoolAnalyzer {
         descriptor_index: u16,
         cp: &mut PhoronConstantPool,
     ) -> ConstantPoolAnalyzerResult<u16> {
-        Ok(*cp
-            .0
-            .entry(PhoronConstantPoolKind::NameAndType {
+        Ok(self.intern(
+            PhoronConstantPoolKind::NameAndType {
                 name_index,
                 descriptor_index,
-            })
-            .or_insert_with(|| {
-                let curr_cp_index = self.cp_index;
-                self.cp_index += 1;
-                curr_cp_index
-            }))
+            },
+            1,
+            cp,
+        ))
     }
 
     /// check if the Fieldref is already in the constant pool, and if not, insert it.
@@ -176,17 +212,14 @@ impl ConstantPoolAnalyzer {
         name_and_type_index: u16,
         cp: &mut PhoronConstantPool,
     ) -> ConstantPoolAnalyzerResult<u16> {
-        Ok(*cp
-            .0
-            .entry(PhoronConstantPoolKind::Fieldref {
+        Ok(self.intern(
+            PhoronConstantPoolKind::Fieldref {
                 class_index,
                 name_and_type_index,
-            })
-            .or_insert_with(|| {
-                let curr_cp_index = self.cp_index;
-                self.cp_index += 1;
-                curr_cp_index
-            }))
+            },
+            1,
+            cp,
+        ))
     }
 
     /// check if the Methodref is already in the constant pool, and if not, insert it.
@@ -197,17 +230,391 @@ impl ConstantPoolAnalyzer {
         name_and_type_index: u16,
         cp: &mut PhoronConstantPool,
     ) -> ConstantPoolAnalyzerResult<u16> {
-        Ok(*cp
-            .0
-            .entry(PhoronConstantPoolKind::Methodref {
+        Ok(self.intern(
+            PhoronConstantPoolKind::Methodref {
                 class_index,
                 name_and_type_index,
+            },
+            1,
+            cp,
+        ))
+    }
+
+    /// check if the InterfaceMethodref is already in the constant pool, and if not, insert it.
+    /// Update the Constant Pool index accordingly.
+    fn analyze_interface_method_ref(
+        &mut self,
+        class_index: u16,
+        name_and_type_index: u16,
+        cp: &mut PhoronConstantPool,
+    ) -> ConstantPoolAnalyzerResult<u16> {
+        Ok(self.intern(
+            PhoronConstantPoolKind::InterfaceMethodref {
+                class_index,
+                name_and_type_index,
+            },
+            1,
+            cp,
+        ))
+    }
+
+    /// check if the MethodType is already in the constant pool, and if not, insert it.
+    /// Update the Constant Pool index accordingly.
+    fn analyze_method_type(
+        &mut self,
+        descriptor_index: u16,
+        cp: &mut PhoronConstantPool,
+    ) -> ConstantPoolAnalyzerResult<u16> {
+        Ok(self.intern(
+            PhoronConstantPoolKind::MethodType { descriptor_index },
+            1,
+            cp,
+        ))
+    }
+
+    /// check if the MethodHandle is already in the constant pool, and if not, insert it.
+    /// Update the Constant Pool index accordingly.
+    fn analyze_method_handle(
+        &mut self,
+        reference_kind: u8,
+        reference_index: u16,
+        cp: &mut PhoronConstantPool,
+    ) -> ConstantPoolAnalyzerResult<u16> {
+        Ok(self.intern(
+            PhoronConstantPoolKind::MethodHandle {
+                reference_kind,
+                reference_index,
+            },
+            1,
+            cp,
+        ))
+    }
+
+    /// check if the InvokeDynamic is already in the constant pool, and if not, insert it.
+    /// Update the Constant Pool index accordingly.
+    fn analyze_invoke_dynamic(
+        &mut self,
+        bootstrap_method_attr_index: u16,
+        name_and_type_index: u16,
+        cp: &mut PhoronConstantPool,
+    ) -> ConstantPoolAnalyzerResult<u16> {
+        Ok(self.intern(
+            PhoronConstantPoolKind::InvokeDynamic {
+                bootstrap_method_attr_index,
+                name_and_type_index,
+            },
+            1,
+            cp,
+        ))
+    }
+
+    /// check if the Dynamic is already in the constant pool, and if not, insert it.
+    /// Update the Constant Pool index accordingly.
+    fn analyze_dynamic(
+        &mut self,
+        bootstrap_method_attr_index: u16,
+        name_and_type_index: u16,
+        cp: &mut PhoronConstantPool,
+    ) -> ConstantPoolAnalyzerResult<u16> {
+        Ok(self.intern(
+            PhoronConstantPoolKind::Dynamic {
+                bootstrap_method_attr_index,
+                name_and_type_index,
+            },
+            1,
+            cp,
+        ))
+    }
+
+    /// check if the name, descriptor, and Dynamic entries of a dynamically-computed constant are
+    /// already in the constant pool, and if not, insert them. The bootstrap method must have been
+    /// declared before the constant is used.
+    fn analyze_dynamic_constant(
+        &mut self,
+        dynamic_constant: &PhoronDynamicConstant,
+        cp: &mut PhoronConstantPool,
+    ) -> ConstantPoolAnalyzerResult<u16> {
+        let bootstrap_method_attr_index = *self
+            .bootstrap_methods
+            .get(&dynamic_constant.bootstrap_method)
+            .ok_or(ConstantPoolAnalyzerError::IndexNotAvailable {
+                component: "bootstrap method",
+            })?;
+
+        let name_index = self.analyze_name(&dynamic_constant.name, cp)?;
+        let field_descriptor_index =
+            self.analyze_name(&dynamic_constant.field_descriptor.to_string(), cp)?;
+        let name_and_type_index =
+            self.analyze_name_and_type(name_index, field_descriptor_index, cp)?;
+
+        self.analyze_dynamic(bootstrap_method_attr_index, name_and_type_index, cp)
+    }
+
+    /// check if the class, name and descriptor of a field or method reference are already in the
+    /// constant pool, and if not, insert them. Return the indices of the class and the
+    /// NameAndType.
+    fn analyze_member(
+        &mut self,
+        class_name: &str,
+        member_name: &str,
+        descriptor: &str,
+        cp: &mut PhoronConstantPool,
+    ) -> ConstantPoolAnalyzerResult<(u16, u16)> {
+        let class_name_index = self.analyze_name(class_name, cp)?;
+        let class_index = self.analyze_class(class_name_index, cp)?;
+        let member_name_index = self.analyze_name(member_name, cp)?;
+        let descriptor_index = self.analyze_name(descriptor, cp)?;
+        let name_and_type_index =
+            self.analyze_name_and_type(member_name_index, descriptor_index, cp)?;
+
+        Ok((class_index, name_and_type_index))
+    }
+
+    /// check if the method handle (and the member that it refers to) is already in the constant
+    /// pool, and if not, insert it. Update the Constant Pool index accordingly.
+    fn analyze_method_handle_ref(
+        &mut self,
+        method_handle: &PhoronMethodHandle,
+        cp: &mut PhoronConstantPool,
+    ) -> ConstantPoolAnalyzerResult<u16> {
+        use PhoronMethodHandle::*;
+
+        let reference_index = match method_handle {
+            Getfield {
+                ref class_name,
+                ref field_name,
+                ref field_descriptor,
+            }
+            | Getstatic {
+                ref class_name,
+                ref field_name,
+                ref field_descriptor,
+            }
+            | Putfield {
+                ref class_name,
+                ref field_name,
+                ref field_descriptor,
+            }
+            | Putstatic {
+                ref class_name,
+                ref field_name,
+                ref field_descriptor,
+            } => {
+                let (class_index, name_and_type_index) =
+                    self.analyze_member(class_name, field_name, &field_descriptor.to_string(), cp)?;
+                self.analyze_field_ref(class_index, name_and_type_index, cp)?
+            }
+
+            Invokestatic {
+                ref class_name,
+                ref method_name,
+                ref method_descriptor,
+                is_interface: true,
+            }
+            | Invokespecial {
+                ref class_name,
+                ref method_name,
+                ref method_descriptor,
+                is_interface: true,
+            }
+            | Invokeinterface {
+                interface_name: ref class_name,
+                ref method_name,
+                ref method_descriptor,
+            } => {
+                let (class_index, name_and_type_index) = self.analyze_member(
+                    class_name,
+                    method_name,
+                    &method_descriptor.to_string(),
+                    cp,
+                )?;
+                self.analyze_interface_method_ref(class_index, name_and_type_index, cp)?
+            }
+
+            Invokevirtual {
+                ref class_name,
+                ref method_name,
+                ref method_descriptor,
+            }
+            | Invokestatic {
+                ref class_name,
+                ref method_name,
+                ref method_descriptor,
+                ..
+            }
+            | Invokespecial {
+                ref class_name,
+                ref method_name,
+                ref method_descriptor,
+                ..
+            } => {
+                let (class_index, name_and_type_index) = self.analyze_member(
+                    class_name,
+                    method_name,
+                    &method_descriptor.to_string(),
+                    cp,
+                )?;
+                self.analyze_method_ref(class_index, name_and_type_index, cp)?
+            }
+
+            Newinvokespecial {
+                ref class_name,
+                ref method_descriptor,
+            } => {
+                let (class_index, name_and_type_index) =
+                    self.analyze_member(class_name, "<init>", &method_descriptor.to_string(), cp)?;
+                self.analyze_method_ref(class_index, name_and_type_index, cp)?
+            }
+        };
+
+        self.analyze_method_handle(method_handle.reference_kind(), reference_index, cp)
+    }
+
+    /// check if the static argument to a bootstrap method is already in the constant pool, and
+    /// if not, insert it. Update the Constant Pool index accordingly.
+    fn analyze_bootstrap_argument(
+        &mut self,
+        argument: &BootstrapArgument,
+        cp: &mut PhoronConstantPool,
+    ) -> ConstantPoolAnalyzerResult<u16> {
+        match argument {
+            BootstrapArgument::Integer(int) => self.analyze_integer(*int, cp),
+            BootstrapArgument::Float(float) => self.analyze_float(*float, cp),
+            BootstrapArgument::Long(long) => self.analyze_long(*long, cp),
+            BootstrapArgument::Double(double) => self.analyze_double(*double, cp),
+            BootstrapArgument::QuotedString(ref string) => {
+                let string_index = self.analyze_name(string, cp)?;
+                self.analyze_string(string_index, cp)
+            }
+            BootstrapArgument::Class(ref class_name) => {
+                let name_index = self.analyze_name(class_name, cp)?;
+                self.analyze_class(name_index, cp)
+            }
+            BootstrapArgument::MethodType(ref method_descriptor) => {
+                let descriptor_index = self.analyze_name(&method_descriptor.to_string(), cp)?;
+                self.analyze_method_type(descriptor_index, cp)
+            }
+            BootstrapArgument::MethodHandle(ref method_handle) => {
+                self.analyze_method_handle_ref(method_handle, cp)
+            }
+            BootstrapArgument::Dynamic(ref dynamic_constant) => {
+                self.analyze_dynamic_constant(dynamic_constant, cp)
+            }
+        }
+    }
+
+    /// Intern the attribute name for the visibility of the annotation, along with the annotation
+    /// itself.
+    fn analyze_annotation_def(
+        &mut self,
+        annotation_def: &PhoronAnnotationDef,
+        cp: &mut PhoronConstantPool,
+    ) -> ConstantPoolAnalyzerResult<()> {
+        self.analyze_name(
+            match annotation_def.visibility {
+                PhoronAnnotationVisibility::Visible => PHORON_RUNTIME_VISIBLE_ANNOTATIONS,
+                PhoronAnnotationVisibility::Invisible => PHORON_RUNTIME_INVISIBLE_ANNOTATIONS,
+            },
+            cp,
+        )?;
+
+        self.analyze_annotation(&annotation_def.annotation, cp)
+    }
+
+    /// Intern the attribute name for the visibility of the parameter annotation, along with the
+    /// annotation itself.
+    fn analyze_parameter_annotation_def(
+        &mut self,
+        annotation_def: &PhoronAnnotationDef,
+        cp: &mut PhoronConstantPool,
+    ) -> ConstantPoolAnalyzerResult<()> {
+        self.analyze_name(
+            match annotation_def.visibility {
+                PhoronAnnotationVisibility::Visible => PHORON_RUNTIME_VISIBLE_PARAMETER_ANNOTATIONS,
+                PhoronAnnotationVisibility::Invisible => {
+                    PHORON_RUNTIME_INVISIBLE_PARAMETER_ANNOTATIONS
+                }
+            },
+            cp,
+        )?;
+
+        self.analyze_annotation(&annotation_def.annotation, cp)
+    }
+
+    /// Intern the attribute name for the visibility of the type annotation, along with the
+    /// annotation itself. The target and type path do not refer to the constant pool.
+    fn analyze_type_annotation_def(
+        &mut self,
+        type_annotation_def: &PhoronTypeAnnotationDef,
+        cp: &mut PhoronConstantPool,
+    ) -> ConstantPoolAnalyzerResult<()> {
+        let annotation_def = &type_annotation_def.annotation_def;
+
+        self.analyze_name(
+            match annotation_def.visibility {
+                PhoronAnnotationVisibility::Visible => PHORON_RUNTIME_VISIBLE_TYPE_ANNOTATIONS,
+                PhoronAnnotationVisibility::Invisible => PHORON_RUNTIME_INVISIBLE_TYPE_ANNOTATIONS,
+            },
+            cp,
+        )?;
+
+        self.analyze_annotation(&annotation_def.annotation, cp)
+    }
+
+    fn analyze_annotation(
+        &mut self,
+        annotation: &PhoronAnnotation,
+        cp: &mut PhoronConstantPool,
+    ) -> ConstantPoolAnalyzerResult<()> {
+        self.analyze_name(&annotation.annotation_type.to_string(), cp)?;
+
+        annotation
+            .element_value_pairs
+            .iter()
+            .try_for_each(|element_value_pair| {
+                self.analyze_name(&element_value_pair.name, cp)?;
+                self.analyze_element_value(&element_value_pair.value, cp)
             })
-            .or_insert_with(|| {
-                let curr_cp_index = self.cp_index;
-                self.cp_index += 1;
-                curr_cp_index
-            }))
+    }
+
+    /// Constant element values refer directly to the constant pool entries for their values - note
+    /// that strings are `Utf8` entries, and not `String` entries.
+    fn analyze_element_value(
+        &mut self,
+        value: &PhoronElementValue,
+        cp: &mut PhoronConstantPool,
+    ) -> ConstantPoolAnalyzerResult<()> {
+        match value {
+            PhoronElementValue::Byte(byte) => self.analyze_integer(*byte as i32, cp).map(|_| ()),
+            PhoronElementValue::Char(c) => self.analyze_integer(*c as i32, cp).map(|_| ()),
+            PhoronElementValue::Double(double) => self.analyze_double(*double, cp).map(|_| ()),
+            PhoronElementValue::Float(float) => self.analyze_float(*float, cp).map(|_| ()),
+            PhoronElementValue::Integer(int) => self.analyze_integer(*int, cp).map(|_| ()),
+            PhoronElementValue::Long(long) => self.analyze_long(*long, cp).map(|_| ()),
+            PhoronElementValue::Short(short) => self.analyze_integer(*short as i32, cp).map(|_| ()),
+            PhoronElementValue::Boolean(boolean) => {
+                self.analyze_integer(*boolean as i32, cp).map(|_| ())
+            }
+            PhoronElementValue::QuotedString(ref string) => {
+                self.analyze_name(string, cp).map(|_| ())
+            }
+            PhoronElementValue::Enum {
+                ref enum_type,
+                ref const_name,
+            } => {
+                self.analyze_name(&enum_type.to_string(), cp)?;
+                self.analyze_name(const_name, cp).map(|_| ())
+            }
+            PhoronElementValue::Class(ref return_descriptor) => self
+                .analyze_name(&return_descriptor.to_string(), cp)
+                .map(|_| ()),
+            PhoronElementValue::Annotation(ref annotation) => {
+                self.analyze_annotation(annotation, cp)
+            }
+            PhoronElementValue::Array(ref values) => values
+                .iter()
+                .try_for_each(|value| self.analyze_element_value(value, cp)),
+        }
     }
 
     /// check if the Class is already in the constant pool, and if not, insert it.
@@ -217,14 +624,27 @@ impl ConstantPoolAnalyzer {
         name_index: u16,
         cp: &mut PhoronConstantPool,
     ) -> ConstantPoolAnalyzerResult<u16> {
-        Ok(*cp
-            .0
-            .entry(PhoronConstantPoolKind::Class { name_index })
-            .or_insert_with(|| {
-                let curr_cp_index = self.cp_index;
-                self.cp_index += 1;
-                curr_cp_index
-            }))
+        Ok(self.intern(PhoronConstantPoolKind::Class { name_index }, 1, cp))
+    }
+
+    /// check if the Module is already in the constant pool, and if not, insert it.
+    /// Update the Constant Pool index accordingly.
+    fn analyze_module(
+        &mut self,
+        name_index: u16,
+        cp: &mut PhoronConstantPool,
+    ) -> ConstantPoolAnalyzerResult<u16> {
+        Ok(self.intern(PhoronConstantPoolKind::Module { name_index }, 1, cp))
+    }
+
+    /// check if the Package is already in the constant pool, and if not, insert it.
+    /// Update the Constant Pool index accordingly.
+    fn analyze_package(
+        &mut self,
+        name_index: u16,
+        cp: &mut PhoronConstantPool,
+    ) -> ConstantPoolAnalyzerResult<u16> {
+        Ok(self.intern(PhoronConstantPoolKind::Package { name_index }, 1, cp))
     }
 
     /// check if the class represented by the type descriptor is already in the constant pool,
@@ -282,6 +702,50 @@ impl ConstantPoolAnalyzer {
         Ok(())
     }
 
+    /// The positions of the instructions following the conditional branches of the method, if the
+    /// method may be long enough for the code generator to widen any of them (in which case the
+    /// instruction following a widened branch needs a stack map frame). The code length is
+    /// overestimated by assuming the maximum `switch` padding, that every branch is widened, and
+    /// that every `ldc` is promoted to `ldc_w`.
+    fn far_conditional_targets(method_def: &PhoronMethodDef) -> Vec<usize> {
+        let jvm_instrs = method_def
+            .instructions
+            .iter()
+            .filter_map(|instr| match instr {
+                PhoronInstruction::JvmInstruction(ref jvm_instr) => Some(jvm_instr),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+
+        let max_code_length = jvm_instrs
+            .iter()
+            .map(|jvm_instr| {
+                jvm_instr.code_length(0)
+                    + match jvm_instr {
+                        JvmInstruction::Ldc(..) => 1,
+                        _ if jvm_instr.short_branch_target().is_some() => 5, // for a `goto_w`
+                        _ => 0,
+                    }
+            })
+            .sum::<u32>();
+
+        if max_code_length <= i16::MAX as u32 {
+            return vec![];
+        }
+
+        jvm_instrs
+            .iter()
+            .enumerate()
+            .filter(|(_, jvm_instr)| {
+                !matches!(
+                    jvm_instr,
+                    JvmInstruction::Goto { .. } | JvmInstruction::Jsr { .. }
+                ) && jvm_instr.short_branch_target().is_some()
+            })
+            .map(|(index, _)| index + 1)
+            .collect()
+    }
+
     pub fn analyze(
         &mut self,
         program: &PhoronProgram,
@@ -289,6 +753,22 @@ impl ConstantPoolAnalyzer {
         let mut cp = PhoronConstantPool::new();
         self.visit_program(program, &mut cp)?;
 
+        // the `constant_pool_count` of the class file is the next free index, which is a `u16`
+        if self.cp_index > u16::MAX as u32 {
+            return Err(ConstantPoolAnalyzerError::ConstantPoolOverflow {
+                slots: self.cp_index - 1,
+            });
+        }
+
+        if self.optimize_layout {
+            cp = cp.relayout(|_, cp_index| {
+                (
+                    !self.ldc_indices.contains(&cp_index),
+                    Reverse(self.use_counts.get(&cp_index).copied().unwrap_or_default()),
+                )
+            });
+        }
+
         Ok(cp)
     }
 }
@@ -305,6 +785,7 @@ impl<'a> PhoronAstVisitor<'a> for ConstantPoolAnalyzer {
     }
 
     fn visit_header(&mut self, header: &PhoronHeader, cp: Self::Input) -> Self::Result {
+        self.major_version = header.version_def.major_version;
         self.visit_sourcefile_def(&header.sourcefile_def, cp)?;
 
         match header.class_or_interface_def {
@@ -312,15 +793,29 @@ impl<'a> PhoronAstVisitor<'a> for ConstantPoolAnalyzer {
             PhoronClassOrInterface::Interface(ref interface_def) => {
                 self.visit_interface_def(interface_def, cp)?
             }
+            PhoronClassOrInterface::Module(ref module_def) => {
+                self.visit_module_def(module_def, cp)?
+            }
         }
 
-        self.visit_super_def(&header.super_def, cp)?;
+        // the `module-info` class has no super class
+        if !matches!(
+            header.class_or_interface_def,
+            PhoronClassOrInterface::Module(..)
+        ) {
+            self.visit_super_def(&header.super_def, cp)?;
+        }
 
         header
             .implements_defs
             .iter()
             .try_for_each(|impl_def| self.visit_implements_def(impl_def, cp))?;
 
+        header
+            .class_directives
+            .iter()
+            .try_for_each(|class_directive| self.visit_class_directive(class_directive, cp))?;
+
         Ok(())
     }
 
@@ -336,6 +831,7 @@ impl<'a> PhoronAstVisitor<'a> for ConstantPoolAnalyzer {
     }
 
     fn visit_class_def(&mut self, class_def: &PhoronClassDef, cp: Self::Input) -> Self::Result {
+        self.class_name = class_def.name.to_owned();
         let name_index = self.analyze_name(&class_def.name, cp)?;
         self.analyze_class(name_index, cp)?;
 
@@ -347,13 +843,80 @@ impl<'a> PhoronAstVisitor<'a> for ConstantPoolAnalyzer {
         interface_def: &PhoronInterfaceDef,
         cp: Self::Input,
     ) -> Self::Result {
+        self.class_name = interface_def.name.to_owned();
         let name_index = self.analyze_name(&interface_def.name, cp)?;
         self.analyze_class(name_index, cp)?;
 
         Ok(())
     }
 
+    fn visit_module_def(&mut self, module_def: &PhoronModuleDef, cp: Self::Input) -> Self::Result {
+        self.class_name = MODULE_INFO_CLASS_NAME.to_owned();
+        let name_index = self.analyze_name(MODULE_INFO_CLASS_NAME, cp)?;
+        self.analyze_class(name_index, cp)?;
+
+        self.analyze_name(PHORON_MODULE, cp)?;
+        let name_index = self.analyze_name(&module_def.name, cp)?;
+        self.analyze_module(name_index, cp)?;
+
+        if let Some(ref version) = module_def.version {
+            self.analyze_name(version, cp)?;
+        }
+
+        for requires_def in &module_def.requires {
+            let name_index = self.analyze_name(&requires_def.module_name, cp)?;
+            self.analyze_module(name_index, cp)?;
+
+            if let Some(ref version) = requires_def.version {
+                self.analyze_name(version, cp)?;
+            }
+        }
+
+        for exports_def in module_def.exports.iter().chain(module_def.opens.iter()) {
+            let name_index = self.analyze_name(&exports_def.package_name, cp)?;
+            self.analyze_package(name_index, cp)?;
+
+            for module_name in &exports_def.to_modules {
+                let name_index = self.analyze_name(module_name, cp)?;
+                self.analyze_module(name_index, cp)?;
+            }
+        }
+
+        for service_name in &module_def.uses {
+            let name_index = self.analyze_name(service_name, cp)?;
+            self.analyze_class(name_index, cp)?;
+        }
+
+        for provides_def in &module_def.provides {
+            let name_index = self.analyze_name(&provides_def.service_name, cp)?;
+            self.analyze_class(name_index, cp)?;
+
+            for class_name in &provides_def.with_classes {
+                let name_index = self.analyze_name(class_name, cp)?;
+                self.analyze_class(name_index, cp)?;
+            }
+        }
+
+        if !module_def.packages.is_empty() {
+            self.analyze_name(PHORON_MODULE_PACKAGES, cp)?;
+
+            for package_name in &module_def.packages {
+                let name_index = self.analyze_name(package_name, cp)?;
+                self.analyze_package(name_index, cp)?;
+            }
+        }
+
+        if let Some(ref main_class) = module_def.main_class {
+            self.analyze_name(PHORON_MODULE_MAIN_CLASS, cp)?;
+            let name_index = self.analyze_name(main_class, cp)?;
+            self.analyze_class(name_index, cp)?;
+        }
+
+        Ok(())
+    }
+
     fn visit_super_def(&mut self, super_def: &PhoronSuperDef, cp: Self::Input) -> Self::Result {
+        self.super_class_name = super_def.super_class_name.to_owned();
         let name_index = self.analyze_name(&super_def.super_class_name, cp)?;
         self.analyze_class(name_index, cp)?;
 
@@ -371,6 +934,145 @@ impl<'a> PhoronAstVisitor<'a> for ConstantPoolAnalyzer {
         Ok(())
     }
 
+    fn visit_class_directive(
+        &mut self,
+        class_directive: &PhoronClassDirective,
+        cp: Self::Input,
+    ) -> Self::Result {
+        match class_directive {
+            PhoronClassDirective::Bootstrap {
+                ref name,
+                ref method_handle,
+                ref arguments,
+            } => {
+                self.analyze_name(PHORON_BOOTSTRAP_METHODS, cp)?;
+                self.analyze_method_handle_ref(method_handle, cp)?;
+                arguments.iter().try_for_each(|argument| {
+                    self.analyze_bootstrap_argument(argument, cp).map(|_| ())
+                })?;
+
+                // bootstrap methods are indexed in the order in which they are declared
+                let bootstrap_method_attr_index = self.bootstrap_methods.len() as u16;
+                self.bootstrap_methods
+                    .insert(name.to_owned(), bootstrap_method_attr_index);
+            }
+
+            PhoronClassDirective::Signature(ref signature) => {
+                self.analyze_name(PHORON_SIGNATURE, cp)?;
+                self.analyze_name(signature, cp)?;
+            }
+
+            PhoronClassDirective::Annotation(ref annotation_def) => {
+                self.analyze_annotation_def(annotation_def, cp)?;
+            }
+
+            PhoronClassDirective::TypeAnnotation(ref type_annotation_def) => {
+                self.analyze_type_annotation_def(type_annotation_def, cp)?;
+            }
+
+            PhoronClassDirective::InnerClass(PhoronInnerClassDef {
+                ref inner_class_name,
+                ref outer_class_name,
+                ref inner_name,
+                ..
+            }) => {
+                self.analyze_name(PHORON_INNER_CLASSES, cp)?;
+
+                let name_index = self.analyze_name(inner_class_name, cp)?;
+                self.analyze_class(name_index, cp)?;
+
+                if let Some(outer_class_name) = outer_class_name {
+                    let name_index = self.analyze_name(outer_class_name, cp)?;
+                    self.analyze_class(name_index, cp)?;
+                }
+
+                if let Some(inner_name) = inner_name {
+                    self.analyze_name(inner_name, cp)?;
+                }
+            }
+
+            PhoronClassDirective::EnclosingMethod(PhoronEnclosingMethodDef {
+                ref class_name,
+                ref method,
+            }) => {
+                self.analyze_name(PHORON_ENCLOSING_METHOD, cp)?;
+
+                let name_index = self.analyze_name(class_name, cp)?;
+                self.analyze_class(name_index, cp)?;
+
+                if let Some((method_name, method_descriptor)) = method {
+                    let name_index = self.analyze_name(method_name, cp)?;
+                    let descriptor_index = self.analyze_name(&method_descriptor.to_string(), cp)?;
+                    self.analyze_name_and_type(name_index, descriptor_index, cp)?;
+                }
+            }
+
+            PhoronClassDirective::NestHost(ref class_name) => {
+                self.analyze_name(PHORON_NEST_HOST, cp)?;
+
+                let name_index = self.analyze_name(class_name, cp)?;
+                self.analyze_class(name_index, cp)?;
+            }
+
+            PhoronClassDirective::NestMember(ref class_name) => {
+                self.analyze_name(PHORON_NEST_MEMBERS, cp)?;
+
+                let name_index = self.analyze_name(class_name, cp)?;
+                self.analyze_class(name_index, cp)?;
+            }
+
+            PhoronClassDirective::Permits(ref class_name) => {
+                self.analyze_name(PHORON_PERMITTED_SUBCLASSES, cp)?;
+
+                let name_index = self.analyze_name(class_name, cp)?;
+                self.analyze_class(name_index, cp)?;
+            }
+
+            PhoronClassDirective::Debug(..) => {
+                self.analyze_name(PHORON_SOURCE_DEBUG_EXTENSION, cp)?;
+            }
+
+            PhoronClassDirective::Attribute(ref attribute_def) => {
+                self.analyze_name(&attribute_def.name, cp)?;
+            }
+
+            PhoronClassDirective::Deprecated => {
+                self.analyze_name(PHORON_DEPRECATED, cp)?;
+            }
+
+            PhoronClassDirective::Synthetic { .. } => {
+                self.analyze_name(PHORON_SYNTHETIC, cp)?;
+            }
+
+            PhoronClassDirective::RecordComponent(ref component) => {
+                self.analyze_name(PHORON_RECORD, cp)?;
+                self.analyze_name(&component.name, cp)?;
+                self.analyze_name(&component.field_descriptor.to_string(), cp)?;
+
+                if let Some(ref signature) = component.signature {
+                    self.analyze_name(PHORON_SIGNATURE, cp)?;
+                    self.analyze_name(signature, cp)?;
+                }
+
+                component
+                    .annotations
+                    .iter()
+                    .try_for_each(|annotation_def| {
+                        self.analyze_annotation_def(annotation_def, cp)
+                    })?;
+
+                component
+                    .type_annotations
+                    .iter()
+                    .try_for_each(|type_annotation_def| {
+                        self.analyze_type_annotation_def(type_annotation_def, cp)
+                    })?;
+            }
+        }
+
+        Ok(())
+    }
+
     fn visit_body(&mut self, body: &PhoronBody, cp: Self::Input) -> Self::Result {
         body.field_defs
             .iter()
@@ -386,6 +1088,35 @@ impl<'a> PhoronAstVisitor<'a> for ConstantPoolAnalyzer {
         self.analyze_name(&field_def.name, cp)?;
         self.analyze_name(&field_def.field_descriptor.to_string(), cp)?;
 
+        if let Some(ref signature) = field_def.signature {
+            self.analyze_name(PHORON_SIGNATURE, cp)?;
+            self.analyze_name(signature, cp)?;
+        }
+
+        field_def
+            .annotations
+            .iter()
+            .try_for_each(|annotation_def| self.analyze_annotation_def(annotation_def, cp))?;
+
+        field_def
+            .type_annotations
+            .iter()
+            .try_for_each(|type_annotation_def| {
+                self.analyze_type_annotation_def(type_annotation_def, cp)
+            })?;
+
+        for attribute_def in &field_def.attributes {
+            self.analyze_name(&attribute_def.name, cp)?;
+        }
+
+        if field_def.deprecated {
+            self.analyze_name(PHORON_DEPRECATED, cp)?;
+        }
+
+        if field_def.synthetic {
+            self.analyze_name(PHORON_SYNTHETIC, cp)?;
+        }
+
         if let Some(field_init_val) = &field_def.init_val {
             match field_init_val {
                 PhoronFieldInitValue::Integer(int) => {
@@ -422,6 +1153,31 @@ impl<'a> PhoronAstVisitor<'a> for ConstantPoolAnalyzer {
                 PhoronInstruction::PhoronLabel(..) => Ok(()),
             })?;
 
+        // the classes referred to by the stack map frames must be in the constant pool as well.
+        // Errors in the frames themselves are reported during code generation.
+        if self.major_version >= stack_map::STACK_MAP_MAJOR_VERSION {
+            if let Ok(method_frames) = stack_map::compute_frames(
+                method_def,
+                &self.class_name,
+                &self.super_class_name,
+                self.class_hierarchy.as_ref(),
+                &Self::far_conditional_targets(method_def),
+            ) {
+                if !method_frames.frames.is_empty() {
+                    self.analyze_name(PHORON_STACK_MAP_TABLE, cp)?;
+                }
+
+                for (_, frame) in &method_frames.frames {
+                    for vtype in frame.locals.iter().chain(frame.stack.iter()) {
+                        if let VerificationType::Object(ref class_name) = vtype {
+                            let name_index = self.analyze_name(class_name, cp)?;
+                            self.analyze_class(name_index, cp)?;
+                        }
+                    }
+                }
+            }
+        }
+
         Ok(())
     }
 
@@ -445,12 +1201,18 @@ impl<'a> PhoronAstVisitor<'a> for ConstantPoolAnalyzer {
             PhoronDirective::Var {
                 ref name,
                 ref field_descriptor,
+                ref signature,
                 ..
             } => {
                 self.analyze_name(PHORON_LOCAL_VARIABLE_TABLE, cp)?;
                 self.analyze_name(name, cp)?;
                 self.analyze_name(&field_descriptor.to_string(), cp)?;
                 self.analyze_field_descriptor(field_descriptor, cp)?;
+
+                if let Some(ref signature) = signature {
+                    self.analyze_name(PHORON_LOCAL_VARIABLE_TYPE_TABLE, cp)?;
+                    self.analyze_name(signature, cp)?;
+                }
             }
 
             PhoronDirective::Catch { ref class_name, .. } => {
@@ -459,6 +1221,48 @@ impl<'a> PhoronAstVisitor<'a> for ConstantPoolAnalyzer {
                     self.analyze_class(name_index, cp)?;
                 }
             }
+
+            PhoronDirective::Signature(ref signature) => {
+                self.analyze_name(PHORON_SIGNATURE, cp)?;
+                self.analyze_name(signature, cp)?;
+            }
+
+            PhoronDirective::Annotation(ref annotation_def) => {
+                self.analyze_annotation_def(annotation_def, cp)?;
+            }
+
+            PhoronDirective::ParameterAnnotation {
+                ref annotation_def, ..
+            } => {
+                self.analyze_parameter_annotation_def(annotation_def, cp)?;
+            }
+
+            PhoronDirective::TypeAnnotation(ref type_annotation_def) => {
+                self.analyze_type_annotation_def(type_annotation_def, cp)?;
+            }
+
+            PhoronDirective::AnnotationDefault(ref default_value) => {
+                self.analyze_name(PHORON_ANNOTATION_DEFAULT, cp)?;
+                self.analyze_element_value(default_value, cp)?;
+            }
+
+            PhoronDirective::Parameter { ref name, .. } => {
+                self.analyze_name(PHORON_METHOD_PARAMETERS, cp)?;
+                self.analyze_name(name, cp)?;
+            }
+
+            PhoronDirective::Attribute(ref attribute_def)
+            | PhoronDirective::CodeAttribute(ref attribute_def) => {
+                self.analyze_name(&attribute_def.name, cp)?;
+            }
+
+            PhoronDirective::Deprecated => {
+                self.analyze_name(PHORON_DEPRECATED, cp)?;
+            }
+
+            PhoronDirective::Synthetic { .. } => {
+                self.analyze_name(PHORON_SYNTHETIC, cp)?;
+            }
         }
         Ok(())
     }
@@ -520,6 +1324,29 @@ impl<'a> PhoronAstVisitor<'a> for ConstantPoolAnalyzer {
                 self.analyze_class_or_interface_type_descriptor(check_type, cp)?;
             }
 
+            Invokedynamic {
+                ref method_name,
+                ref method_descriptor,
+                ref bootstrap_method,
+            } => {
+                let bootstrap_method_attr_index = *self
+                    .bootstrap_methods
+                    .get(bootstrap_method)
+                    .ok_or(ConstantPoolAnalyzerError::IndexNotAvailable {
+                        component: "bootstrap method",
+                    })?;
+
+                let method_name_index = self.analyze_name(method_name, cp)?;
+                let method_descriptor_index =
+                    self.analyze_name(&method_descriptor.to_string(), cp)?;
+                let method_name_and_type_index =
+                    self.analyze_name_and_type(method_name_index, method_descriptor_index, cp)?;
+                self.analyze_invoke_dynamic(
+                    bootstrap_method_attr_index,
+                    method_name_and_type_index,
+                    cp,
+                )?;
+            }
             Invokeinterface {
                 ref interface_name,
                 ref method_name,
@@ -596,33 +1423,69 @@ impl<'a> PhoronAstVisitor<'a> for ConstantPoolAnalyzer {
                 self.analyze_method_ref(class_index, method_name_and_type_index, cp)?;
             }
 
-            Ldc(ref ldc_val) => match ldc_val {
-                LdcValue::Integer(int) => {
+            Ldc(ref ldc_val) => {
+                let ldc_index = match ldc_val {
+                    LdcValue::Integer(int) => self.analyze_integer(*int, cp)?,
+
+                    LdcValue::Float(float) => self.analyze_float(*float, cp)?,
+
+                    LdcValue::QuotedString(string) => {
+                        let string_index = self.analyze_name(string, cp)?;
+                        self.analyze_string(string_index, cp)?
+                    }
+
+                    LdcValue::Class(class_name) => {
+                        let name_index = self.analyze_name(class_name, cp)?;
+                        self.analyze_class(name_index, cp)?
+                    }
+
+                    LdcValue::MethodType(method_descriptor) => {
+                        let descriptor_index =
+                            self.analyze_name(&method_descriptor.to_string(), cp)?;
+                        self.analyze_method_type(descriptor_index, cp)?
+                    }
+
+                    LdcValue::MethodHandle(method_handle) => {
+                        self.analyze_method_handle_ref(method_handle, cp)?
+                    }
+
+                    LdcValue::Dynamic(dynamic_constant) => {
+                        self.analyze_dynamic_constant(dynamic_constant, cp)?
+                    }
+                };
+                self.ldc_indices.insert(ldc_index);
+            }
+
+            Ldcw(ref ldcw_val) => match ldcw_val {
+                LdcwValue::Integer(int) => {
                     self.analyze_integer(*int, cp)?;
                 }
 
-                LdcValue::Float(float) => {
+                LdcwValue::Float(float) => {
                     self.analyze_float(*float, cp)?;
                 }
 
-                LdcValue::QuotedString(string) => {
+                LdcwValue::QuotedString(string) => {
                     let string_index = self.analyze_name(string, cp)?;
                     self.analyze_string(string_index, cp)?;
                 }
-            },
 
-            Ldcw(ref ldcw_val) => match ldcw_val {
-                LdcwValue::Integer(int) => {
-                    self.analyze_integer(*int, cp)?;
+                LdcwValue::Class(class_name) => {
+                    let name_index = self.analyze_name(class_name, cp)?;
+                    self.analyze_class(name_index, cp)?;
                 }
 
-                LdcwValue::Float(float) => {
-                    self.analyze_float(*float, cp)?;
+                LdcwValue::MethodType(method_descriptor) => {
+                    let descriptor_index = self.analyze_name(&method_descriptor.to_string(), cp)?;
+                    self.analyze_method_type(descriptor_index, cp)?;
                 }
 
-                LdcwValue::QuotedString(string) => {
-                    let string_index = self.analyze_name(string, cp)?;
-                    self.analyze_string(string_index, cp)?;
+                LdcwValue::MethodHandle(method_handle) => {
+                    self.analyze_method_handle_ref(method_handle, cp)?;
+                }
+
+                LdcwValue::Dynamic(dynamic_constant) => {
+                    self.analyze_dynamic_constant(dynamic_constant, cp)?;
                 }
             },
 
@@ -633,6 +1496,9 @@ impl<'a> PhoronAstVisitor<'a> for ConstantPoolAnalyzer {
                 Ldc2wValue::Double(double) => {
                     self.analyze_double(*double, cp)?;
                 }
+                Ldc2wValue::Dynamic(dynamic_constant) => {
+                    self.analyze_dynamic_constant(dynamic_constant, cp)?;
+                }
             },
 
             Multianewarray {
@@ -692,3 +1558,162 @@ impl<'a> PhoronAstVisitor<'a> for ConstantPoolAnalyzer {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Analyze the program whose `run` method has the given code, followed by a `return`, with the
+    /// Constant Pool laid out as given by `optimize_layout`, and return the analyzer along with the
+    /// result.
+    fn analyze_code(
+        mut code: Vec<JvmInstruction>,
+        optimize_layout: bool,
+    ) -> (
+        ConstantPoolAnalyzer,
+        ConstantPoolAnalyzerResult<PhoronConstantPool>,
+    ) {
+        code.push(JvmInstruction::Return);
+        let program = PhoronProgram::with_method(
+            vec![],
+            code.into_iter()
+                .map(PhoronInstruction::JvmInstruction)
+                .collect(),
+        );
+
+        let mut analyzer = ConstantPoolAnalyzer::new();
+        analyzer.optimize_layout(optimize_layout);
+        let result = analyzer.analyze(&program);
+
+        (analyzer, result)
+    }
+
+    /// Loads of `ints` distinct `int` constants, followed by a `long` constant if `long` is set.
+    fn constant_loads(ints: i32, long: bool) -> Vec<JvmInstruction> {
+        let mut code = (0..ints)
+            .map(|int| JvmInstruction::Ldc(LdcValue::Integer(100_000 + int)))
+            .collect::<Vec<_>>();
+        if long {
+            code.push(JvmInstruction::Ldc2w(Ldc2wValue::Long(42)));
+        }
+
+        code
+    }
+
+    #[test]
+    fn test_constant_pool_overflow_boundary() {
+        // the slots taken up by everything but the constants
+        let (analyzer, result) = analyze_code(constant_loads(0, false), false);
+        assert!(result.is_ok());
+        let base_slots = analyzer.cp_index - 1;
+
+        let max_slots = u16::MAX as u32 - 1;
+        let ints = (max_slots - base_slots) as i32;
+
+        // the last index of the Constant Pool is taken by an `int`
+        let (_, result) = analyze_code(constant_loads(ints, false), false);
+        assert_eq!(max_slots as usize, result.unwrap().len());
+
+        let (_, result) = analyze_code(constant_loads(ints + 1, false), false);
+        assert!(matches!(
+            result,
+            Err(ConstantPoolAnalyzerError::ConstantPoolOverflow { slots }) if slots == max_slots + 1
+        ));
+
+        // the last two indices of the Constant Pool are taken by a `long`
+        let (_, result) = analyze_code(constant_loads(ints - 2, true), false);
+        let cp = result.unwrap();
+        assert_eq!(max_slots as usize, cp.len());
+        assert_eq!(Some(&(max_slots as u16 - 1)), cp.get_long(42));
+
+        // only the first half of the `long` fits
+        let (_, result) = analyze_code(constant_loads(ints - 1, true), false);
+        assert!(matches!(
+            result,
+            Err(ConstantPoolAnalyzerError::ConstantPoolOverflow { slots }) if slots == max_slots + 1
+        ));
+    }
+
+    #[test]
+    fn test_optimize_layout() {
+        let print_stream = || PhoronFieldDescriptor::ObjectType {
+            class_name: "java/io/PrintStream".to_string(),
+        };
+        let out = || JvmInstruction::Getstatic {
+            class_name: "java/lang/System".to_string(),
+            field_name: "out".to_string(),
+            field_descriptor: print_stream(),
+        };
+        let println = |param_descriptor| JvmInstruction::Invokevirtual {
+            class_name: "java/io/PrintStream".to_string(),
+            method_name: "println".to_string(),
+            method_descriptor: PhoronMethodDescriptor {
+                param_descriptor: vec![param_descriptor],
+                return_descriptor: PhoronReturnDescriptor::VoidDescriptor,
+            },
+        };
+
+        // the constants are loaded last, so they come last in the order of visitation
+        let code = || {
+            vec![
+                out(),
+                JvmInstruction::Ldc2w(Ldc2wValue::Long(42)),
+                println(PhoronFieldDescriptor::BaseType(PhoronBaseType::Long)),
+                out(),
+                JvmInstruction::Ldc(LdcValue::QuotedString("hello".to_string())),
+                println(PhoronFieldDescriptor::ObjectType {
+                    class_name: "java/lang/String".to_string(),
+                }),
+                out(),
+                JvmInstruction::Ldc(LdcValue::Integer(100_000)),
+                println(PhoronFieldDescriptor::BaseType(PhoronBaseType::Integer)),
+                JvmInstruction::Ldc(LdcValue::Integer(100_000)),
+                JvmInstruction::Pop,
+            ]
+        };
+
+        let (analyzer, result) = analyze_code(code(), false);
+        let visited = result.unwrap();
+        let (_, result) = analyze_code(code(), true);
+        let optimized = result.unwrap();
+
+        // the `ldc` entries come first, the one loaded twice before the one loaded once
+        assert_eq!(Some(&1), optimized.get_integer(100_000));
+        assert_eq!(Some(&2), optimized.get_string("hello"));
+        assert!(visited.get_integer(100_000) > visited.get_string("hello"));
+
+        // the rest follow in decreasing order of use, and then in the order of visitation, as
+        // checked for the entries which do not refer to others, and so are unchanged by the layout
+        let sort_key = |cp_index| {
+            (
+                !analyzer.ldc_indices.contains(&cp_index),
+                Reverse(analyzer.use_counts[&cp_index]),
+                cp_index,
+            )
+        };
+        let mut entries = optimized
+            .iter()
+            .filter(|(cp_kind, _)| {
+                matches!(
+                    cp_kind,
+                    PhoronConstantPoolKind::Utf8(..)
+                        | PhoronConstantPoolKind::Integer(..)
+                        | PhoronConstantPoolKind::Long(..)
+                )
+            })
+            .map(|(cp_kind, cp_index)| (*cp_index, visited.0[cp_kind]))
+            .collect::<Vec<_>>();
+        entries.sort();
+        assert!(entries.len() > 10);
+        assert!(entries
+            .windows(2)
+            .all(|pair| sort_key(pair[0].1) < sort_key(pair[1].1)));
+        assert!(
+            optimized.get_fieldref("java/lang/System", "out", "Ljava/io/PrintStream;")
+                < optimized.get_long(42)
+        );
+
+        // the layout does not depend on the order of the entries in the `HashMap`
+        assert_eq!(optimized, analyze_code(code(), true).1.unwrap());
+    }
+}
@@ -128,7 +128,7 @@ impl<'a> Lexer<'a> {
 
     fn extract_ident(&mut self) -> String {
         let is_ident_char = |c| match c {
-            '/' | '.' | '<' | '>' | '_' | '[' | ';' => true,
+            '/' | '.' | '<' | '>' | '_' | '$' | '[' | ';' => true,
             c if c.is_alphabetic() => true,
             c if c.is_digit(10) => true,
             _ => false,
@@ -154,17 +154,35 @@ impl<'a> Lexer<'a> {
         use TokenKind::*;
 
         Some(match ident {
+            "annotation" => TAnnotation,
+            "attribute" => TAttribute,
+            "bootstrap" => TBootstrap,
+            "bytecode" | "version" => TVersion,
             "catch" => TCatch,
             "class" => TClass,
+            "codeattribute" => TCodeAttribute,
+            "component" => TComponent,
+            "debug" => TDebug,
+            "default" => TDefault,
+            "deprecated" => TDeprecated,
+            "enclosing" => TEnclosing,
             "end" => TEnd,
             "field" => TField,
             "implements" => TImplements,
+            "inner" => TInner,
             "interface" => TInterface,
             "limit" => TLimit,
             "line" => TLine,
             "method" => TMethod,
+            "module" => TModule,
+            "nesthost" => TNestHost,
+            "nestmember" => TNestMember,
+            "parameter" => TParameter,
+            "permits" => TPermits,
+            "signature" => TSignature,
             "source" => TSource,
             "super" => TSuper,
+            "synthetic" => TSynthetic,
             "throws" => TThrows,
             "var" => TVar,
             _ => return None,
@@ -308,6 +326,7 @@ impl<'a> Lexer<'a> {
             "imul" => TImul,
             "ineg" => TIneg,
             "instanceof" => TInstanceof,
+            "invokedynamic" => TInvokedynamic,
             "invokeinterface" => TInvokeinterface,
             "invokenonvirtual" | "invokespecial" => TInvokespecial,
             "invokestatic" => TInvokestatic,
@@ -490,6 +509,21 @@ impl<'a> Lexer<'a> {
                 TRightParen
             }
 
+            '{' => {
+                self.src.next();
+                TLeftBrace
+            }
+
+            '}' => {
+                self.src.next();
+                TRightBrace
+            }
+
+            '@' => {
+                self.src.next();
+                TAt
+            }
+
             '=' => {
                 self.src.next();
                 TAssign
@@ -580,6 +614,38 @@ impl<'a> Lexer<'a> {
                                         self.src.next();
                                     }
 
+                                    'u' => {
+                                        self.src.next();
+
+                                        let mut hexbuf = String::new();
+                                        while hexbuf.len() < 4 {
+                                            match self.src.peek() {
+                                                Some((_idx, h)) if h.is_ascii_hexdigit() => {
+                                                    hexbuf.push(self.src.next().unwrap().1)
+                                                }
+                                                _ => break,
+                                            }
+                                        }
+
+                                        match u32::from_str_radix(&hexbuf, 16)
+                                            .ok()
+                                            .filter(|_| hexbuf.len() == 4)
+                                            .and_then(char::from_u32)
+                                        {
+                                            Some(uc) => strbuf.push(uc),
+                                            None => {
+                                                let high = self.curr_pos();
+
+                                                return Err(LexerError {
+                                                    span: Span { low, high },
+                                                    message: format!(
+                                                        "invalid unicode escape sequence: `\\u{hexbuf}`"
+                                                    ),
+                                                });
+                                            }
+                                        }
+                                    }
+
                                     d => {
                                         let high = self.curr_pos();
 
@@ -36,17 +36,22 @@ pub enum TokenKind {
     TAstore1,
     TAstore2,
     TAstore3,
+    TAt,
     TAthrow,
+    TAttribute,
     TBaload,
     TBastore,
     TBipush,
+    TBootstrap,
     TBridge,
     TCaload,
     TCastore,
     TCatch,
     TCheckcast,
     TClass,
+    TCodeAttribute,
     TColon,
+    TComponent,
     TD2f,
     TD2i,
     TD2l,
@@ -58,7 +63,9 @@ pub enum TokenKind {
     TDconst0,
     TDconst1,
     TDdiv,
+    TDebug,
     TDefault,
+    TDeprecated,
     TDload,
     TDload0,
     TDload1,
@@ -81,6 +88,7 @@ pub enum TokenKind {
     TDup2x2,
     TDupx1,
     TDupx2,
+    TEnclosing,
     TEnd,
     TEndMethod,
     TEnum,
@@ -164,9 +172,11 @@ pub enum TokenKind {
     TImplements,
     TImul,
     TIneg,
+    TInner,
     TInstanceof,
     TInt(i64),
     TInterface,
+    TInvokedynamic,
     TInvokeinterface,
     TInvokespecial,
     TInvokestatic,
@@ -201,6 +211,7 @@ pub enum TokenKind {
     TLdc2w,
     TLdcw,
     TLdiv,
+    TLeftBrace,
     TLeftParen,
     TLimit,
     TLine,
@@ -233,9 +244,13 @@ pub enum TokenKind {
     TMonitorexit,
     TMultianewarray,
     TNative,
+    TNestHost,
+    TNestMember,
     TNew,
     TNewarray,
     TNop,
+    TParameter,
+    TPermits,
     TPop,
     TPop2,
     TPrivate,
@@ -245,9 +260,11 @@ pub enum TokenKind {
     TPutstatic,
     TRet,
     TReturn,
+    TRightBrace,
     TRightParen,
     TSaload,
     TSastore,
+    TSignature,
     TSipush,
     TSource,
     TStack,
@@ -265,6 +282,7 @@ pub enum TokenKind {
     TUsing,
     TVar,
     TVarargs,
+    TVersion,
     TVolatile,
     Twide,
 }
@@ -298,17 +316,22 @@ impl fmt::Display for TokenKind {
                 TAstore1 => "astore_1",
                 TAstore2 => "astore_2",
                 TAstore3 => "astore_3",
+                TAt => "at",
                 TAthrow => "athrow",
+                TAttribute => "attribute",
                 TBaload => "baload",
                 TBastore => "bastore",
                 TBipush => "bipush",
+                TBootstrap => "bootstrap",
                 TBridge => "bridge",
                 TCaload => "caload",
                 TCastore => "castore",
                 TCatch => "catch",
                 TCheckcast => "checkcast",
                 TClass => "class",
+                TCodeAttribute => "codeattribute",
                 TColon => "colon",
+                TComponent => "component",
                 TD2f => "d2f",
                 TD2i => "d2i",
                 TD2l => "d2l",
@@ -320,7 +343,9 @@ impl fmt::Display for TokenKind {
                 TDconst0 => "dconst_0",
                 TDconst1 => "dconst_1",
                 TDdiv => "ddiv",
+                TDebug => "debug",
                 TDefault => "default",
+                TDeprecated => "deprecated",
                 TDload => "dload",
                 TDload0 => "dload_0",
                 TDload1 => "dload_1",
@@ -343,6 +368,7 @@ impl fmt::Display for TokenKind {
                 TDup2x2 => "dup2_x2",
                 TDupx1 => "dup_x1",
                 TDupx2 => "dup_x2",
+                TEnclosing => "enclosing",
                 TEnd => "end",
                 TEndMethod => "endMethod",
                 TEnum => "enum",
@@ -426,9 +452,11 @@ impl fmt::Display for TokenKind {
                 TImplements => "implements",
                 TImul => "imul",
                 TIneg => "ineg",
+                TInner => "inner",
                 TInstanceof => "instanceof",
                 TInt(..) => "int",
                 TInterface => "interface",
+                TInvokedynamic => "invokedynamic",
                 TInvokeinterface => "invokeinterface",
                 TInvokespecial => "invokespecial",
                 TInvokestatic => "invokestatic",
@@ -463,6 +491,7 @@ impl fmt::Display for TokenKind {
                 TLdc2w => "ldc2w",
                 TLdcw => "ldcw",
                 TLdiv => "ldiv",
+                TLeftBrace => "leftBrace",
                 TLeftParen => "leftParen",
                 TLimit => "limit",
                 TLine => "line",
@@ -495,9 +524,13 @@ impl fmt::Display for TokenKind {
                 TMonitorexit => "monitorexit",
                 TMultianewarray => "multianewarray",
                 TNative => "native",
+                TNestHost => "nesthost",
+                TNestMember => "nestmember",
                 TNew => "new",
                 TNewarray => "newarray",
                 TNop => "nop",
+                TParameter => "parameter",
+                TPermits => "permits",
                 TPop => "pop",
                 TPop2 => "pop2",
                 TPrivate => "private",
@@ -507,9 +540,11 @@ impl fmt::Display for TokenKind {
                 TPutstatic => "putstatic",
                 TRet => "ret",
                 TReturn => "return",
+                TRightBrace => "rightBrace",
                 TRightParen => "rightParen",
                 TSaload => "saload",
                 TSastore => "sastore",
+                TSignature => "signature",
                 TSipush => "sipush",
                 TSource => "source",
                 TStack => "stack",
@@ -527,6 +562,7 @@ impl fmt::Display for TokenKind {
                 TUsing => "using",
                 TVar => "var",
                 TVarargs => "varargs",
+                TVersion => "version",
                 TVolatile => "volatile",
                 Twide => "wide",
             }
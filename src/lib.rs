@@ -11,3 +11,4 @@ pub mod diagnostics;
 pub mod lexer;
 pub mod parser;
 pub mod sourcefile;
+pub mod stack_map;
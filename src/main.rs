@@ -1,4 +1,5 @@
 use phoron_asm::{
+    ast::PhoronVersionDef,
     codegen::{Codegen, CodegenError},
     cp_analyzer::{ConstantPoolAnalyzer, ConstantPoolAnalyzerError},
     diagnostics::DiagnosticManager,
@@ -15,7 +16,7 @@ use std::{
 };
 
 const PHORON_VERSION: &'static str = "1.0.0";
-const USAGE_STR: &'static str = r#"usage: phoron [-d <outpath>] -f <file> [<file> ...]
+const USAGE_STR: &'static str = r#"usage: phoron [--target <major>.<minor>] [--warn-ldc] [--optimize-cp] [-d <outpath>] -f <file> [<file> ...]
         or: phoron -v"#;
 
 #[derive(Debug)]
@@ -64,16 +65,53 @@ impl From<()> for PhoronError {
 
 pub type PhoronResult<T> = Result<T, PhoronError>;
 
+/// The command-line options, which apply to every file being assembled.
+#[derive(Default, Clone, Copy)]
+struct Options {
+    /// the class file version, overriding that of the `.version` directive
+    target_version: Option<PhoronVersionDef>,
+    /// whether to warn about `ldc` instructions generated as `ldc_w`
+    warn_ldc_promotion: bool,
+    /// whether to give the lowest Constant Pool indices to the `ldc` constants
+    optimize_cp_layout: bool,
+}
+
 fn usage() {
     eprintln!("{USAGE_STR}");
     std::process::exit(0);
 }
 
-fn process_file(src_file: &PathBuf) -> PhoronResult<()> {
+/// Parse the `--target` class file version, in the same `<major>.<minor>` format as the
+/// `.version` directive.
+fn parse_target_version(target: &str) -> PhoronResult<PhoronVersionDef> {
+    let (major_version, minor_version) = target.split_once('.').unwrap_or((target, "0"));
+
+    let version_def = PhoronVersionDef {
+        major_version: major_version.parse().map_err(|_| PhoronError::Error {
+            details: "invalid target major version",
+        })?,
+        minor_version: minor_version.parse().map_err(|_| PhoronError::Error {
+            details: "invalid target minor version",
+        })?,
+    };
+
+    if !version_def.is_valid() {
+        return Err(PhoronError::Error {
+            details: "unsupported target class file version",
+        });
+    }
+
+    Ok(version_def)
+}
+
+fn process_file(src_file: &PathBuf, options: Options) -> PhoronResult<()> {
     let outfile = src_file.with_extension("class");
 
     let source_file = SourceFile::new(src_file).map_err(DiagnosticManager::failfast)?;
     let mut parser = Parser::new(Lexer::new(&source_file));
+    if let Some(target_version) = options.target_version {
+        parser.target_version(target_version);
+    }
     let ast = parser.parse().unwrap();
 
     if parser.errored() {
@@ -82,31 +120,64 @@ fn process_file(src_file: &PathBuf) -> PhoronResult<()> {
     }
 
     let mut cp_analyzer = ConstantPoolAnalyzer::new();
+    cp_analyzer.optimize_layout(options.optimize_cp_layout);
     let cp = cp_analyzer
         .analyze(&ast)
         .map_err(DiagnosticManager::failfast)?;
 
     let mut outfile_w = BufWriter::new(fs::File::create(&outfile)?);
     let mut codegen = Codegen::new(&mut outfile_w);
+    codegen.warn_ldc_promotion(options.warn_ldc_promotion);
     codegen
         .gen_bytecode(&ast, &cp)
         .map_err(DiagnosticManager::failfast)?;
 
+    for warning in codegen.warnings() {
+        eprintln!("{}: warning: {warning}", src_file.display());
+    }
+
     println!("Generated {}", outfile.display());
 
     Ok(())
 }
 
-fn process_files(output_dir: &Path, srcfiles: &[PathBuf]) -> PhoronResult<()> {
+fn process_files(output_dir: &Path, srcfiles: &[PathBuf], options: Options) -> PhoronResult<()> {
     srcfiles
         .iter()
-        .try_for_each(|srcfile| process_file(&output_dir.join(srcfile)))?;
+        .try_for_each(|srcfile| process_file(&output_dir.join(srcfile), options))?;
     Ok(())
 }
 
 /// The entrypoint for Phoron
 fn main() -> PhoronResult<()> {
-    let args = std::env::args().skip(1).collect::<Vec<String>>();
+    let mut args = std::env::args().skip(1).collect::<Vec<String>>();
+
+    let mut options = Options::default();
+    while !args.is_empty() {
+        match args[0].as_str() {
+            "--target" => {
+                if args.len() < 2 {
+                    eprintln!("missing target version");
+                    usage();
+                }
+
+                options.target_version = Some(parse_target_version(&args[1])?);
+                args.drain(..2);
+            }
+
+            "--warn-ldc" => {
+                options.warn_ldc_promotion = true;
+                args.drain(..1);
+            }
+
+            "--optimize-cp" => {
+                options.optimize_cp_layout = true;
+                args.drain(..1);
+            }
+
+            _ => break,
+        }
+    }
 
     if args.len() == 0 {
         usage();
@@ -135,7 +206,7 @@ fn main() -> PhoronResult<()> {
                         if !Path::new(output_dir).exists() {
                             fs::create_dir(output_dir)?;
                         }
-                        process_files(Path::new(output_dir), &srcfiles)?;
+                        process_files(Path::new(output_dir), &srcfiles, options)?;
                     }
                 }
             }
@@ -151,7 +222,7 @@ fn main() -> PhoronResult<()> {
                         srcfiles.push(srcfile);
                     }
 
-                    process_files(Path::new("."), &srcfiles)?;
+                    process_files(Path::new("."), &srcfiles, options)?;
                 }
             }
 
@@ -9,7 +9,7 @@ use std::cmp::{max, min};
 
 const LEVENSHTEIN_THRESHOLD: f64 = 0.50;
 
-const JVM_OPCODES: [&'static str; 204] = [
+const JVM_OPCODES: [&'static str; 205] = [
     "aaload",
     "aastore",
     "aconst_null",
@@ -139,6 +139,7 @@ const JVM_OPCODES: [&'static str; 204] = [
     "imul",
     "ineg",
     "instanceof",
+    "invokedynamic",
     "invokeinterface",
     "invokenonvirtual",
     "invokespecial",
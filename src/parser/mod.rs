@@ -12,16 +12,68 @@ use crate::{
     },
     sourcefile::Span,
 };
+use std::{fs, path::Path};
 
 mod type_descriptor_parser;
 use type_descriptor_parser as tdp;
 
+mod signature_parser;
+use signature_parser::SignatureParser;
+
 mod levenshtein;
 
+/// Where an `.annotation` block appears, which decides the kinds of annotations allowed there.
+#[derive(Clone, Copy, PartialEq)]
+enum AnnotationSite {
+    Class,
+    Field,
+    Method,
+    Component,
+}
+
+impl AnnotationSite {
+    fn describe(self) -> &'static str {
+        match self {
+            AnnotationSite::Class => "a class",
+            AnnotationSite::Field => "a field",
+            AnnotationSite::Method => "a method",
+            AnnotationSite::Component => "a record component",
+        }
+    }
+}
+
+/// The kinds of `.annotation` blocks.
+enum AnnotationDirective {
+    Annotation(PhoronAnnotationDef),
+    ParameterAnnotation {
+        parameter_index: u8,
+        annotation_def: PhoronAnnotationDef,
+    },
+    TypeAnnotation(PhoronTypeAnnotationDef),
+}
+
+/// The type of an annotation element, as given by its tag.
+enum ElementTag {
+    Const(String),
+    Enum(PhoronFieldDescriptor),
+    Class,
+    Annotation(PhoronFieldDescriptor),
+    Array(Box<ElementTag>),
+    Invalid,
+}
+
 /// The Phoron parser
 pub struct Parser<'p> {
     lexer: Lexer<'p>,
     curr_tok: Token,
+    version_def: PhoronVersionDef,
+    target_version: Option<PhoronVersionDef>,
+    bootstrap_methods: Vec<String>,
+    method_parameter_count: usize,
+    record_component_spans: Vec<Span>,
+    is_final_class: bool,
+    is_annotation_interface: bool,
+    is_module_declaration: bool,
     pub errored: bool,
 }
 
@@ -33,10 +85,24 @@ impl<'p> Parser<'p> {
                 kind: TokenKind::TEof,
                 span: Span::default(),
             },
+            version_def: PhoronVersionDef::default(),
+            target_version: None,
+            bootstrap_methods: Vec::new(),
+            method_parameter_count: 0,
+            record_component_spans: Vec::new(),
+            is_final_class: false,
+            is_annotation_interface: false,
+            is_module_declaration: false,
             errored: false,
         }
     }
 
+    /// Use the given class file version in place of any `.version` directive (as with the
+    /// `--target` command-line option), so that features are checked against it while parsing.
+    pub fn target_version(&mut self, target_version: PhoronVersionDef) {
+        self.target_version = Some(target_version);
+    }
+
     pub fn errored(&self) -> bool {
         self.errored | self.lexer.errored()
     }
@@ -80,12 +146,34 @@ impl<'p> Parser<'p> {
 
     fn is_class_or_interface_access_flag(&self, tok: &TokenKind) -> bool {
         match tok {
-            TPublic | TFinal | TSuper | TInterface | TAbstract | TSynthetic | TAnnotation
-            | TEnum | TModule => true,
+            TPublic | TFinal | TSuper | TInterface | TAbstract | TSynthetic | TEnum | TModule => {
+                true
+            }
+            // `annotation` is not a keyword, so that it may still be used as a name
+            TIdent(ref flag) if flag == "annotation" => true,
             _ => false,
         }
     }
 
+    fn is_class_directive(&self, tok: &TokenKind) -> bool {
+        matches!(
+            tok,
+            TAnnotation
+                | TBootstrap
+                | TSignature
+                | TInner
+                | TEnclosing
+                | TNestHost
+                | TNestMember
+                | TComponent
+                | TPermits
+                | TDebug
+                | TAttribute
+                | TDeprecated
+                | TSynthetic
+        )
+    }
+
     fn is_field_access_flag(&self, tok: &TokenKind) -> bool {
         match tok {
             TPublic | TPrivate | TProtected | TStatic | TFinal | TVolatile | TTransient
@@ -94,6 +182,13 @@ impl<'p> Parser<'p> {
         }
     }
 
+    fn is_inner_class_access_flag(&self, tok: &TokenKind) -> bool {
+        matches!(
+            tok,
+            TPublic | TPrivate | TProtected | TStatic | TFinal | TAbstract | TSynthetic | TEnum
+        ) || matches!(tok, TIdent(ref flag) if flag == "annotation")
+    }
+
     fn is_method_access_flag(&self, tok: &TokenKind) -> bool {
         match tok {
             TPublic | TPrivate | TProtected | TStatic | TFinal | TSynthetic | TSynchronized
@@ -113,7 +208,9 @@ impl<'p> Parser<'p> {
             TInterface => PhoronClassOrInterfaceAccessFlag::AccInterface,
             TAbstract => PhoronClassOrInterfaceAccessFlag::AccAbstract,
             TSynthetic => PhoronClassOrInterfaceAccessFlag::AccSynthetic,
-            TAnnotation => PhoronClassOrInterfaceAccessFlag::AccAnnotation,
+            TIdent(ref flag) if flag == "annotation" => {
+                PhoronClassOrInterfaceAccessFlag::AccAnnotation
+            }
             TEnum => PhoronClassOrInterfaceAccessFlag::AccEnum,
             TModule => PhoronClassOrInterfaceAccessFlag::AccModule,
             _ => {
@@ -159,20 +256,48 @@ impl<'p> Parser<'p> {
         }
     }
 
+    fn get_inner_class_access_flag(&self, tok: &TokenKind) -> PhoronInnerClassAccessFlag {
+        match tok {
+            TPublic => PhoronInnerClassAccessFlag::AccPublic,
+            TPrivate => PhoronInnerClassAccessFlag::AccPrivate,
+            TProtected => PhoronInnerClassAccessFlag::AccProtected,
+            TStatic => PhoronInnerClassAccessFlag::AccStatic,
+            TFinal => PhoronInnerClassAccessFlag::AccFinal,
+            TAbstract => PhoronInnerClassAccessFlag::AccAbstract,
+            TSynthetic => PhoronInnerClassAccessFlag::AccSynthetic,
+            TIdent(ref flag) if flag == "annotation" => PhoronInnerClassAccessFlag::AccAnnotation,
+            TEnum => PhoronInnerClassAccessFlag::AccEnum,
+            _ => {
+                panic!("unknown inner class access flag")
+            }
+        }
+    }
+
     /// ClassDef <- CLASS_keyword AccessFlag* ClassName newline
     fn parse_class_def(&mut self) -> Option<PhoronClassDef> {
+        let start_span = self.curr_span();
         self.advance();
 
         let mut access_flags = vec![self.get_class_or_interface_access_flag(&TSuper)];
 
         match &self.see().kind {
-            TPublic | TFinal | TSuper | TInterface | TAbstract | TSynthetic | TAnnotation
-            | TEnum | TModule => {
+            tok_kind if self.is_class_or_interface_access_flag(tok_kind) => {
                 while self.is_class_or_interface_access_flag(&self.see().kind) {
                     access_flags.push(self.get_class_or_interface_access_flag(&self.see().kind));
                     self.advance();
                 }
 
+                // annotation interfaces must also be interfaces (and so abstract)
+                if access_flags.contains(&PhoronClassOrInterfaceAccessFlag::AccAnnotation) {
+                    self.report_diagnostic_no_advance(
+                        start_span,
+                        "annotation interfaces must be declared using `.interface`".to_string(),
+                    );
+                }
+
+                self.is_final_class =
+                    access_flags.contains(&PhoronClassOrInterfaceAccessFlag::AccFinal);
+
                 if let Token {
                     kind: TokenKind::TIdent(name),
                     ..
@@ -207,16 +332,21 @@ impl<'p> Parser<'p> {
         self.advance();
 
         let curr_tok = self.see();
-        let mut access_flags = vec![self.get_class_or_interface_access_flag(&TAbstract)];
+        let mut access_flags = vec![
+            self.get_class_or_interface_access_flag(&TInterface),
+            self.get_class_or_interface_access_flag(&TAbstract),
+        ];
 
         match &curr_tok.kind {
-            TPublic | TFinal | TSuper | TInterface | TAbstract | TSynthetic | TAnnotation
-            | TEnum | TModule => {
+            tok_kind if self.is_class_or_interface_access_flag(tok_kind) => {
                 while self.is_class_or_interface_access_flag(&self.see().kind) {
                     access_flags.push(self.get_class_or_interface_access_flag(&self.see().kind));
                     self.advance();
                 }
 
+                self.is_annotation_interface =
+                    access_flags.contains(&PhoronClassOrInterfaceAccessFlag::AccAnnotation);
+
                 if let Token {
                     kind: TokenKind::TIdent(ident),
                     ..
@@ -246,6 +376,303 @@ impl<'p> Parser<'p> {
         }
     }
 
+    /// ModuleDef <- MODULE_keyword ModuleAccessFlag* ModuleName ModuleVersion? newline ModuleStatement*
+    ///                END_keyword MODULE_END_keyword newline
+    fn parse_module_def(&mut self) -> Option<PhoronModuleDef> {
+        let start_span = self.curr_span();
+        self.advance();
+
+        self.check_class_file_version(53, "modules", start_span);
+
+        let mut module_def = PhoronModuleDef::default();
+
+        loop {
+            match self.see().kind {
+                TokenKind::TIdent(ref flag) if flag == "open" => module_def
+                    .access_flags
+                    .push(PhoronModuleAccessFlag::AccOpen),
+                TokenKind::TIdent(ref flag) if flag == "synthetic" => module_def
+                    .access_flags
+                    .push(PhoronModuleAccessFlag::AccSynthetic),
+                TokenKind::TIdent(ref flag) if flag == "mandated" => module_def
+                    .access_flags
+                    .push(PhoronModuleAccessFlag::AccMandated),
+                _ => break,
+            }
+            self.advance();
+        }
+
+        if let TokenKind::TIdent(ref name) = self.see().kind {
+            module_def.name = name.to_owned();
+            self.advance();
+        } else {
+            self.report_diagnostic_no_advance(self.curr_span(), "missing module name".to_string());
+        }
+
+        module_def.version = self.parse_module_version();
+
+        loop {
+            let stmt_span = self.curr_span();
+
+            match self.see().kind {
+                TokenKind::TIdent(ref stmt) if stmt == "requires" => {
+                    let requires_def = self.parse_module_requires();
+                    module_def.requires.push(requires_def);
+                }
+
+                TokenKind::TIdent(ref stmt) if stmt == "exports" => {
+                    let exports_def = self.parse_module_exports("exports");
+                    module_def.exports.push(exports_def);
+                }
+
+                TokenKind::TIdent(ref stmt) if stmt == "opens" => {
+                    if module_def
+                        .access_flags
+                        .contains(&PhoronModuleAccessFlag::AccOpen)
+                    {
+                        self.report_diagnostic_no_advance(
+                            stmt_span,
+                            "an `open` module cannot have `opens` statements".to_string(),
+                        );
+                    }
+
+                    let opens_def = self.parse_module_exports("opens");
+                    module_def.opens.push(opens_def);
+                }
+
+                TokenKind::TIdent(ref stmt) if stmt == "uses" => {
+                    self.advance();
+                    let mut service_names = self.parse_module_names("uses", "service name");
+                    module_def.uses.append(&mut service_names);
+                }
+
+                TokenKind::TIdent(ref stmt) if stmt == "provides" => {
+                    let provides_def = self.parse_module_provides();
+                    module_def.provides.push(provides_def);
+                }
+
+                TokenKind::TIdent(ref stmt) if stmt == "package" => {
+                    self.advance();
+                    let mut package_names = self.parse_module_names("package", "package name");
+                    module_def.packages.append(&mut package_names);
+                }
+
+                TokenKind::TIdent(ref stmt) if stmt == "mainclass" => {
+                    self.advance();
+
+                    if module_def.main_class.is_some() {
+                        self.report_diagnostic_no_advance(
+                            stmt_span,
+                            "duplicate `mainclass` statement for module".to_string(),
+                        );
+                    }
+
+                    if let TokenKind::TIdent(ref main_class) = self.see().kind {
+                        module_def.main_class = Some(main_class.to_owned());
+                        self.advance();
+                    } else {
+                        self.report_diagnostic_no_advance(
+                            self.curr_span(),
+                            "missing main class name after `mainclass`".to_string(),
+                        );
+                    }
+                }
+
+                TokenKind::TEnd => {
+                    let end_span = self.curr_span();
+                    self.advance();
+
+                    match self.see().kind {
+                        TokenKind::TIdent(ref marker) if marker == "module" => {
+                            self.advance();
+                        }
+
+                        _ => self.report_diagnostic_no_advance(
+                            end_span,
+                            "missing end module marker".to_string(),
+                        ),
+                    }
+
+                    break;
+                }
+
+                _ => {
+                    self.report_diagnostic_no_advance(
+                        start_span.merge(&self.curr_span()),
+                        "missing `.end module` for module".to_string(),
+                    );
+
+                    break;
+                }
+            }
+        }
+
+        Some(module_def)
+    }
+
+    /// ModuleVersion <- QuotedString
+    fn parse_module_version(&mut self) -> Option<String> {
+        if let TokenKind::TString(ref version) = self.see().kind {
+            let version = version.to_owned();
+            self.advance();
+            Some(version)
+        } else {
+            None
+        }
+    }
+
+    /// Check if the identifier starts a statement in a module declaration, and so cannot be the name
+    /// of a module, package, or class in the statement before it.
+    fn is_module_statement(&self, ident: &str) -> bool {
+        matches!(
+            ident,
+            "requires" | "exports" | "opens" | "uses" | "provides" | "package" | "mainclass"
+        )
+    }
+
+    /// Parse one or more names (of modules, packages, or classes) following `keyword`.
+    fn parse_module_names(&mut self, keyword: &str, kind: &str) -> Vec<String> {
+        let mut names = Vec::new();
+
+        while let TokenKind::TIdent(ref name) = self.see().kind {
+            if self.is_module_statement(name) {
+                break;
+            }
+
+            names.push(name.to_owned());
+            self.advance();
+        }
+
+        if names.is_empty() {
+            self.report_diagnostic_no_advance(
+                self.curr_span(),
+                format!("missing {kind} after `{keyword}`"),
+            );
+        }
+
+        names
+    }
+
+    /// RequiresStatement <- REQUIRES_keyword RequiresAccessFlag* ModuleName ModuleVersion? newline
+    fn parse_module_requires(&mut self) -> PhoronRequiresDef {
+        self.advance();
+
+        let mut requires_def = PhoronRequiresDef::default();
+
+        loop {
+            match self.see().kind {
+                TokenKind::TIdent(ref flag) if flag == "transitive" => requires_def
+                    .access_flags
+                    .push(PhoronRequiresAccessFlag::AccTransitive),
+                TokenKind::TStatic => requires_def
+                    .access_flags
+                    .push(PhoronRequiresAccessFlag::AccStaticPhase),
+                TokenKind::TIdent(ref flag) if flag == "synthetic" => requires_def
+                    .access_flags
+                    .push(PhoronRequiresAccessFlag::AccSynthetic),
+                TokenKind::TIdent(ref flag) if flag == "mandated" => requires_def
+                    .access_flags
+                    .push(PhoronRequiresAccessFlag::AccMandated),
+                _ => break,
+            }
+            self.advance();
+        }
+
+        match self.see().kind {
+            TokenKind::TIdent(ref module_name) if !self.is_module_statement(module_name) => {
+                requires_def.module_name = module_name.to_owned();
+                self.advance();
+            }
+
+            _ => self.report_diagnostic_no_advance(
+                self.curr_span(),
+                "missing module name after `requires`".to_string(),
+            ),
+        }
+
+        requires_def.version = self.parse_module_version();
+
+        requires_def
+    }
+
+    /// ExportsStatement <- (EXPORTS_keyword / OPENS_keyword) ExportsAccessFlag* PackageName
+    ///                       (TO_keyword ModuleName+)? newline
+    fn parse_module_exports(&mut self, keyword: &str) -> PhoronExportsDef {
+        self.advance();
+
+        let mut exports_def = PhoronExportsDef::default();
+
+        loop {
+            match self.see().kind {
+                TokenKind::TIdent(ref flag) if flag == "synthetic" => exports_def
+                    .access_flags
+                    .push(PhoronExportsAccessFlag::AccSynthetic),
+                TokenKind::TIdent(ref flag) if flag == "mandated" => exports_def
+                    .access_flags
+                    .push(PhoronExportsAccessFlag::AccMandated),
+                _ => break,
+            }
+            self.advance();
+        }
+
+        match self.see().kind {
+            TokenKind::TIdent(ref package_name) if !self.is_module_statement(package_name) => {
+                exports_def.package_name = package_name.to_owned();
+                self.advance();
+            }
+
+            _ => self.report_diagnostic_no_advance(
+                self.curr_span(),
+                format!("missing package name after `{keyword}`"),
+            ),
+        }
+
+        if self.advance_if(&TokenKind::TTo) {
+            exports_def.to_modules = self.parse_module_names("to", "module name");
+        }
+
+        exports_def
+    }
+
+    /// ProvidesStatement <- PROVIDES_keyword ClassName WITH_keyword ClassName+ newline
+    fn parse_module_provides(&mut self) -> PhoronProvidesDef {
+        self.advance();
+
+        let mut provides_def = PhoronProvidesDef::default();
+
+        match self.see().kind {
+            TokenKind::TIdent(ref service_name) if !self.is_module_statement(service_name) => {
+                provides_def.service_name = service_name.to_owned();
+                self.advance();
+            }
+
+            _ => {
+                self.report_diagnostic_no_advance(
+                    self.curr_span(),
+                    "missing service name after `provides`".to_string(),
+                );
+                return provides_def;
+            }
+        }
+
+        match self.see().kind {
+            TokenKind::TIdent(ref with) if with == "with" => {
+                self.advance();
+                provides_def.with_classes = self.parse_module_names("with", "class name");
+            }
+
+            _ => self.report_diagnostic_no_advance(
+                self.curr_span(),
+                format!(
+                    "missing `with` after `provides {}`",
+                    provides_def.service_name
+                ),
+            ),
+        }
+
+        provides_def
+    }
+
     /// ImplementsDef <- IMPLEMENTS_keyword ClassName newline
     fn parse_implements_def(&mut self) -> Option<PhoronImplementsDef> {
         let start_span = self.curr_span();
@@ -340,7 +767,10 @@ impl<'p> Parser<'p> {
         }
     }
 
-    /// FieldDef <- line_comment* FIELD_keyword FieldAccessFlag* FieldName FieldDescriptor (EQ_symbol FieldIniValue)? newline
+    /// FieldDef <- line_comment* FIELD_keyword FieldAccessFlag* FieldName FieldDescriptor (SIGNATURE_keyword Signature)? (EQ_symbol FieldIniValue)? newline
+    ///     FieldDirective* (END_Keyword FIELD_END_keyword newline)?
+    ///
+    /// The directives following a field apply to it, and may be closed off by `.end field`.
     fn parse_field_def(&mut self) -> Option<PhoronFieldDef> {
         let start_span = self.curr_span();
 
@@ -361,13 +791,101 @@ impl<'p> Parser<'p> {
             self.advance();
 
             let field_descriptor = self.parse_field_descriptor()?;
+
+            let mut signature = match self.see().kind {
+                TokenKind::TIdent(ref keyword) if keyword == "signature" => {
+                    let signature_span = self.curr_span();
+                    self.advance();
+
+                    Some(self.parse_signature(signature_span, "field", |parser| {
+                        parser.parse_field_signature()
+                    })?)
+                }
+                _ => None,
+            };
+
             let init_val = self.parse_field_init_value()?;
 
+            let mut annotations = Vec::new();
+            let mut type_annotations = Vec::new();
+            let mut attributes = Vec::new();
+            let mut deprecated = false;
+            let mut synthetic = false;
+            loop {
+                match self.see().kind {
+                    TokenKind::TSignature => {
+                        let signature_span = self.curr_span();
+                        self.advance();
+
+                        let field_signature =
+                            self.parse_signature(signature_span, "field", |parser| {
+                                parser.parse_field_signature()
+                            })?;
+
+                        if signature.is_some() {
+                            self.report_diagnostic_no_advance(
+                                signature_span,
+                                format!("duplicate signature for field `{name}`"),
+                            );
+                        }
+                        signature = Some(field_signature);
+                    }
+                    TokenKind::TDeprecated => {
+                        self.advance();
+                        deprecated = true;
+                    }
+                    TokenKind::TSynthetic => {
+                        if self.parse_synthetic_directive()
+                            && !access_flags.contains(&PhoronFieldAccessFlag::AccSynthetic)
+                        {
+                            access_flags.push(PhoronFieldAccessFlag::AccSynthetic);
+                        }
+                        synthetic = true;
+                    }
+                    TokenKind::TAnnotation => {
+                        match self.parse_annotation_directive(AnnotationSite::Field)? {
+                            AnnotationDirective::Annotation(annotation_def) => {
+                                annotations.push(annotation_def)
+                            }
+                            AnnotationDirective::TypeAnnotation(type_annotation_def) => {
+                                type_annotations.push(type_annotation_def)
+                            }
+                            // already reported as an error
+                            AnnotationDirective::ParameterAnnotation { .. } => {}
+                        }
+                    }
+                    TokenKind::TAttribute => attributes.push(self.parse_attribute_def()?),
+                    _ => break,
+                }
+            }
+
+            if let TokenKind::TEnd = self.see().kind {
+                let end_span = self.curr_span();
+                self.advance();
+
+                match self.see().kind {
+                    TokenKind::TIdent(ref marker) if marker == "field" => {
+                        self.advance();
+                    }
+
+                    _ => self.report_diagnostic_no_advance(
+                        end_span,
+                        "missing end field marker".to_string(),
+                    ),
+                }
+            }
+
             Some(PhoronFieldDef {
                 name,
                 access_flags,
                 field_descriptor,
+                signature,
                 init_val,
+                annotations,
+                type_annotations,
+                attributes,
+                deprecated,
+                synthetic,
             })
         } else {
             self.report_diagnostic(
@@ -397,27 +915,1887 @@ impl<'p> Parser<'p> {
             let classname = classname.to_owned();
             self.advance();
 
-            Some(classname)
-        } else {
-            self.report_diagnostic(self.curr_span(), format!("malformed class name"));
-            Some(String::default())
-        }
-    }
+            Some(classname)
+        } else {
+            self.report_diagnostic(self.curr_span(), format!("malformed class name"));
+            Some(String::default())
+        }
+    }
+
+    fn parse_label(&mut self) -> Option<String> {
+        if let Token {
+            kind: TokenKind::TIdent(label),
+            ..
+        } = self.see()
+        {
+            let label = label.to_owned();
+            self.advance();
+
+            Some(label)
+        } else {
+            self.report_diagnostic(self.curr_span(), format!("malformed label"));
+            Some(String::default())
+        }
+    }
+
+    /// Report an error if the class file version is older than `major_version`, the version in
+    /// which `feature` was introduced.
+    fn check_class_file_version(&mut self, major_version: u16, feature: &str, span: Span) {
+        if self.version_def.major_version < major_version {
+            self.report_diagnostic_no_advance(
+                span,
+                format!(
+                    "{feature} require a class file version of {major_version}.0 or above, but the class file version is {}",
+                    self.version_def
+                ),
+            );
+        }
+    }
+
+    /// Signature <- QuotedString
+    ///
+    /// The signature is checked against the grammar of the given kind of signature (class, field,
+    /// or method), as validated by `validate`.
+    fn parse_signature(
+        &mut self,
+        start_span: Span,
+        kind: &str,
+        validate: fn(&mut SignatureParser) -> tdp::TypeParseResult<()>,
+    ) -> Option<String> {
+        self.check_class_file_version(49, "signatures", start_span);
+
+        if let TokenKind::TString(ref signature) = self.see().kind {
+            let signature = signature.to_owned();
+            let signature_span = self.curr_span();
+            self.advance();
+
+            if let Err(err) = validate(&mut SignatureParser::new(&signature)) {
+                self.report_diagnostic_no_advance(
+                    signature_span,
+                    format!("invalid {kind} signature `{signature}`: {err}"),
+                );
+            }
+
+            Some(signature)
+        } else {
+            self.report_diagnostic(
+                start_span.merge(&self.curr_span()),
+                format!("missing {kind} signature"),
+            );
+
+            Some(String::default())
+        }
+    }
+
+    /// MemberSpec <- ClassName '/' MemberName
+    fn parse_member_spec(&mut self) -> Option<(String, String)> {
+        let start_span = self.curr_span();
+
+        if let TokenKind::TIdent(ref member_str) = self.see().kind {
+            if let Some(pos) = member_str.rfind('/') {
+                let class_name = member_str[..pos].to_owned();
+                let member_name = member_str[pos + 1..].to_owned();
+                self.advance();
+
+                Some((class_name, member_name))
+            } else {
+                self.report_diagnostic(start_span, "missing member name".to_string());
+                Some((String::default(), String::default()))
+            }
+        } else {
+            self.report_diagnostic(start_span, "missing class name".to_string());
+            Some((String::default(), String::default()))
+        }
+    }
+
+    /// MethodHandle <- (GETFIELD / GETSTATIC / PUTFIELD / PUTSTATIC) MemberSpec FieldDescriptor
+    ///    / (INVOKEVIRTUAL / INVOKESTATIC / INVOKESPECIAL / NEWINVOKESPECIAL / INVOKEINTERFACE)
+    ///         INTERFACE_keyword? MemberSpec MethodDescriptor
+    fn parse_method_handle(&mut self) -> Option<PhoronMethodHandle> {
+        let start_span = self.curr_span();
+        let kind = self.see().kind.clone();
+
+        Some(match kind {
+            TGetfield | TGetstatic | TPutfield | TPutstatic => {
+                self.advance();
+
+                let (class_name, field_name) = self.parse_member_spec()?;
+                let field_descriptor = self.parse_field_descriptor().or_else(|| {
+                    self.report_diagnostic(
+                        start_span.merge(&self.curr_span()),
+                        "missing field descriptor".to_string(),
+                    );
+
+                    Some(PhoronFieldDescriptor::default())
+                })?;
+
+                match kind {
+                    TGetfield => PhoronMethodHandle::Getfield {
+                        class_name,
+                        field_name,
+                        field_descriptor,
+                    },
+                    TGetstatic => PhoronMethodHandle::Getstatic {
+                        class_name,
+                        field_name,
+                        field_descriptor,
+                    },
+                    TPutfield => PhoronMethodHandle::Putfield {
+                        class_name,
+                        field_name,
+                        field_descriptor,
+                    },
+                    _ => PhoronMethodHandle::Putstatic {
+                        class_name,
+                        field_name,
+                        field_descriptor,
+                    },
+                }
+            }
+
+            TInvokevirtual | TInvokestatic | TInvokespecial | TInvokeinterface => {
+                self.parse_method_handle_to_method(kind, start_span)?
+            }
+
+            TIdent(ref ident) if ident == "newinvokespecial" => {
+                self.parse_method_handle_to_method(kind, start_span)?
+            }
+
+            tok_kind => {
+                self.report_diagnostic(
+                    start_span,
+                    format!("found `{tok_kind}`, but I expected a method handle kind (getfield, getstatic, putfield, putstatic, invokevirtual, invokestatic, invokespecial, newinvokespecial, or invokeinterface)"),
+                );
+
+                PhoronMethodHandle::default()
+            }
+        })
+    }
+
+    fn parse_method_handle_to_method(
+        &mut self,
+        kind: TokenKind,
+        start_span: Span,
+    ) -> Option<PhoronMethodHandle> {
+        self.advance();
+
+        // `invokestatic` and `invokespecial` method handles may refer to interface methods
+        let is_interface = match self.see().kind {
+            TIdent(ref ident) if ident == "interface" => {
+                if kind != TInvokestatic && kind != TInvokespecial {
+                    self.report_diagnostic_no_advance(
+                        self.curr_span(),
+                        "`interface` is only allowed for `invokestatic` and `invokespecial` method handles".to_string(),
+                    );
+                }
+
+                self.advance();
+                true
+            }
+            _ => false,
+        };
+
+        let (class_name, method_name) = self.parse_member_spec()?;
+        let method_descriptor = self.parse_method_descriptor().or_else(|| {
+            self.report_diagnostic(
+                start_span.merge(&self.curr_span()),
+                "missing method descriptor".to_string(),
+            );
+
+            Some(PhoronMethodDescriptor::default())
+        })?;
+
+        Some(match kind {
+            TInvokevirtual => PhoronMethodHandle::Invokevirtual {
+                class_name,
+                method_name,
+                method_descriptor,
+            },
+
+            TInvokestatic => PhoronMethodHandle::Invokestatic {
+                class_name,
+                method_name,
+                method_descriptor,
+                is_interface,
+            },
+
+            TInvokespecial => PhoronMethodHandle::Invokespecial {
+                class_name,
+                method_name,
+                method_descriptor,
+                is_interface,
+            },
+
+            TInvokeinterface => PhoronMethodHandle::Invokeinterface {
+                interface_name: class_name,
+                method_name,
+                method_descriptor,
+            },
+
+            _ => {
+                if method_name != "<init>"
+                    || method_descriptor.return_descriptor != PhoronReturnDescriptor::VoidDescriptor
+                {
+                    self.report_diagnostic_no_advance(
+                        start_span.merge(&self.curr_span()),
+                        "a `newinvokespecial` method handle must refer to an instance initialization method `<init>` returning `V`".to_string(),
+                    );
+                }
+
+                PhoronMethodHandle::Newinvokespecial {
+                    class_name,
+                    method_descriptor,
+                }
+            }
+        })
+    }
+
+    /// ClassConstant <- ClassName / ArrayType
+    fn parse_class_constant(&mut self) -> Option<String> {
+        let start_span = self.curr_span();
+        let class_name = self.parse_class_name()?;
+
+        if class_name.starts_with('[') {
+            let mut array_parser = tdp::TypeParser::new(&class_name);
+            if array_parser.parse_field_descriptor().is_err() {
+                self.report_diagnostic_no_advance(
+                    start_span,
+                    format!("malformed array type `{class_name}`"),
+                );
+            }
+        }
+
+        Some(class_name)
+    }
+
+    /// MethodType <- MethodDescriptor
+    fn parse_method_type(&mut self, start_span: Span) -> Option<PhoronMethodDescriptor> {
+        self.parse_method_descriptor().or_else(|| {
+            self.report_diagnostic(
+                start_span.merge(&self.curr_span()),
+                "missing method descriptor".to_string(),
+            );
+
+            Some(PhoronMethodDescriptor::default())
+        })
+    }
+
+    /// DynamicConstant <- DynamicName FieldDescriptor BootstrapName
+    fn parse_dynamic_constant(&mut self, start_span: Span) -> Option<PhoronDynamicConstant> {
+        self.check_class_file_version(55, "dynamic constants", start_span);
+
+        let name = if let TokenKind::TIdent(ref name) = self.see().kind {
+            let name = name.to_owned();
+            self.advance();
+            name
+        } else {
+            self.report_diagnostic(
+                start_span.merge(&self.curr_span()),
+                "missing dynamic constant name".to_string(),
+            );
+            String::default()
+        };
+
+        let field_descriptor = self.parse_field_descriptor().or_else(|| {
+            self.report_diagnostic(
+                start_span.merge(&self.curr_span()),
+                "missing field descriptor".to_string(),
+            );
+
+            Some(PhoronFieldDescriptor::default())
+        })?;
+
+        let bootstrap_method = self.parse_bootstrap_method_name()?;
+
+        Some(PhoronDynamicConstant {
+            name,
+            field_descriptor,
+            bootstrap_method,
+        })
+    }
+
+    /// BootstrapArgument <- Integer / Double / QuotedString / LONG_keyword Integer
+    ///     / DOUBLE_keyword (Double / Integer) / CLASS_keyword ClassConstant
+    ///     / METHODTYPE_keyword MethodDescriptor / METHODHANDLE_keyword MethodHandle
+    ///     / DYNAMIC_keyword DynamicConstant
+    fn parse_bootstrap_argument(&mut self) -> Option<Option<BootstrapArgument>> {
+        let start_span = self.curr_span();
+
+        Some(Some(match self.see().kind {
+            TokenKind::TInt(int) => {
+                self.advance();
+                BootstrapArgument::Integer(int as i32)
+            }
+
+            TokenKind::TFloat(float) => {
+                self.advance();
+                BootstrapArgument::Float(float as f32)
+            }
+
+            TokenKind::TString(ref s) => {
+                let sval = s.to_owned();
+                self.advance();
+                BootstrapArgument::QuotedString(sval)
+            }
+
+            TokenKind::TIdent(ref ident) => match ident.as_str() {
+                "long" => {
+                    self.advance();
+
+                    if let TokenKind::TInt(long) = self.see().kind {
+                        self.advance();
+                        BootstrapArgument::Long(long)
+                    } else {
+                        self.report_diagnostic(
+                            start_span.merge(&self.curr_span()),
+                            "missing long value".to_string(),
+                        );
+                        BootstrapArgument::Long(i64::default())
+                    }
+                }
+
+                "double" => {
+                    self.advance();
+
+                    match self.see().kind {
+                        TokenKind::TFloat(double) => {
+                            self.advance();
+                            BootstrapArgument::Double(double)
+                        }
+
+                        TokenKind::TInt(double) => {
+                            self.advance();
+                            BootstrapArgument::Double(double as f64)
+                        }
+
+                        _ => {
+                            self.report_diagnostic(
+                                start_span.merge(&self.curr_span()),
+                                "missing double value".to_string(),
+                            );
+                            BootstrapArgument::Double(f64::default())
+                        }
+                    }
+                }
+
+                "class" => {
+                    self.advance();
+                    BootstrapArgument::Class(self.parse_class_constant()?)
+                }
+
+                "methodtype" => {
+                    self.advance();
+                    BootstrapArgument::MethodType(self.parse_method_type(start_span)?)
+                }
+
+                "methodhandle" => {
+                    self.advance();
+                    BootstrapArgument::MethodHandle(self.parse_method_handle()?)
+                }
+
+                "dynamic" => {
+                    self.advance();
+                    BootstrapArgument::Dynamic(self.parse_dynamic_constant(start_span)?)
+                }
+
+                _ => {
+                    self.report_diagnostic(
+                        start_span,
+                        format!("found `{ident}`, but I expected a bootstrap method argument"),
+                    );
+
+                    BootstrapArgument::default()
+                }
+            },
+
+            _ => return Some(None),
+        }))
+    }
+
+    /// BootstrapDirective <- BOOTSTRAP_keyword BootstrapName MethodHandle BootstrapArgument* newline
+    fn parse_bootstrap_directive(&mut self) -> Option<PhoronClassDirective> {
+        let start_span = self.curr_span();
+        self.advance();
+
+        let name = if let TokenKind::TIdent(ref name) = self.see().kind {
+            let name = name.to_owned();
+            self.advance();
+            name
+        } else {
+            self.report_diagnostic(
+                start_span.merge(&self.curr_span()),
+                "missing bootstrap method name".to_string(),
+            );
+            String::default()
+        };
+
+        self.check_class_file_version(51, "bootstrap methods", start_span);
+
+        if self.bootstrap_methods.contains(&name) {
+            self.report_diagnostic_no_advance(
+                start_span,
+                format!("duplicate bootstrap method `{name}`"),
+            );
+        } else {
+            self.bootstrap_methods.push(name.clone());
+        }
+
+        let method_handle = self.parse_method_handle()?;
+
+        let mut arguments = Vec::new();
+        while let Some(argument) = self.parse_bootstrap_argument()? {
+            arguments.push(argument);
+        }
+
+        Some(PhoronClassDirective::Bootstrap {
+            name,
+            method_handle,
+            arguments,
+        })
+    }
+
+    /// InnerClassDirective <- INNER_keyword (CLASS_TARGET_keyword / INTERFACE_TARGET_keyword) InnerClassAccessFlag* ClassName
+    ///     (OUTER_keyword ClassName)? (NAME_keyword InnerName)? newline
+    fn parse_inner_class_directive(&mut self) -> Option<PhoronClassDirective> {
+        self.advance();
+
+        // inner interfaces are abstract, just like top-level ones
+        let mut access_flags = match self.see().kind {
+            TokenKind::TIdent(ref kind) if kind == "class" => {
+                self.advance();
+                vec![]
+            }
+
+            TokenKind::TIdent(ref kind) if kind == "interface" => {
+                self.advance();
+                vec![
+                    PhoronInnerClassAccessFlag::AccInterface,
+                    PhoronInnerClassAccessFlag::AccAbstract,
+                ]
+            }
+
+            // skip a misspelt kind, so that it is not taken to be the name of the class
+            TokenKind::TIdent(ref kind) => {
+                self.report_diagnostic(
+                    self.curr_span(),
+                    format!("invalid inner class kind `{kind}`, expected `class` or `interface`"),
+                );
+                vec![]
+            }
+
+            _ => {
+                self.report_diagnostic_no_advance(
+                    self.curr_span(),
+                    "expected `class` or `interface` after `.inner`".to_string(),
+                );
+                vec![]
+            }
+        };
+
+        while self.is_inner_class_access_flag(&self.see().kind) {
+            access_flags.push(self.get_inner_class_access_flag(&self.see().kind));
+            self.advance();
+        }
+
+        let inner_class_name = if let TokenKind::TIdent(ref name) = self.see().kind {
+            let name = name.to_owned();
+            self.advance();
+            name
+        } else {
+            self.report_diagnostic(self.curr_span(), "missing inner class name".to_string());
+            return Some(PhoronClassDirective::InnerClass(
+                PhoronInnerClassDef::default(),
+            ));
+        };
+
+        let outer_class_name = self.parse_inner_class_clause("outer", "outer class name");
+        let inner_name = self.parse_inner_class_clause("name", "simple name");
+
+        Some(PhoronClassDirective::InnerClass(PhoronInnerClassDef {
+            inner_class_name,
+            outer_class_name,
+            inner_name,
+            access_flags,
+        }))
+    }
+
+    /// Parse an optional `<keyword> <name>` clause of an `.inner` directive.
+    fn parse_inner_class_clause(&mut self, keyword: &str, kind: &str) -> Option<String> {
+        match self.see().kind {
+            TokenKind::TIdent(ref ident) if ident == keyword => {
+                self.advance();
+
+                if let TokenKind::TIdent(ref name) = self.see().kind {
+                    let name = name.to_owned();
+                    self.advance();
+                    Some(name)
+                } else {
+                    self.report_diagnostic_no_advance(
+                        self.curr_span(),
+                        format!("missing {kind} after `{keyword}`"),
+                    );
+                    None
+                }
+            }
+
+            _ => None,
+        }
+    }
+
+    /// EnclosingMethodDirective <- ENCLOSING_keyword METHOD_END_keyword ClassName (MethodName MethodDescriptor)? newline
+    fn parse_enclosing_method_directive(&mut self) -> Option<PhoronClassDirective> {
+        let start_span = self.curr_span();
+        self.advance();
+
+        self.check_class_file_version(49, "enclosing methods", start_span);
+
+        if let TokenKind::TEndMethod = self.see().kind {
+            self.advance();
+        } else {
+            self.report_diagnostic_no_advance(
+                self.curr_span(),
+                "expected `method` after `.enclosing`".to_string(),
+            );
+        }
+
+        let class_name = if let TokenKind::TIdent(ref name) = self.see().kind {
+            let name = name.to_owned();
+            self.advance();
+            name
+        } else {
+            self.report_diagnostic(self.curr_span(), "missing enclosing class name".to_string());
+            return Some(PhoronClassDirective::EnclosingMethod(
+                PhoronEnclosingMethodDef::default(),
+            ));
+        };
+
+        // classes enclosed by an initializer have no enclosing method
+        let method = if let TokenKind::TIdent(ref name) = self.see().kind {
+            let name = name.to_owned();
+            self.advance();
+
+            Some((
+                name,
+                self.parse_method_descriptor()
+                    .or(Some(PhoronMethodDescriptor::default()))?,
+            ))
+        } else {
+            None
+        };
+
+        Some(PhoronClassDirective::EnclosingMethod(
+            PhoronEnclosingMethodDef { class_name, method },
+        ))
+    }
+
+    /// NestHostDirective <- NESTHOST_keyword ClassName newline
+    /// NestMemberDirective <- NESTMEMBER_keyword ClassName newline
+    fn parse_nest_directive(&mut self) -> Option<PhoronClassDirective> {
+        let start_span = self.curr_span();
+        let is_host = matches!(self.see().kind, TokenKind::TNestHost);
+        self.advance();
+
+        self.check_class_file_version(55, "nests", start_span);
+
+        let class_name = if let TokenKind::TIdent(ref name) = self.see().kind {
+            let name = name.to_owned();
+            self.advance();
+            name
+        } else {
+            self.report_diagnostic_no_advance(
+                self.curr_span(),
+                format!(
+                    "missing nest {} class name",
+                    if is_host { "host" } else { "member" }
+                ),
+            );
+            String::default()
+        };
+
+        Some(if is_host {
+            PhoronClassDirective::NestHost(class_name)
+        } else {
+            PhoronClassDirective::NestMember(class_name)
+        })
+    }
+
+    /// PermitsDirective <- PERMITS_keyword ClassName newline
+    fn parse_permits_directive(&mut self) -> Option<PhoronClassDirective> {
+        let start_span = self.curr_span();
+        self.advance();
+
+        self.check_class_file_version(61, "sealed classes", start_span);
+
+        let class_name = if let TokenKind::TIdent(ref name) = self.see().kind {
+            let name = name.to_owned();
+            self.advance();
+            name
+        } else {
+            self.report_diagnostic_no_advance(
+                self.curr_span(),
+                "missing permitted subclass name".to_string(),
+            );
+            String::default()
+        };
+
+        Some(PhoronClassDirective::Permits(class_name))
+    }
+
+    /// DebugDirective <- DEBUG_keyword QuotedString newline
+    fn parse_debug_directive(&mut self) -> Option<PhoronClassDirective> {
+        let start_span = self.curr_span();
+        self.advance();
+
+        self.check_class_file_version(49, "source debug extensions", start_span);
+
+        let debug_extension = if let TokenKind::TString(ref debug_extension) = self.see().kind {
+            let debug_extension = debug_extension.to_owned();
+            self.advance();
+            debug_extension
+        } else {
+            self.report_diagnostic_no_advance(
+                self.curr_span(),
+                "missing debug extension string".to_string(),
+            );
+            String::default()
+        };
+
+        Some(PhoronClassDirective::Debug(debug_extension))
+    }
+
+    /// SyntheticDirective <- SYNTHETIC_DIRECTIVE_keyword FLAG_keyword? newline
+    ///
+    /// Returns whether the `ACC_SYNTHETIC` flag was requested as well.
+    fn parse_synthetic_directive(&mut self) -> bool {
+        self.advance();
+
+        match self.see().kind {
+            TokenKind::TIdent(ref keyword) if keyword == "flag" => {
+                let flag_span = self.curr_span();
+                self.advance();
+
+                self.check_class_file_version(49, "synthetic access flags", flag_span);
+
+                if self.is_module_declaration {
+                    self.report_diagnostic_no_advance(
+                        flag_span,
+                        "a module declaration cannot have the `ACC_SYNTHETIC` flag".to_string(),
+                    );
+                }
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// AttributeDirective <- (ATTRIBUTE_keyword / CODE_ATTRIBUTE_keyword) AttributeName (QuotedString / HEX_keyword QuotedString) newline
+    ///
+    /// The contents of the attribute are either read from the given file (relative to the directory
+    /// of the source file), or given inline as hex digits.
+    fn parse_attribute_def(&mut self) -> Option<PhoronAttributeDef> {
+        self.advance();
+
+        let name = if let TokenKind::TIdent(ref name) = self.see().kind {
+            let name = name.to_owned();
+            self.advance();
+            name
+        } else {
+            self.report_diagnostic_no_advance(
+                self.curr_span(),
+                "missing attribute name".to_string(),
+            );
+            String::default()
+        };
+
+        let info_span = self.curr_span();
+        let info = match self.see().kind {
+            TokenKind::TIdent(ref keyword) if keyword == "hex" => {
+                self.advance();
+
+                if let TokenKind::TString(ref hex) = self.see().kind {
+                    let hex = hex.to_owned();
+                    let hex_span = self.curr_span();
+                    self.advance();
+
+                    self.parse_hex_bytes(&hex, &name, hex_span)
+                } else {
+                    self.report_diagnostic_no_advance(
+                        info_span.merge(&self.curr_span()),
+                        format!("missing hex string for attribute `{name}`"),
+                    );
+                    Vec::new()
+                }
+            }
+
+            TokenKind::TString(ref file) => {
+                let file = Path::new(self.lexer.src_file())
+                    .parent()
+                    .map_or_else(|| Path::new(file).to_path_buf(), |dir| dir.join(file));
+                self.advance();
+
+                fs::read(&file).unwrap_or_else(|err| {
+                    self.report_diagnostic_no_advance(
+                        info_span,
+                        format!(
+                            "could not read attribute `{name}` from `{}`: {err}",
+                            file.display()
+                        ),
+                    );
+                    Vec::new()
+                })
+            }
+
+            _ => {
+                self.report_diagnostic_no_advance(
+                    info_span,
+                    format!("missing file name or hex string for attribute `{name}`"),
+                );
+                Vec::new()
+            }
+        };
+
+        if u32::try_from(info.len()).is_err() {
+            self.report_diagnostic_no_advance(
+                info_span,
+                format!("attribute `{name}` exceeds the maximum attribute length"),
+            );
+        }
+
+        Some(PhoronAttributeDef { name, info })
+    }
+
+    /// Decode the hex digits of a raw attribute, ignoring any whitespace between them.
+    fn parse_hex_bytes(&mut self, hex: &str, name: &str, span: Span) -> Vec<u8> {
+        let digits = hex
+            .chars()
+            .filter(|c| !c.is_whitespace())
+            .collect::<Vec<_>>();
+
+        if let Some(c) = digits.iter().find(|c| !c.is_ascii_hexdigit()) {
+            self.report_diagnostic_no_advance(
+                span,
+                format!("invalid hex digit `{c}` for attribute `{name}`"),
+            );
+            return Vec::new();
+        }
+
+        if digits.len() % 2 != 0 {
+            self.report_diagnostic_no_advance(
+                span,
+                format!("odd number of hex digits for attribute `{name}`"),
+            );
+            return Vec::new();
+        }
+
+        digits
+            .chunks(2)
+            .map(|pair| (pair[0].to_digit(16).unwrap() << 4 | pair[1].to_digit(16).unwrap()) as u8)
+            .collect()
+    }
+
+    /// RecordComponentDirective <- COMPONENT_keyword ComponentName FieldDescriptor newline (SignatureDirective / AnnotationDirective)*
+    fn parse_record_component_directive(&mut self) -> Option<PhoronClassDirective> {
+        let start_span = self.curr_span();
+        self.advance();
+
+        self.check_class_file_version(60, "records", start_span);
+        self.record_component_spans.push(start_span);
+
+        let name = if let TokenKind::TIdent(ref name) = self.see().kind {
+            let name = name.to_owned();
+            self.advance();
+            name
+        } else {
+            self.report_diagnostic(
+                self.curr_span(),
+                "missing record component name".to_string(),
+            );
+            return Some(PhoronClassDirective::RecordComponent(
+                PhoronRecordComponentDef::default(),
+            ));
+        };
+
+        let field_descriptor = self.parse_field_descriptor()?;
+
+        let mut signature = None;
+        let mut annotations = Vec::new();
+        let mut type_annotations = Vec::new();
+
+        // the directives following the component apply to it, and not to the class
+        loop {
+            match self.see().kind {
+                TokenKind::TSignature => {
+                    let signature_span = self.curr_span();
+                    self.advance();
+
+                    let component_signature =
+                        self.parse_signature(signature_span, "record component", |parser| {
+                            parser.parse_field_signature()
+                        })?;
+
+                    if signature.is_some() {
+                        self.report_diagnostic_no_advance(
+                            signature_span,
+                            format!(
+                                "duplicate `.signature` directive for record component `{name}`"
+                            ),
+                        );
+                    }
+                    signature = Some(component_signature);
+                }
+
+                TokenKind::TAnnotation => {
+                    match self.parse_annotation_directive(AnnotationSite::Component)? {
+                        AnnotationDirective::Annotation(annotation_def) => {
+                            annotations.push(annotation_def)
+                        }
+                        AnnotationDirective::TypeAnnotation(type_annotation_def) => {
+                            type_annotations.push(type_annotation_def)
+                        }
+                        // already reported as an error
+                        AnnotationDirective::ParameterAnnotation { .. } => {}
+                    }
+                }
+
+                _ => break,
+            }
+        }
+
+        Some(PhoronClassDirective::RecordComponent(
+            PhoronRecordComponentDef {
+                name,
+                field_descriptor,
+                signature,
+                annotations,
+                type_annotations,
+            },
+        ))
+    }
+
+    /// Check that a record class extends `java/lang/Record`, and that it declares a `private final`
+    /// field with the same name and type for each of its components.
+    fn check_record_components(&mut self, header: &PhoronHeader, body: &PhoronBody) {
+        let components = header
+            .class_directives
+            .iter()
+            .filter_map(|dir| match dir {
+                PhoronClassDirective::RecordComponent(component) => Some(component),
+                _ => None,
+            })
+            .zip(std::mem::take(&mut self.record_component_spans))
+            .collect::<Vec<_>>();
+
+        let Some((_, first_span)) = components.first() else {
+            return;
+        };
+
+        if header.super_def.super_class_name != "java/lang/Record" {
+            self.report_diagnostic_no_advance(
+                *first_span,
+                format!(
+                    "record classes must extend `java/lang/Record`, not `{}`",
+                    header.super_def.super_class_name
+                ),
+            );
+        }
+
+        for (component, span) in components {
+            match body
+                .field_defs
+                .iter()
+                .find(|field_def| field_def.name == component.name)
+            {
+                None => self.report_diagnostic_no_advance(
+                    span,
+                    format!(
+                        "missing `private final` field for record component `{}`",
+                        component.name
+                    ),
+                ),
+
+                Some(field_def) if field_def.field_descriptor != component.field_descriptor => {
+                    self.report_diagnostic_no_advance(
+                        span,
+                        format!(
+                            "record component `{}` has type `{}`, but its field has type `{}`",
+                            component.name, component.field_descriptor, field_def.field_descriptor
+                        ),
+                    )
+                }
+
+                Some(field_def)
+                    if !field_def
+                        .access_flags
+                        .contains(&PhoronFieldAccessFlag::AccPrivate)
+                        || !field_def
+                            .access_flags
+                            .contains(&PhoronFieldAccessFlag::AccFinal)
+                        || field_def
+                            .access_flags
+                            .contains(&PhoronFieldAccessFlag::AccStatic) =>
+                {
+                    self.report_diagnostic_no_advance(
+                        span,
+                        format!(
+                            "the field for record component `{}` must be `private final`, and not `static`",
+                            component.name
+                        ),
+                    )
+                }
+
+                _ => {}
+            }
+        }
+    }
+
+    /// BootstrapName <- [a-zA-Z_][a-zA-Z0-9_$]*
+    fn parse_bootstrap_method_name(&mut self) -> Option<String> {
+        let start_span = self.curr_span();
+
+        if let TokenKind::TIdent(ref name) = self.see().kind {
+            let name = name.to_owned();
+
+            if !self.bootstrap_methods.contains(&name) {
+                self.report_diagnostic_no_advance(
+                    start_span,
+                    format!("unknown bootstrap method `{name}`"),
+                );
+            }
+
+            self.advance();
+            Some(name)
+        } else {
+            self.report_diagnostic(start_span, "missing bootstrap method name".to_string());
+            Some(String::default())
+        }
+    }
+
+    /// ObjectType <- 'L' ClassName ';'
+    ///
+    /// The type of an annotation, or of an enum constant in an annotation, which must be a class
+    /// type.
+    fn parse_object_type(&mut self, kind: &str) -> Option<PhoronFieldDescriptor> {
+        let start_span = self.curr_span();
+
+        match self.parse_field_descriptor() {
+            Some(object_type @ PhoronFieldDescriptor::ObjectType { .. }) => Some(object_type),
+
+            Some(field_descriptor) => {
+                self.report_diagnostic_no_advance(
+                    start_span,
+                    format!("invalid {kind} type `{field_descriptor}`, expected a class type"),
+                );
+                Some(PhoronFieldDescriptor::default())
+            }
+
+            None => {
+                self.report_diagnostic(start_span, format!("missing {kind} type"));
+                Some(PhoronFieldDescriptor::default())
+            }
+        }
+    }
+
+    /// AnnotationDirective <- ANNOTATION_keyword (PARAM_keyword Integer / TYPE_keyword TypeAnnotationTarget TypePath?)? AnnotationDef
+    ///
+    /// Parameter annotations are only allowed in methods, and the targets of type annotations
+    /// depend on `site`.
+    fn parse_annotation_directive(&mut self, site: AnnotationSite) -> Option<AnnotationDirective> {
+        let start_span = self.curr_span();
+        self.advance();
+
+        Some(match self.see().kind {
+            TokenKind::TIdent(ref kind) if kind == "param" => {
+                self.advance();
+                self.check_class_file_version(49, "parameter annotations", start_span);
+
+                let index_span = self.curr_span();
+                let parameter_index = self.parse_annotation_index("parameter index");
+
+                if site != AnnotationSite::Method {
+                    self.report_diagnostic_no_advance(
+                        start_span,
+                        format!(
+                            "parameter annotations are only allowed in a method, not {}",
+                            site.describe()
+                        ),
+                    );
+                } else {
+                    self.check_parameter_index(parameter_index, index_span);
+                }
+
+                AnnotationDirective::ParameterAnnotation {
+                    parameter_index,
+                    annotation_def: self.parse_annotation_def(start_span)?,
+                }
+            }
+
+            TokenKind::TIdent(ref kind) if kind == "type" => {
+                self.advance();
+                self.check_class_file_version(52, "type annotations", start_span);
+
+                let target = self.parse_type_annotation_target(site)?;
+
+                let type_path = match self.see().kind {
+                    TokenKind::TIdent(ref keyword) if keyword == "path" => {
+                        self.advance();
+                        self.parse_type_path()?
+                    }
+                    _ => vec![],
+                };
+
+                AnnotationDirective::TypeAnnotation(PhoronTypeAnnotationDef {
+                    target,
+                    type_path,
+                    annotation_def: self.parse_annotation_def(start_span)?,
+                })
+            }
+
+            _ => {
+                self.check_class_file_version(49, "annotations", start_span);
+                AnnotationDirective::Annotation(self.parse_annotation_def(start_span)?)
+            }
+        })
+    }
+
+    /// Parse an unsigned index used by a parameter or type annotation, reporting an error if it is
+    /// missing or out of range.
+    fn parse_annotation_index<T: TryFrom<i64> + Default>(&mut self, kind: &str) -> T {
+        match self.see().kind {
+            TokenKind::TInt(index) => match T::try_from(index) {
+                Ok(index) => {
+                    self.advance();
+                    index
+                }
+
+                Err(..) => {
+                    self.report_diagnostic(self.curr_span(), format!("invalid {kind} `{index}`"));
+                    T::default()
+                }
+            },
+
+            _ => {
+                self.report_diagnostic_no_advance(self.curr_span(), format!("missing {kind}"));
+                T::default()
+            }
+        }
+    }
+
+    /// Report an error if the method being parsed has no parameter at `parameter_index`.
+    fn check_parameter_index(&mut self, parameter_index: u8, span: Span) {
+        if parameter_index as usize >= self.method_parameter_count {
+            self.report_diagnostic_no_advance(
+                span,
+                format!(
+                    "invalid parameter index `{parameter_index}` for a method with {} parameter(s)",
+                    self.method_parameter_count
+                ),
+            );
+        }
+    }
+
+    /// TypeAnnotationTarget <- TYPEPARAM_keyword Integer / TYPEPARAMBOUND_keyword Integer Integer / EXTENDS_keyword
+    ///     / IMPLEMENTS_keyword Integer / FIELD_keyword / RETURN_keyword / RECEIVER_keyword / PARAM_keyword Integer
+    ///     / THROWS_keyword Integer / (LOCALVAR_keyword / RESOURCEVAR_keyword) LocalVarRange+ / CATCH_keyword Integer
+    ///     / (INSTANCEOF_keyword / NEW_keyword / NEWREF_keyword / METHODREF_keyword) Label
+    ///     / (CAST_keyword / NEWTYPEARG_keyword / METHODTYPEARG_keyword / NEWREFTYPEARG_keyword / METHODREFTYPEARG_keyword) Label Integer
+    fn parse_type_annotation_target(
+        &mut self,
+        site: AnnotationSite,
+    ) -> Option<PhoronTypeAnnotationTarget> {
+        use AnnotationSite::{Class, Method};
+        use PhoronTypeAnnotationTarget::*;
+
+        let target_span = self.curr_span();
+
+        // some of the targets, such as `return` and `new`, are lexed as instructions
+        let target_name = match self.see().kind {
+            TokenKind::TIdent(ref target_name)
+                if target_name == "visible" || target_name == "invisible" =>
+            {
+                self.report_diagnostic_no_advance(
+                    target_span,
+                    "missing type annotation target".to_string(),
+                );
+                return Some(PhoronTypeAnnotationTarget::default());
+            }
+            TokenKind::TIdent(ref target_name) => target_name.to_owned(),
+            ref kind => kind.to_string(),
+        };
+        self.advance();
+
+        Some(match (site, target_name.as_str()) {
+            (Class, "typeparam") => ClassTypeParameter {
+                type_parameter_index: self.parse_annotation_index("type parameter index"),
+            },
+
+            (Method, "typeparam") => MethodTypeParameter {
+                type_parameter_index: self.parse_annotation_index("type parameter index"),
+            },
+
+            (Class, "extends") => Supertype {
+                supertype_index: u16::MAX,
+            },
+
+            (Class, "implements") => Supertype {
+                supertype_index: self.parse_annotation_index("interface index"),
+            },
+
+            (Class, "typeparambound") => ClassTypeParameterBound {
+                type_parameter_index: self.parse_annotation_index("type parameter index"),
+                bound_index: self.parse_annotation_index("bound index"),
+            },
+
+            (Method, "typeparambound") => MethodTypeParameterBound {
+                type_parameter_index: self.parse_annotation_index("type parameter index"),
+                bound_index: self.parse_annotation_index("bound index"),
+            },
+
+            // record components share the target type of fields
+            (AnnotationSite::Field | AnnotationSite::Component, "field") => {
+                PhoronTypeAnnotationTarget::Field
+            }
+
+            (Method, "return") => Return,
+
+            (Method, "receiver") => Receiver,
+
+            (Method, "param") => {
+                let index_span = self.curr_span();
+                let formal_parameter_index = self.parse_annotation_index("parameter index");
+                self.check_parameter_index(formal_parameter_index, index_span);
+
+                FormalParameter {
+                    formal_parameter_index,
+                }
+            }
+
+            (Method, "throws") => Throws {
+                throws_type_index: self.parse_annotation_index("throws index"),
+            },
+
+            (Method, "localvar") => LocalVar {
+                ranges: self.parse_local_var_ranges()?,
+            },
+
+            (Method, "resourcevar") => ResourceVar {
+                ranges: self.parse_local_var_ranges()?,
+            },
+
+            (Method, "catch") => Catch {
+                exception_table_index: self.parse_annotation_index("exception table index"),
+            },
+
+            (Method, "instanceof") => Instanceof {
+                label: self.parse_label()?,
+            },
+
+            (Method, "new") => New {
+                label: self.parse_label()?,
+            },
+
+            (Method, "newref") => ConstructorReference {
+                label: self.parse_label()?,
+            },
+
+            (Method, "methodref") => MethodReference {
+                label: self.parse_label()?,
+            },
+
+            (Method, "cast") => Cast {
+                label: self.parse_label()?,
+                type_argument_index: self.parse_annotation_index("type argument index"),
+            },
+
+            (Method, "newtypearg") => ConstructorInvocationTypeArgument {
+                label: self.parse_label()?,
+                type_argument_index: self.parse_annotation_index("type argument index"),
+            },
+
+            (Method, "methodtypearg") => MethodInvocationTypeArgument {
+                label: self.parse_label()?,
+                type_argument_index: self.parse_annotation_index("type argument index"),
+            },
+
+            (Method, "newreftypearg") => ConstructorReferenceTypeArgument {
+                label: self.parse_label()?,
+                type_argument_index: self.parse_annotation_index("type argument index"),
+            },
+
+            (Method, "methodreftypearg") => MethodReferenceTypeArgument {
+                label: self.parse_label()?,
+                type_argument_index: self.parse_annotation_index("type argument index"),
+            },
+
+            _ => {
+                self.report_diagnostic_no_advance(
+                    target_span,
+                    format!(
+                        "invalid type annotation target `{target_name}` for {}",
+                        site.describe()
+                    ),
+                );
+                PhoronTypeAnnotationTarget::default()
+            }
+        })
+    }
+
+    /// LocalVarRange <- Integer FROM_keyword Label TO_keyword Label
+    fn parse_local_var_ranges(&mut self) -> Option<Vec<PhoronLocalVarRange>> {
+        let mut ranges = Vec::new();
+
+        loop {
+            let start_span = self.curr_span();
+            let varnum = self.parse_annotation_index("var num");
+
+            if !self.advance_if(&TokenKind::TFrom) {
+                self.report_diagnostic_no_advance(
+                    start_span.merge(&self.curr_span()),
+                    "missing `from` label".to_string(),
+                );
+            }
+            let from_label = self.parse_label()?;
+
+            if !self.advance_if(&TokenKind::TTo) {
+                self.report_diagnostic_no_advance(
+                    start_span.merge(&self.curr_span()),
+                    "missing `to` label".to_string(),
+                );
+            }
+            let to_label = self.parse_label()?;
+
+            ranges.push(PhoronLocalVarRange {
+                varnum,
+                from_label,
+                to_label,
+            });
+
+            if !matches!(self.see().kind, TokenKind::TInt(..)) {
+                break;
+            }
+        }
+
+        Some(ranges)
+    }
+
+    /// TypePath <- PATH_keyword QuotedString
+    ///
+    /// The path is made up of `[` (deeper in an array type), `.` (deeper in a nested type), `*` (on
+    /// the bound of a wildcard type argument), and `<n>;` (on the n-th type argument) steps.
+    fn parse_type_path(&mut self) -> Option<Vec<PhoronTypePathEntry>> {
+        let path = if let TokenKind::TString(ref path) = self.see().kind {
+            path.to_owned()
+        } else {
+            self.report_diagnostic_no_advance(self.curr_span(), "missing type path".to_string());
+            return Some(vec![]);
+        };
+
+        let mut type_path = Vec::new();
+        let mut chars = path.chars().peekable();
+        let mut valid = true;
+
+        while let Some(c) = chars.next() {
+            type_path.push(match c {
+                '[' => PhoronTypePathEntry::Array,
+                '.' => PhoronTypePathEntry::Nested,
+                '*' => PhoronTypePathEntry::WildcardBound,
+                '0'..='9' => {
+                    let mut index = c.to_string();
+                    while let Some(d) = chars.next_if(|d| d.is_ascii_digit()) {
+                        index.push(d);
+                    }
+
+                    match (index.parse::<u8>(), chars.next()) {
+                        (Ok(index), Some(';')) => PhoronTypePathEntry::TypeArgument(index),
+                        _ => {
+                            valid = false;
+                            break;
+                        }
+                    }
+                }
+                _ => {
+                    valid = false;
+                    break;
+                }
+            });
+        }
+
+        if !valid || type_path.len() > u8::MAX as usize {
+            self.report_diagnostic(self.curr_span(), format!("invalid type path `{path}`"));
+            return Some(vec![]);
+        }
+
+        self.advance();
+        Some(type_path)
+    }
+
+    /// AnnotationDef <- (VISIBLE_keyword / INVISIBLE_keyword) ObjectType Annotation
+    fn parse_annotation_def(&mut self, start_span: Span) -> Option<PhoronAnnotationDef> {
+        let visibility = match self.see().kind {
+            TokenKind::TIdent(ref visibility) if visibility == "visible" => {
+                self.advance();
+                PhoronAnnotationVisibility::Visible
+            }
+
+            TokenKind::TIdent(ref visibility) if visibility == "invisible" => {
+                self.advance();
+                PhoronAnnotationVisibility::Invisible
+            }
+
+            // skip over a misspelt visibility, but not the annotation type
+            TokenKind::TIdent(ref visibility) if !visibility.starts_with(['L', '[']) => {
+                self.report_diagnostic(
+                    self.curr_span(),
+                    format!("invalid annotation visibility `{visibility}`, expected `visible` or `invisible`"),
+                );
+                PhoronAnnotationVisibility::default()
+            }
+
+            _ => {
+                self.report_diagnostic_no_advance(
+                    start_span.merge(&self.curr_span()),
+                    "missing annotation visibility, expected `visible` or `invisible`".to_string(),
+                );
+                PhoronAnnotationVisibility::default()
+            }
+        };
+
+        let annotation_type = self.parse_object_type("annotation")?;
+        let annotation = self.parse_annotation(start_span, annotation_type)?;
+
+        Some(PhoronAnnotationDef {
+            visibility,
+            annotation,
+        })
+    }
+
+    /// Annotation <- ElementValuePair* END_keyword ANNOTATION_keyword
+    fn parse_annotation(
+        &mut self,
+        start_span: Span,
+        annotation_type: PhoronFieldDescriptor,
+    ) -> Option<PhoronAnnotation> {
+        let mut element_value_pairs: Vec<PhoronElementValuePair> = Vec::new();
+
+        loop {
+            match self.see().kind {
+                TokenKind::TEnd => {
+                    let end_span = self.curr_span();
+                    self.advance();
+
+                    match self.see().kind {
+                        TokenKind::TIdent(ref marker) if marker == "annotation" => {
+                            self.advance();
+                        }
+
+                        _ => self.report_diagnostic_no_advance(
+                            end_span,
+                            "missing end annotation marker".to_string(),
+                        ),
+                    }
+
+                    break;
+                }
+
+                TokenKind::TIdent(..) => {
+                    let pair_span = self.curr_span();
+                    let element_value_pair = self.parse_element_value_pair()?;
+
+                    if element_value_pairs
+                        .iter()
+                        .any(|pair| pair.name == element_value_pair.name)
+                    {
+                        self.report_diagnostic_no_advance(
+                            pair_span,
+                            format!(
+                                "duplicate element `{}` in annotation",
+                                element_value_pair.name
+                            ),
+                        );
+                    }
+
+                    element_value_pairs.push(element_value_pair);
+                }
+
+                _ => {
+                    self.report_diagnostic_no_advance(
+                        start_span.merge(&self.curr_span()),
+                        "missing `.end annotation` for annotation".to_string(),
+                    );
+
+                    break;
+                }
+            }
+        }
+
+        Some(PhoronAnnotation {
+            annotation_type,
+            element_value_pairs,
+        })
+    }
+
+    /// ElementValuePair <- ElementName ElementTag EQ_symbol ElementValue
+    fn parse_element_value_pair(&mut self) -> Option<PhoronElementValuePair> {
+        let name = if let TokenKind::TIdent(ref name) = self.see().kind {
+            let name = name.to_owned();
+            self.advance();
+            name
+        } else {
+            unreachable!()
+        };
+
+        let tag = self.parse_element_tag()?;
+
+        if !self.advance_if(&TokenKind::TAssign) {
+            self.report_diagnostic_no_advance(
+                self.curr_span(),
+                format!("missing `=` after element `{name}`"),
+            );
+
+            // the value is missing as well, so leave the rest of the annotation intact
+            if matches!(self.see().kind, TokenKind::TEnd | TokenKind::TEof) {
+                return Some(PhoronElementValuePair {
+                    name,
+                    value: PhoronElementValue::default(),
+                });
+            }
+        }
+
+        let value = self.parse_element_value(&tag)?;
+
+        Some(PhoronElementValuePair { name, value })
+    }
+
+    /// ElementTag <- '['* (BaseType / 's' / 'c' / 'e' ObjectType / '@' ObjectType)
+    fn parse_element_tag(&mut self) -> Option<ElementTag> {
+        let start_span = self.curr_span();
+
+        let (dimensions, mut tag) = match self.see().kind {
+            TokenKind::TIdent(ref ident) => {
+                let dimensions = ident.chars().take_while(|&c| c == '[').count();
+                let tag = ident[dimensions..].to_owned();
+                self.advance();
+
+                (dimensions, tag)
+            }
+
+            TokenKind::TAt => (0, String::default()),
+
+            _ => {
+                self.report_diagnostic_no_advance(start_span, "missing element tag".to_string());
+                return Some(ElementTag::Invalid);
+            }
+        };
+
+        // `@` is lexed on its own, and so follows any `[` in a separate token
+        if tag.is_empty() && self.advance_if(&TokenKind::TAt) {
+            tag.push('@');
+        }
+
+        let mut element_tag = match tag.as_str() {
+            "B" | "C" | "D" | "F" | "I" | "J" | "S" | "Z" | "s" => ElementTag::Const(tag),
+            "c" => ElementTag::Class,
+            "e" => ElementTag::Enum(self.parse_object_type("enum")?),
+            "@" => ElementTag::Annotation(self.parse_object_type("annotation")?),
+            _ => {
+                self.report_diagnostic_no_advance(
+                    start_span,
+                    format!("invalid element tag `{tag}`"),
+                );
+                ElementTag::Invalid
+            }
+        };
+
+        for _ in 0..dimensions {
+            element_tag = ElementTag::Array(Box::new(element_tag));
+        }
+
+        Some(element_tag)
+    }
+
+    /// Report a value which does not match the tag of the element, skipping over it unless it ends
+    /// the enclosing annotation or array.
+    fn report_invalid_element_value(&mut self, tag: &str, expected: &str) {
+        let message = format!("invalid value for element with tag `{tag}`, expected {expected}");
+
+        match self.see().kind {
+            TokenKind::TEnd | TokenKind::TRightBrace | TokenKind::TEof => {
+                self.report_diagnostic_no_advance(self.curr_span(), message)
+            }
+            _ => self.report_diagnostic(self.curr_span(), message),
+        }
+    }
+
+    /// Parse an integral element value, checking that it is within the range of the given type.
+    fn parse_int_element_value<T: TryFrom<i64>>(&mut self, tag: &str, expected: &str) -> Option<T> {
+        if let TokenKind::TInt(int) = self.see().kind {
+            if let Ok(value) = T::try_from(int) {
+                self.advance();
+                return Some(value);
+            }
+        }
+
+        self.report_invalid_element_value(tag, expected);
+        None
+    }
+
+    /// ConstElementValue <- Integer / Double / QuotedString / TRUE_keyword / FALSE_keyword
+    fn parse_const_element_value(&mut self, tag: &str) -> Option<PhoronElementValue> {
+        use PhoronElementValue::*;
+
+        Some(match tag {
+            "B" => Byte(
+                self.parse_int_element_value(tag, "a byte")
+                    .unwrap_or_default(),
+            ),
+
+            "C" => match self.see().kind {
+                TokenKind::TString(ref s) if s.chars().count() == 1 => {
+                    // characters outside the Basic Multilingual Plane take up two `char`s
+                    match u16::try_from(s.chars().next().unwrap() as u32) {
+                        Ok(c) => {
+                            self.advance();
+                            Char(c)
+                        }
+
+                        Err(..) => {
+                            self.report_invalid_element_value(tag, "a single `char`");
+                            Char(u16::default())
+                        }
+                    }
+                }
+
+                _ => Char(
+                    self.parse_int_element_value(tag, "a char or a single-character string")
+                        .unwrap_or_default(),
+                ),
+            },
+
+            "S" => Short(
+                self.parse_int_element_value(tag, "a short")
+                    .unwrap_or_default(),
+            ),
+            "I" => Integer(
+                self.parse_int_element_value(tag, "an int")
+                    .unwrap_or_default(),
+            ),
+            "J" => Long(
+                self.parse_int_element_value(tag, "a long")
+                    .unwrap_or_default(),
+            ),
+
+            "Z" => match self.see().kind {
+                TokenKind::TIdent(ref ident) if ident == "true" || ident == "false" => {
+                    let boolean = ident == "true";
+                    self.advance();
+                    Boolean(boolean)
+                }
+
+                TokenKind::TInt(boolean @ (0 | 1)) => {
+                    self.advance();
+                    Boolean(boolean == 1)
+                }
+
+                _ => {
+                    self.report_invalid_element_value(tag, "`true`, `false`, 0, or 1");
+                    Boolean(bool::default())
+                }
+            },
+
+            "F" | "D" => {
+                let number = match self.see().kind {
+                    TokenKind::TFloat(float) => Some(float),
+                    TokenKind::TInt(int) => Some(int as f64),
+                    _ => None,
+                };
+
+                match number {
+                    Some(number) => {
+                        self.advance();
+                        if tag == "F" {
+                            Float(number as f32)
+                        } else {
+                            Double(number)
+                        }
+                    }
+
+                    None => {
+                        self.report_invalid_element_value(tag, "a number");
+                        if tag == "F" {
+                            Float(f32::default())
+                        } else {
+                            Double(f64::default())
+                        }
+                    }
+                }
+            }
+
+            "s" => {
+                if let TokenKind::TString(ref s) = self.see().kind {
+                    let sval = s.to_owned();
+                    self.advance();
+                    QuotedString(sval)
+                } else {
+                    self.report_invalid_element_value(tag, "a quoted string");
+                    QuotedString(String::default())
+                }
+            }
+
+            _ => unreachable!(),
+        })
+    }
+
+    /// ElementValue <- ConstElementValue / EnumConstName / ReturnDescriptor
+    ///     / ANNOTATION_keyword Annotation / LBRACE_symbol ElementValue* RBRACE_symbol
+    fn parse_element_value(&mut self, tag: &ElementTag) -> Option<PhoronElementValue> {
+        let start_span = self.curr_span();
+
+        Some(match tag {
+            ElementTag::Const(ref tag) => self.parse_const_element_value(tag)?,
+
+            ElementTag::Enum(ref enum_type) => {
+                let const_name = if let TokenKind::TIdent(ref const_name) = self.see().kind {
+                    let const_name = const_name.to_owned();
+                    self.advance();
+                    const_name
+                } else {
+                    self.report_invalid_element_value("e", "an enum constant name");
+                    String::default()
+                };
+
+                PhoronElementValue::Enum {
+                    enum_type: enum_type.clone(),
+                    const_name,
+                }
+            }
+
+            ElementTag::Class => match self.see().kind {
+                TokenKind::TIdent(ref ident) if ident == "V" => {
+                    self.advance();
+                    PhoronElementValue::Class(PhoronReturnDescriptor::VoidDescriptor)
+                }
+
+                _ => match self.parse_field_descriptor() {
+                    Some(field_descriptor) => PhoronElementValue::Class(
+                        PhoronReturnDescriptor::FieldDescriptor(field_descriptor),
+                    ),
+
+                    None => {
+                        self.report_invalid_element_value("c", "a type descriptor");
+                        PhoronElementValue::Class(PhoronReturnDescriptor::default())
+                    }
+                },
+            },
+
+            ElementTag::Annotation(ref annotation_type) => {
+                if let TokenKind::TAnnotation = self.see().kind {
+                    self.advance();
+                    PhoronElementValue::Annotation(
+                        self.parse_annotation(start_span, annotation_type.clone())?,
+                    )
+                } else {
+                    self.report_invalid_element_value("@", "a nested `.annotation`");
+                    PhoronElementValue::Annotation(PhoronAnnotation::default())
+                }
+            }
+
+            ElementTag::Array(ref component_tag) => {
+                let mut values = Vec::new();
+
+                if self.advance_if(&TokenKind::TLeftBrace) {
+                    loop {
+                        match self.see().kind {
+                            TokenKind::TRightBrace => {
+                                self.advance();
+                                break;
+                            }
+
+                            TokenKind::TEnd | TokenKind::TEof => {
+                                self.report_diagnostic_no_advance(
+                                    start_span.merge(&self.curr_span()),
+                                    "missing `}` for array element value".to_string(),
+                                );
+                                break;
+                            }
+
+                            _ => values.push(self.parse_element_value(component_tag)?),
+                        }
+                    }
+                } else {
+                    self.report_invalid_element_value("[", "an array value in `{` and `}`");
+                }
+
+                PhoronElementValue::Array(values)
+            }
+
+            // the tag has already been reported, so just skip over the value
+            ElementTag::Invalid => {
+                if let TokenKind::TInt(..)
+                | TokenKind::TFloat(..)
+                | TokenKind::TString(..)
+                | TokenKind::TIdent(..) = self.see().kind
+                {
+                    self.advance();
+                }
+
+                PhoronElementValue::default()
+            }
+        })
+    }
+
+    /// ClassDirective <- BootstrapDirective / SignatureDirective / AnnotationDirective
+    /// SignatureDirective <- SIGNATURE_keyword Signature newline
+    fn parse_class_directive(&mut self) -> Option<PhoronClassDirective> {
+        match self.see().kind {
+            TokenKind::TAnnotation => Some(
+                match self.parse_annotation_directive(AnnotationSite::Class)? {
+                    AnnotationDirective::Annotation(annotation_def)
+                    // already reported as an error
+                    | AnnotationDirective::ParameterAnnotation { annotation_def, .. } => {
+                        PhoronClassDirective::Annotation(annotation_def)
+                    }
+                    AnnotationDirective::TypeAnnotation(type_annotation_def) => {
+                        PhoronClassDirective::TypeAnnotation(type_annotation_def)
+                    }
+                },
+            ),
+            TokenKind::TBootstrap => self.parse_bootstrap_directive(),
+            TokenKind::TInner => self.parse_inner_class_directive(),
+            TokenKind::TEnclosing => self.parse_enclosing_method_directive(),
+            TokenKind::TNestHost | TokenKind::TNestMember => self.parse_nest_directive(),
+            TokenKind::TComponent => self.parse_record_component_directive(),
+            TokenKind::TPermits => self.parse_permits_directive(),
+            TokenKind::TDebug => self.parse_debug_directive(),
+            TokenKind::TAttribute => {
+                Some(PhoronClassDirective::Attribute(self.parse_attribute_def()?))
+            }
+            TokenKind::TDeprecated => {
+                self.advance();
+                Some(PhoronClassDirective::Deprecated)
+            }
+            TokenKind::TSynthetic => Some(PhoronClassDirective::Synthetic {
+                flag: self.parse_synthetic_directive(),
+            }),
+            TokenKind::TSignature => {
+                let start_span = self.curr_span();
+                self.advance();
+
+                Some(PhoronClassDirective::Signature(self.parse_signature(
+                    start_span,
+                    "class",
+                    |parser| parser.parse_class_signature(),
+                )?))
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    fn parse_class_directives(&mut self) -> Option<Vec<PhoronClassDirective>> {
+        let mut class_directives = Vec::new();
+
+        while self.is_class_directive(&self.see().kind) {
+            let start_span = self.curr_span();
+            let class_directive = self.parse_class_directive()?;
+
+            if let PhoronClassDirective::Signature(..) = class_directive {
+                if class_directives
+                    .iter()
+                    .any(|dir| matches!(dir, PhoronClassDirective::Signature(..)))
+                {
+                    self.report_diagnostic_no_advance(
+                        start_span,
+                        "duplicate `.signature` directive for class".to_string(),
+                    );
+                }
+            }
+
+            if let PhoronClassDirective::Deprecated = class_directive {
+                if class_directives
+                    .iter()
+                    .any(|dir| matches!(dir, PhoronClassDirective::Deprecated))
+                {
+                    self.report_diagnostic_no_advance(
+                        start_span,
+                        "duplicate `.deprecated` directive for class".to_string(),
+                    );
+                }
+            }
+
+            if let PhoronClassDirective::Synthetic { .. } = class_directive {
+                if class_directives
+                    .iter()
+                    .any(|dir| matches!(dir, PhoronClassDirective::Synthetic { .. }))
+                {
+                    self.report_diagnostic_no_advance(
+                        start_span,
+                        "duplicate `.synthetic` directive for class".to_string(),
+                    );
+                }
+            }
+
+            if let PhoronClassDirective::EnclosingMethod(..) = class_directive {
+                if class_directives
+                    .iter()
+                    .any(|dir| matches!(dir, PhoronClassDirective::EnclosingMethod(..)))
+                {
+                    self.report_diagnostic_no_advance(
+                        start_span,
+                        "duplicate `.enclosing` directive for class".to_string(),
+                    );
+                }
+            }
+
+            // a nest host is not itself a member of another nest, and vice versa
+            let has_nest_host = class_directives
+                .iter()
+                .any(|dir| matches!(dir, PhoronClassDirective::NestHost(..)));
+            let has_nest_member = class_directives
+                .iter()
+                .any(|dir| matches!(dir, PhoronClassDirective::NestMember(..)));
+
+            match class_directive {
+                PhoronClassDirective::NestHost(..) if has_nest_host => {
+                    self.report_diagnostic_no_advance(
+                        start_span,
+                        "duplicate `.nesthost` directive for class".to_string(),
+                    );
+                }
+
+                PhoronClassDirective::NestHost(..) if has_nest_member => {
+                    self.report_diagnostic_no_advance(
+                        start_span,
+                        "a class cannot have both `.nesthost` and `.nestmember` directives"
+                            .to_string(),
+                    );
+                }
+
+                PhoronClassDirective::NestMember(..) if has_nest_host && !has_nest_member => {
+                    self.report_diagnostic_no_advance(
+                        start_span,
+                        "a class cannot have both `.nesthost` and `.nestmember` directives"
+                            .to_string(),
+                    );
+                }
+
+                // a sealed class must have subclasses, and so cannot be final
+                PhoronClassDirective::Permits(..)
+                    if self.is_final_class
+                        && !class_directives
+                            .iter()
+                            .any(|dir| matches!(dir, PhoronClassDirective::Permits(..))) =>
+                {
+                    self.report_diagnostic_no_advance(
+                        start_span,
+                        "a `final` class cannot have `.permits` directives".to_string(),
+                    );
+                }
 
-    fn parse_label(&mut self) -> Option<String> {
-        if let Token {
-            kind: TokenKind::TIdent(label),
-            ..
-        } = self.see()
-        {
-            let label = label.to_owned();
-            self.advance();
+                _ => {}
+            }
 
-            Some(label)
-        } else {
-            self.report_diagnostic(self.curr_span(), format!("malformed label"));
-            Some(String::default())
+            class_directives.push(class_directive);
         }
+
+        Some(class_directives)
     }
 
     /// Directive <- (LIMIT_keyword (StackDirective / LocalDirective) / ThrowsDirective / LineNumberDirective / VarDirective / CatchDirective) newline
@@ -427,6 +2805,8 @@ impl<'p> Parser<'p> {
     /// LineNumberDirective <- LINE_keyword Integer
     /// VarDirective <- VAR_keyword Integer IS_keyword VarName FieldDescriptor FROM_keyword Label TO_keyword Label
     /// CatchDirective <- CATCH_keyword ClassName FROM_keyword Label TO_keyword Label USING_keyword Label
+    /// SignatureDirective <- SIGNATURE_keyword Signature
+    /// DefaultDirective <- DEFAULT_keyword ElementTag EQ_symbol ElementValue
     fn parse_directive(&mut self) -> Option<PhoronDirective> {
         Some(match &self.see().kind {
             TokenKind::TLimit => {
@@ -540,9 +2920,27 @@ impl<'p> Parser<'p> {
                     Some(PhoronFieldDescriptor::default())
                 })?;
 
+                // `signature` is optional
+                let signature = match self.see().kind {
+                    TokenKind::TIdent(ref keyword) if keyword == "signature" => {
+                        let signature_span = self.curr_span();
+                        self.advance();
+
+                        Some(
+                            self.parse_signature(signature_span, "local variable", |parser| {
+                                parser.parse_field_signature()
+                            })?,
+                        )
+                    }
+                    _ => None,
+                };
+
+                let mut from_label = String::default();
+                let mut to_label = String::default();
+
                 // `from` is optional
                 if self.advance_if(&TokenKind::TFrom) {
-                    let from_label = self.parse_label().or_else(|| {
+                    from_label = self.parse_label().or_else(|| {
                         self.report_diagnostic(
                             start_span.merge(&self.curr_span()),
                             format!("missing `from` label"),
@@ -553,7 +2951,7 @@ impl<'p> Parser<'p> {
 
                     // `to` is optional
                     if self.advance_if(&TokenKind::TTo) {
-                        let to_label = self.parse_label().or_else(|| {
+                        to_label = self.parse_label().or_else(|| {
                             self.report_diagnostic(
                                 start_span.merge(&self.curr_span()),
                                 format!("missing `from` label"),
@@ -561,32 +2959,17 @@ impl<'p> Parser<'p> {
 
                             Some(String::default())
                         })?;
-
-                        PhoronDirective::Var {
-                            varnum,
-                            name,
-                            field_descriptor,
-                            from_label,
-                            to_label,
-                        }
-                    } else {
-                        PhoronDirective::Var {
-                            varnum,
-                            name,
-                            field_descriptor,
-                            from_label,
-                            to_label: String::default(),
-                        }
-                    }
-                } else {
-                    PhoronDirective::Var {
-                        varnum,
-                        name,
-                        field_descriptor,
-                        from_label: String::default(),
-                        to_label: String::default(),
                     }
                 }
+
+                PhoronDirective::Var {
+                    varnum,
+                    name,
+                    field_descriptor,
+                    signature,
+                    from_label,
+                    to_label,
+                }
             }
 
             TokenKind::TCatch => {
@@ -659,6 +3042,129 @@ impl<'p> Parser<'p> {
                 }
             }
 
+            TokenKind::TAnnotation => {
+                match self.parse_annotation_directive(AnnotationSite::Method)? {
+                    AnnotationDirective::Annotation(annotation_def) => {
+                        PhoronDirective::Annotation(annotation_def)
+                    }
+                    AnnotationDirective::ParameterAnnotation {
+                        parameter_index,
+                        annotation_def,
+                    } => PhoronDirective::ParameterAnnotation {
+                        parameter_index,
+                        annotation_def,
+                    },
+                    AnnotationDirective::TypeAnnotation(type_annotation_def) => {
+                        PhoronDirective::TypeAnnotation(type_annotation_def)
+                    }
+                }
+            }
+
+            TokenKind::TSignature => {
+                let start_span = self.curr_span();
+                self.advance();
+
+                PhoronDirective::Signature(self.parse_signature(
+                    start_span,
+                    "method",
+                    |parser| parser.parse_method_signature(),
+                )?)
+            }
+
+            TokenKind::TDefault => {
+                let start_span = self.curr_span();
+                self.advance();
+
+                self.check_class_file_version(49, "annotation defaults", start_span);
+
+                // only the elements of annotation interfaces, which take no parameters, have defaults
+                if !self.is_annotation_interface {
+                    self.report_diagnostic_no_advance(
+                        start_span,
+                        "`.default` is only allowed in the methods of annotation interfaces"
+                            .to_string(),
+                    );
+                } else if self.method_parameter_count > 0 {
+                    self.report_diagnostic_no_advance(
+                        start_span,
+                        format!(
+                            "`.default` is only allowed in methods without parameters, but the method has {}",
+                            self.method_parameter_count
+                        ),
+                    );
+                }
+
+                let tag = self.parse_element_tag()?;
+
+                if !self.advance_if(&TokenKind::TAssign) {
+                    self.report_diagnostic_no_advance(
+                        self.curr_span(),
+                        "missing `=` after `.default` element tag".to_string(),
+                    );
+                }
+
+                PhoronDirective::AnnotationDefault(self.parse_element_value(&tag)?)
+            }
+
+            TokenKind::TParameter => {
+                let start_span = self.curr_span();
+                self.advance();
+
+                self.check_class_file_version(52, "method parameters", start_span);
+
+                let index_span = self.curr_span();
+                let parameter_index = self.parse_annotation_index("parameter index");
+                self.check_parameter_index(parameter_index, index_span);
+
+                let mut access_flags = Vec::new();
+                loop {
+                    match self.see().kind {
+                        TokenKind::TFinal => access_flags.push(PhoronParameterAccessFlag::AccFinal),
+                        TokenKind::TIdent(ref flag) if flag == "synthetic" => {
+                            access_flags.push(PhoronParameterAccessFlag::AccSynthetic)
+                        }
+                        TokenKind::TIdent(ref flag) if flag == "mandated" => {
+                            access_flags.push(PhoronParameterAccessFlag::AccMandated)
+                        }
+                        _ => break,
+                    }
+                    self.advance();
+                }
+
+                let name = if let TokenKind::TIdent(ref name) = self.see().kind {
+                    let name = name.to_owned();
+                    self.advance();
+                    name
+                } else {
+                    self.report_diagnostic_no_advance(
+                        self.curr_span(),
+                        "missing parameter name".to_string(),
+                    );
+                    String::default()
+                };
+
+                PhoronDirective::Parameter {
+                    parameter_index,
+                    name,
+                    access_flags,
+                }
+            }
+
+            TokenKind::TAttribute => PhoronDirective::Attribute(self.parse_attribute_def()?),
+
+            TokenKind::TCodeAttribute => {
+                PhoronDirective::CodeAttribute(self.parse_attribute_def()?)
+            }
+
+            TokenKind::TDeprecated => {
+                self.advance();
+                PhoronDirective::Deprecated
+            }
+
+            TokenKind::TSynthetic => PhoronDirective::Synthetic {
+                flag: self.parse_synthetic_directive(),
+            },
+
             _ => {
                 unreachable!()
             }
@@ -2057,6 +4563,45 @@ impl<'p> Parser<'p> {
                 }
             }
 
+            // invokedynamic <method-name><method-descriptor> <bootstrap-method>
+            TokenKind::TInvokedynamic => {
+                let start_span = self.curr_span();
+                self.advance();
+
+                if let TokenKind::TIdent(ref method_name) = self.see().kind {
+                    let method_name = method_name.to_owned();
+                    self.advance();
+
+                    let method_descriptor = self.parse_method_descriptor().or_else(|| {
+                        self.report_diagnostic(
+                            start_span.merge(&self.curr_span()),
+                            "missing method descriptor".to_string(),
+                        );
+
+                        Some(PhoronMethodDescriptor::default())
+                    })?;
+
+                    let bootstrap_method = self.parse_bootstrap_method_name()?;
+
+                    JvmInstruction::Invokedynamic {
+                        method_name,
+                        method_descriptor,
+                        bootstrap_method,
+                    }
+                } else {
+                    self.report_diagnostic(
+                        start_span.merge(&self.curr_span()),
+                        "missing method name".to_string(),
+                    );
+
+                    JvmInstruction::Invokedynamic {
+                        method_name: String::default(),
+                        method_descriptor: PhoronMethodDescriptor::default(),
+                        bootstrap_method: String::default(),
+                    }
+                }
+            }
+
             // invokestatic <method-spec>
             TokenKind::TInvokestatic => {
                 let start_span = self.curr_span();
@@ -2375,7 +4920,7 @@ impl<'p> Parser<'p> {
                 JvmInstruction::Lconst1
             }
 
-            // ldc <integer / float / quoted string>
+            // ldc <integer / float / quoted string / class / method type / method handle / dynamic>
             TokenKind::TLdc => {
                 let start_span = self.curr_span();
                 self.advance();
@@ -2399,10 +4944,47 @@ impl<'p> Parser<'p> {
                         JvmInstruction::Ldc(LdcValue::QuotedString(sval))
                     }
 
+                    TokenKind::TIdent(ref ident) if ident == "class" => {
+                        self.advance();
+                        self.check_class_file_version(49, "class constants", start_span);
+                        JvmInstruction::Ldc(LdcValue::Class(self.parse_class_constant()?))
+                    }
+
+                    TokenKind::TIdent(ref ident) if ident == "methodtype" => {
+                        self.advance();
+                        self.check_class_file_version(51, "method type constants", start_span);
+                        JvmInstruction::Ldc(LdcValue::MethodType(
+                            self.parse_method_type(start_span)?,
+                        ))
+                    }
+
+                    TokenKind::TIdent(ref ident) if ident == "methodhandle" => {
+                        self.advance();
+                        self.check_class_file_version(51, "method handle constants", start_span);
+                        JvmInstruction::Ldc(LdcValue::MethodHandle(self.parse_method_handle()?))
+                    }
+
+                    TokenKind::TIdent(ref ident) if ident == "dynamic" => {
+                        self.advance();
+                        let dynamic_constant = self.parse_dynamic_constant(start_span)?;
+
+                        if dynamic_constant.field_descriptor.is_category2() {
+                            self.report_diagnostic_no_advance(
+                                start_span,
+                                format!(
+                                    "dynamic constant `{}` has type `{}`, and must be loaded using ldc2_w",
+                                    dynamic_constant.name, dynamic_constant.field_descriptor
+                                ),
+                            );
+                        }
+
+                        JvmInstruction::Ldc(LdcValue::Dynamic(dynamic_constant))
+                    }
+
                     tok_kind => {
                         self.report_diagnostic(
                             start_span,
-                            format!("found `{tok_kind}`, but I expected an int, float, or string value here")
+                            format!("found `{tok_kind}`, but I expected an int, float, string, class, method type, method handle, or dynamic constant value here")
                         );
 
                         JvmInstruction::Ldc(LdcValue::default())
@@ -2410,7 +4992,7 @@ impl<'p> Parser<'p> {
                 }
             }
 
-            // ldcw <integer / float / quoted string>
+            // ldcw <integer / float / quoted string / class / method type / method handle / dynamic>
             TokenKind::TLdcw => {
                 let start_span = self.curr_span();
                 self.advance();
@@ -2434,11 +5016,48 @@ impl<'p> Parser<'p> {
                         JvmInstruction::Ldcw(LdcwValue::QuotedString(sval))
                     }
 
+                    TokenKind::TIdent(ref ident) if ident == "class" => {
+                        self.advance();
+                        self.check_class_file_version(49, "class constants", start_span);
+                        JvmInstruction::Ldcw(LdcwValue::Class(self.parse_class_constant()?))
+                    }
+
+                    TokenKind::TIdent(ref ident) if ident == "methodtype" => {
+                        self.advance();
+                        self.check_class_file_version(51, "method type constants", start_span);
+                        JvmInstruction::Ldcw(LdcwValue::MethodType(
+                            self.parse_method_type(start_span)?,
+                        ))
+                    }
+
+                    TokenKind::TIdent(ref ident) if ident == "methodhandle" => {
+                        self.advance();
+                        self.check_class_file_version(51, "method handle constants", start_span);
+                        JvmInstruction::Ldcw(LdcwValue::MethodHandle(self.parse_method_handle()?))
+                    }
+
+                    TokenKind::TIdent(ref ident) if ident == "dynamic" => {
+                        self.advance();
+                        let dynamic_constant = self.parse_dynamic_constant(start_span)?;
+
+                        if dynamic_constant.field_descriptor.is_category2() {
+                            self.report_diagnostic_no_advance(
+                                start_span,
+                                format!(
+                                    "dynamic constant `{}` has type `{}`, and must be loaded using ldc2_w",
+                                    dynamic_constant.name, dynamic_constant.field_descriptor
+                                ),
+                            );
+                        }
+
+                        JvmInstruction::Ldcw(LdcwValue::Dynamic(dynamic_constant))
+                    }
+
                     tok_kind => {
                         self.report_diagnostic(
                             start_span,
                             format!(
-                                "found `{tok_kind}`, but I expected an int, float, or string here"
+                                "found `{tok_kind}`, but I expected an int, float, string, class, method type, method handle, or dynamic constant here"
                             ),
                         );
 
@@ -2447,7 +5066,7 @@ impl<'p> Parser<'p> {
                 }
             }
 
-            // ldc2_w <Long / Double>
+            // ldc2_w <Long / Double / dynamic>
             TokenKind::TLdc2w => {
                 let start_span = self.curr_span();
                 self.advance();
@@ -2465,10 +5084,27 @@ impl<'p> Parser<'p> {
                         JvmInstruction::Ldc2w(Ldc2wValue::Double(dval))
                     }
 
+                    TokenKind::TIdent(ref ident) if ident == "dynamic" => {
+                        self.advance();
+                        let dynamic_constant = self.parse_dynamic_constant(start_span)?;
+
+                        if !dynamic_constant.field_descriptor.is_category2() {
+                            self.report_diagnostic_no_advance(
+                                start_span,
+                                format!(
+                                    "dynamic constant `{}` has type `{}`, but ldc2_w can only load dynamic constants of type `J` or `D`",
+                                    dynamic_constant.name, dynamic_constant.field_descriptor
+                                ),
+                            );
+                        }
+
+                        JvmInstruction::Ldc2w(Ldc2wValue::Dynamic(dynamic_constant))
+                    }
+
                     tok_kind => {
                         self.report_diagnostic(
                             start_span,
-                            format!("found `{tok_kind}`, but I expected a long or double here"),
+                            format!("found `{tok_kind}`, but I expected a long, double, or dynamic constant here"),
                         );
 
                         JvmInstruction::Ldc2w(Ldc2wValue::default())
@@ -3148,7 +5784,8 @@ impl<'p> Parser<'p> {
     /// Instruction <- line_comment* (Directive / JvmInstruction / Label) line_comment?  newline
     fn parse_instruction(&mut self) -> Option<PhoronInstruction> {
         Some(match &self.see().kind {
-            TThrows | TCatch | TLimit | TVar | TLine => {
+            TThrows | TCatch | TLimit | TVar | TLine | TSignature | TAnnotation | TDefault
+            | TParameter | TAttribute | TCodeAttribute | TDeprecated | TSynthetic => {
                 PhoronInstruction::PhoronDirective(self.parse_directive()?)
             }
 
@@ -3156,28 +5793,28 @@ impl<'p> Parser<'p> {
             | TAnewarray | TAreturn | TArraylength | TAssign | TAstore | TAstore0 | TAstore1
             | TAstore2 | TAstore3 | TAthrow | TBaload | TBastore | TBipush | TBridge | TCaload
             | TCastore | TCheckcast | TD2f | TD2i | TD2l | TDadd | TDaload | TDastore | TDcmpg
-            | TDcmpl | TDconst0 | TDconst1 | TDdiv | TDefault | TDload | TDload0 | TDload1
-            | TDload2 | TDload3 | TDmul | TDneg | TDot | TDrem | TDreturn | TDstore | TDstore0
-            | TDstore1 | TDstore2 | TDstore3 | TDsub | TDup | TDup2 | TDup2x1 | TDup2x2
-            | TDupx1 | TDupx2 | TEnum | TF2d | TF2i | TF2l | TFadd | TFaload | TFastore
-            | TFcmpg | TFcmpl | TFconst0 | TFconst1 | TFconst2 | TFdiv | TField | TFinal
-            | TFload | TFload0 | TFload1 | TFload2 | TFload3 | TFmul | TFneg | TFrem | TFreturn
-            | TFrom | TFstore | TFstore0 | TFstore1 | TFstore2 | TFstore3 | TFsub | TGetfield
-            | TGetstatic | TGoto | TGotow | TI2b | TI2c | TI2d | TI2f | TI2l | TI2s | TIadd
-            | TIaload | TIand | TIastore | TIconst0 | TIconst1 | TIconst2 | TIconst3 | TIconst4
+            | TDcmpl | TDconst0 | TDconst1 | TDdiv | TDload | TDload0 | TDload1 | TDload2
+            | TDload3 | TDmul | TDneg | TDot | TDrem | TDreturn | TDstore | TDstore0 | TDstore1
+            | TDstore2 | TDstore3 | TDsub | TDup | TDup2 | TDup2x1 | TDup2x2 | TDupx1 | TDupx2
+            | TEnum | TF2d | TF2i | TF2l | TFadd | TFaload | TFastore | TFcmpg | TFcmpl
+            | TFconst0 | TFconst1 | TFconst2 | TFdiv | TField | TFinal | TFload | TFload0
+            | TFload1 | TFload2 | TFload3 | TFmul | TFneg | TFrem | TFreturn | TFrom | TFstore
+            | TFstore0 | TFstore1 | TFstore2 | TFstore3 | TFsub | TGetfield | TGetstatic
+            | TGoto | TGotow | TI2b | TI2c | TI2d | TI2f | TI2l | TI2s | TIadd | TIaload
+            | TIand | TIastore | TIconst0 | TIconst1 | TIconst2 | TIconst3 | TIconst4
             | TIconst5 | TIconstm1 | TIdiv | TIfacmpeq | TIfacmpne | TIfeq | TIfge | TIfgt
             | TIficmpeq | TIficmpge | TIficmpgt | TIficmple | TIficmplt | TIficmpne | TIfle
             | TIflt | TIfne | TIfnonnull | TIfnull | TIinc | TIload | TIload0 | TIload1
-            | TIload2 | TIload3 | TImul | TIneg | TInstanceof | TInvokeinterface
-            | TInvokespecial | TInvokestatic | TInvokevirtual | TIor | TIrem | TIreturn | TIshl
-            | TIshr | TIstore | TIstore0 | TIstore1 | TIstore2 | TIstore3 | TIsub | TIushr
-            | TIxor | TJsr | TJsrw | TL2d | TL2f | TL2i | TLadd | TLand | TLastore | TLcmp
-            | TLconst0 | TLconst1 | TLdc | TLdc2w | TLdcw | TLdiv | TLload | TLload0 | TLload1
-            | TLload2 | TLload3 | TLmul | TLneg | TLoaload | TLookupswitch | TLor | TLrem
-            | TLreturn | TLshl | TLshr | TLstore | TLstore0 | TLstore1 | TLstore2 | TLstore3
-            | TLsub | TLushr | TLxor | TMonitorenter | TMonitorexit | TMultianewarray | TNew
-            | TNewarray | TNop | TPop | TPop2 | TPutfield | TPutstatic | TRet | TReturn
-            | TSaload | TSastore | TSipush | TSuper | TSwap | TTableswitch => {
+            | TIload2 | TIload3 | TImul | TIneg | TInstanceof | TInvokedynamic
+            | TInvokeinterface | TInvokespecial | TInvokestatic | TInvokevirtual | TIor | TIrem
+            | TIreturn | TIshl | TIshr | TIstore | TIstore0 | TIstore1 | TIstore2 | TIstore3
+            | TIsub | TIushr | TIxor | TJsr | TJsrw | TL2d | TL2f | TL2i | TLadd | TLand
+            | TLastore | TLcmp | TLconst0 | TLconst1 | TLdc | TLdc2w | TLdcw | TLdiv | TLload
+            | TLload0 | TLload1 | TLload2 | TLload3 | TLmul | TLneg | TLoaload | TLookupswitch
+            | TLor | TLrem | TLreturn | TLshl | TLshr | TLstore | TLstore0 | TLstore1
+            | TLstore2 | TLstore3 | TLsub | TLushr | TLxor | TMonitorenter | TMonitorexit
+            | TMultianewarray | TNew | TNewarray | TNop | TPop | TPop2 | TPutfield | TPutstatic
+            | TRet | TReturn | TSaload | TSastore | TSipush | TSuper | TSwap | TTableswitch => {
                 PhoronInstruction::JvmInstruction(self.parse_jvm_instruction()?)
             }
 
@@ -3379,9 +6016,82 @@ impl<'p> Parser<'p> {
             let method_descriptor = self
                 .parse_method_descriptor()
                 .or(Some(PhoronMethodDescriptor::default()))?;
+            self.method_parameter_count = method_descriptor.param_descriptor.len();
 
             let instructions = self.parse_instructions().or(Some(vec![]))?;
 
+            for (directive, marker) in [
+                (PhoronDirective::Deprecated, "deprecated"),
+                (PhoronDirective::Synthetic { flag: false }, "synthetic"),
+            ] {
+                if instructions
+                    .iter()
+                    .filter(|instr| {
+                        matches!(instr, PhoronInstruction::PhoronDirective(dir) if std::mem::discriminant(dir) == std::mem::discriminant(&directive))
+                    })
+                    .count()
+                    > 1
+                {
+                    self.report_diagnostic_no_advance(
+                        start_span,
+                        format!("duplicate `.{marker}` directive in method `{name}`"),
+                    );
+                }
+            }
+
+            if instructions
+                .iter()
+                .filter(|instr| {
+                    matches!(
+                        instr,
+                        PhoronInstruction::PhoronDirective(PhoronDirective::Signature(..))
+                    )
+                })
+                .count()
+                > 1
+            {
+                self.report_diagnostic_no_advance(
+                    start_span,
+                    format!("duplicate `.signature` directive in method `{name}`"),
+                );
+            }
+
+            if instructions
+                .iter()
+                .filter(|instr| {
+                    matches!(
+                        instr,
+                        PhoronInstruction::PhoronDirective(PhoronDirective::AnnotationDefault(..))
+                    )
+                })
+                .count()
+                > 1
+            {
+                self.report_diagnostic_no_advance(
+                    start_span,
+                    format!("duplicate `.default` directive in method `{name}`"),
+                );
+            }
+
+            let mut parameter_indices = Vec::new();
+            for instr in &instructions {
+                if let PhoronInstruction::PhoronDirective(PhoronDirective::Parameter {
+                    parameter_index,
+                    ..
+                }) = instr
+                {
+                    if parameter_indices.contains(parameter_index) {
+                        self.report_diagnostic_no_advance(
+                            start_span,
+                            format!(
+                                "duplicate `.parameter` directive for parameter {parameter_index} in method `{name}`"
+                            ),
+                        );
+                    }
+                    parameter_indices.push(*parameter_index);
+                }
+            }
+
             Some(PhoronMethodDef {
                 name,
                 access_flags,
@@ -3442,10 +6152,102 @@ impl<'p> Parser<'p> {
         }
     }
 
-    /// Header <- SourceFileDef? (ClassDef / InterfaceDef) SuperDef
+    /// VersionDef <- VERSION_keyword Major (DOT_symbol Minor)? newline
+    fn parse_version_def(&mut self) -> Option<PhoronVersionDef> {
+        let start_span = self.curr_span();
+        self.advance();
+
+        // `45.3` is lexed as a single float token, so the major and minor parts are recovered
+        // from the source text (so that, for instance, `45.30` is not read as `45.3`).
+        let version = match self.see().kind {
+            TokenKind::TInt(major) => Some((major, 0)),
+            TokenKind::TFloat(_) => {
+                let version = self.curr_span().source(self.lexer.source_file);
+                let mut parts = version.trim().splitn(2, '.');
+
+                match (
+                    parts.next().and_then(|major| major.parse::<i64>().ok()),
+                    parts
+                        .next()
+                        .map_or(Some(0), |minor| minor.parse::<i64>().ok()),
+                ) {
+                    (Some(major), Some(minor)) => Some((major, minor)),
+                    _ => None,
+                }
+            }
+            _ => None,
+        };
+
+        let (major_version, minor_version) = match version {
+            None => {
+                self.report_diagnostic(
+                    start_span.merge(&self.curr_span()),
+                    "malformed class file version - expected <major>.<minor>".to_string(),
+                );
+
+                return Some(PhoronVersionDef::default());
+            }
+
+            Some(version) => {
+                let version_span = start_span.merge(&self.curr_span());
+                self.advance();
+
+                match version {
+                    (major, minor)
+                        if major < PhoronVersionDef::DEFAULT_MAJOR_VERSION as i64
+                            || major > u16::MAX as i64
+                            || minor > u16::MAX as i64 =>
+                    {
+                        self.report_diagnostic_no_advance(
+                            version_span,
+                            format!("unsupported class file version {major}.{minor}"),
+                        );
+
+                        return Some(PhoronVersionDef::default());
+                    }
+
+                    // from Java 12 (56.0) onwards, the minor version must be either 0, or 65535
+                    // to indicate that the class depends on preview features.
+                    (major, minor)
+                        if major >= 56
+                            && minor != 0
+                            && minor != PhoronVersionDef::PREVIEW_MINOR_VERSION as i64 =>
+                    {
+                        self.report_diagnostic_no_advance(
+                            version_span,
+                            format!(
+                                "invalid minor version {minor} for class file version {major} - expected 0 or 65535"
+                            ),
+                        );
+
+                        (major as u16, 0)
+                    }
+
+                    (major, minor) => (major as u16, minor as u16),
+                }
+            }
+        };
+
+        Some(PhoronVersionDef {
+            major_version,
+            minor_version,
+        })
+    }
+
+    /// Header <- VersionDef? SourceFileDef? (ClassDef / InterfaceDef) SuperDef ImplementsDef* ClassDirective*
     fn parse_header(&mut self) -> Option<PhoronHeader> {
         self.advance();
 
+        let version_def = if let TokenKind::TVersion = self.see().kind {
+            self.parse_version_def()?
+        } else {
+            PhoronVersionDef::default()
+        };
+
+        // the target version takes precedence over the `.version` directive
+        let version_def = self.target_version.unwrap_or(version_def);
+        self.version_def = version_def;
+
         Some(match &self.see().kind {
             TokenKind::TSource => {
                 self.advance();
@@ -3454,6 +6256,10 @@ impl<'p> Parser<'p> {
                     .parse_sourcefile_def()
                     .or(Some(PhoronSourceFileDef::default()))?;
 
+                if let TokenKind::TModule = self.see().kind {
+                    return self.parse_module_header(version_def, sourcefile_def);
+                }
+
                 let class_or_interface_def = match &self.see().kind {
                     TokenKind::TClass => PhoronClassOrInterface::Class(self.parse_class_def()?),
                     TokenKind::TInterface => {
@@ -3462,7 +6268,9 @@ impl<'p> Parser<'p> {
                     tok_kind => {
                         self.report_diagnostic(
                             self.curr_span(),
-                            format!(" found `{tok_kind}`, but I expected `.class` or `.interface`"),
+                            format!(
+                        " found `{tok_kind}`, but I expected `.class`, `.interface`, or `.module`"
+                    ),
                         );
 
                         PhoronClassOrInterface::default()
@@ -3471,12 +6279,15 @@ impl<'p> Parser<'p> {
 
                 let super_def = self.parse_super_def().or(Some(PhoronSuperDef::default()))?;
                 let implements_defs = self.parse_implements_defs().or(Some(vec![]))?;
+                let class_directives = self.parse_class_directives().or(Some(vec![]))?;
 
                 PhoronHeader {
+                    version_def,
                     sourcefile_def,
                     class_or_interface_def,
                     super_def,
                     implements_defs,
+                    class_directives,
                 }
             }
 
@@ -3490,12 +6301,15 @@ impl<'p> Parser<'p> {
                 );
                 let super_def = self.parse_super_def()?;
                 let implements_defs = self.parse_implements_defs().or(Some(vec![]))?;
+                let class_directives = self.parse_class_directives().or(Some(vec![]))?;
 
                 PhoronHeader {
+                    version_def,
                     sourcefile_def,
                     class_or_interface_def,
                     super_def,
                     implements_defs,
+                    class_directives,
                 }
             }
 
@@ -3510,15 +6324,26 @@ impl<'p> Parser<'p> {
                 );
                 let super_def = self.parse_super_def()?;
                 let implements_defs = self.parse_implements_defs().or(Some(vec![]))?;
+                let class_directives = self.parse_class_directives().or(Some(vec![]))?;
 
                 PhoronHeader {
+                    version_def,
                     sourcefile_def,
                     class_or_interface_def,
                     super_def,
                     implements_defs,
+                    class_directives,
                 }
             }
 
+            TokenKind::TModule => {
+                let sourcefile_def = PhoronSourceFileDef {
+                    source_file: self.lexer.src_file().to_string(),
+                };
+
+                return self.parse_module_header(version_def, sourcefile_def);
+            }
+
             tok_kind => {
                 self.report_diagnostic(
                     self.curr_span(),
@@ -3530,11 +6355,42 @@ impl<'p> Parser<'p> {
         })
     }
 
+    /// ModuleHeader <- ModuleDef ClassDirective*
+    fn parse_module_header(
+        &mut self,
+        version_def: PhoronVersionDef,
+        sourcefile_def: PhoronSourceFileDef,
+    ) -> Option<PhoronHeader> {
+        let module_def = self.parse_module_def()?;
+
+        self.is_module_declaration = true;
+        let class_directives = self.parse_class_directives().or(Some(vec![]))?;
+
+        // the `module-info` class has no fields or methods
+        if matches!(self.see().kind, TokenKind::TField | TokenKind::TMethod) {
+            self.report_diagnostic_no_advance(
+                self.curr_span(),
+                "a module declaration cannot have fields or methods".to_string(),
+            );
+        }
+
+        Some(PhoronHeader {
+            version_def,
+            sourcefile_def,
+            class_or_interface_def: PhoronClassOrInterface::Module(module_def),
+            super_def: PhoronSuperDef::default(),
+            implements_defs: vec![],
+            class_directives,
+        })
+    }
+
     /// PhoronProgram <- line_comment* Header Body eof
     pub fn parse(&mut self) -> Option<PhoronProgram> {
         let header = self.parse_header()?;
         let body = self.parse_body()?;
 
+        self.check_record_components(&header, &body);
+
         Some(PhoronProgram { header, body })
     }
 }
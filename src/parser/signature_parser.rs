@@ -0,0 +1,274 @@
+//! A parser for the generic signatures stored in `Signature` attributes, as per the JVM
+//! specification. Signatures are kept as strings in the AST, and so this only checks that they are
+//! well-formed.
+
+use super::type_descriptor_parser::{TypeParseError, TypeParseResult};
+use std::{iter::Peekable, str::Chars};
+
+pub struct SignatureParser<'s> {
+    src: Peekable<Chars<'s>>,
+}
+
+impl<'s> SignatureParser<'s> {
+    pub fn new(src: &'s str) -> Self {
+        SignatureParser {
+            src: src.chars().peekable(),
+        }
+    }
+
+    fn peek(&mut self) -> Option<char> {
+        self.src.peek().copied()
+    }
+
+    fn advance_if(&mut self, c: char) -> bool {
+        if self.peek() == Some(c) {
+            self.src.next();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, c: char, details: &'static str) -> TypeParseResult<()> {
+        if self.advance_if(c) {
+            Ok(())
+        } else {
+            Err(Box::new(TypeParseError::Missing { details }))
+        }
+    }
+
+    fn expect_end(&mut self) -> TypeParseResult<()> {
+        match self.peek() {
+            None => Ok(()),
+            Some(_) => Err(Box::new(TypeParseError::Malformed {
+                details: "unexpected characters at the end of the signature",
+            })),
+        }
+    }
+
+    // ClassSignature <- TypeParameters? SuperclassSignature SuperinterfaceSignature*
+    // SuperclassSignature <- ClassTypeSignature
+    // SuperinterfaceSignature <- ClassTypeSignature
+    pub fn parse_class_signature(&mut self) -> TypeParseResult<()> {
+        self.parse_type_parameters()?;
+        self.parse_class_type_signature()?;
+
+        while self.peek().is_some() {
+            self.parse_class_type_signature()?;
+        }
+
+        Ok(())
+    }
+
+    // MethodSignature <- TypeParameters? '(' JavaTypeSignature* ')' Result ThrowsSignature*
+    // Result <- JavaTypeSignature / VoidDescriptor
+    // ThrowsSignature <- '^' ClassTypeSignature / '^' TypeVariableSignature
+    pub fn parse_method_signature(&mut self) -> TypeParseResult<()> {
+        self.parse_type_parameters()?;
+
+        self.expect('(', "missing ( for the parameters in method signature")?;
+        while !self.advance_if(')') {
+            if self.peek().is_none() {
+                return Err(Box::new(TypeParseError::Missing {
+                    details: "missing ) for the parameters in method signature",
+                }));
+            }
+            self.parse_java_type_signature()?;
+        }
+
+        if !self.advance_if('V') {
+            self.parse_java_type_signature()?;
+        }
+
+        while self.advance_if('^') {
+            match self.peek() {
+                Some('L') => self.parse_class_type_signature()?,
+                Some('T') => self.parse_type_variable_signature()?,
+                _ => {
+                    return Err(Box::new(TypeParseError::Malformed {
+                        details: "expected a class type or type variable signature after ^",
+                    }))
+                }
+            }
+        }
+
+        self.expect_end()
+    }
+
+    // FieldSignature <- ReferenceTypeSignature
+    pub fn parse_field_signature(&mut self) -> TypeParseResult<()> {
+        self.parse_reference_type_signature()?;
+        self.expect_end()
+    }
+
+    // TypeParameters <- '<' TypeParameter TypeParameter* '>'
+    fn parse_type_parameters(&mut self) -> TypeParseResult<()> {
+        if self.advance_if('<') {
+            self.parse_type_parameter()?;
+
+            while !self.advance_if('>') {
+                if self.peek().is_none() {
+                    return Err(Box::new(TypeParseError::Missing {
+                        details: "missing > for type parameters",
+                    }));
+                }
+                self.parse_type_parameter()?;
+            }
+        }
+
+        Ok(())
+    }
+
+    // TypeParameter <- Identifier ClassBound InterfaceBound*
+    // ClassBound <- ':' ReferenceTypeSignature?
+    // InterfaceBound <- ':' ReferenceTypeSignature
+    fn parse_type_parameter(&mut self) -> TypeParseResult<()> {
+        self.parse_identifier()?;
+
+        self.expect(':', "missing : for the class bound of type parameter")?;
+        if let Some('L' | 'T' | '[') = self.peek() {
+            self.parse_reference_type_signature()?;
+        }
+
+        while self.advance_if(':') {
+            self.parse_reference_type_signature()?;
+        }
+
+        Ok(())
+    }
+
+    // JavaTypeSignature <- ReferenceTypeSignature / BaseType
+    // BaseType <- 'B' / 'C' / 'D' / 'F' / 'I' / 'J' / 'S' / 'Z'
+    fn parse_java_type_signature(&mut self) -> TypeParseResult<()> {
+        match self.peek() {
+            Some('B' | 'C' | 'D' | 'F' | 'I' | 'J' | 'S' | 'Z') => {
+                self.src.next();
+                Ok(())
+            }
+            _ => self.parse_reference_type_signature(),
+        }
+    }
+
+    // ReferenceTypeSignature <- ClassTypeSignature / TypeVariableSignature / ArrayTypeSignature
+    // ArrayTypeSignature <- '[' JavaTypeSignature
+    fn parse_reference_type_signature(&mut self) -> TypeParseResult<()> {
+        match self.peek() {
+            Some('L') => self.parse_class_type_signature(),
+            Some('T') => self.parse_type_variable_signature(),
+            Some('[') => {
+                self.src.next();
+                self.parse_java_type_signature()
+            }
+            _ => Err(Box::new(TypeParseError::Malformed {
+                details: "expected a class type, type variable, or array type signature",
+            })),
+        }
+    }
+
+    // ClassTypeSignature <- 'L' PackageSpecifier? SimpleClassTypeSignature ClassTypeSignatureSuffix* ';'
+    // PackageSpecifier <- Identifier '/' PackageSpecifier*
+    // SimpleClassTypeSignature <- Identifier TypeArguments?
+    // ClassTypeSignatureSuffix <- '.' SimpleClassTypeSignature
+    fn parse_class_type_signature(&mut self) -> TypeParseResult<()> {
+        self.expect('L', "missing L for class type signature")?;
+
+        self.parse_identifier()?;
+        while self.advance_if('/') {
+            self.parse_identifier()?;
+        }
+        self.parse_type_arguments()?;
+
+        while self.advance_if('.') {
+            self.parse_identifier()?;
+            self.parse_type_arguments()?;
+        }
+
+        self.expect(';', "missing ; for class type signature")
+    }
+
+    // TypeArguments <- '<' TypeArgument TypeArgument* '>'
+    // TypeArgument <- WildcardIndicator? ReferenceTypeSignature / '*'
+    // WildcardIndicator <- '+' / '-'
+    fn parse_type_arguments(&mut self) -> TypeParseResult<()> {
+        if !self.advance_if('<') {
+            return Ok(());
+        }
+
+        loop {
+            if !self.advance_if('*') {
+                let _ = self.advance_if('+') || self.advance_if('-');
+                self.parse_reference_type_signature()?;
+            }
+
+            if self.advance_if('>') {
+                break Ok(());
+            }
+
+            if self.peek().is_none() {
+                break Err(Box::new(TypeParseError::Missing {
+                    details: "missing > for type arguments",
+                }));
+            }
+        }
+    }
+
+    // TypeVariableSignature <- 'T' Identifier ';'
+    fn parse_type_variable_signature(&mut self) -> TypeParseResult<()> {
+        self.expect('T', "missing T for type variable signature")?;
+        self.parse_identifier()?;
+        self.expect(';', "missing ; for type variable signature")
+    }
+
+    // Identifier <- (!('.' / ';' / '[' / '/' / '<' / '>' / ':') .)+
+    fn parse_identifier(&mut self) -> TypeParseResult<()> {
+        let mut len = 0;
+
+        while let Some(c) = self.peek() {
+            if matches!(c, '.' | ';' | '[' | '/' | '<' | '>' | ':') {
+                break;
+            }
+            self.src.next();
+            len += 1;
+        }
+
+        if len == 0 {
+            return Err(Box::new(TypeParseError::Missing {
+                details: "missing identifier in signature",
+            }));
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_signatures() {
+        let class_signature = |sig| SignatureParser::new(sig).parse_class_signature().is_ok();
+        let method_signature = |sig| SignatureParser::new(sig).parse_method_signature().is_ok();
+        let field_signature = |sig| SignatureParser::new(sig).parse_field_signature().is_ok();
+
+        assert!(class_signature(
+            "<K::Ljava/lang/Comparable<-TK;>;V:Ljava/lang/Object;>Ljava/lang/Object;Ljava/util/Map<TK;TV;>;"
+        ));
+        assert!(class_signature("Ljava/util/ArrayList<Ljava/lang/String;>;"));
+        assert!(!class_signature("<T>Ljava/lang/Object;"));
+        assert!(!class_signature("<>Ljava/lang/Object;"));
+
+        assert!(method_signature(
+            "<T:Ljava/lang/Object;>(Ljava/util/List<+TT;>;I)[TT;^Ljava/io/IOException;^TE;"
+        ));
+        assert!(method_signature("()V"));
+        assert!(!method_signature("(Ljava/util/List<*>;"));
+        assert!(!method_signature("()VV"));
+
+        assert!(field_signature("Ljava/util/Map$Entry<TK;TV;>;"));
+        assert!(field_signature("Lcom/example/Outer<TT;>.Inner<[I>;"));
+        assert!(field_signature("[[TT;"));
+        assert!(!field_signature("I"));
+        assert!(!field_signature("Ljava/util/List<Ljava/lang/String;>"));
+    }
+}
@@ -0,0 +1,141 @@
+//! Resolution of the superclass relationships between classes, needed when merging the types of
+//! references which flow into the same instruction along different paths.
+
+use std::collections::HashMap;
+
+pub const JAVA_LANG_OBJECT: &str = "java/lang/Object";
+
+/// Provides the superclass of a given class. Since Phoron assembles a single class at a time, it
+/// cannot know the hierarchy of the classes referred to by the code, and so this can be plugged in
+/// by the user of the library (for instance, by looking up the classes in a classpath).
+pub trait ClassHierarchyResolver {
+    /// Return the name of the direct superclass of `class_name`, or `None` if it is not known, or
+    /// if `class_name` is `java/lang/Object` itself. Interfaces are expected to report
+    /// `java/lang/Object` as their superclass, as per the JVM verifier.
+    fn super_class(&self, class_name: &str) -> Option<String>;
+}
+
+/// A resolver which knows about a handful of commonly used classes from `java.base`, and to which
+/// further classes may be added. Classes which it does not know about are treated as direct
+/// subclasses of `java/lang/Object`.
+#[derive(Debug)]
+pub struct DefaultClassHierarchyResolver {
+    super_classes: HashMap<String, String>,
+}
+
+impl DefaultClassHierarchyResolver {
+    pub fn new() -> Self {
+        let mut resolver = DefaultClassHierarchyResolver {
+            super_classes: HashMap::new(),
+        };
+
+        for (class_name, super_class_name) in [
+            ("java/lang/String", JAVA_LANG_OBJECT),
+            ("java/lang/AbstractStringBuilder", JAVA_LANG_OBJECT),
+            ("java/lang/StringBuilder", "java/lang/AbstractStringBuilder"),
+            ("java/lang/StringBuffer", "java/lang/AbstractStringBuilder"),
+            ("java/lang/Number", JAVA_LANG_OBJECT),
+            ("java/lang/Byte", "java/lang/Number"),
+            ("java/lang/Short", "java/lang/Number"),
+            ("java/lang/Integer", "java/lang/Number"),
+            ("java/lang/Long", "java/lang/Number"),
+            ("java/lang/Float", "java/lang/Number"),
+            ("java/lang/Double", "java/lang/Number"),
+            ("java/lang/Throwable", JAVA_LANG_OBJECT),
+            ("java/lang/Error", "java/lang/Throwable"),
+            ("java/lang/Exception", "java/lang/Throwable"),
+            ("java/lang/RuntimeException", "java/lang/Exception"),
+            (
+                "java/lang/ArithmeticException",
+                "java/lang/RuntimeException",
+            ),
+            ("java/lang/ClassCastException", "java/lang/RuntimeException"),
+            (
+                "java/lang/IllegalArgumentException",
+                "java/lang/RuntimeException",
+            ),
+            (
+                "java/lang/IllegalStateException",
+                "java/lang/RuntimeException",
+            ),
+            (
+                "java/lang/IndexOutOfBoundsException",
+                "java/lang/RuntimeException",
+            ),
+            (
+                "java/lang/NullPointerException",
+                "java/lang/RuntimeException",
+            ),
+            (
+                "java/lang/UnsupportedOperationException",
+                "java/lang/RuntimeException",
+            ),
+            (
+                "java/lang/NumberFormatException",
+                "java/lang/IllegalArgumentException",
+            ),
+            (
+                "java/lang/ArrayIndexOutOfBoundsException",
+                "java/lang/IndexOutOfBoundsException",
+            ),
+            (
+                "java/lang/StringIndexOutOfBoundsException",
+                "java/lang/IndexOutOfBoundsException",
+            ),
+            ("java/lang/InterruptedException", "java/lang/Exception"),
+            (
+                "java/lang/ReflectiveOperationException",
+                "java/lang/Exception",
+            ),
+            (
+                "java/lang/ClassNotFoundException",
+                "java/lang/ReflectiveOperationException",
+            ),
+            ("java/io/IOException", "java/lang/Exception"),
+            ("java/io/FileNotFoundException", "java/io/IOException"),
+            ("java/util/AbstractCollection", JAVA_LANG_OBJECT),
+            ("java/util/AbstractList", "java/util/AbstractCollection"),
+            ("java/util/AbstractSequentialList", "java/util/AbstractList"),
+            ("java/util/ArrayList", "java/util/AbstractList"),
+            ("java/util/LinkedList", "java/util/AbstractSequentialList"),
+            ("java/util/AbstractSet", "java/util/AbstractCollection"),
+            ("java/util/HashSet", "java/util/AbstractSet"),
+            ("java/util/TreeSet", "java/util/AbstractSet"),
+            ("java/util/AbstractMap", JAVA_LANG_OBJECT),
+            ("java/util/HashMap", "java/util/AbstractMap"),
+            ("java/util/TreeMap", "java/util/AbstractMap"),
+        ] {
+            resolver.add_class(class_name, super_class_name);
+        }
+
+        resolver
+    }
+
+    /// Record `super_class_name` as the direct superclass of `class_name`.
+    pub fn add_class(&mut self, class_name: &str, super_class_name: &str) {
+        self.super_classes
+            .insert(class_name.to_owned(), super_class_name.to_owned());
+    }
+}
+
+impl Default for DefaultClassHierarchyResolver {
+    fn default() -> Self {
+        DefaultClassHierarchyResolver::new()
+    }
+}
+
+impl ClassHierarchyResolver for DefaultClassHierarchyResolver {
+    fn super_class(&self, class_name: &str) -> Option<String> {
+        if class_name == JAVA_LANG_OBJECT {
+            return None;
+        }
+
+        Some(
+            self.super_classes
+                .get(class_name)
+                .map_or(JAVA_LANG_OBJECT.to_owned(), |super_class_name| {
+                    super_class_name.to_owned()
+                }),
+        )
+    }
+}
@@ -0,0 +1,1541 @@
+//! Inference of the `StackMapTable` frames required by the type-checking verifier for class files
+//! of version 50.0 and above.
+//!
+//! The type checker expects a frame describing the verification types of the local variables and
+//! the operand stack at the start of every basic block which can be entered other than by falling
+//! through - branch targets, `switch` targets, and exception handlers. These are inferred by a
+//! simple dataflow analysis over the JVM instructions of the method, starting from the frame
+//! implied by the method descriptor. Where references of different types flow into the same
+//! instruction, they are merged into their common superclass, as determined by a
+//! `ClassHierarchyResolver`.
+//!
+//! Frames are keyed by the position of the instruction in the method (labels and directives
+//! excluded) rather than by bytecode offset, and it is left to the code generator to map these to
+//! the actual offsets.
+//!
+//! The same analysis also yields the `max_stack` and `max_locals` limits of a method, which are
+//! needed regardless of the class file version. In that case, subroutines (`jsr` and `ret`) are
+//! followed as well, since no frames need to be described for them.
+
+pub mod class_hierarchy;
+
+use crate::ast::*;
+use class_hierarchy::{ClassHierarchyResolver, JAVA_LANG_OBJECT};
+
+use std::{
+    collections::{BTreeSet, HashMap, HashSet},
+    error::Error,
+    fmt,
+};
+
+/// The class file version from which on the `StackMapTable` attribute is used by the verifier.
+pub const STACK_MAP_MAJOR_VERSION: u16 = 50;
+
+#[derive(Debug)]
+pub enum StackMapError {
+    UndefinedLabel { label: String },
+    StackUnderflow { method: String, instruction: usize },
+    InconsistentStack { method: String, instruction: usize },
+    Subroutine { method: String },
+}
+
+impl Error for StackMapError {}
+
+impl fmt::Display for StackMapError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use StackMapError::*;
+
+        write!(
+            f,
+            "{}",
+            match *self {
+                UndefinedLabel { ref label } => format!("undefined label `{label}`"),
+                StackUnderflow {
+                    ref method,
+                    instruction,
+                } => format!("operand stack underflow at instruction {instruction} of method `{method}`"),
+                InconsistentStack {
+                    ref method,
+                    instruction,
+                } => format!(
+                    "the operand stack differs between the paths leading to instruction {instruction} of method `{method}`"
+                ),
+                Subroutine { ref method } => format!(
+                    "cannot compute stack map frames for method `{method}` since it uses `jsr` or `ret`"
+                ),
+            }
+        )
+    }
+}
+
+pub type StackMapResult<T> = Result<T, StackMapError>;
+
+/// The type of a local variable or operand stack entry, as seen by the verifier.
+#[derive(Debug, Clone, PartialEq)]
+pub enum VerificationType {
+    Top,
+    Integer,
+    Float,
+    Long,
+    Double,
+    Null,
+    UninitializedThis,
+    /// a class, interface, or array type, named as in its `CONSTANT_Class` entry.
+    Object(String),
+    /// the object created by the `new` instruction at the given position, whose constructor has not
+    /// been invoked yet.
+    Uninitialized(usize),
+    /// the return address pushed by a `jsr` to the subroutine at the given position. This never
+    /// appears in a stack map frame.
+    ReturnAddress(usize),
+}
+
+impl VerificationType {
+    fn from_field_descriptor(field_descriptor: &PhoronFieldDescriptor) -> Self {
+        match field_descriptor {
+            PhoronFieldDescriptor::BaseType(PhoronBaseType::Long) => VerificationType::Long,
+            PhoronFieldDescriptor::BaseType(PhoronBaseType::Double) => VerificationType::Double,
+            PhoronFieldDescriptor::BaseType(PhoronBaseType::Float) => VerificationType::Float,
+            PhoronFieldDescriptor::BaseType(..) => VerificationType::Integer,
+            PhoronFieldDescriptor::ObjectType { ref class_name } => {
+                VerificationType::Object(class_name.to_owned())
+            }
+            PhoronFieldDescriptor::ArrayType { .. } => {
+                VerificationType::Object(field_descriptor.to_string())
+            }
+        }
+    }
+
+    /// The type of the elements of an array, given the descriptor of its component type.
+    fn from_component_descriptor(component_descriptor: &str) -> Self {
+        match component_descriptor.chars().next() {
+            Some('J') => VerificationType::Long,
+            Some('D') => VerificationType::Double,
+            Some('F') => VerificationType::Float,
+            Some('L') => VerificationType::Object(
+                component_descriptor[1..component_descriptor.len() - 1].to_owned(),
+            ),
+            Some('[') => VerificationType::Object(component_descriptor.to_owned()),
+            _ => VerificationType::Integer,
+        }
+    }
+
+    /// `long` and `double` values take up two slots in the local variables and operand stack.
+    pub fn is_category2(&self) -> bool {
+        matches!(self, VerificationType::Long | VerificationType::Double)
+    }
+}
+
+/// The state of the local variables and operand stack at a given instruction. Both are kept as a
+/// list of slots, `long` and `double` values being followed by a `Top` slot.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Frame {
+    pub locals: Vec<VerificationType>,
+    pub stack: Vec<VerificationType>,
+}
+
+impl Frame {
+    /// The local variables as listed in a `StackMapTable` frame - `long` and `double` values take
+    /// up a single entry, and trailing `Top` entries are dropped.
+    pub fn frame_locals(&self) -> Vec<VerificationType> {
+        let mut locals = Frame::collapse(&self.locals);
+        while let Some(VerificationType::Top) = locals.last() {
+            locals.pop();
+        }
+        locals
+    }
+
+    /// The operand stack as listed in a `StackMapTable` frame.
+    pub fn frame_stack(&self) -> Vec<VerificationType> {
+        Frame::collapse(&self.stack)
+    }
+
+    fn collapse(slots: &[VerificationType]) -> Vec<VerificationType> {
+        let mut entries = Vec::new();
+        let mut slots = slots.iter();
+
+        while let Some(slot) = slots.next() {
+            if slot.is_category2() {
+                slots.next();
+            }
+            entries.push(slot.clone());
+        }
+
+        entries
+    }
+
+    fn push(&mut self, vtype: VerificationType) {
+        let category2 = vtype.is_category2();
+        self.stack.push(vtype);
+        if category2 {
+            self.stack.push(VerificationType::Top);
+        }
+    }
+
+    fn pop(&mut self) -> Option<VerificationType> {
+        self.stack.pop()
+    }
+
+    /// Pop a single value, which takes up two slots if it is a `long` or a `double`.
+    fn pop_value(&mut self) -> Option<VerificationType> {
+        match self.stack.pop()? {
+            VerificationType::Top => self.stack.pop(),
+            vtype => Some(vtype),
+        }
+    }
+
+    fn pop_slots(&mut self, count: usize) -> Option<Vec<VerificationType>> {
+        if self.stack.len() < count {
+            return None;
+        }
+        Some(self.stack.split_off(self.stack.len() - count))
+    }
+
+    fn load(&self, varnum: usize) -> VerificationType {
+        self.locals
+            .get(varnum)
+            .cloned()
+            .unwrap_or(VerificationType::Top)
+    }
+
+    fn store(&mut self, varnum: usize, vtype: VerificationType) {
+        let slots = if vtype.is_category2() { 2 } else { 1 };
+        if self.locals.len() < varnum + slots {
+            self.locals.resize(varnum + slots, VerificationType::Top);
+        }
+
+        // overwriting the second half of a `long` or `double` invalidates it
+        if varnum > 0 && self.locals[varnum - 1].is_category2() {
+            self.locals[varnum - 1] = VerificationType::Top;
+        }
+
+        self.locals[varnum] = vtype;
+        if slots == 2 {
+            self.locals[varnum + 1] = VerificationType::Top;
+        }
+    }
+
+    /// Once the constructor of an uninitialized object has been invoked, all references to it
+    /// become references to an initialized object.
+    fn initialize(&mut self, uninitialized: &VerificationType, class_name: &str) {
+        for slot in self.locals.iter_mut().chain(self.stack.iter_mut()) {
+            if slot == uninitialized {
+                *slot = VerificationType::Object(class_name.to_owned());
+            }
+        }
+    }
+}
+
+/// The frames inferred for a method.
+#[derive(Debug, Default)]
+pub struct MethodFrames {
+    /// the frame implied by the method descriptor, which precedes the first explicit frame.
+    pub initial_frame: Frame,
+    /// the explicit frames, along with the position of the instruction they apply to, in order.
+    pub frames: Vec<(usize, Frame)>,
+}
+
+/// The sizes of the local variables array and operand stack needed by a method, in slots.
+#[derive(Debug, Default, PartialEq)]
+pub struct MethodLimits {
+    pub max_stack: u16,
+    pub max_locals: u16,
+}
+
+struct ExceptionHandler {
+    start: usize,
+    end: usize,
+    handler: usize,
+    catch_type: VerificationType,
+}
+
+enum Flow {
+    /// execution continues with the next instruction.
+    Next,
+    /// execution may continue either with the next instruction or at the given targets.
+    Branch(Vec<usize>),
+    /// execution continues at the given targets (if any) only.
+    Jump(Vec<usize>),
+}
+
+struct FrameAnalyzer<'a> {
+    method_name: &'a str,
+    class_name: &'a str,
+    super_class_name: &'a str,
+    resolver: &'a dyn ClassHierarchyResolver,
+    code: Vec<&'a JvmInstruction>,
+    labels: HashMap<&'a str, usize>,
+    handlers: Vec<ExceptionHandler>,
+    /// whether `jsr` and `ret` are followed, rather than rejected.
+    follow_subroutines: bool,
+}
+
+impl<'a> FrameAnalyzer<'a> {
+    fn target(&self, label: &str) -> StackMapResult<usize> {
+        match self.labels.get(label) {
+            Some(&index) if index < self.code.len() => Ok(index),
+            _ => Err(StackMapError::UndefinedLabel {
+                label: label.to_owned(),
+            }),
+        }
+    }
+
+    fn super_class(&self, class_name: &str) -> Option<String> {
+        if class_name == self.class_name {
+            Some(self.super_class_name.to_owned())
+        } else {
+            self.resolver.super_class(class_name)
+        }
+    }
+
+    fn common_super_class(&self, first: &str, second: &str) -> String {
+        let mut ancestors = HashSet::new();
+        let mut curr = Some(first.to_owned());
+        while let Some(class_name) = curr {
+            if !ancestors.insert(class_name.clone()) {
+                break;
+            }
+            curr = self.super_class(&class_name);
+        }
+
+        let mut seen = HashSet::new();
+        let mut curr = Some(second.to_owned());
+        while let Some(class_name) = curr {
+            if ancestors.contains(&class_name) {
+                return class_name;
+            }
+            if !seen.insert(class_name.clone()) {
+                break;
+            }
+            curr = self.super_class(&class_name);
+        }
+
+        JAVA_LANG_OBJECT.to_owned()
+    }
+
+    /// Merge two class or array types into the most specific type both are assignable to.
+    fn merge_object_types(&self, first: &str, second: &str) -> String {
+        if first == second {
+            return first.to_owned();
+        }
+
+        match (first.strip_prefix('['), second.strip_prefix('[')) {
+            // arrays of references are covariant in their component types
+            (Some(first_component), Some(second_component))
+                if (first_component.starts_with('L') || first_component.starts_with('['))
+                    && (second_component.starts_with('L') || second_component.starts_with('[')) =>
+            {
+                let as_class_name = |descriptor: &str| -> String {
+                    if descriptor.starts_with('L') {
+                        descriptor[1..descriptor.len() - 1].to_owned()
+                    } else {
+                        descriptor.to_owned()
+                    }
+                };
+
+                let merged = self.merge_object_types(
+                    &as_class_name(first_component),
+                    &as_class_name(second_component),
+                );
+
+                if merged.starts_with('[') {
+                    format!("[{merged}")
+                } else {
+                    format!("[L{merged};")
+                }
+            }
+
+            (None, None) => self.common_super_class(first, second),
+
+            _ => JAVA_LANG_OBJECT.to_owned(),
+        }
+    }
+
+    /// Merge two verification types, returning `None` if they have nothing in common.
+    fn merge_types(
+        &self,
+        first: &VerificationType,
+        second: &VerificationType,
+    ) -> Option<VerificationType> {
+        use VerificationType::*;
+
+        match (first, second) {
+            _ if first == second => Some(first.clone()),
+            (Null, Object(..)) => Some(second.clone()),
+            (Object(..), Null) => Some(first.clone()),
+            (Object(ref first), Object(ref second)) => {
+                Some(Object(self.merge_object_types(first, second)))
+            }
+            _ => None,
+        }
+    }
+
+    fn merge_frames(&self, old: &Frame, new: &Frame, instruction: usize) -> StackMapResult<Frame> {
+        if old.stack.len() != new.stack.len() {
+            return Err(StackMapError::InconsistentStack {
+                method: self.method_name.to_owned(),
+                instruction: instruction + 1,
+            });
+        }
+
+        let stack = old
+            .stack
+            .iter()
+            .zip(new.stack.iter())
+            .map(|(old, new)| {
+                self.merge_types(old, new)
+                    .ok_or(StackMapError::InconsistentStack {
+                        method: self.method_name.to_owned(),
+                        instruction: instruction + 1,
+                    })
+            })
+            .collect::<StackMapResult<Vec<_>>>()?;
+
+        let mut locals = (0..old.locals.len().max(new.locals.len()))
+            .map(|varnum| {
+                self.merge_types(&old.load(varnum), &new.load(varnum))
+                    .unwrap_or(VerificationType::Top)
+            })
+            .collect::<Vec<_>>();
+
+        // a `long` or `double` is only valid if both of its halves are
+        for varnum in 0..locals.len() {
+            if locals[varnum].is_category2()
+                && locals.get(varnum + 1) != Some(&VerificationType::Top)
+            {
+                locals[varnum] = VerificationType::Top;
+            }
+        }
+
+        Ok(Frame { locals, stack })
+    }
+
+    fn initial_frame(&self, method_def: &PhoronMethodDef) -> Frame {
+        let mut frame = Frame::default();
+
+        if !method_def
+            .access_flags
+            .contains(&PhoronMethodAccessFlag::AccStatic)
+        {
+            frame.locals.push(
+                if method_def.name == "<init>" && self.class_name != JAVA_LANG_OBJECT {
+                    VerificationType::UninitializedThis
+                } else {
+                    VerificationType::Object(self.class_name.to_owned())
+                },
+            );
+        }
+
+        for param in &method_def.method_descriptor.param_descriptor {
+            let varnum = frame.locals.len();
+            frame.store(varnum, VerificationType::from_field_descriptor(param));
+        }
+
+        frame
+    }
+
+    fn ldc_type(&self, value: LoadableConstant) -> VerificationType {
+        match value {
+            LoadableConstant::Integer => VerificationType::Integer,
+            LoadableConstant::Float => VerificationType::Float,
+            LoadableConstant::Object(class_name) => VerificationType::Object(class_name.to_owned()),
+            LoadableConstant::Dynamic(field_descriptor) => {
+                VerificationType::from_field_descriptor(field_descriptor)
+            }
+        }
+    }
+
+    fn invoke(&self, frame: &mut Frame, method_descriptor: &PhoronMethodDescriptor) -> Option<()> {
+        for _ in &method_descriptor.param_descriptor {
+            frame.pop_value()?;
+        }
+        Some(())
+    }
+
+    fn push_return(&self, frame: &mut Frame, method_descriptor: &PhoronMethodDescriptor) {
+        if let PhoronReturnDescriptor::FieldDescriptor(ref return_type) =
+            method_descriptor.return_descriptor
+        {
+            frame.push(VerificationType::from_field_descriptor(return_type));
+        }
+    }
+
+    /// Returning from a subroutine continues after every `jsr` which calls it, with the local
+    /// variables and operand stack as they are at the `ret`.
+    fn ret(&self, frame: &Frame, varnum: usize) -> StackMapResult<Option<Flow>> {
+        if !self.follow_subroutines {
+            return Err(StackMapError::Subroutine {
+                method: self.method_name.to_owned(),
+            });
+        }
+
+        let subroutine = match frame.load(varnum) {
+            VerificationType::ReturnAddress(subroutine) => subroutine,
+            _ => return Ok(Some(Flow::Jump(vec![]))),
+        };
+
+        let mut return_points = Vec::new();
+        for (index, instr) in self.code.iter().enumerate() {
+            if let JvmInstruction::Jsr { ref label } | JvmInstruction::Jsrw { ref label } = **instr
+            {
+                if self.target(label)? == subroutine {
+                    return_points.push(index + 1);
+                }
+            }
+        }
+
+        Ok(Some(Flow::Jump(return_points)))
+    }
+
+    /// Run the instruction at `index` against `frame`, returning where execution may continue.
+    fn execute(&self, index: usize, frame: &mut Frame) -> StackMapResult<Flow> {
+        self.execute_instruction(index, frame)?
+            .ok_or(StackMapError::StackUnderflow {
+                method: self.method_name.to_owned(),
+                instruction: index + 1,
+            })
+    }
+
+    fn execute_instruction(&self, index: usize, frame: &mut Frame) -> StackMapResult<Option<Flow>> {
+        use JvmInstruction::*;
+        use VerificationType as VT;
+
+        macro_rules! pop {
+            ($n:expr) => {
+                if frame.pop_slots($n).is_none() {
+                    return Ok(None);
+                }
+            };
+        }
+
+        macro_rules! unary {
+            ($pops:expr, $result:expr) => {{
+                pop!($pops);
+                frame.push($result);
+            }};
+        }
+
+        match *self.code[index] {
+            Nop => {}
+
+            Aconstnull => frame.push(VT::Null),
+
+            Iconstm1 | Iconst0 | Iconst1 | Iconst2 | Iconst3 | Iconst4 | Iconst5 | Bipush(..)
+            | Sipush(..) => frame.push(VT::Integer),
+            Lconst0 | Lconst1 => frame.push(VT::Long),
+            Fconst0 | Fconst1 | Fconst2 => frame.push(VT::Float),
+            Dconst0 | Dconst1 => frame.push(VT::Double),
+
+            Ldc(ref value) => frame.push(self.ldc_type(LoadableConstant::from_ldc(value))),
+            Ldcw(ref value) => frame.push(self.ldc_type(LoadableConstant::from_ldcw(value))),
+            Ldc2w(ref value) => frame.push(match value {
+                Ldc2wValue::Long(..) => VT::Long,
+                Ldc2wValue::Double(..) => VT::Double,
+                Ldc2wValue::Dynamic(ref dynamic_constant) => {
+                    VT::from_field_descriptor(&dynamic_constant.field_descriptor)
+                }
+            }),
+
+            Iload0 | Iload1 | Iload2 | Iload3 | Iload { .. } => frame.push(VT::Integer),
+            Lload0 | Lload1 | Lload2 | Lload3 | Lload { .. } => frame.push(VT::Long),
+            Fload0 | Fload1 | Fload2 | Fload3 | Fload { .. } => frame.push(VT::Float),
+            Dload0 | Dload1 | Dload2 | Dload3 | Dload { .. } => frame.push(VT::Double),
+            Aload0 => frame.push(frame.load(0)),
+            Aload1 => frame.push(frame.load(1)),
+            Aload2 => frame.push(frame.load(2)),
+            Aload3 => frame.push(frame.load(3)),
+            Aload { varnum } => frame.push(frame.load(varnum as usize)),
+
+            Istore0
+            | Istore1
+            | Istore2
+            | Istore3
+            | Istore { .. }
+            | Lstore0
+            | Lstore1
+            | Lstore2
+            | Lstore3
+            | Lstore { .. }
+            | Fstore0
+            | Fstore1
+            | Fstore2
+            | Fstore3
+            | Fstore { .. }
+            | Dstore0
+            | Dstore1
+            | Dstore2
+            | Dstore3
+            | Dstore { .. }
+            | Astore0
+            | Astore1
+            | Astore2
+            | Astore3
+            | Astore { .. } => {
+                let varnum = match *self.code[index] {
+                    Istore0 | Lstore0 | Fstore0 | Dstore0 | Astore0 => 0,
+                    Istore1 | Lstore1 | Fstore1 | Dstore1 | Astore1 => 1,
+                    Istore2 | Lstore2 | Fstore2 | Dstore2 | Astore2 => 2,
+                    Istore3 | Lstore3 | Fstore3 | Dstore3 | Astore3 => 3,
+                    Istore { varnum }
+                    | Lstore { varnum }
+                    | Fstore { varnum }
+                    | Dstore { varnum }
+                    | Astore { varnum } => varnum as usize,
+                    _ => unreachable!(),
+                };
+
+                match frame.pop_value() {
+                    Some(vtype) => frame.store(varnum, vtype),
+                    None => return Ok(None),
+                }
+            }
+
+            Wide(ref wide_instr) => match *wide_instr {
+                WideInstruction::Iload { .. } => frame.push(VT::Integer),
+                WideInstruction::Lload { .. } => frame.push(VT::Long),
+                WideInstruction::Fload { .. } => frame.push(VT::Float),
+                WideInstruction::Dload { .. } => frame.push(VT::Double),
+                WideInstruction::Aload { varnum } => frame.push(frame.load(varnum as usize)),
+                WideInstruction::Istore { varnum }
+                | WideInstruction::Lstore { varnum }
+                | WideInstruction::Fstore { varnum }
+                | WideInstruction::Dstore { varnum }
+                | WideInstruction::Astore { varnum } => match frame.pop_value() {
+                    Some(vtype) => frame.store(varnum as usize, vtype),
+                    None => return Ok(None),
+                },
+                WideInstruction::IInc { .. } => {}
+                WideInstruction::Ret { varnum } => return self.ret(frame, varnum as usize),
+            },
+
+            Iaload | Baload | Caload | Saload => unary!(2, VT::Integer),
+            Laload => unary!(2, VT::Long),
+            Faload => unary!(2, VT::Float),
+            Daload => unary!(2, VT::Double),
+            Aaload => {
+                pop!(1);
+                let element_type = match frame.pop() {
+                    Some(VT::Object(ref array_type)) if array_type.starts_with('[') => {
+                        VT::from_component_descriptor(&array_type[1..])
+                    }
+                    Some(..) => VT::Null,
+                    None => return Ok(None),
+                };
+                frame.push(element_type);
+            }
+
+            Iastore | Bastore | Castore | Sastore | Fastore | Aastore => pop!(3),
+            Lastore | Dastore => pop!(4),
+
+            Pop => pop!(1),
+            Pop2 => pop!(2),
+
+            Dup | Dupx1 | Dupx2 | Dup2 | Dup2x1 | Dup2x2 | Swap => {
+                // these operate on slots, regardless of the types of the values
+                let (count, order): (usize, &[usize]) = match *self.code[index] {
+                    Dup => (1, &[0, 0]),
+                    Dupx1 => (2, &[1, 0, 1]),
+                    Dupx2 => (3, &[2, 0, 1, 2]),
+                    Dup2 => (2, &[0, 1, 0, 1]),
+                    Dup2x1 => (3, &[1, 2, 0, 1, 2]),
+                    Dup2x2 => (4, &[2, 3, 0, 1, 2, 3]),
+                    Swap => (2, &[1, 0]),
+                    _ => unreachable!(),
+                };
+
+                match frame.pop_slots(count) {
+                    Some(slots) => frame
+                        .stack
+                        .extend(order.iter().map(|&slot| slots[slot].clone())),
+                    None => return Ok(None),
+                }
+            }
+
+            Iadd | Isub | Imul | Idiv | Irem | Iand | Ior | Ixor | Ishl | Ishr | Iushr => {
+                unary!(2, VT::Integer)
+            }
+            Ladd | Lsub | Lmul | Ldiv | Lrem | Land | Lor | Lxor => unary!(4, VT::Long),
+            Lshl | Lshr | Lushr => unary!(3, VT::Long),
+            Fadd | Fsub | Fmul | Fdiv | Frem => unary!(2, VT::Float),
+            Dadd | Dsub | Dmul | Ddiv | Drem => unary!(4, VT::Double),
+            Ineg | I2b | I2c | I2s => unary!(1, VT::Integer),
+            Lneg => unary!(2, VT::Long),
+            Fneg => unary!(1, VT::Float),
+            Dneg => unary!(2, VT::Double),
+            Iinc { .. } => {}
+
+            I2l => unary!(1, VT::Long),
+            I2f => unary!(1, VT::Float),
+            I2d => unary!(1, VT::Double),
+            L2i => unary!(2, VT::Integer),
+            L2f => unary!(2, VT::Float),
+            L2d => unary!(2, VT::Double),
+            F2i => unary!(1, VT::Integer),
+            F2l => unary!(1, VT::Long),
+            F2d => unary!(1, VT::Double),
+            D2i => unary!(2, VT::Integer),
+            D2l => unary!(2, VT::Long),
+            D2f => unary!(2, VT::Float),
+
+            Lcmp | Dcmpl | Dcmpg => unary!(4, VT::Integer),
+            Fcmpl | Fcmpg => unary!(2, VT::Integer),
+
+            Ifeq { ref label }
+            | Ifne { ref label }
+            | Iflt { ref label }
+            | Ifge { ref label }
+            | Ifgt { ref label }
+            | Ifle { ref label }
+            | Ifnull { ref label }
+            | Ifnonnull { ref label } => {
+                pop!(1);
+                return Ok(Some(Flow::Branch(vec![self.target(label)?])));
+            }
+
+            Ificmpeq { ref label }
+            | Ificmpne { ref label }
+            | Ificmplt { ref label }
+            | Ificmpge { ref label }
+            | Ificmpgt { ref label }
+            | Ificmple { ref label }
+            | Ifacmpeq { ref label }
+            | Ifacmpne { ref label } => {
+                pop!(2);
+                return Ok(Some(Flow::Branch(vec![self.target(label)?])));
+            }
+
+            Goto { ref label } | Gotow { ref label } => {
+                return Ok(Some(Flow::Jump(vec![self.target(label)?])));
+            }
+
+            Jsr { ref label } | Jsrw { ref label } => {
+                if !self.follow_subroutines {
+                    return Err(StackMapError::Subroutine {
+                        method: self.method_name.to_owned(),
+                    });
+                }
+
+                let subroutine = self.target(label)?;
+                frame.push(VT::ReturnAddress(subroutine));
+                return Ok(Some(Flow::Jump(vec![subroutine])));
+            }
+
+            Ret { varnum } => return self.ret(frame, varnum as usize),
+
+            Tableswitch {
+                ref switches,
+                ref default,
+                ..
+            } => {
+                pop!(1);
+
+                let mut targets = vec![self.target(default)?];
+                for label in switches {
+                    targets.push(self.target(label)?);
+                }
+                return Ok(Some(Flow::Jump(targets)));
+            }
+
+            Lookupswitch {
+                ref switches,
+                ref default,
+            } => {
+                pop!(1);
+
+                let mut targets = vec![self.target(default)?];
+                for switch in switches {
+                    targets.push(self.target(&switch.label)?);
+                }
+                return Ok(Some(Flow::Jump(targets)));
+            }
+
+            Ireturn | Freturn | Areturn => {
+                pop!(1);
+                return Ok(Some(Flow::Jump(vec![])));
+            }
+            Lreturn | Dreturn => {
+                pop!(2);
+                return Ok(Some(Flow::Jump(vec![])));
+            }
+            Return => return Ok(Some(Flow::Jump(vec![]))),
+            Athrow => {
+                pop!(1);
+                return Ok(Some(Flow::Jump(vec![])));
+            }
+
+            Getstatic {
+                ref field_descriptor,
+                ..
+            } => frame.push(VT::from_field_descriptor(field_descriptor)),
+
+            Putstatic { .. } => {
+                if frame.pop_value().is_none() {
+                    return Ok(None);
+                }
+            }
+
+            Getfield {
+                ref field_descriptor,
+                ..
+            } => unary!(1, VT::from_field_descriptor(field_descriptor)),
+
+            Putfield { .. } => {
+                if frame.pop_value().is_none() {
+                    return Ok(None);
+                }
+                pop!(1);
+            }
+
+            Invokevirtual {
+                ref method_descriptor,
+                ..
+            }
+            | Invokeinterface {
+                ref method_descriptor,
+                ..
+            } => {
+                if self.invoke(frame, method_descriptor).is_none() {
+                    return Ok(None);
+                }
+                pop!(1);
+                self.push_return(frame, method_descriptor);
+            }
+
+            Invokespecial {
+                ref class_name,
+                ref method_name,
+                ref method_descriptor,
+            } => {
+                if self.invoke(frame, method_descriptor).is_none() {
+                    return Ok(None);
+                }
+
+                let receiver = match frame.pop() {
+                    Some(receiver) => receiver,
+                    None => return Ok(None),
+                };
+
+                if method_name == "<init>" {
+                    match receiver {
+                        VT::UninitializedThis => frame.initialize(&receiver, self.class_name),
+                        VT::Uninitialized(..) => frame.initialize(&receiver, class_name),
+                        _ => {}
+                    }
+                }
+
+                self.push_return(frame, method_descriptor);
+            }
+
+            Invokestatic {
+                ref method_descriptor,
+                ..
+            }
+            | Invokedynamic {
+                ref method_descriptor,
+                ..
+            } => {
+                if self.invoke(frame, method_descriptor).is_none() {
+                    return Ok(None);
+                }
+                self.push_return(frame, method_descriptor);
+            }
+
+            New { .. } => frame.push(VT::Uninitialized(index)),
+
+            Newarray { ref component_type } => {
+                unary!(1, VT::Object(format!("[{component_type}")))
+            }
+
+            Anewarray { ref component_type } => {
+                unary!(1, VT::Object(format!("[{component_type}")))
+            }
+
+            Multianewarray {
+                ref component_type,
+                dimensions,
+            } => unary!(
+                dimensions as usize,
+                VT::from_field_descriptor(component_type)
+            ),
+
+            Arraylength => unary!(1, VT::Integer),
+
+            Checkcast { ref cast_type } => unary!(1, VT::from_field_descriptor(cast_type)),
+
+            Instanceof { .. } => unary!(1, VT::Integer),
+
+            Monitorenter | Monitorexit => pop!(1),
+        }
+
+        Ok(Some(Flow::Next))
+    }
+
+    /// Infer the frames of the method, along with the maximum depth of its operand stack.
+    fn analyze(
+        &self,
+        method_def: &PhoronMethodDef,
+        extra_targets: &[usize],
+    ) -> StackMapResult<(MethodFrames, usize)> {
+        let initial_frame = self.initial_frame(method_def);
+
+        if self.code.is_empty() {
+            return Ok((
+                MethodFrames {
+                    initial_frame,
+                    frames: vec![],
+                },
+                0,
+            ));
+        }
+
+        let mut frames: Vec<Option<Frame>> = vec![None; self.code.len()];
+        let mut frame_targets = extra_targets
+            .iter()
+            .copied()
+            .filter(|index| *index < self.code.len())
+            .collect::<BTreeSet<_>>();
+        let mut worklist = vec![0];
+        let mut max_stack = 0;
+        frames[0] = Some(initial_frame.clone());
+
+        loop {
+            while let Some(index) = worklist.pop() {
+                let in_frame = frames[index].clone().unwrap();
+
+                for handler in &self.handlers {
+                    if handler.start <= index && index < handler.end {
+                        let handler_frame = Frame {
+                            locals: in_frame.locals.clone(),
+                            stack: vec![handler.catch_type.clone()],
+                        };
+
+                        frame_targets.insert(handler.handler);
+                        self.flow_into(handler.handler, handler_frame, &mut frames, &mut worklist)?;
+                    }
+                }
+
+                let mut out_frame = in_frame;
+                max_stack = max_stack.max(out_frame.stack.len());
+
+                let successors = match self.execute(index, &mut out_frame)? {
+                    Flow::Next => vec![index + 1],
+                    Flow::Branch(targets) => {
+                        frame_targets.extend(targets.iter().copied());
+                        std::iter::once(index + 1).chain(targets).collect()
+                    }
+                    Flow::Jump(targets) => {
+                        frame_targets.extend(targets.iter().copied());
+                        targets
+                    }
+                };
+                max_stack = max_stack.max(out_frame.stack.len());
+
+                // a store changes the local variables as seen by the handlers covering it, since
+                // the exception may be thrown after the instruction as well as before it.
+                if self.is_store(index) {
+                    for handler in &self.handlers {
+                        if handler.start <= index && index < handler.end {
+                            let handler_frame = Frame {
+                                locals: out_frame.locals.clone(),
+                                stack: vec![handler.catch_type.clone()],
+                            };
+
+                            self.flow_into(
+                                handler.handler,
+                                handler_frame,
+                                &mut frames,
+                                &mut worklist,
+                            )?;
+                        }
+                    }
+                }
+
+                for successor in successors {
+                    if successor < self.code.len() {
+                        self.flow_into(successor, out_frame.clone(), &mut frames, &mut worklist)?;
+                    }
+                }
+            }
+
+            // Code which cannot be reached is still checked by the verifier, and so it is
+            // analysed as if it were entered with the local variables of the preceding
+            // instruction, and an empty stack (or just the exception, for handlers).
+            match frames.iter().position(|frame| frame.is_none()) {
+                None => break,
+                Some(index) => {
+                    let locals = frames[..index]
+                        .iter()
+                        .rev()
+                        .find_map(|frame| frame.as_ref())
+                        .map_or(initial_frame.locals.clone(), |frame| frame.locals.clone());
+
+                    let stack = self
+                        .handlers
+                        .iter()
+                        .find(|handler| handler.handler == index)
+                        .map_or(vec![], |handler| vec![handler.catch_type.clone()]);
+
+                    frames[index] = Some(Frame { locals, stack });
+                    frame_targets.insert(index);
+                    worklist.push(index);
+                }
+            }
+        }
+
+        Ok((
+            MethodFrames {
+                initial_frame,
+                frames: frame_targets
+                    .into_iter()
+                    .map(|index| (index, frames[index].take().unwrap()))
+                    .collect(),
+            },
+            max_stack,
+        ))
+    }
+
+    /// The number of local variable slots used by the method - those taken up by its arguments,
+    /// and those accessed by any of its instructions, reachable or not.
+    fn max_locals(&self, method_def: &PhoronMethodDef) -> usize {
+        use JvmInstruction::*;
+
+        let initial_locals = self.initial_frame(method_def).locals.len();
+
+        self.code
+            .iter()
+            .filter_map(|instr| match **instr {
+                Iload0 | Fload0 | Aload0 | Istore0 | Fstore0 | Astore0 => Some(1),
+                Iload1 | Fload1 | Aload1 | Istore1 | Fstore1 | Astore1 => Some(2),
+                Iload2 | Fload2 | Aload2 | Istore2 | Fstore2 | Astore2 => Some(3),
+                Iload3 | Fload3 | Aload3 | Istore3 | Fstore3 | Astore3 => Some(4),
+                Lload0 | Dload0 | Lstore0 | Dstore0 => Some(2),
+                Lload1 | Dload1 | Lstore1 | Dstore1 => Some(3),
+                Lload2 | Dload2 | Lstore2 | Dstore2 => Some(4),
+                Lload3 | Dload3 | Lstore3 | Dstore3 => Some(5),
+
+                Iload { varnum }
+                | Fload { varnum }
+                | Aload { varnum }
+                | Istore { varnum }
+                | Fstore { varnum }
+                | Astore { varnum }
+                | Iinc { varnum, .. }
+                | Ret { varnum } => Some(varnum as usize + 1),
+
+                Lload { varnum } | Dload { varnum } | Lstore { varnum } | Dstore { varnum } => {
+                    Some(varnum as usize + 2)
+                }
+
+                Wide(ref wide_instr) => Some(match *wide_instr {
+                    WideInstruction::Iload { varnum }
+                    | WideInstruction::Fload { varnum }
+                    | WideInstruction::Aload { varnum }
+                    | WideInstruction::Istore { varnum }
+                    | WideInstruction::Fstore { varnum }
+                    | WideInstruction::Astore { varnum }
+                    | WideInstruction::Ret { varnum }
+                    | WideInstruction::IInc { varnum, .. } => varnum as usize + 1,
+
+                    WideInstruction::Lload { varnum }
+                    | WideInstruction::Dload { varnum }
+                    | WideInstruction::Lstore { varnum }
+                    | WideInstruction::Dstore { varnum } => varnum as usize + 2,
+                }),
+
+                _ => None,
+            })
+            .fold(initial_locals, usize::max)
+    }
+
+    /// Whether the instruction at `index` stores a value into a local variable.
+    fn is_store(&self, index: usize) -> bool {
+        use JvmInstruction::*;
+
+        match *self.code[index] {
+            Istore0
+            | Istore1
+            | Istore2
+            | Istore3
+            | Istore { .. }
+            | Lstore0
+            | Lstore1
+            | Lstore2
+            | Lstore3
+            | Lstore { .. }
+            | Fstore0
+            | Fstore1
+            | Fstore2
+            | Fstore3
+            | Fstore { .. }
+            | Dstore0
+            | Dstore1
+            | Dstore2
+            | Dstore3
+            | Dstore { .. }
+            | Astore0
+            | Astore1
+            | Astore2
+            | Astore3
+            | Astore { .. } => true,
+            Wide(ref wide_instr) => matches!(
+                *wide_instr,
+                WideInstruction::Istore { .. }
+                    | WideInstruction::Lstore { .. }
+                    | WideInstruction::Fstore { .. }
+                    | WideInstruction::Dstore { .. }
+                    | WideInstruction::Astore { .. }
+            ),
+            _ => false,
+        }
+    }
+
+    fn flow_into(
+        &self,
+        index: usize,
+        frame: Frame,
+        frames: &mut [Option<Frame>],
+        worklist: &mut Vec<usize>,
+    ) -> StackMapResult<()> {
+        let merged = match frames[index] {
+            None => frame,
+            Some(ref old) => {
+                let merged = self.merge_frames(old, &frame, index)?;
+                if &merged == old {
+                    return Ok(());
+                }
+                merged
+            }
+        };
+
+        frames[index] = Some(merged);
+        if !worklist.contains(&index) {
+            worklist.push(index);
+        }
+
+        Ok(())
+    }
+}
+
+/// The type of the value pushed by `ldc` and `ldc_w`.
+enum LoadableConstant<'a> {
+    Integer,
+    Float,
+    Object(&'static str),
+    Dynamic(&'a PhoronFieldDescriptor),
+}
+
+impl<'a> LoadableConstant<'a> {
+    fn from_ldc(value: &'a LdcValue) -> Self {
+        match value {
+            LdcValue::Integer(..) => LoadableConstant::Integer,
+            LdcValue::Float(..) => LoadableConstant::Float,
+            LdcValue::QuotedString(..) => LoadableConstant::Object("java/lang/String"),
+            LdcValue::Class(..) => LoadableConstant::Object("java/lang/Class"),
+            LdcValue::MethodType(..) => LoadableConstant::Object("java/lang/invoke/MethodType"),
+            LdcValue::MethodHandle(..) => LoadableConstant::Object("java/lang/invoke/MethodHandle"),
+            LdcValue::Dynamic(ref dynamic_constant) => {
+                LoadableConstant::Dynamic(&dynamic_constant.field_descriptor)
+            }
+        }
+    }
+
+    fn from_ldcw(value: &'a LdcwValue) -> Self {
+        match value {
+            LdcwValue::Integer(..) => LoadableConstant::Integer,
+            LdcwValue::Float(..) => LoadableConstant::Float,
+            LdcwValue::QuotedString(..) => LoadableConstant::Object("java/lang/String"),
+            LdcwValue::Class(..) => LoadableConstant::Object("java/lang/Class"),
+            LdcwValue::MethodType(..) => LoadableConstant::Object("java/lang/invoke/MethodType"),
+            LdcwValue::MethodHandle(..) => {
+                LoadableConstant::Object("java/lang/invoke/MethodHandle")
+            }
+            LdcwValue::Dynamic(ref dynamic_constant) => {
+                LoadableConstant::Dynamic(&dynamic_constant.field_descriptor)
+            }
+        }
+    }
+}
+
+/// Infer the stack map frames for the given method of the class `class_name`, whose superclass is
+/// `super_class_name`. Besides the branch targets, frames are also inferred for the instructions at
+/// the positions in `extra_targets`, such as those which become branch targets when the code
+/// generator widens a conditional branch.
+pub fn compute_frames(
+    method_def: &PhoronMethodDef,
+    class_name: &str,
+    super_class_name: &str,
+    resolver: &dyn ClassHierarchyResolver,
+    extra_targets: &[usize],
+) -> StackMapResult<MethodFrames> {
+    let analyzer = frame_analyzer(method_def, class_name, super_class_name, resolver, false)?;
+    let (method_frames, _) = analyzer.analyze(method_def, extra_targets)?;
+
+    Ok(method_frames)
+}
+
+/// Compute the `max_stack` and `max_locals` limits of the given method of the class `class_name`,
+/// whose superclass is `super_class_name`.
+pub fn compute_limits(
+    method_def: &PhoronMethodDef,
+    class_name: &str,
+    super_class_name: &str,
+    resolver: &dyn ClassHierarchyResolver,
+) -> StackMapResult<MethodLimits> {
+    let analyzer = frame_analyzer(method_def, class_name, super_class_name, resolver, true)?;
+    let (_, max_stack) = analyzer.analyze(method_def, &[])?;
+
+    Ok(MethodLimits {
+        max_stack: max_stack.min(u16::MAX as usize) as u16,
+        max_locals: analyzer.max_locals(method_def).min(u16::MAX as usize) as u16,
+    })
+}
+
+fn frame_analyzer<'a>(
+    method_def: &'a PhoronMethodDef,
+    class_name: &'a str,
+    super_class_name: &'a str,
+    resolver: &'a dyn ClassHierarchyResolver,
+    follow_subroutines: bool,
+) -> StackMapResult<FrameAnalyzer<'a>> {
+    let mut code = Vec::new();
+    let mut labels = HashMap::new();
+
+    for instr in &method_def.instructions {
+        match instr {
+            PhoronInstruction::JvmInstruction(ref jvm_instr) => code.push(jvm_instr),
+            PhoronInstruction::PhoronLabel(ref label) => {
+                labels.insert(label.as_str(), code.len());
+            }
+            PhoronInstruction::PhoronDirective(..) => {}
+        }
+    }
+
+    let mut analyzer = FrameAnalyzer {
+        method_name: &method_def.name,
+        class_name,
+        super_class_name,
+        resolver,
+        code,
+        labels,
+        handlers: Vec::new(),
+        follow_subroutines,
+    };
+
+    for instr in &method_def.instructions {
+        if let PhoronInstruction::PhoronDirective(PhoronDirective::Catch {
+            ref class_name,
+            ref from_label,
+            ref to_label,
+            ref handler_label,
+        }) = instr
+        {
+            let label_index =
+                |label: &String| {
+                    analyzer.labels.get(label.as_str()).copied().ok_or(
+                        StackMapError::UndefinedLabel {
+                            label: label.to_owned(),
+                        },
+                    )
+                };
+
+            let handler = ExceptionHandler {
+                start: label_index(from_label)?,
+                end: label_index(to_label)?,
+                handler: analyzer.target(handler_label)?,
+                catch_type: VerificationType::Object(if class_name == "all" {
+                    "java/lang/Throwable".to_owned()
+                } else {
+                    class_name.to_owned()
+                }),
+            };
+            analyzer.handlers.push(handler);
+        }
+    }
+
+    Ok(analyzer)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{class_hierarchy::DefaultClassHierarchyResolver, *};
+
+    fn analyzer(resolver: &DefaultClassHierarchyResolver) -> FrameAnalyzer<'_> {
+        FrameAnalyzer {
+            method_name: "test",
+            class_name: "Foo",
+            super_class_name: "java/lang/Exception",
+            resolver,
+            code: vec![],
+            labels: HashMap::new(),
+            handlers: vec![],
+            follow_subroutines: false,
+        }
+    }
+
+    #[test]
+    fn test_merge_object_types() {
+        let resolver = DefaultClassHierarchyResolver::new();
+        let analyzer = analyzer(&resolver);
+
+        assert_eq!(
+            "java/lang/Number",
+            analyzer.merge_object_types("java/lang/Integer", "java/lang/Double")
+        );
+        assert_eq!(
+            "java/lang/Exception",
+            analyzer.merge_object_types("Foo", "java/lang/RuntimeException")
+        );
+        assert_eq!(
+            "[Ljava/lang/Number;",
+            analyzer.merge_object_types("[Ljava/lang/Long;", "[Ljava/lang/Integer;")
+        );
+        assert_eq!(
+            "java/lang/Object",
+            analyzer.merge_object_types("[I", "[Ljava/lang/Integer;")
+        );
+        assert_eq!(
+            "java/lang/Object",
+            analyzer.merge_object_types("java/lang/String", "Bar")
+        );
+    }
+
+    #[test]
+    fn test_frame_locals() {
+        let mut frame = Frame::default();
+        frame.store(0, VerificationType::Long);
+        frame.store(2, VerificationType::Integer);
+        frame.store(4, VerificationType::Double);
+        assert_eq!(
+            vec![
+                VerificationType::Long,
+                VerificationType::Integer,
+                VerificationType::Top,
+                VerificationType::Double
+            ],
+            frame.frame_locals()
+        );
+
+        // overwriting the second half of the `double` invalidates it
+        frame.store(5, VerificationType::Integer);
+        assert_eq!(
+            vec![
+                VerificationType::Long,
+                VerificationType::Integer,
+                VerificationType::Top,
+                VerificationType::Top,
+                VerificationType::Integer
+            ],
+            frame.frame_locals()
+        );
+    }
+
+    #[test]
+    fn test_compute_limits() -> StackMapResult<()> {
+        use crate::ast::JvmInstruction::*;
+        use PhoronInstruction::*;
+
+        let resolver = DefaultClassHierarchyResolver::new();
+
+        // `this`, a `long`, and a `double` take up the first 5 slots
+        let method_def = PhoronMethodDef {
+            name: "test".to_string(),
+            access_flags: vec![PhoronMethodAccessFlag::AccPublic],
+            method_descriptor: PhoronMethodDescriptor {
+                param_descriptor: vec![
+                    PhoronFieldDescriptor::BaseType(PhoronBaseType::Long),
+                    PhoronFieldDescriptor::BaseType(PhoronBaseType::Double),
+                ],
+                return_descriptor: PhoronReturnDescriptor::VoidDescriptor,
+            },
+            instructions: vec![
+                JvmInstruction(Lload1),
+                JvmInstruction(Dload3),
+                JvmInstruction(Pop2),
+                JvmInstruction(Pop2),
+                JvmInstruction(Iconst0),
+                JvmInstruction(Istore { varnum: 7 }),
+                JvmInstruction(Return),
+            ],
+        };
+
+        assert_eq!(
+            MethodLimits {
+                max_stack: 4,
+                max_locals: 8,
+            },
+            compute_limits(&method_def, "Foo", JAVA_LANG_OBJECT, &resolver)?
+        );
+
+        // the subroutine leaves its result on the stack for the caller
+        let method_def = PhoronMethodDef {
+            name: "test".to_string(),
+            access_flags: vec![PhoronMethodAccessFlag::AccStatic],
+            method_descriptor: PhoronMethodDescriptor {
+                param_descriptor: vec![],
+                return_descriptor: PhoronReturnDescriptor::FieldDescriptor(
+                    PhoronFieldDescriptor::BaseType(PhoronBaseType::Integer),
+                ),
+            },
+            instructions: vec![
+                JvmInstruction(Iconst1),
+                JvmInstruction(Jsr {
+                    label: "Add".to_string(),
+                }),
+                JvmInstruction(Ireturn),
+                PhoronLabel("Add".to_string()),
+                JvmInstruction(Astore0),
+                JvmInstruction(Iconst2),
+                JvmInstruction(Iadd),
+                JvmInstruction(Ret { varnum: 0 }),
+            ],
+        };
+
+        assert_eq!(
+            MethodLimits {
+                max_stack: 2,
+                max_locals: 1,
+            },
+            compute_limits(&method_def, "Foo", JAVA_LANG_OBJECT, &resolver)?
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_compute_frames_extra_targets() -> StackMapResult<()> {
+        use crate::ast::JvmInstruction::*;
+        use PhoronInstruction::*;
+
+        let resolver = DefaultClassHierarchyResolver::new();
+
+        let method_def = PhoronMethodDef {
+            name: "test".to_string(),
+            access_flags: vec![PhoronMethodAccessFlag::AccStatic],
+            method_descriptor: PhoronMethodDescriptor {
+                param_descriptor: vec![PhoronFieldDescriptor::ObjectType {
+                    class_name: "java/lang/String".to_string(),
+                }],
+                return_descriptor: PhoronReturnDescriptor::VoidDescriptor,
+            },
+            instructions: vec![
+                JvmInstruction(Aload0),
+                JvmInstruction(Ifnull {
+                    label: "Done".to_string(),
+                }),
+                JvmInstruction(Iconst0),
+                JvmInstruction(Pop),
+                PhoronLabel("Done".to_string()),
+                JvmInstruction(Return),
+            ],
+        };
+
+        let method_frames = compute_frames(&method_def, "Foo", JAVA_LANG_OBJECT, &resolver, &[])?;
+        assert_eq!(
+            vec![4],
+            method_frames
+                .frames
+                .iter()
+                .map(|(index, _)| *index)
+                .collect::<Vec<_>>()
+        );
+
+        // the instruction following the branch gets a frame as well
+        let method_frames = compute_frames(&method_def, "Foo", JAVA_LANG_OBJECT, &resolver, &[2])?;
+        assert_eq!(
+            vec![
+                (
+                    2,
+                    Frame {
+                        locals: vec![VerificationType::Object("java/lang/String".to_string())],
+                        stack: vec![],
+                    }
+                ),
+                (
+                    4,
+                    Frame {
+                        locals: vec![VerificationType::Object("java/lang/String".to_string())],
+                        stack: vec![],
+                    }
+                ),
+            ],
+            method_frames.frames
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_compute_frames_store_in_handler_range() -> StackMapResult<()> {
+        use crate::ast::JvmInstruction::*;
+        use PhoronInstruction::*;
+
+        let resolver = DefaultClassHierarchyResolver::new();
+
+        // the handler of a range ending in a store, which is found at index 3
+        let handler_frame = |param_descriptor, value, store| -> StackMapResult<Frame> {
+            let method_def = PhoronMethodDef {
+                name: "test".to_string(),
+                access_flags: vec![PhoronMethodAccessFlag::AccStatic],
+                method_descriptor: PhoronMethodDescriptor {
+                    param_descriptor: vec![param_descriptor],
+                    return_descriptor: PhoronReturnDescriptor::VoidDescriptor,
+                },
+                instructions: vec![
+                    PhoronDirective(crate::ast::PhoronDirective::Catch {
+                        class_name: "all".to_string(),
+                        from_label: "Start".to_string(),
+                        to_label: "End".to_string(),
+                        handler_label: "Handler".to_string(),
+                    }),
+                    PhoronLabel("Start".to_string()),
+                    JvmInstruction(value),
+                    JvmInstruction(store),
+                    PhoronLabel("End".to_string()),
+                    JvmInstruction(Return),
+                    PhoronLabel("Handler".to_string()),
+                    JvmInstruction(Pop),
+                    JvmInstruction(Return),
+                ],
+            };
+
+            let method_frames =
+                compute_frames(&method_def, "Foo", JAVA_LANG_OBJECT, &resolver, &[])?;
+            Ok(method_frames
+                .frames
+                .into_iter()
+                .find(|(index, _)| *index == 3)
+                .unwrap()
+                .1)
+        };
+
+        // an `int` stored over a reference
+        assert_eq!(
+            Frame {
+                locals: vec![VerificationType::Top],
+                stack: vec![VerificationType::Object("java/lang/Throwable".to_string())],
+            },
+            handler_frame(
+                PhoronFieldDescriptor::ObjectType {
+                    class_name: "java/lang/String".to_string(),
+                },
+                Iconst0,
+                Istore0
+            )?
+        );
+
+        // a `long` stored over an `int`
+        assert_eq!(
+            Frame {
+                locals: vec![VerificationType::Top, VerificationType::Top],
+                stack: vec![VerificationType::Object("java/lang/Throwable".to_string())],
+            },
+            handler_frame(
+                PhoronFieldDescriptor::BaseType(PhoronBaseType::Integer),
+                Lconst0,
+                Lstore0
+            )?
+        );
+
+        Ok(())
+    }
+}
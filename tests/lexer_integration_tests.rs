@@ -14,6 +14,23 @@ where
 {
     let source_file = SourceFile::new(testfile.as_ref()).map_err(|err| Box::new(err))?;
     let mut lexer = Lexer::new(&source_file);
+
+    Ok(lex_all(&mut lexer))
+}
+
+/// Lex the test file, and return the tokens along with whether any errors were reported.
+fn lex_errored<P>(testfile: P) -> Result<(Vec<Token>, bool), Box<dyn Error>>
+where
+    P: AsRef<Path> + Copy,
+{
+    let source_file = SourceFile::new(testfile.as_ref())?;
+    let mut lexer = Lexer::new(&source_file);
+    let tokens = lex_all(&mut lexer);
+
+    Ok((tokens, lexer.errored()))
+}
+
+fn lex_all(lexer: &mut Lexer) -> Vec<Token> {
     let mut tokens = Vec::new();
 
     loop {
@@ -29,7 +46,7 @@ where
         tokens.push(tok);
     }
 
-    Ok(tokens)
+    tokens
 }
 
 #[test]
@@ -9625,3 +9642,183 @@ fn test_lex_all_in_one() -> Result<(), Box<dyn Error>> {
 
     Ok(())
 }
+
+/// Check that the expected tokens occur, one after the other, among the actual tokens.
+fn contains_tokens(actual_tokens: &[Token], expected_tokens: &[Token]) -> bool {
+    actual_tokens
+        .windows(expected_tokens.len())
+        .any(|tokens| tokens == expected_tokens)
+}
+
+#[test]
+fn test_lex_nested_annotation_demo() -> Result<(), Box<dyn Error>> {
+    let (actual_tokens, errored) = lex_errored("samples/NestedAnnotationDemo.pho")?;
+    assert!(!errored);
+
+    // `[@` is lexed as an array dimension followed by the `@` tag
+    assert!(contains_tokens(
+        &actual_tokens,
+        &[
+            Token {
+                kind: TIdent("value".to_string()),
+                span: Span::default(),
+            },
+            Token {
+                kind: TIdent("[".to_string()),
+                span: Span::default(),
+            },
+            Token {
+                kind: TAt,
+                span: Span::default(),
+            },
+            Token {
+                kind: TIdent("Lcom/example/Review;".to_string()),
+                span: Span::default(),
+            },
+            Token {
+                kind: TAssign,
+                span: Span::default(),
+            },
+            Token {
+                kind: TLeftBrace,
+                span: Span::default(),
+            },
+            Token {
+                kind: TAnnotation,
+                span: Span::default(),
+            },
+            Token {
+                kind: TIdent("reviewer".to_string()),
+                span: Span::default(),
+            },
+            Token {
+                kind: TIdent("s".to_string()),
+                span: Span::default(),
+            },
+            Token {
+                kind: TAssign,
+                span: Span::default(),
+            },
+            Token {
+                kind: TString("Ren\u{e9}e".to_string()),
+                span: Span::default(),
+            },
+            Token {
+                kind: TEnd,
+                span: Span::default(),
+            },
+            Token {
+                kind: TIdent("annotation".to_string()),
+                span: Span::default(),
+            },
+            Token {
+                kind: TRightBrace,
+                span: Span::default(),
+            },
+        ]
+    ));
+
+    assert!(contains_tokens(
+        &actual_tokens,
+        &[
+            Token {
+                kind: TIdent("lead".to_string()),
+                span: Span::default(),
+            },
+            Token {
+                kind: TAt,
+                span: Span::default(),
+            },
+            Token {
+                kind: TIdent("Lcom/example/Review;".to_string()),
+                span: Span::default(),
+            },
+        ]
+    ));
+
+    // an escape takes exactly four hex digits, so the trailing `6` is a character of its own
+    assert!(contains_tokens(
+        &actual_tokens,
+        &[
+            Token {
+                kind: TLdc,
+                span: Span::default(),
+            },
+            Token {
+                kind: TString("caf\u{e9} AA6".to_string()),
+                span: Span::default(),
+            },
+        ]
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn test_lex_dollar_in_identifiers() -> Result<(), Box<dyn Error>> {
+    let actual_tokens = lex(Path::new("samples/nested/Outer.pho"))?;
+
+    assert!(contains_tokens(
+        &actual_tokens,
+        &[
+            Token {
+                kind: TInner,
+                span: Span::default(),
+            },
+            Token {
+                kind: TIdent("class".to_string()),
+                span: Span::default(),
+            },
+            Token {
+                kind: TPublic,
+                span: Span::default(),
+            },
+            Token {
+                kind: TStatic,
+                span: Span::default(),
+            },
+            Token {
+                kind: TIdent("Outer$Member".to_string()),
+                span: Span::default(),
+            },
+        ]
+    ));
+
+    assert!(contains_tokens(
+        &actual_tokens,
+        &[
+            Token {
+                kind: TNew,
+                span: Span::default(),
+            },
+            Token {
+                kind: TIdent("Outer$1".to_string()),
+                span: Span::default(),
+            },
+        ]
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn test_lex_malformed_unicode_escape() -> Result<(), Box<dyn Error>> {
+    // the string is dropped, since fewer than four hex digits follow the `\u`
+    let (actual_tokens, errored) = lex_errored("samples/invalid/MalformedUnicodeEscape.pho")?;
+    assert!(errored);
+    assert!(contains_tokens(
+        &actual_tokens,
+        &[
+            Token {
+                kind: TLdc,
+                span: Span::default(),
+            },
+            Token {
+                kind: TPop,
+                span: Span::default(),
+            },
+        ]
+    ));
+
+    Ok(())
+}
@@ -21,10 +21,40 @@ where
     Ok(program)
 }
 
+/// Parse the test file, and return whether any errors were reported.
+fn parse_errored<P>(testfile: P) -> Result<bool, Box<dyn Error>>
+where
+    P: AsRef<Path> + Copy,
+{
+    let source_file = SourceFile::new(testfile.as_ref())?;
+    let mut parser = Parser::new(Lexer::new(&source_file));
+    parser.parse();
+
+    Ok(parser.errored())
+}
+
+/// Parse the test file for the given target version (as with the `--target` command-line option),
+/// and return whether any errors were reported.
+fn parse_errored_with_target<P>(
+    testfile: P,
+    target_version: PhoronVersionDef,
+) -> Result<bool, Box<dyn Error>>
+where
+    P: AsRef<Path> + Copy,
+{
+    let source_file = SourceFile::new(testfile.as_ref())?;
+    let mut parser = Parser::new(Lexer::new(&source_file));
+    parser.target_version(target_version);
+    parser.parse();
+
+    Ok(parser.errored())
+}
+
 #[test]
 fn test_parse_malign() -> Result<(), Box<dyn Error>> {
     let expected_ast = PhoronProgram {
         header: PhoronHeader {
+            version_def: PhoronVersionDef::default(),
             sourcefile_def: PhoronSourceFileDef {
                 source_file: "samples/Malign.pho".to_string(),
             },
@@ -39,6 +69,7 @@ fn test_parse_malign() -> Result<(), Box<dyn Error>> {
                 super_class_name: "java/lang/Object".to_string(),
             },
             implements_defs: vec![],
+            class_directives: vec![],
         },
         body: PhoronBody {
             field_defs: vec![],
@@ -107,6 +138,7 @@ fn test_parse_malign() -> Result<(), Box<dyn Error>> {
 fn test_parse_fields() -> Result<(), Box<dyn Error>> {
     let expected_ast = PhoronProgram {
         header: PhoronHeader {
+            version_def: PhoronVersionDef::default(),
             sourcefile_def: PhoronSourceFileDef {
                 source_file: "samples/FieldsDemo.pho".to_string(),
             },
@@ -121,6 +153,7 @@ fn test_parse_fields() -> Result<(), Box<dyn Error>> {
                 super_class_name: "java/lang/Object".to_string(),
             },
             implements_defs: vec![],
+            class_directives: vec![],
         },
         body: PhoronBody {
             field_defs: vec![
@@ -128,13 +161,25 @@ fn test_parse_fields() -> Result<(), Box<dyn Error>> {
                     name: "x".to_string(),
                     access_flags: vec![PhoronFieldAccessFlag::AccPrivate],
                     field_descriptor: BaseType(Integer),
+                    signature: None,
                     init_val: None,
+                    annotations: vec![],
+                    type_annotations: vec![],
+                    attributes: vec![],
+                    deprecated: false,
+                    synthetic: false,
                 },
                 PhoronFieldDef {
                     name: "y".to_string(),
                     access_flags: vec![PhoronFieldAccessFlag::AccPrivate],
                     field_descriptor: BaseType(Double),
+                    signature: None,
                     init_val: None,
+                    annotations: vec![],
+                    type_annotations: vec![],
+                    attributes: vec![],
+                    deprecated: false,
+                    synthetic: false,
                 },
                 PhoronFieldDef {
                     name: "z".to_string(),
@@ -142,7 +187,13 @@ fn test_parse_fields() -> Result<(), Box<dyn Error>> {
                     field_descriptor: ObjectType {
                         class_name: "java/lang/String".to_string(),
                     },
+                    signature: None,
                     init_val: Some(PhoronFieldInitValue::QuotedString("Foo".to_string())),
+                    annotations: vec![],
+                    type_annotations: vec![],
+                    attributes: vec![],
+                    deprecated: false,
+                    synthetic: false,
                 },
                 PhoronFieldDef {
                     name: "PI".to_string(),
@@ -152,7 +203,13 @@ fn test_parse_fields() -> Result<(), Box<dyn Error>> {
                         PhoronFieldAccessFlag::AccFinal,
                     ],
                     field_descriptor: BaseType(Float),
+                    signature: None,
                     init_val: Some(PhoronFieldInitValue::Double(3.14159)),
+                    annotations: vec![],
+                    type_annotations: vec![],
+                    attributes: vec![],
+                    deprecated: false,
+                    synthetic: false,
                 },
             ],
             method_defs: vec![
@@ -210,6 +267,7 @@ fn test_parse_fields() -> Result<(), Box<dyn Error>> {
 fn test_parse_hola_mundo() -> Result<(), Box<dyn Error>> {
     let expected_ast = PhoronProgram {
         header: PhoronHeader {
+            version_def: PhoronVersionDef::default(),
             sourcefile_def: PhoronSourceFileDef {
                 source_file: "samples/HolaMundo.pho".to_string(),
             },
@@ -224,6 +282,7 @@ fn test_parse_hola_mundo() -> Result<(), Box<dyn Error>> {
                 super_class_name: "java/lang/Object".to_string(),
             },
             implements_defs: vec![],
+            class_directives: vec![],
         },
         body: PhoronBody {
             field_defs: vec![],
@@ -299,6 +358,7 @@ fn test_parse_hola_mundo() -> Result<(), Box<dyn Error>> {
 fn test_parse_hello_world() -> Result<(), Box<dyn Error>> {
     let expected_ast = PhoronProgram {
         header: PhoronHeader {
+            version_def: PhoronVersionDef::default(),
             sourcefile_def: PhoronSourceFileDef {
                 source_file: "samples/HelloWorld.pho".to_string(),
             },
@@ -313,6 +373,7 @@ fn test_parse_hello_world() -> Result<(), Box<dyn Error>> {
                 super_class_name: "java/lang/Object".to_string(),
             },
             implements_defs: vec![],
+            class_directives: vec![],
         },
         body: PhoronBody {
             field_defs: vec![],
@@ -388,6 +449,7 @@ fn test_parse_hello_world() -> Result<(), Box<dyn Error>> {
 fn test_parse_malign_jasmin() -> Result<(), Box<dyn Error>> {
     let expected_ast = PhoronProgram {
         header: PhoronHeader {
+            version_def: PhoronVersionDef::default(),
             sourcefile_def: PhoronSourceFileDef {
                 source_file: "samples/MalignJasmin.pho".to_string(),
             },
@@ -402,6 +464,7 @@ fn test_parse_malign_jasmin() -> Result<(), Box<dyn Error>> {
                 super_class_name: "java/lang/Object".to_string(),
             },
             implements_defs: vec![],
+            class_directives: vec![],
         },
         body: PhoronBody {
             field_defs: vec![],
@@ -471,6 +534,7 @@ fn test_parse_malign_jasmin() -> Result<(), Box<dyn Error>> {
 fn test_parse_privet_mir() -> Result<(), Box<dyn Error>> {
     let expected_ast = PhoronProgram {
         header: PhoronHeader {
+            version_def: PhoronVersionDef::default(),
             sourcefile_def: PhoronSourceFileDef {
                 source_file: "samples/PrivetMir.pho".to_string(),
             },
@@ -485,6 +549,7 @@ fn test_parse_privet_mir() -> Result<(), Box<dyn Error>> {
                 super_class_name: "java/lang/Object".to_string(),
             },
             implements_defs: vec![],
+            class_directives: vec![],
         },
         body: PhoronBody {
             field_defs: vec![],
@@ -560,6 +625,7 @@ fn test_parse_privet_mir() -> Result<(), Box<dyn Error>> {
 fn test_parse_areturn() -> Result<(), Box<dyn Error>> {
     let expected_ast = PhoronProgram {
         header: PhoronHeader {
+            version_def: PhoronVersionDef::default(),
             sourcefile_def: PhoronSourceFileDef {
                 source_file: "samples/Areturn.pho".to_string(),
             },
@@ -574,6 +640,7 @@ fn test_parse_areturn() -> Result<(), Box<dyn Error>> {
                 super_class_name: "java/lang/Object".to_string(),
             },
             implements_defs: vec![],
+            class_directives: vec![],
         },
         body: PhoronBody {
             field_defs: vec![],
@@ -665,6 +732,7 @@ fn test_parse_areturn() -> Result<(), Box<dyn Error>> {
 fn test_parse_create_array_of_threads() -> Result<(), Box<dyn Error>> {
     let expected_ast = PhoronProgram {
         header: PhoronHeader {
+            version_def: PhoronVersionDef::default(),
             sourcefile_def: PhoronSourceFileDef {
                 source_file: "samples/CreateArrayOfThreads.pho".to_string(),
             },
@@ -679,6 +747,7 @@ fn test_parse_create_array_of_threads() -> Result<(), Box<dyn Error>> {
                 super_class_name: "java/lang/Object".to_string(),
             },
             implements_defs: vec![],
+            class_directives: vec![],
         },
         body: PhoronBody {
             field_defs: vec![],
@@ -766,6 +835,7 @@ fn test_parse_create_array_of_threads() -> Result<(), Box<dyn Error>> {
 fn test_parse_swap_top_two_items() -> Result<(), Box<dyn Error>> {
     let expected_ast = PhoronProgram {
         header: PhoronHeader {
+            version_def: PhoronVersionDef::default(),
             sourcefile_def: PhoronSourceFileDef {
                 source_file: "samples/SwapTopTwoItems.pho".to_string(),
             },
@@ -780,6 +850,7 @@ fn test_parse_swap_top_two_items() -> Result<(), Box<dyn Error>> {
                 super_class_name: "java/lang/Object".to_string(),
             },
             implements_defs: vec![],
+            class_directives: vec![],
         },
         body: PhoronBody {
             field_defs: vec![],
@@ -856,6 +927,7 @@ fn test_parse_swap_top_two_items() -> Result<(), Box<dyn Error>> {
 fn test_parse_create_matrix_of_int() -> Result<(), Box<dyn Error>> {
     let expected_ast = PhoronProgram {
         header: PhoronHeader {
+            version_def: PhoronVersionDef::default(),
             sourcefile_def: PhoronSourceFileDef {
                 source_file: "samples/CreateMatrixOfInt.pho".to_string(),
             },
@@ -870,6 +942,7 @@ fn test_parse_create_matrix_of_int() -> Result<(), Box<dyn Error>> {
                 super_class_name: "java/lang/Object".to_string(),
             },
             implements_defs: vec![],
+            class_directives: vec![],
         },
         body: PhoronBody {
             field_defs: vec![],
@@ -969,6 +1042,7 @@ fn test_parse_create_matrix_of_int() -> Result<(), Box<dyn Error>> {
 fn test_parse_count() -> Result<(), Box<dyn Error>> {
     let expected_ast = PhoronProgram {
         header: PhoronHeader {
+            version_def: PhoronVersionDef::default(),
             sourcefile_def: PhoronSourceFileDef {
                 source_file: "samples/Count.pho".to_string(),
             },
@@ -983,6 +1057,7 @@ fn test_parse_count() -> Result<(), Box<dyn Error>> {
                 super_class_name: "java/lang/Object".to_string(),
             },
             implements_defs: vec![],
+            class_directives: vec![],
         },
         body: PhoronBody {
             field_defs: vec![],
@@ -1086,6 +1161,7 @@ fn test_parse_count() -> Result<(), Box<dyn Error>> {
 fn test_parse_catcher() -> Result<(), Box<dyn Error>> {
     let expected_ast = PhoronProgram {
         header: PhoronHeader {
+            version_def: PhoronVersionDef::default(),
             sourcefile_def: PhoronSourceFileDef {
                 source_file: "samples/Catcher.pho".to_string(),
             },
@@ -1100,6 +1176,7 @@ fn test_parse_catcher() -> Result<(), Box<dyn Error>> {
                 super_class_name: "java/lang/Object".to_string(),
             },
             implements_defs: vec![],
+            class_directives: vec![],
         },
         body: PhoronBody {
             field_defs: vec![],
@@ -1199,6 +1276,7 @@ fn test_parse_catcher() -> Result<(), Box<dyn Error>> {
 fn test_parse_anewarray() -> Result<(), Box<dyn Error>> {
     let expected_ast = PhoronProgram {
         header: PhoronHeader {
+            version_def: PhoronVersionDef::default(),
             sourcefile_def: PhoronSourceFileDef {
                 source_file: "samples/Anewarray.pho".to_string(),
             },
@@ -1213,6 +1291,7 @@ fn test_parse_anewarray() -> Result<(), Box<dyn Error>> {
                 super_class_name: "java/lang/Object".to_string(),
             },
             implements_defs: vec![],
+            class_directives: vec![],
         },
         body: PhoronBody {
             field_defs: vec![],
@@ -1304,6 +1383,7 @@ fn test_parse_anewarray() -> Result<(), Box<dyn Error>> {
 fn test_parse_args_to_main() -> Result<(), Box<dyn Error>> {
     let expected_ast = PhoronProgram {
         header: PhoronHeader {
+            version_def: PhoronVersionDef::default(),
             sourcefile_def: PhoronSourceFileDef {
                 source_file: "samples/ArgsToMain.pho".to_string(),
             },
@@ -1318,6 +1398,7 @@ fn test_parse_args_to_main() -> Result<(), Box<dyn Error>> {
                 super_class_name: "java/lang/Object".to_string(),
             },
             implements_defs: vec![],
+            class_directives: vec![],
         },
         body: PhoronBody {
             field_defs: vec![],
@@ -1392,6 +1473,7 @@ fn test_parse_args_to_main() -> Result<(), Box<dyn Error>> {
 fn test_parse_count_jasmin2() -> Result<(), Box<dyn Error>> {
     let expected_ast = PhoronProgram {
         header: PhoronHeader {
+            version_def: PhoronVersionDef::default(),
             sourcefile_def: PhoronSourceFileDef {
                 source_file: "samples/CountJasmin2.pho".to_string(),
             },
@@ -1406,6 +1488,7 @@ fn test_parse_count_jasmin2() -> Result<(), Box<dyn Error>> {
                 super_class_name: "java/lang/Object".to_string(),
             },
             implements_defs: vec![],
+            class_directives: vec![],
         },
         body: PhoronBody {
             field_defs: vec![],
@@ -1493,6 +1576,7 @@ fn test_parse_count_jasmin2() -> Result<(), Box<dyn Error>> {
 fn test_parse_add_nums_jasmin() -> Result<(), Box<dyn Error>> {
     let expected_ast = PhoronProgram {
         header: PhoronHeader {
+            version_def: PhoronVersionDef::default(),
             sourcefile_def: PhoronSourceFileDef {
                 source_file: "samples/AddNumsJasmin.pho".to_string(),
             },
@@ -1507,6 +1591,7 @@ fn test_parse_add_nums_jasmin() -> Result<(), Box<dyn Error>> {
                 super_class_name: "java/lang/Object".to_string(),
             },
             implements_defs: vec![],
+            class_directives: vec![],
         },
         body: PhoronBody {
             field_defs: vec![],
@@ -1608,6 +1693,7 @@ fn test_parse_add_nums_jasmin() -> Result<(), Box<dyn Error>> {
 fn test_parse_stack_push_jasmin() -> Result<(), Box<dyn Error>> {
     let expected_ast = PhoronProgram {
         header: PhoronHeader {
+            version_def: PhoronVersionDef::default(),
             sourcefile_def: PhoronSourceFileDef {
                 source_file: "samples/StackPushJasmin.pho".to_string(),
             },
@@ -1622,6 +1708,7 @@ fn test_parse_stack_push_jasmin() -> Result<(), Box<dyn Error>> {
                 super_class_name: "java/lang/Object".to_string(),
             },
             implements_defs: vec![],
+            class_directives: vec![],
         },
         body: PhoronBody {
             field_defs: vec![],
@@ -1698,6 +1785,7 @@ fn test_parse_stack_push_jasmin() -> Result<(), Box<dyn Error>> {
 fn test_parse_factorial_goto() -> Result<(), Box<dyn Error>> {
     let expected_ast = PhoronProgram {
         header: PhoronHeader {
+            version_def: PhoronVersionDef::default(),
             sourcefile_def: PhoronSourceFileDef {
                 source_file: "samples/FactorialGoto.pho".to_string(),
             },
@@ -1712,6 +1800,7 @@ fn test_parse_factorial_goto() -> Result<(), Box<dyn Error>> {
                 super_class_name: "java/lang/Object".to_string(),
             },
             implements_defs: vec![],
+            class_directives: vec![],
         },
         body: PhoronBody {
             field_defs: vec![],
@@ -1833,6 +1922,7 @@ fn test_parse_factorial_goto() -> Result<(), Box<dyn Error>> {
 fn test_parse_factorial_jasmin() -> Result<(), Box<dyn Error>> {
     let expected_ast = PhoronProgram {
         header: PhoronHeader {
+            version_def: PhoronVersionDef::default(),
             sourcefile_def: PhoronSourceFileDef {
                 source_file: "samples/FactorialJasmin.pho".to_string(),
             },
@@ -1847,6 +1937,7 @@ fn test_parse_factorial_jasmin() -> Result<(), Box<dyn Error>> {
                 super_class_name: "java/lang/Object".to_string(),
             },
             implements_defs: vec![],
+            class_directives: vec![],
         },
         body: PhoronBody {
             field_defs: vec![],
@@ -1964,6 +2055,7 @@ fn test_parse_factorial_jasmin() -> Result<(), Box<dyn Error>> {
 fn test_parse_check_array_type() -> Result<(), Box<dyn Error>> {
     let expected_ast = PhoronProgram {
         header: PhoronHeader {
+            version_def: PhoronVersionDef::default(),
             sourcefile_def: PhoronSourceFileDef {
                 source_file: "samples/CheckArrayType.pho".to_string(),
             },
@@ -1978,6 +2070,7 @@ fn test_parse_check_array_type() -> Result<(), Box<dyn Error>> {
                 super_class_name: "java/lang/Object".to_string(),
             },
             implements_defs: vec![],
+            class_directives: vec![],
         },
         body: PhoronBody {
             field_defs: vec![],
@@ -2088,6 +2181,7 @@ fn test_parse_check_array_type() -> Result<(), Box<dyn Error>> {
 fn test_parse_print_hello_10_times() -> Result<(), Box<dyn Error>> {
     let expected_ast = PhoronProgram {
         header: PhoronHeader {
+            version_def: PhoronVersionDef::default(),
             sourcefile_def: PhoronSourceFileDef {
                 source_file: "samples/PrintHello10Times.pho".to_string(),
             },
@@ -2102,6 +2196,7 @@ fn test_parse_print_hello_10_times() -> Result<(), Box<dyn Error>> {
                 super_class_name: "java/lang/Object".to_string(),
             },
             implements_defs: vec![],
+            class_directives: vec![],
         },
         body: PhoronBody {
             field_defs: vec![],
@@ -2213,6 +2308,7 @@ fn test_parse_print_hello_10_times() -> Result<(), Box<dyn Error>> {
 fn test_parse_add_nums() -> Result<(), Box<dyn Error>> {
     let expected_ast = PhoronProgram {
         header: PhoronHeader {
+            version_def: PhoronVersionDef::default(),
             sourcefile_def: PhoronSourceFileDef {
                 source_file: "samples/AddNums.pho".to_string(),
             },
@@ -2227,6 +2323,7 @@ fn test_parse_add_nums() -> Result<(), Box<dyn Error>> {
                 super_class_name: "java/lang/Object".to_string(),
             },
             implements_defs: vec![],
+            class_directives: vec![],
         },
         body: PhoronBody {
             field_defs: vec![],
@@ -2371,6 +2468,7 @@ fn test_parse_add_nums() -> Result<(), Box<dyn Error>> {
 fn test_parse_count_jasmin() -> Result<(), Box<dyn Error>> {
     let expected_ast = PhoronProgram {
         header: PhoronHeader {
+            version_def: PhoronVersionDef::default(),
             sourcefile_def: PhoronSourceFileDef {
                 source_file: "samples/CountJasmin.pho".to_string(),
             },
@@ -2385,6 +2483,7 @@ fn test_parse_count_jasmin() -> Result<(), Box<dyn Error>> {
                 super_class_name: "java/lang/Object".to_string(),
             },
             implements_defs: vec![],
+            class_directives: vec![],
         },
         body: PhoronBody {
             field_defs: vec![],
@@ -2487,6 +2586,7 @@ fn test_parse_count_jasmin() -> Result<(), Box<dyn Error>> {
 fn test_parse_string_buffer_demo() -> Result<(), Box<dyn Error>> {
     let expected_ast = PhoronProgram {
         header: PhoronHeader {
+            version_def: PhoronVersionDef::default(),
             sourcefile_def: PhoronSourceFileDef {
                 source_file: "samples/StringBufferDemo.pho".to_string(),
             },
@@ -2501,6 +2601,7 @@ fn test_parse_string_buffer_demo() -> Result<(), Box<dyn Error>> {
                 super_class_name: "java/lang/Object".to_string(),
             },
             implements_defs: vec![],
+            class_directives: vec![],
         },
         body: PhoronBody {
             field_defs: vec![],
@@ -2647,6 +2748,7 @@ fn test_parse_string_buffer_demo() -> Result<(), Box<dyn Error>> {
 fn test_parse_array_demo() -> Result<(), Box<dyn Error>> {
     let expected_ast = PhoronProgram {
         header: PhoronHeader {
+            version_def: PhoronVersionDef::default(),
             sourcefile_def: PhoronSourceFileDef {
                 source_file: "samples/ArrayDemo.pho".to_string(),
             },
@@ -2661,6 +2763,7 @@ fn test_parse_array_demo() -> Result<(), Box<dyn Error>> {
                 super_class_name: "java/lang/Object".to_string(),
             },
             implements_defs: vec![],
+            class_directives: vec![],
         },
         body: PhoronBody {
             field_defs: vec![],
@@ -2927,6 +3030,7 @@ fn test_parse_array_demo() -> Result<(), Box<dyn Error>> {
 fn test_parse_all_in_one() -> Result<(), Box<dyn Error>> {
     let expected_ast = PhoronProgram {
         header: PhoronHeader {
+            version_def: PhoronVersionDef::default(),
             sourcefile_def: PhoronSourceFileDef {
                 source_file: "AllInOne.pho".to_string(),
             },
@@ -2941,6 +3045,7 @@ fn test_parse_all_in_one() -> Result<(), Box<dyn Error>> {
                 super_class_name: "java/lang/Thread".to_string(),
             },
             implements_defs: vec![],
+            class_directives: vec![],
         },
         body: PhoronBody {
             field_defs: vec![
@@ -2948,19 +3053,37 @@ fn test_parse_all_in_one() -> Result<(), Box<dyn Error>> {
                     name: "x".to_string(),
                     access_flags: vec![PhoronFieldAccessFlag::AccPrivate],
                     field_descriptor: BaseType(Integer),
+                    signature: None,
                     init_val: None,
+                    annotations: vec![],
+                    type_annotations: vec![],
+                    attributes: vec![],
+                    deprecated: false,
+                    synthetic: false,
                 },
                 PhoronFieldDef {
                     name: "y".to_string(),
                     access_flags: vec![PhoronFieldAccessFlag::AccPrivate],
                     field_descriptor: BaseType(Double),
+                    signature: None,
                     init_val: Some(PhoronFieldInitValue::Double(1.2345)),
+                    annotations: vec![],
+                    type_annotations: vec![],
+                    attributes: vec![],
+                    deprecated: false,
+                    synthetic: false,
                 },
                 PhoronFieldDef {
                     name: "z".to_string(),
                     access_flags: vec![PhoronFieldAccessFlag::AccPublic],
                     field_descriptor: BaseType(Integer),
+                    signature: None,
                     init_val: Some(PhoronFieldInitValue::Integer(12345)),
+                    annotations: vec![],
+                    type_annotations: vec![],
+                    attributes: vec![],
+                    deprecated: false,
+                    synthetic: false,
                 },
                 PhoronFieldDef {
                     name: "PREFIX".to_string(),
@@ -2972,7 +3095,13 @@ fn test_parse_all_in_one() -> Result<(), Box<dyn Error>> {
                     field_descriptor: ObjectType {
                         class_name: "java/lang/String".to_string(),
                     },
+                    signature: None,
                     init_val: Some(PhoronFieldInitValue::QuotedString("FooBar".to_string())),
+                    annotations: vec![],
+                    type_annotations: vec![],
+                    attributes: vec![],
+                    deprecated: false,
+                    synthetic: false,
                 },
             ],
             method_defs: vec![
@@ -3431,6 +3560,7 @@ fn test_parse_all_in_one() -> Result<(), Box<dyn Error>> {
                             varnum: 0,
                             name: "Count".to_string(),
                             field_descriptor: BaseType(Integer),
+                            signature: None,
                             from_label: "Label1".to_string(),
                             to_label: "Label2".to_string(),
                         }),
@@ -3598,4 +3728,3683 @@ fn test_parse_all_in_one() -> Result<(), Box<dyn Error>> {
     assert_eq!(expected_ast, actual_ast);
 
     Ok(())
-}
\ No newline at end of file
+}
+
+#[test]
+fn test_parse_version_demo() -> Result<(), Box<dyn Error>> {
+    let expected_ast = PhoronProgram {
+        header: PhoronHeader {
+            version_def: PhoronVersionDef {
+                major_version: 52,
+                minor_version: 0,
+            },
+            sourcefile_def: PhoronSourceFileDef {
+                source_file: "samples/VersionDemo.pho".to_string(),
+            },
+            class_or_interface_def: Class(PhoronClassDef {
+                name: "VersionDemo".to_string(),
+                access_flags: vec![
+                    PhoronClassOrInterfaceAccessFlag::AccSuper,
+                    PhoronClassOrInterfaceAccessFlag::AccPublic,
+                ],
+            }),
+            super_def: PhoronSuperDef {
+                super_class_name: "java/lang/Object".to_string(),
+            },
+            implements_defs: vec![],
+            class_directives: vec![],
+        },
+        body: PhoronBody {
+            field_defs: vec![],
+            method_defs: vec![
+                PhoronMethodDef {
+                    name: "<init>".to_string(),
+                    access_flags: vec![PhoronMethodAccessFlag::AccPublic],
+                    method_descriptor: PhoronMethodDescriptor {
+                        param_descriptor: vec![],
+                        return_descriptor: VoidDescriptor,
+                    },
+                    instructions: vec![
+                        JvmInstruction(Aload0),
+                        JvmInstruction(Invokespecial {
+                            class_name: "java/lang/Object".to_string(),
+                            method_name: "<init>".to_string(),
+                            method_descriptor: PhoronMethodDescriptor {
+                                param_descriptor: vec![],
+                                return_descriptor: VoidDescriptor,
+                            },
+                        }),
+                        JvmInstruction(Return),
+                    ],
+                },
+                PhoronMethodDef {
+                    name: "main".to_string(),
+                    access_flags: vec![
+                        PhoronMethodAccessFlag::AccPublic,
+                        PhoronMethodAccessFlag::AccStatic,
+                    ],
+                    method_descriptor: PhoronMethodDescriptor {
+                        param_descriptor: vec![ArrayType {
+                            component_type: Box::new(ObjectType {
+                                class_name: "java/lang/String".to_string(),
+                            }),
+                        }],
+                        return_descriptor: VoidDescriptor,
+                    },
+                    instructions: vec![
+                        PhoronDirective(LimitStack(2)),
+                        JvmInstruction(Getstatic {
+                            class_name: "java/lang/System".to_string(),
+                            field_name: "out".to_string(),
+                            field_descriptor: ObjectType {
+                                class_name: "java/io/PrintStream".to_string(),
+                            },
+                        }),
+                        JvmInstruction(Ldc(LdcValue::QuotedString(
+                            "Hello from a Java 8 class file".to_string(),
+                        ))),
+                        JvmInstruction(Invokevirtual {
+                            class_name: "java/io/PrintStream".to_string(),
+                            method_name: "println".to_string(),
+                            method_descriptor: PhoronMethodDescriptor {
+                                param_descriptor: vec![ObjectType {
+                                    class_name: "java/lang/String".to_string(),
+                                }],
+                                return_descriptor: VoidDescriptor,
+                            },
+                        }),
+                        JvmInstruction(Return),
+                    ],
+                },
+            ],
+        },
+    };
+
+    let actual_ast = parse("samples/VersionDemo.pho")?;
+    assert_eq!(expected_ast, actual_ast);
+
+    Ok(())
+}
+
+#[test]
+fn test_parse_invokedynamic_demo() -> Result<(), Box<dyn Error>> {
+    let void_method_type = || PhoronMethodDescriptor {
+        param_descriptor: vec![],
+        return_descriptor: VoidDescriptor,
+    };
+
+    let bootstrap_params = |extra_params: Vec<PhoronFieldDescriptor>| {
+        let mut params = vec![
+            ObjectType {
+                class_name: "java/lang/invoke/MethodHandles$Lookup".to_string(),
+            },
+            ObjectType {
+                class_name: "java/lang/String".to_string(),
+            },
+            ObjectType {
+                class_name: "java/lang/invoke/MethodType".to_string(),
+            },
+        ];
+        params.extend(extra_params);
+        params
+    };
+
+    let call_site = || {
+        FieldDescriptor(ObjectType {
+            class_name: "java/lang/invoke/CallSite".to_string(),
+        })
+    };
+
+    let expected_ast = PhoronProgram {
+        header: PhoronHeader {
+            version_def: PhoronVersionDef {
+                major_version: 52,
+                minor_version: 0,
+            },
+            sourcefile_def: PhoronSourceFileDef {
+                source_file: "samples/InvokedynamicDemo.pho".to_string(),
+            },
+            class_or_interface_def: Class(PhoronClassDef {
+                name: "InvokedynamicDemo".to_string(),
+                access_flags: vec![
+                    PhoronClassOrInterfaceAccessFlag::AccSuper,
+                    PhoronClassOrInterfaceAccessFlag::AccPublic,
+                ],
+            }),
+            super_def: PhoronSuperDef {
+                super_class_name: "java/lang/Object".to_string(),
+            },
+            implements_defs: vec![],
+            class_directives: vec![
+                PhoronClassDirective::Bootstrap {
+                    name: "concat".to_string(),
+                    method_handle: PhoronMethodHandle::Invokestatic {
+                        class_name: "java/lang/invoke/StringConcatFactory".to_string(),
+                        method_name: "makeConcatWithConstants".to_string(),
+                        method_descriptor: PhoronMethodDescriptor {
+                            param_descriptor: bootstrap_params(vec![
+                                ObjectType {
+                                    class_name: "java/lang/String".to_string(),
+                                },
+                                ArrayType {
+                                    component_type: Box::new(ObjectType {
+                                        class_name: "java/lang/Object".to_string(),
+                                    }),
+                                },
+                            ]),
+                            return_descriptor: call_site(),
+                        },
+                        is_interface: false,
+                    },
+                    arguments: vec![BootstrapArgument::QuotedString("Hello, \u{1}!".to_string())],
+                },
+                PhoronClassDirective::Bootstrap {
+                    name: "lambda".to_string(),
+                    method_handle: PhoronMethodHandle::Invokestatic {
+                        class_name: "java/lang/invoke/LambdaMetafactory".to_string(),
+                        method_name: "metafactory".to_string(),
+                        method_descriptor: PhoronMethodDescriptor {
+                            param_descriptor: bootstrap_params(vec![
+                                ObjectType {
+                                    class_name: "java/lang/invoke/MethodType".to_string(),
+                                },
+                                ObjectType {
+                                    class_name: "java/lang/invoke/MethodHandle".to_string(),
+                                },
+                                ObjectType {
+                                    class_name: "java/lang/invoke/MethodType".to_string(),
+                                },
+                            ]),
+                            return_descriptor: call_site(),
+                        },
+                        is_interface: false,
+                    },
+                    arguments: vec![
+                        BootstrapArgument::MethodType(void_method_type()),
+                        BootstrapArgument::MethodHandle(PhoronMethodHandle::Invokestatic {
+                            class_name: "InvokedynamicDemo".to_string(),
+                            method_name: "greet".to_string(),
+                            method_descriptor: void_method_type(),
+                            is_interface: false,
+                        }),
+                        BootstrapArgument::MethodType(void_method_type()),
+                    ],
+                },
+            ],
+        },
+        body: PhoronBody {
+            field_defs: vec![],
+            method_defs: vec![
+                PhoronMethodDef {
+                    name: "<init>".to_string(),
+                    access_flags: vec![PhoronMethodAccessFlag::AccPublic],
+                    method_descriptor: void_method_type(),
+                    instructions: vec![
+                        JvmInstruction(Aload0),
+                        JvmInstruction(Invokespecial {
+                            class_name: "java/lang/Object".to_string(),
+                            method_name: "<init>".to_string(),
+                            method_descriptor: void_method_type(),
+                        }),
+                        JvmInstruction(Return),
+                    ],
+                },
+                PhoronMethodDef {
+                    name: "greet".to_string(),
+                    access_flags: vec![
+                        PhoronMethodAccessFlag::AccPrivate,
+                        PhoronMethodAccessFlag::AccStatic,
+                    ],
+                    method_descriptor: void_method_type(),
+                    instructions: vec![
+                        PhoronDirective(LimitStack(2)),
+                        JvmInstruction(Getstatic {
+                            class_name: "java/lang/System".to_string(),
+                            field_name: "out".to_string(),
+                            field_descriptor: ObjectType {
+                                class_name: "java/io/PrintStream".to_string(),
+                            },
+                        }),
+                        JvmInstruction(Ldc(LdcValue::QuotedString("world".to_string()))),
+                        JvmInstruction(Invokedynamic {
+                            method_name: "makeConcatWithConstants".to_string(),
+                            method_descriptor: PhoronMethodDescriptor {
+                                param_descriptor: vec![ObjectType {
+                                    class_name: "java/lang/String".to_string(),
+                                }],
+                                return_descriptor: FieldDescriptor(ObjectType {
+                                    class_name: "java/lang/String".to_string(),
+                                }),
+                            },
+                            bootstrap_method: "concat".to_string(),
+                        }),
+                        JvmInstruction(Invokevirtual {
+                            class_name: "java/io/PrintStream".to_string(),
+                            method_name: "println".to_string(),
+                            method_descriptor: PhoronMethodDescriptor {
+                                param_descriptor: vec![ObjectType {
+                                    class_name: "java/lang/String".to_string(),
+                                }],
+                                return_descriptor: VoidDescriptor,
+                            },
+                        }),
+                        JvmInstruction(Return),
+                    ],
+                },
+                PhoronMethodDef {
+                    name: "main".to_string(),
+                    access_flags: vec![
+                        PhoronMethodAccessFlag::AccPublic,
+                        PhoronMethodAccessFlag::AccStatic,
+                    ],
+                    method_descriptor: PhoronMethodDescriptor {
+                        param_descriptor: vec![ArrayType {
+                            component_type: Box::new(ObjectType {
+                                class_name: "java/lang/String".to_string(),
+                            }),
+                        }],
+                        return_descriptor: VoidDescriptor,
+                    },
+                    instructions: vec![
+                        PhoronDirective(LimitStack(3)),
+                        JvmInstruction(New {
+                            class_name: "java/lang/Thread".to_string(),
+                        }),
+                        JvmInstruction(Dup),
+                        JvmInstruction(Invokedynamic {
+                            method_name: "run".to_string(),
+                            method_descriptor: PhoronMethodDescriptor {
+                                param_descriptor: vec![],
+                                return_descriptor: FieldDescriptor(ObjectType {
+                                    class_name: "java/lang/Runnable".to_string(),
+                                }),
+                            },
+                            bootstrap_method: "lambda".to_string(),
+                        }),
+                        JvmInstruction(Invokespecial {
+                            class_name: "java/lang/Thread".to_string(),
+                            method_name: "<init>".to_string(),
+                            method_descriptor: PhoronMethodDescriptor {
+                                param_descriptor: vec![ObjectType {
+                                    class_name: "java/lang/Runnable".to_string(),
+                                }],
+                                return_descriptor: VoidDescriptor,
+                            },
+                        }),
+                        JvmInstruction(Invokevirtual {
+                            class_name: "java/lang/Thread".to_string(),
+                            method_name: "run".to_string(),
+                            method_descriptor: void_method_type(),
+                        }),
+                        JvmInstruction(Return),
+                    ],
+                },
+            ],
+        },
+    };
+
+    let actual_ast = parse("samples/InvokedynamicDemo.pho")?;
+    assert_eq!(expected_ast, actual_ast);
+
+    Ok(())
+}
+
+#[test]
+fn test_parse_ldc_constants_demo() -> Result<(), Box<dyn Error>> {
+    let println_object = || Invokevirtual {
+        class_name: "java/io/PrintStream".to_string(),
+        method_name: "println".to_string(),
+        method_descriptor: PhoronMethodDescriptor {
+            param_descriptor: vec![ObjectType {
+                class_name: "java/lang/Object".to_string(),
+            }],
+            return_descriptor: VoidDescriptor,
+        },
+    };
+
+    let system_out = || Getstatic {
+        class_name: "java/lang/System".to_string(),
+        field_name: "out".to_string(),
+        field_descriptor: ObjectType {
+            class_name: "java/io/PrintStream".to_string(),
+        },
+    };
+
+    let expected_ast = PhoronProgram {
+        header: PhoronHeader {
+            version_def: PhoronVersionDef {
+                major_version: 52,
+                minor_version: 0,
+            },
+            sourcefile_def: PhoronSourceFileDef {
+                source_file: "samples/LdcConstantsDemo.pho".to_string(),
+            },
+            class_or_interface_def: Class(PhoronClassDef {
+                name: "LdcConstantsDemo".to_string(),
+                access_flags: vec![
+                    PhoronClassOrInterfaceAccessFlag::AccSuper,
+                    PhoronClassOrInterfaceAccessFlag::AccPublic,
+                ],
+            }),
+            super_def: PhoronSuperDef {
+                super_class_name: "java/lang/Object".to_string(),
+            },
+            implements_defs: vec![],
+            class_directives: vec![],
+        },
+        body: PhoronBody {
+            field_defs: vec![],
+            method_defs: vec![PhoronMethodDef {
+                name: "main".to_string(),
+                access_flags: vec![
+                    PhoronMethodAccessFlag::AccPublic,
+                    PhoronMethodAccessFlag::AccStatic,
+                ],
+                method_descriptor: PhoronMethodDescriptor {
+                    param_descriptor: vec![ArrayType {
+                        component_type: Box::new(ObjectType {
+                            class_name: "java/lang/String".to_string(),
+                        }),
+                    }],
+                    return_descriptor: VoidDescriptor,
+                },
+                instructions: vec![
+                    PhoronDirective(LimitStack(2)),
+                    PhoronDirective(LimitLocals(1)),
+                    JvmInstruction(system_out()),
+                    JvmInstruction(Ldc(LdcValue::Class("java/lang/String".to_string()))),
+                    JvmInstruction(println_object()),
+                    JvmInstruction(system_out()),
+                    JvmInstruction(Ldcw(LdcwValue::Class("[I".to_string()))),
+                    JvmInstruction(println_object()),
+                    JvmInstruction(system_out()),
+                    JvmInstruction(Ldc(LdcValue::MethodType(PhoronMethodDescriptor {
+                        param_descriptor: vec![
+                            BaseType(Integer),
+                            ObjectType {
+                                class_name: "java/lang/String".to_string(),
+                            },
+                        ],
+                        return_descriptor: VoidDescriptor,
+                    }))),
+                    JvmInstruction(println_object()),
+                    JvmInstruction(system_out()),
+                    JvmInstruction(Ldcw(LdcwValue::MethodHandle(
+                        PhoronMethodHandle::Invokestatic {
+                            class_name: "java/lang/Integer".to_string(),
+                            method_name: "toHexString".to_string(),
+                            method_descriptor: PhoronMethodDescriptor {
+                                param_descriptor: vec![BaseType(Integer)],
+                                return_descriptor: FieldDescriptor(ObjectType {
+                                    class_name: "java/lang/String".to_string(),
+                                }),
+                            },
+                            is_interface: false,
+                        },
+                    ))),
+                    JvmInstruction(println_object()),
+                    JvmInstruction(Return),
+                ],
+            }],
+        },
+    };
+
+    let actual_ast = parse("samples/LdcConstantsDemo.pho")?;
+    assert_eq!(expected_ast, actual_ast);
+
+    Ok(())
+}
+
+#[test]
+fn test_parse_dynamic_constant_demo() -> Result<(), Box<dyn Error>> {
+    let string_type = || ObjectType {
+        class_name: "java/lang/String".to_string(),
+    };
+
+    let object_type = |class_name: &str| ObjectType {
+        class_name: class_name.to_string(),
+    };
+
+    let constant_bootstraps_invoke = || PhoronMethodHandle::Invokestatic {
+        class_name: "java/lang/invoke/ConstantBootstraps".to_string(),
+        method_name: "invoke".to_string(),
+        method_descriptor: PhoronMethodDescriptor {
+            param_descriptor: vec![
+                object_type("java/lang/invoke/MethodHandles$Lookup"),
+                object_type("java/lang/String"),
+                object_type("java/lang/Class"),
+                object_type("java/lang/invoke/MethodHandle"),
+                ArrayType {
+                    component_type: Box::new(object_type("java/lang/Object")),
+                },
+            ],
+            return_descriptor: FieldDescriptor(object_type("java/lang/Object")),
+        },
+        is_interface: false,
+    };
+
+    let system_out = || Getstatic {
+        class_name: "java/lang/System".to_string(),
+        field_name: "out".to_string(),
+        field_descriptor: object_type("java/io/PrintStream"),
+    };
+
+    let println = |param_descriptor| Invokevirtual {
+        class_name: "java/io/PrintStream".to_string(),
+        method_name: "println".to_string(),
+        method_descriptor: PhoronMethodDescriptor {
+            param_descriptor: vec![param_descriptor],
+            return_descriptor: VoidDescriptor,
+        },
+    };
+
+    let expected_ast = PhoronProgram {
+        header: PhoronHeader {
+            version_def: PhoronVersionDef {
+                major_version: 55,
+                minor_version: 0,
+            },
+            sourcefile_def: PhoronSourceFileDef {
+                source_file: "samples/DynamicConstantDemo.pho".to_string(),
+            },
+            class_or_interface_def: Class(PhoronClassDef {
+                name: "DynamicConstantDemo".to_string(),
+                access_flags: vec![
+                    PhoronClassOrInterfaceAccessFlag::AccSuper,
+                    PhoronClassOrInterfaceAccessFlag::AccPublic,
+                ],
+            }),
+            super_def: PhoronSuperDef {
+                super_class_name: "java/lang/Object".to_string(),
+            },
+            implements_defs: vec![],
+            class_directives: vec![
+                PhoronClassDirective::Bootstrap {
+                    name: "greeting".to_string(),
+                    method_handle: constant_bootstraps_invoke(),
+                    arguments: vec![BootstrapArgument::MethodHandle(
+                        PhoronMethodHandle::Invokestatic {
+                            class_name: "DynamicConstantDemo".to_string(),
+                            method_name: "greeting".to_string(),
+                            method_descriptor: PhoronMethodDescriptor {
+                                param_descriptor: vec![],
+                                return_descriptor: FieldDescriptor(string_type()),
+                            },
+                            is_interface: false,
+                        },
+                    )],
+                },
+                PhoronClassDirective::Bootstrap {
+                    name: "answer".to_string(),
+                    method_handle: constant_bootstraps_invoke(),
+                    arguments: vec![BootstrapArgument::MethodHandle(
+                        PhoronMethodHandle::Invokestatic {
+                            class_name: "DynamicConstantDemo".to_string(),
+                            method_name: "answer".to_string(),
+                            method_descriptor: PhoronMethodDescriptor {
+                                param_descriptor: vec![],
+                                return_descriptor: FieldDescriptor(BaseType(Long)),
+                            },
+                            is_interface: false,
+                        },
+                    )],
+                },
+                PhoronClassDirective::Bootstrap {
+                    name: "describe".to_string(),
+                    method_handle: constant_bootstraps_invoke(),
+                    arguments: vec![
+                        BootstrapArgument::MethodHandle(PhoronMethodHandle::Invokestatic {
+                            class_name: "java/lang/String".to_string(),
+                            method_name: "valueOf".to_string(),
+                            method_descriptor: PhoronMethodDescriptor {
+                                param_descriptor: vec![object_type("java/lang/Object")],
+                                return_descriptor: FieldDescriptor(string_type()),
+                            },
+                            is_interface: false,
+                        }),
+                        BootstrapArgument::Dynamic(PhoronDynamicConstant {
+                            name: "ANSWER".to_string(),
+                            field_descriptor: BaseType(Long),
+                            bootstrap_method: "answer".to_string(),
+                        }),
+                    ],
+                },
+            ],
+        },
+        body: PhoronBody {
+            field_defs: vec![],
+            method_defs: vec![
+                PhoronMethodDef {
+                    name: "greeting".to_string(),
+                    access_flags: vec![
+                        PhoronMethodAccessFlag::AccPrivate,
+                        PhoronMethodAccessFlag::AccStatic,
+                    ],
+                    method_descriptor: PhoronMethodDescriptor {
+                        param_descriptor: vec![],
+                        return_descriptor: FieldDescriptor(string_type()),
+                    },
+                    instructions: vec![
+                        PhoronDirective(LimitStack(1)),
+                        JvmInstruction(Ldc(LdcValue::QuotedString("Hello, world!".to_string()))),
+                        JvmInstruction(Areturn),
+                    ],
+                },
+                PhoronMethodDef {
+                    name: "answer".to_string(),
+                    access_flags: vec![
+                        PhoronMethodAccessFlag::AccPrivate,
+                        PhoronMethodAccessFlag::AccStatic,
+                    ],
+                    method_descriptor: PhoronMethodDescriptor {
+                        param_descriptor: vec![],
+                        return_descriptor: FieldDescriptor(BaseType(Long)),
+                    },
+                    instructions: vec![
+                        PhoronDirective(LimitStack(2)),
+                        JvmInstruction(Ldc2w(Ldc2wValue::Long(42))),
+                        JvmInstruction(Lreturn),
+                    ],
+                },
+                PhoronMethodDef {
+                    name: "main".to_string(),
+                    access_flags: vec![
+                        PhoronMethodAccessFlag::AccPublic,
+                        PhoronMethodAccessFlag::AccStatic,
+                    ],
+                    method_descriptor: PhoronMethodDescriptor {
+                        param_descriptor: vec![ArrayType {
+                            component_type: Box::new(string_type()),
+                        }],
+                        return_descriptor: VoidDescriptor,
+                    },
+                    instructions: vec![
+                        PhoronDirective(LimitStack(3)),
+                        JvmInstruction(system_out()),
+                        JvmInstruction(Ldc(LdcValue::Dynamic(PhoronDynamicConstant {
+                            name: "GREETING".to_string(),
+                            field_descriptor: string_type(),
+                            bootstrap_method: "greeting".to_string(),
+                        }))),
+                        JvmInstruction(println(string_type())),
+                        JvmInstruction(system_out()),
+                        JvmInstruction(Ldc2w(Ldc2wValue::Dynamic(PhoronDynamicConstant {
+                            name: "ANSWER".to_string(),
+                            field_descriptor: BaseType(Long),
+                            bootstrap_method: "answer".to_string(),
+                        }))),
+                        JvmInstruction(println(BaseType(Long))),
+                        JvmInstruction(system_out()),
+                        JvmInstruction(Ldcw(LdcwValue::Dynamic(PhoronDynamicConstant {
+                            name: "DESCRIPTION".to_string(),
+                            field_descriptor: string_type(),
+                            bootstrap_method: "describe".to_string(),
+                        }))),
+                        JvmInstruction(println(string_type())),
+                        JvmInstruction(Return),
+                    ],
+                },
+            ],
+        },
+    };
+
+    let actual_ast = parse("samples/DynamicConstantDemo.pho")?;
+    assert_eq!(expected_ast, actual_ast);
+
+    Ok(())
+}
+
+#[test]
+fn test_parse_stack_map_demo() -> Result<(), Box<dyn Error>> {
+    let object_type = |class_name: &str| ObjectType {
+        class_name: class_name.to_string(),
+    };
+
+    let system_out = || Getstatic {
+        class_name: "java/lang/System".to_string(),
+        field_name: "out".to_string(),
+        field_descriptor: object_type("java/io/PrintStream"),
+    };
+
+    let println = |param_descriptor| Invokevirtual {
+        class_name: "java/io/PrintStream".to_string(),
+        method_name: "println".to_string(),
+        method_descriptor: PhoronMethodDescriptor {
+            param_descriptor: vec![param_descriptor],
+            return_descriptor: VoidDescriptor,
+        },
+    };
+
+    let invoke_demo = |method_name: &str, param_descriptor, return_descriptor| Invokestatic {
+        class_name: "StackMapDemo".to_string(),
+        method_name: method_name.to_string(),
+        method_descriptor: PhoronMethodDescriptor {
+            param_descriptor,
+            return_descriptor: FieldDescriptor(return_descriptor),
+        },
+    };
+
+    let expected_ast = PhoronProgram {
+        header: PhoronHeader {
+            version_def: PhoronVersionDef {
+                major_version: 52,
+                minor_version: 0,
+            },
+            sourcefile_def: PhoronSourceFileDef {
+                source_file: "samples/StackMapDemo.pho".to_string(),
+            },
+            class_or_interface_def: Class(PhoronClassDef {
+                name: "StackMapDemo".to_string(),
+                access_flags: vec![
+                    PhoronClassOrInterfaceAccessFlag::AccSuper,
+                    PhoronClassOrInterfaceAccessFlag::AccPublic,
+                ],
+            }),
+            super_def: PhoronSuperDef {
+                super_class_name: "java/lang/Object".to_string(),
+            },
+            implements_defs: vec![],
+            class_directives: vec![],
+        },
+        body: PhoronBody {
+            field_defs: vec![],
+            method_defs: vec![
+                PhoronMethodDef {
+                    name: "<init>".to_string(),
+                    access_flags: vec![PhoronMethodAccessFlag::AccPublic],
+                    method_descriptor: PhoronMethodDescriptor {
+                        param_descriptor: vec![],
+                        return_descriptor: VoidDescriptor,
+                    },
+                    instructions: vec![
+                        JvmInstruction(Aload0),
+                        JvmInstruction(Invokespecial {
+                            class_name: "java/lang/Object".to_string(),
+                            method_name: "<init>".to_string(),
+                            method_descriptor: PhoronMethodDescriptor {
+                                param_descriptor: vec![],
+                                return_descriptor: VoidDescriptor,
+                            },
+                        }),
+                        JvmInstruction(Return),
+                    ],
+                },
+                PhoronMethodDef {
+                    name: "sum".to_string(),
+                    access_flags: vec![
+                        PhoronMethodAccessFlag::AccPublic,
+                        PhoronMethodAccessFlag::AccStatic,
+                    ],
+                    method_descriptor: PhoronMethodDescriptor {
+                        param_descriptor: vec![BaseType(Integer)],
+                        return_descriptor: FieldDescriptor(BaseType(Long)),
+                    },
+                    instructions: vec![
+                        PhoronDirective(LimitStack(4)),
+                        PhoronDirective(LimitLocals(4)),
+                        JvmInstruction(Lconst0),
+                        JvmInstruction(Lstore1),
+                        JvmInstruction(Iconst1),
+                        JvmInstruction(Istore3),
+                        PhoronLabel("Loop".to_string()),
+                        JvmInstruction(Iload3),
+                        JvmInstruction(Iload0),
+                        JvmInstruction(Ificmpgt {
+                            label: "Done".to_string(),
+                        }),
+                        JvmInstruction(Lload1),
+                        JvmInstruction(Iload3),
+                        JvmInstruction(I2l),
+                        JvmInstruction(Ladd),
+                        JvmInstruction(Lstore1),
+                        JvmInstruction(Iinc {
+                            varnum: 3,
+                            delta: 1,
+                        }),
+                        JvmInstruction(Goto {
+                            label: "Loop".to_string(),
+                        }),
+                        PhoronLabel("Done".to_string()),
+                        JvmInstruction(Lload1),
+                        JvmInstruction(Lreturn),
+                    ],
+                },
+                PhoronMethodDef {
+                    name: "describe".to_string(),
+                    access_flags: vec![
+                        PhoronMethodAccessFlag::AccPublic,
+                        PhoronMethodAccessFlag::AccStatic,
+                    ],
+                    method_descriptor: PhoronMethodDescriptor {
+                        param_descriptor: vec![BaseType(Boolean)],
+                        return_descriptor: FieldDescriptor(object_type("java/lang/Object")),
+                    },
+                    instructions: vec![
+                        PhoronDirective(LimitStack(4)),
+                        PhoronDirective(LimitLocals(2)),
+                        JvmInstruction(New {
+                            class_name: "java/lang/StringBuilder".to_string(),
+                        }),
+                        JvmInstruction(Dup),
+                        JvmInstruction(Iload0),
+                        JvmInstruction(Ifeq {
+                            label: "Empty".to_string(),
+                        }),
+                        JvmInstruction(Ldc(LdcValue::QuotedString("flag is set".to_string()))),
+                        JvmInstruction(Goto {
+                            label: "Construct".to_string(),
+                        }),
+                        PhoronLabel("Empty".to_string()),
+                        JvmInstruction(Ldc(LdcValue::QuotedString("flag is not set".to_string()))),
+                        PhoronLabel("Construct".to_string()),
+                        JvmInstruction(Invokespecial {
+                            class_name: "java/lang/StringBuilder".to_string(),
+                            method_name: "<init>".to_string(),
+                            method_descriptor: PhoronMethodDescriptor {
+                                param_descriptor: vec![object_type("java/lang/String")],
+                                return_descriptor: VoidDescriptor,
+                            },
+                        }),
+                        JvmInstruction(Astore1),
+                        JvmInstruction(Iload0),
+                        JvmInstruction(Ifeq {
+                            label: "Plain".to_string(),
+                        }),
+                        JvmInstruction(Aload1),
+                        JvmInstruction(Areturn),
+                        PhoronLabel("Plain".to_string()),
+                        JvmInstruction(Aload1),
+                        JvmInstruction(Invokevirtual {
+                            class_name: "java/lang/StringBuilder".to_string(),
+                            method_name: "toString".to_string(),
+                            method_descriptor: PhoronMethodDescriptor {
+                                param_descriptor: vec![],
+                                return_descriptor: FieldDescriptor(object_type("java/lang/String")),
+                            },
+                        }),
+                        JvmInstruction(Areturn),
+                    ],
+                },
+                PhoronMethodDef {
+                    name: "safeDivide".to_string(),
+                    access_flags: vec![
+                        PhoronMethodAccessFlag::AccPublic,
+                        PhoronMethodAccessFlag::AccStatic,
+                    ],
+                    method_descriptor: PhoronMethodDescriptor {
+                        param_descriptor: vec![BaseType(Integer), BaseType(Integer)],
+                        return_descriptor: FieldDescriptor(BaseType(Integer)),
+                    },
+                    instructions: vec![
+                        PhoronDirective(LimitStack(2)),
+                        PhoronDirective(LimitLocals(3)),
+                        PhoronLabel("Start".to_string()),
+                        JvmInstruction(Iload0),
+                        JvmInstruction(Iload1),
+                        JvmInstruction(Idiv),
+                        PhoronLabel("End".to_string()),
+                        JvmInstruction(Ireturn),
+                        PhoronLabel("Handler".to_string()),
+                        JvmInstruction(Astore2),
+                        JvmInstruction(Iconstm1),
+                        JvmInstruction(Ireturn),
+                        PhoronDirective(Catch {
+                            class_name: "java/lang/ArithmeticException".to_string(),
+                            from_label: "Start".to_string(),
+                            to_label: "End".to_string(),
+                            handler_label: "Handler".to_string(),
+                        }),
+                    ],
+                },
+                PhoronMethodDef {
+                    name: "main".to_string(),
+                    access_flags: vec![
+                        PhoronMethodAccessFlag::AccPublic,
+                        PhoronMethodAccessFlag::AccStatic,
+                    ],
+                    method_descriptor: PhoronMethodDescriptor {
+                        param_descriptor: vec![ArrayType {
+                            component_type: Box::new(object_type("java/lang/String")),
+                        }],
+                        return_descriptor: VoidDescriptor,
+                    },
+                    instructions: vec![
+                        PhoronDirective(LimitStack(4)),
+                        PhoronDirective(LimitLocals(1)),
+                        JvmInstruction(system_out()),
+                        JvmInstruction(Bipush(100)),
+                        JvmInstruction(invoke_demo("sum", vec![BaseType(Integer)], BaseType(Long))),
+                        JvmInstruction(println(BaseType(Long))),
+                        JvmInstruction(system_out()),
+                        JvmInstruction(Iconst1),
+                        JvmInstruction(invoke_demo(
+                            "describe",
+                            vec![BaseType(Boolean)],
+                            object_type("java/lang/Object"),
+                        )),
+                        JvmInstruction(println(object_type("java/lang/Object"))),
+                        JvmInstruction(system_out()),
+                        JvmInstruction(Iconst0),
+                        JvmInstruction(invoke_demo(
+                            "describe",
+                            vec![BaseType(Boolean)],
+                            object_type("java/lang/Object"),
+                        )),
+                        JvmInstruction(println(object_type("java/lang/Object"))),
+                        JvmInstruction(system_out()),
+                        JvmInstruction(Bipush(42)),
+                        JvmInstruction(Iconst2),
+                        JvmInstruction(invoke_demo(
+                            "safeDivide",
+                            vec![BaseType(Integer), BaseType(Integer)],
+                            BaseType(Integer),
+                        )),
+                        JvmInstruction(println(BaseType(Integer))),
+                        JvmInstruction(system_out()),
+                        JvmInstruction(Bipush(42)),
+                        JvmInstruction(Iconst0),
+                        JvmInstruction(invoke_demo(
+                            "safeDivide",
+                            vec![BaseType(Integer), BaseType(Integer)],
+                            BaseType(Integer),
+                        )),
+                        JvmInstruction(println(BaseType(Integer))),
+                        JvmInstruction(Return),
+                    ],
+                },
+            ],
+        },
+    };
+
+    let actual_ast = parse("samples/StackMapDemo.pho")?;
+    assert_eq!(expected_ast, actual_ast);
+
+    Ok(())
+}
+
+#[test]
+fn test_parse_limits_demo() -> Result<(), Box<dyn Error>> {
+    let object_init = |class_name: &str| Invokespecial {
+        class_name: class_name.to_string(),
+        method_name: "<init>".to_string(),
+        method_descriptor: PhoronMethodDescriptor {
+            param_descriptor: vec![],
+            return_descriptor: VoidDescriptor,
+        },
+    };
+
+    let expected_ast = PhoronProgram {
+        header: PhoronHeader {
+            version_def: PhoronVersionDef {
+                major_version: 45,
+                minor_version: 3,
+            },
+            sourcefile_def: PhoronSourceFileDef {
+                source_file: "samples/LimitsDemo.pho".to_string(),
+            },
+            class_or_interface_def: Class(PhoronClassDef {
+                name: "LimitsDemo".to_string(),
+                access_flags: vec![
+                    PhoronClassOrInterfaceAccessFlag::AccSuper,
+                    PhoronClassOrInterfaceAccessFlag::AccPublic,
+                ],
+            }),
+            super_def: PhoronSuperDef {
+                super_class_name: "java/lang/Object".to_string(),
+            },
+            implements_defs: vec![],
+            class_directives: vec![],
+        },
+        body: PhoronBody {
+            field_defs: vec![],
+            method_defs: vec![
+                PhoronMethodDef {
+                    name: "<init>".to_string(),
+                    access_flags: vec![PhoronMethodAccessFlag::AccPublic],
+                    method_descriptor: PhoronMethodDescriptor {
+                        param_descriptor: vec![],
+                        return_descriptor: VoidDescriptor,
+                    },
+                    instructions: vec![
+                        JvmInstruction(Aload0),
+                        JvmInstruction(object_init("java/lang/Object")),
+                        JvmInstruction(Return),
+                    ],
+                },
+                PhoronMethodDef {
+                    name: "average".to_string(),
+                    access_flags: vec![PhoronMethodAccessFlag::AccPublic],
+                    method_descriptor: PhoronMethodDescriptor {
+                        param_descriptor: vec![BaseType(Long), BaseType(Double)],
+                        return_descriptor: FieldDescriptor(BaseType(Double)),
+                    },
+                    instructions: vec![
+                        JvmInstruction(Lload1),
+                        JvmInstruction(L2d),
+                        JvmInstruction(Dload3),
+                        JvmInstruction(Dadd),
+                        JvmInstruction(Dconst1),
+                        JvmInstruction(Dconst1),
+                        JvmInstruction(Dadd),
+                        JvmInstruction(Ddiv),
+                        JvmInstruction(Dstore { varnum: 5 }),
+                        JvmInstruction(Dload { varnum: 5 }),
+                        JvmInstruction(Dreturn),
+                    ],
+                },
+                PhoronMethodDef {
+                    name: "main".to_string(),
+                    access_flags: vec![
+                        PhoronMethodAccessFlag::AccPublic,
+                        PhoronMethodAccessFlag::AccStatic,
+                    ],
+                    method_descriptor: PhoronMethodDescriptor {
+                        param_descriptor: vec![ArrayType {
+                            component_type: Box::new(ObjectType {
+                                class_name: "java/lang/String".to_string(),
+                            }),
+                        }],
+                        return_descriptor: VoidDescriptor,
+                    },
+                    instructions: vec![
+                        JvmInstruction(Getstatic {
+                            class_name: "java/lang/System".to_string(),
+                            field_name: "out".to_string(),
+                            field_descriptor: ObjectType {
+                                class_name: "java/io/PrintStream".to_string(),
+                            },
+                        }),
+                        JvmInstruction(New {
+                            class_name: "LimitsDemo".to_string(),
+                        }),
+                        JvmInstruction(Dup),
+                        JvmInstruction(object_init("LimitsDemo")),
+                        JvmInstruction(Ldc2w(Ldc2wValue::Long(3))),
+                        JvmInstruction(Ldc2w(Ldc2wValue::Double(4.0))),
+                        JvmInstruction(Invokevirtual {
+                            class_name: "LimitsDemo".to_string(),
+                            method_name: "average".to_string(),
+                            method_descriptor: PhoronMethodDescriptor {
+                                param_descriptor: vec![BaseType(Long), BaseType(Double)],
+                                return_descriptor: FieldDescriptor(BaseType(Double)),
+                            },
+                        }),
+                        JvmInstruction(Invokevirtual {
+                            class_name: "java/io/PrintStream".to_string(),
+                            method_name: "println".to_string(),
+                            method_descriptor: PhoronMethodDescriptor {
+                                param_descriptor: vec![BaseType(Double)],
+                                return_descriptor: VoidDescriptor,
+                            },
+                        }),
+                        JvmInstruction(Return),
+                    ],
+                },
+            ],
+        },
+    };
+
+    let actual_ast = parse("samples/LimitsDemo.pho")?;
+    assert_eq!(expected_ast, actual_ast);
+
+    Ok(())
+}
+
+#[test]
+fn test_parse_signature_demo() -> Result<(), Box<dyn Error>> {
+    let array_list = || ObjectType {
+        class_name: "java/util/ArrayList".to_string(),
+    };
+
+    let object = || ObjectType {
+        class_name: "java/lang/Object".to_string(),
+    };
+
+    let items_field = |get: bool| {
+        let class_name = "SignatureDemo".to_string();
+        let field_name = "items".to_string();
+        let field_descriptor = array_list();
+
+        if get {
+            Getfield {
+                class_name,
+                field_name,
+                field_descriptor,
+            }
+        } else {
+            Putfield {
+                class_name,
+                field_name,
+                field_descriptor,
+            }
+        }
+    };
+
+    let init = |class_name: &str| Invokespecial {
+        class_name: class_name.to_string(),
+        method_name: "<init>".to_string(),
+        method_descriptor: PhoronMethodDescriptor {
+            param_descriptor: vec![],
+            return_descriptor: VoidDescriptor,
+        },
+    };
+
+    let expected_ast = PhoronProgram {
+        header: PhoronHeader {
+            version_def: PhoronVersionDef {
+                major_version: 49,
+                minor_version: 0,
+            },
+            sourcefile_def: PhoronSourceFileDef {
+                source_file: "samples/SignatureDemo.pho".to_string(),
+            },
+            class_or_interface_def: Class(PhoronClassDef {
+                name: "SignatureDemo".to_string(),
+                access_flags: vec![
+                    PhoronClassOrInterfaceAccessFlag::AccSuper,
+                    PhoronClassOrInterfaceAccessFlag::AccPublic,
+                ],
+            }),
+            super_def: PhoronSuperDef {
+                super_class_name: "java/lang/Object".to_string(),
+            },
+            implements_defs: vec![],
+            class_directives: vec![PhoronClassDirective::Signature(
+                "<T:Ljava/lang/Object;>Ljava/lang/Object;".to_string(),
+            )],
+        },
+        body: PhoronBody {
+            field_defs: vec![PhoronFieldDef {
+                name: "items".to_string(),
+                access_flags: vec![PhoronFieldAccessFlag::AccPrivate],
+                field_descriptor: array_list(),
+                signature: Some("Ljava/util/ArrayList<TT;>;".to_string()),
+                init_val: None,
+                annotations: vec![],
+                type_annotations: vec![],
+                attributes: vec![],
+                deprecated: false,
+                synthetic: false,
+            }],
+            method_defs: vec![
+                PhoronMethodDef {
+                    name: "<init>".to_string(),
+                    access_flags: vec![PhoronMethodAccessFlag::AccPublic],
+                    method_descriptor: PhoronMethodDescriptor {
+                        param_descriptor: vec![],
+                        return_descriptor: VoidDescriptor,
+                    },
+                    instructions: vec![
+                        PhoronDirective(LimitStack(3)),
+                        PhoronDirective(LimitLocals(1)),
+                        JvmInstruction(Aload0),
+                        JvmInstruction(init("java/lang/Object")),
+                        JvmInstruction(Aload0),
+                        JvmInstruction(New {
+                            class_name: "java/util/ArrayList".to_string(),
+                        }),
+                        JvmInstruction(Dup),
+                        JvmInstruction(init("java/util/ArrayList")),
+                        JvmInstruction(items_field(false)),
+                        JvmInstruction(Return),
+                    ],
+                },
+                PhoronMethodDef {
+                    name: "add".to_string(),
+                    access_flags: vec![PhoronMethodAccessFlag::AccPublic],
+                    method_descriptor: PhoronMethodDescriptor {
+                        param_descriptor: vec![object()],
+                        return_descriptor: VoidDescriptor,
+                    },
+                    instructions: vec![
+                        PhoronDirective(Signature("(TT;)V".to_string())),
+                        PhoronDirective(LimitStack(2)),
+                        PhoronDirective(LimitLocals(2)),
+                        JvmInstruction(Aload0),
+                        JvmInstruction(items_field(true)),
+                        JvmInstruction(Aload1),
+                        JvmInstruction(Invokevirtual {
+                            class_name: "java/util/ArrayList".to_string(),
+                            method_name: "add".to_string(),
+                            method_descriptor: PhoronMethodDescriptor {
+                                param_descriptor: vec![object()],
+                                return_descriptor: FieldDescriptor(BaseType(Boolean)),
+                            },
+                        }),
+                        JvmInstruction(Pop),
+                        JvmInstruction(Return),
+                    ],
+                },
+                PhoronMethodDef {
+                    name: "items".to_string(),
+                    access_flags: vec![PhoronMethodAccessFlag::AccPublic],
+                    method_descriptor: PhoronMethodDescriptor {
+                        param_descriptor: vec![],
+                        return_descriptor: FieldDescriptor(ObjectType {
+                            class_name: "java/util/List".to_string(),
+                        }),
+                    },
+                    instructions: vec![
+                        PhoronDirective(Signature("()Ljava/util/List<TT;>;".to_string())),
+                        PhoronDirective(LimitStack(1)),
+                        PhoronDirective(LimitLocals(1)),
+                        JvmInstruction(Aload0),
+                        JvmInstruction(items_field(true)),
+                        JvmInstruction(Areturn),
+                    ],
+                },
+                PhoronMethodDef {
+                    name: "main".to_string(),
+                    access_flags: vec![
+                        PhoronMethodAccessFlag::AccPublic,
+                        PhoronMethodAccessFlag::AccStatic,
+                    ],
+                    method_descriptor: PhoronMethodDescriptor {
+                        param_descriptor: vec![ArrayType {
+                            component_type: Box::new(ObjectType {
+                                class_name: "java/lang/String".to_string(),
+                            }),
+                        }],
+                        return_descriptor: VoidDescriptor,
+                    },
+                    instructions: vec![
+                        PhoronDirective(LimitStack(2)),
+                        PhoronDirective(LimitLocals(2)),
+                        JvmInstruction(New {
+                            class_name: "SignatureDemo".to_string(),
+                        }),
+                        JvmInstruction(Dup),
+                        JvmInstruction(init("SignatureDemo")),
+                        JvmInstruction(Astore1),
+                        JvmInstruction(Aload1),
+                        JvmInstruction(Ldc(LdcValue::QuotedString("Hello, generics!".to_string()))),
+                        JvmInstruction(Invokevirtual {
+                            class_name: "SignatureDemo".to_string(),
+                            method_name: "add".to_string(),
+                            method_descriptor: PhoronMethodDescriptor {
+                                param_descriptor: vec![object()],
+                                return_descriptor: VoidDescriptor,
+                            },
+                        }),
+                        JvmInstruction(Getstatic {
+                            class_name: "java/lang/System".to_string(),
+                            field_name: "out".to_string(),
+                            field_descriptor: ObjectType {
+                                class_name: "java/io/PrintStream".to_string(),
+                            },
+                        }),
+                        JvmInstruction(Aload1),
+                        JvmInstruction(Invokevirtual {
+                            class_name: "SignatureDemo".to_string(),
+                            method_name: "items".to_string(),
+                            method_descriptor: PhoronMethodDescriptor {
+                                param_descriptor: vec![],
+                                return_descriptor: FieldDescriptor(ObjectType {
+                                    class_name: "java/util/List".to_string(),
+                                }),
+                            },
+                        }),
+                        JvmInstruction(Invokevirtual {
+                            class_name: "java/io/PrintStream".to_string(),
+                            method_name: "println".to_string(),
+                            method_descriptor: PhoronMethodDescriptor {
+                                param_descriptor: vec![object()],
+                                return_descriptor: VoidDescriptor,
+                            },
+                        }),
+                        JvmInstruction(Return),
+                    ],
+                },
+            ],
+        },
+    };
+
+    let actual_ast = parse("samples/SignatureDemo.pho")?;
+    assert_eq!(expected_ast, actual_ast);
+
+    Ok(())
+}
+
+#[test]
+fn test_parse_annotation_demo() -> Result<(), Box<dyn Error>> {
+    let object_type = |class_name: &str| ObjectType {
+        class_name: class_name.to_string(),
+    };
+
+    let annotation_def = |visibility, class_name: &str, element_value_pairs| PhoronAnnotationDef {
+        visibility,
+        annotation: PhoronAnnotation {
+            annotation_type: object_type(class_name),
+            element_value_pairs,
+        },
+    };
+
+    let pair = |name: &str, value| PhoronElementValuePair {
+        name: name.to_string(),
+        value,
+    };
+
+    let enum_value = |enum_type: &str, const_name: &str| PhoronElementValue::Enum {
+        enum_type: object_type(enum_type),
+        const_name: const_name.to_string(),
+    };
+
+    let annotations_array = || ArrayType {
+        component_type: Box::new(object_type("java/lang/annotation/Annotation")),
+    };
+
+    let print_annotations = || {
+        vec![
+            JvmInstruction(Invokestatic {
+                class_name: "java/util/Arrays".to_string(),
+                method_name: "toString".to_string(),
+                method_descriptor: PhoronMethodDescriptor {
+                    param_descriptor: vec![ArrayType {
+                        component_type: Box::new(object_type("java/lang/Object")),
+                    }],
+                    return_descriptor: FieldDescriptor(object_type("java/lang/String")),
+                },
+            }),
+            JvmInstruction(Invokevirtual {
+                class_name: "java/io/PrintStream".to_string(),
+                method_name: "println".to_string(),
+                method_descriptor: PhoronMethodDescriptor {
+                    param_descriptor: vec![object_type("java/lang/String")],
+                    return_descriptor: VoidDescriptor,
+                },
+            }),
+        ]
+    };
+
+    let get_out = || {
+        JvmInstruction(Getstatic {
+            class_name: "java/lang/System".to_string(),
+            field_name: "out".to_string(),
+            field_descriptor: object_type("java/io/PrintStream"),
+        })
+    };
+
+    let mut main_instructions = vec![
+        PhoronDirective(Annotation(annotation_def(
+            PhoronAnnotationVisibility::Visible,
+            "java/lang/annotation/Retention",
+            vec![pair(
+                "value",
+                enum_value("java/lang/annotation/RetentionPolicy", "RUNTIME"),
+            )],
+        ))),
+        PhoronDirective(Throws {
+            class_name: "java/lang/NoSuchFieldException".to_string(),
+        }),
+        get_out(),
+        JvmInstruction(Ldc(LdcValue::Class("AnnotationDemo".to_string()))),
+        JvmInstruction(Invokevirtual {
+            class_name: "java/lang/Class".to_string(),
+            method_name: "getAnnotations".to_string(),
+            method_descriptor: PhoronMethodDescriptor {
+                param_descriptor: vec![],
+                return_descriptor: FieldDescriptor(annotations_array()),
+            },
+        }),
+    ];
+    main_instructions.extend(print_annotations());
+    main_instructions.extend([
+        get_out(),
+        JvmInstruction(Ldc(LdcValue::Class("AnnotationDemo".to_string()))),
+        JvmInstruction(Ldc(LdcValue::QuotedString("answer".to_string()))),
+        JvmInstruction(Invokevirtual {
+            class_name: "java/lang/Class".to_string(),
+            method_name: "getField".to_string(),
+            method_descriptor: PhoronMethodDescriptor {
+                param_descriptor: vec![object_type("java/lang/String")],
+                return_descriptor: FieldDescriptor(object_type("java/lang/reflect/Field")),
+            },
+        }),
+        JvmInstruction(Invokevirtual {
+            class_name: "java/lang/reflect/Field".to_string(),
+            method_name: "getAnnotations".to_string(),
+            method_descriptor: PhoronMethodDescriptor {
+                param_descriptor: vec![],
+                return_descriptor: FieldDescriptor(annotations_array()),
+            },
+        }),
+    ]);
+    main_instructions.extend(print_annotations());
+    main_instructions.push(JvmInstruction(Return));
+
+    let expected_ast = PhoronProgram {
+        header: PhoronHeader {
+            version_def: PhoronVersionDef {
+                major_version: 49,
+                minor_version: 0,
+            },
+            sourcefile_def: PhoronSourceFileDef {
+                source_file: "samples/AnnotationDemo.pho".to_string(),
+            },
+            class_or_interface_def: Class(PhoronClassDef {
+                name: "AnnotationDemo".to_string(),
+                access_flags: vec![
+                    PhoronClassOrInterfaceAccessFlag::AccSuper,
+                    PhoronClassOrInterfaceAccessFlag::AccPublic,
+                ],
+            }),
+            super_def: PhoronSuperDef {
+                super_class_name: "java/lang/Object".to_string(),
+            },
+            implements_defs: vec![],
+            class_directives: vec![
+                PhoronClassDirective::Annotation(annotation_def(
+                    PhoronAnnotationVisibility::Visible,
+                    "java/lang/Deprecated",
+                    vec![
+                        pair("since", PhoronElementValue::QuotedString("1.0".to_string())),
+                        pair("forRemoval", PhoronElementValue::Boolean(true)),
+                    ],
+                )),
+                PhoronClassDirective::Annotation(annotation_def(
+                    PhoronAnnotationVisibility::Visible,
+                    "java/lang/annotation/Target",
+                    vec![pair(
+                        "value",
+                        PhoronElementValue::Array(vec![
+                            enum_value("java/lang/annotation/ElementType", "TYPE"),
+                            enum_value("java/lang/annotation/ElementType", "METHOD"),
+                        ]),
+                    )],
+                )),
+                PhoronClassDirective::Annotation(annotation_def(
+                    PhoronAnnotationVisibility::Invisible,
+                    "com/example/Audited",
+                    vec![
+                        pair(
+                            "reviewers",
+                            PhoronElementValue::Array(vec![
+                                PhoronElementValue::QuotedString("alice".to_string()),
+                                PhoronElementValue::QuotedString("bob".to_string()),
+                            ]),
+                        ),
+                        pair("level", PhoronElementValue::Integer(3)),
+                    ],
+                )),
+            ],
+        },
+        body: PhoronBody {
+            field_defs: vec![PhoronFieldDef {
+                name: "answer".to_string(),
+                access_flags: vec![
+                    PhoronFieldAccessFlag::AccPublic,
+                    PhoronFieldAccessFlag::AccStatic,
+                ],
+                field_descriptor: BaseType(Integer),
+                signature: None,
+                init_val: None,
+                annotations: vec![annotation_def(
+                    PhoronAnnotationVisibility::Visible,
+                    "java/lang/Deprecated",
+                    vec![],
+                )],
+                type_annotations: vec![],
+                attributes: vec![],
+                deprecated: false,
+                synthetic: false,
+            }],
+            method_defs: vec![
+                PhoronMethodDef {
+                    name: "<init>".to_string(),
+                    access_flags: vec![PhoronMethodAccessFlag::AccPublic],
+                    method_descriptor: PhoronMethodDescriptor {
+                        param_descriptor: vec![],
+                        return_descriptor: VoidDescriptor,
+                    },
+                    instructions: vec![
+                        JvmInstruction(Aload0),
+                        JvmInstruction(Invokespecial {
+                            class_name: "java/lang/Object".to_string(),
+                            method_name: "<init>".to_string(),
+                            method_descriptor: PhoronMethodDescriptor {
+                                param_descriptor: vec![],
+                                return_descriptor: VoidDescriptor,
+                            },
+                        }),
+                        JvmInstruction(Return),
+                    ],
+                },
+                PhoronMethodDef {
+                    name: "main".to_string(),
+                    access_flags: vec![
+                        PhoronMethodAccessFlag::AccPublic,
+                        PhoronMethodAccessFlag::AccStatic,
+                    ],
+                    method_descriptor: PhoronMethodDescriptor {
+                        param_descriptor: vec![ArrayType {
+                            component_type: Box::new(object_type("java/lang/String")),
+                        }],
+                        return_descriptor: VoidDescriptor,
+                    },
+                    instructions: main_instructions,
+                },
+            ],
+        },
+    };
+
+    let actual_ast = parse("samples/AnnotationDemo.pho")?;
+    assert_eq!(expected_ast, actual_ast);
+
+    Ok(())
+}
+
+#[test]
+fn test_parse_type_annotation_demo() -> Result<(), Box<dyn Error>> {
+    let object_type = |class_name: &str| ObjectType {
+        class_name: class_name.to_string(),
+    };
+
+    let annotation_def = |visibility, class_name: &str| PhoronAnnotationDef {
+        visibility,
+        annotation: PhoronAnnotation {
+            annotation_type: object_type(class_name),
+            element_value_pairs: vec![],
+        },
+    };
+
+    let type_annotation_def = |target, type_path, class_name: &str| PhoronTypeAnnotationDef {
+        target,
+        type_path,
+        annotation_def: annotation_def(PhoronAnnotationVisibility::Invisible, class_name),
+    };
+
+    let non_null = "org/checkerframework/checker/nullness/qual/NonNull";
+
+    let get_out = || {
+        JvmInstruction(Getstatic {
+            class_name: "java/lang/System".to_string(),
+            field_name: "out".to_string(),
+            field_descriptor: object_type("java/io/PrintStream"),
+        })
+    };
+
+    let println = || {
+        JvmInstruction(Invokevirtual {
+            class_name: "java/io/PrintStream".to_string(),
+            method_name: "println".to_string(),
+            method_descriptor: PhoronMethodDescriptor {
+                param_descriptor: vec![object_type("java/lang/String")],
+                return_descriptor: VoidDescriptor,
+            },
+        })
+    };
+
+    let greet_descriptor = || PhoronMethodDescriptor {
+        param_descriptor: vec![object_type("java/lang/Object")],
+        return_descriptor: FieldDescriptor(object_type("java/lang/String")),
+    };
+
+    let init = |class_name: &str| Invokespecial {
+        class_name: class_name.to_string(),
+        method_name: "<init>".to_string(),
+        method_descriptor: PhoronMethodDescriptor {
+            param_descriptor: vec![],
+            return_descriptor: VoidDescriptor,
+        },
+    };
+
+    let greeting_field = |get: bool| {
+        let class_name = "TypeAnnotationDemo".to_string();
+        let field_name = "greeting".to_string();
+        let field_descriptor = object_type("java/lang/String");
+
+        if get {
+            Getfield {
+                class_name,
+                field_name,
+                field_descriptor,
+            }
+        } else {
+            Putfield {
+                class_name,
+                field_name,
+                field_descriptor,
+            }
+        }
+    };
+
+    let expected_ast = PhoronProgram {
+        header: PhoronHeader {
+            version_def: PhoronVersionDef {
+                major_version: 52,
+                minor_version: 0,
+            },
+            sourcefile_def: PhoronSourceFileDef {
+                source_file: "samples/TypeAnnotationDemo.pho".to_string(),
+            },
+            class_or_interface_def: Class(PhoronClassDef {
+                name: "TypeAnnotationDemo".to_string(),
+                access_flags: vec![
+                    PhoronClassOrInterfaceAccessFlag::AccSuper,
+                    PhoronClassOrInterfaceAccessFlag::AccPublic,
+                ],
+            }),
+            super_def: PhoronSuperDef {
+                super_class_name: "java/lang/Object".to_string(),
+            },
+            implements_defs: vec![],
+            class_directives: vec![PhoronClassDirective::TypeAnnotation(type_annotation_def(
+                PhoronTypeAnnotationTarget::Supertype {
+                    supertype_index: 65535,
+                },
+                vec![],
+                non_null,
+            ))],
+        },
+        body: PhoronBody {
+            field_defs: vec![
+                PhoronFieldDef {
+                    name: "greeting".to_string(),
+                    access_flags: vec![PhoronFieldAccessFlag::AccPrivate],
+                    field_descriptor: object_type("java/lang/String"),
+                    signature: None,
+                    init_val: None,
+                    annotations: vec![],
+                    type_annotations: vec![type_annotation_def(
+                        PhoronTypeAnnotationTarget::Field,
+                        vec![],
+                        non_null,
+                    )],
+                    attributes: vec![],
+                    deprecated: false,
+                    synthetic: false,
+                },
+                PhoronFieldDef {
+                    name: "names".to_string(),
+                    access_flags: vec![PhoronFieldAccessFlag::AccPrivate],
+                    field_descriptor: object_type("java/util/List"),
+                    signature: Some("Ljava/util/List<Ljava/lang/String;>;".to_string()),
+                    init_val: None,
+                    annotations: vec![],
+                    type_annotations: vec![type_annotation_def(
+                        PhoronTypeAnnotationTarget::Field,
+                        vec![PhoronTypePathEntry::TypeArgument(0)],
+                        non_null,
+                    )],
+                    attributes: vec![],
+                    deprecated: false,
+                    synthetic: false,
+                },
+            ],
+            method_defs: vec![
+                PhoronMethodDef {
+                    name: "<init>".to_string(),
+                    access_flags: vec![PhoronMethodAccessFlag::AccPublic],
+                    method_descriptor: PhoronMethodDescriptor {
+                        param_descriptor: vec![],
+                        return_descriptor: VoidDescriptor,
+                    },
+                    instructions: vec![
+                        JvmInstruction(Aload0),
+                        JvmInstruction(init("java/lang/Object")),
+                        JvmInstruction(Aload0),
+                        JvmInstruction(Ldc(LdcValue::QuotedString("Hello, ".to_string()))),
+                        JvmInstruction(greeting_field(false)),
+                        JvmInstruction(Return),
+                    ],
+                },
+                PhoronMethodDef {
+                    name: "greet".to_string(),
+                    access_flags: vec![PhoronMethodAccessFlag::AccPublic],
+                    method_descriptor: greet_descriptor(),
+                    instructions: vec![
+                        PhoronDirective(ParameterAnnotation {
+                            parameter_index: 0,
+                            annotation_def: annotation_def(
+                                PhoronAnnotationVisibility::Visible,
+                                "java/lang/Deprecated",
+                            ),
+                        }),
+                        PhoronDirective(TypeAnnotation(type_annotation_def(
+                            PhoronTypeAnnotationTarget::Return,
+                            vec![],
+                            non_null,
+                        ))),
+                        PhoronDirective(TypeAnnotation(type_annotation_def(
+                            PhoronTypeAnnotationTarget::FormalParameter {
+                                formal_parameter_index: 0,
+                            },
+                            vec![],
+                            "org/checkerframework/checker/nullness/qual/Nullable",
+                        ))),
+                        PhoronDirective(TypeAnnotation(type_annotation_def(
+                            PhoronTypeAnnotationTarget::Cast {
+                                label: "Cast".to_string(),
+                                type_argument_index: 0,
+                            },
+                            vec![],
+                            non_null,
+                        ))),
+                        PhoronDirective(TypeAnnotation(type_annotation_def(
+                            PhoronTypeAnnotationTarget::LocalVar {
+                                ranges: vec![PhoronLocalVarRange {
+                                    varnum: 2,
+                                    from_label: "Start".to_string(),
+                                    to_label: "End".to_string(),
+                                }],
+                            },
+                            vec![],
+                            non_null,
+                        ))),
+                        PhoronDirective(Var {
+                            varnum: 2,
+                            name: "name".to_string(),
+                            field_descriptor: object_type("java/lang/String"),
+                            signature: None,
+                            from_label: "Start".to_string(),
+                            to_label: "End".to_string(),
+                        }),
+                        JvmInstruction(Aload1),
+                        PhoronLabel("Cast".to_string()),
+                        JvmInstruction(Checkcast {
+                            cast_type: object_type("java/lang/String"),
+                        }),
+                        JvmInstruction(Astore2),
+                        PhoronLabel("Start".to_string()),
+                        JvmInstruction(Aload0),
+                        JvmInstruction(greeting_field(true)),
+                        JvmInstruction(Aload2),
+                        JvmInstruction(Invokevirtual {
+                            class_name: "java/lang/String".to_string(),
+                            method_name: "concat".to_string(),
+                            method_descriptor: PhoronMethodDescriptor {
+                                param_descriptor: vec![object_type("java/lang/String")],
+                                return_descriptor: FieldDescriptor(object_type("java/lang/String")),
+                            },
+                        }),
+                        PhoronLabel("End".to_string()),
+                        JvmInstruction(Areturn),
+                    ],
+                },
+                PhoronMethodDef {
+                    name: "main".to_string(),
+                    access_flags: vec![
+                        PhoronMethodAccessFlag::AccPublic,
+                        PhoronMethodAccessFlag::AccStatic,
+                    ],
+                    method_descriptor: PhoronMethodDescriptor {
+                        param_descriptor: vec![ArrayType {
+                            component_type: Box::new(object_type("java/lang/String")),
+                        }],
+                        return_descriptor: VoidDescriptor,
+                    },
+                    instructions: vec![
+                        PhoronDirective(Throws {
+                            class_name: "java/lang/NoSuchMethodException".to_string(),
+                        }),
+                        get_out(),
+                        JvmInstruction(New {
+                            class_name: "TypeAnnotationDemo".to_string(),
+                        }),
+                        JvmInstruction(Dup),
+                        JvmInstruction(init("TypeAnnotationDemo")),
+                        JvmInstruction(Ldc(LdcValue::QuotedString("world".to_string()))),
+                        JvmInstruction(Invokevirtual {
+                            class_name: "TypeAnnotationDemo".to_string(),
+                            method_name: "greet".to_string(),
+                            method_descriptor: greet_descriptor(),
+                        }),
+                        println(),
+                        get_out(),
+                        JvmInstruction(Ldc(LdcValue::Class("TypeAnnotationDemo".to_string()))),
+                        JvmInstruction(Ldc(LdcValue::QuotedString("greet".to_string()))),
+                        JvmInstruction(Iconst1),
+                        JvmInstruction(Anewarray {
+                            component_type: object_type("java/lang/Class"),
+                        }),
+                        JvmInstruction(Dup),
+                        JvmInstruction(Iconst0),
+                        JvmInstruction(Ldc(LdcValue::Class("java/lang/Object".to_string()))),
+                        JvmInstruction(Aastore),
+                        JvmInstruction(Invokevirtual {
+                            class_name: "java/lang/Class".to_string(),
+                            method_name: "getMethod".to_string(),
+                            method_descriptor: PhoronMethodDescriptor {
+                                param_descriptor: vec![
+                                    object_type("java/lang/String"),
+                                    ArrayType {
+                                        component_type: Box::new(object_type("java/lang/Class")),
+                                    },
+                                ],
+                                return_descriptor: FieldDescriptor(object_type(
+                                    "java/lang/reflect/Method",
+                                )),
+                            },
+                        }),
+                        JvmInstruction(Invokevirtual {
+                            class_name: "java/lang/reflect/Method".to_string(),
+                            method_name: "getParameterAnnotations".to_string(),
+                            method_descriptor: PhoronMethodDescriptor {
+                                param_descriptor: vec![],
+                                return_descriptor: FieldDescriptor(ArrayType {
+                                    component_type: Box::new(ArrayType {
+                                        component_type: Box::new(object_type(
+                                            "java/lang/annotation/Annotation",
+                                        )),
+                                    }),
+                                }),
+                            },
+                        }),
+                        JvmInstruction(Invokestatic {
+                            class_name: "java/util/Arrays".to_string(),
+                            method_name: "deepToString".to_string(),
+                            method_descriptor: PhoronMethodDescriptor {
+                                param_descriptor: vec![ArrayType {
+                                    component_type: Box::new(object_type("java/lang/Object")),
+                                }],
+                                return_descriptor: FieldDescriptor(object_type("java/lang/String")),
+                            },
+                        }),
+                        println(),
+                        JvmInstruction(Return),
+                    ],
+                },
+            ],
+        },
+    };
+
+    let actual_ast = parse("samples/TypeAnnotationDemo.pho")?;
+    assert_eq!(expected_ast, actual_ast);
+
+    Ok(())
+}
+
+#[test]
+fn test_parse_greeting() -> Result<(), Box<dyn Error>> {
+    let expected_ast = PhoronProgram {
+        header: PhoronHeader {
+            version_def: PhoronVersionDef {
+                major_version: 49,
+                minor_version: 0,
+            },
+            sourcefile_def: PhoronSourceFileDef {
+                source_file: "samples/Greeting.pho".to_string(),
+            },
+            class_or_interface_def: Interface(PhoronInterfaceDef {
+                name: "Greeting".to_string(),
+                access_flags: vec![
+                    PhoronClassOrInterfaceAccessFlag::AccInterface,
+                    PhoronClassOrInterfaceAccessFlag::AccAbstract,
+                    PhoronClassOrInterfaceAccessFlag::AccPublic,
+                    PhoronClassOrInterfaceAccessFlag::AccAnnotation,
+                ],
+            }),
+            super_def: PhoronSuperDef {
+                super_class_name: "java/lang/Object".to_string(),
+            },
+            implements_defs: vec![PhoronImplementsDef {
+                class_name: "java/lang/annotation/Annotation".to_string(),
+            }],
+            class_directives: vec![PhoronClassDirective::Annotation(PhoronAnnotationDef {
+                visibility: PhoronAnnotationVisibility::Visible,
+                annotation: PhoronAnnotation {
+                    annotation_type: ObjectType {
+                        class_name: "java/lang/annotation/Retention".to_string(),
+                    },
+                    element_value_pairs: vec![PhoronElementValuePair {
+                        name: "value".to_string(),
+                        value: PhoronElementValue::Enum {
+                            enum_type: ObjectType {
+                                class_name: "java/lang/annotation/RetentionPolicy".to_string(),
+                            },
+                            const_name: "RUNTIME".to_string(),
+                        },
+                    }],
+                },
+            })],
+        },
+        body: PhoronBody {
+            field_defs: vec![],
+            method_defs: vec![
+                PhoronMethodDef {
+                    name: "message".to_string(),
+                    access_flags: vec![
+                        PhoronMethodAccessFlag::AccPublic,
+                        PhoronMethodAccessFlag::AccAbstract,
+                    ],
+                    method_descriptor: PhoronMethodDescriptor {
+                        param_descriptor: vec![],
+                        return_descriptor: FieldDescriptor(ObjectType {
+                            class_name: "java/lang/String".to_string(),
+                        }),
+                    },
+                    instructions: vec![PhoronDirective(AnnotationDefault(
+                        PhoronElementValue::QuotedString("Hello, world".to_string()),
+                    ))],
+                },
+                PhoronMethodDef {
+                    name: "count".to_string(),
+                    access_flags: vec![
+                        PhoronMethodAccessFlag::AccPublic,
+                        PhoronMethodAccessFlag::AccAbstract,
+                    ],
+                    method_descriptor: PhoronMethodDescriptor {
+                        param_descriptor: vec![],
+                        return_descriptor: FieldDescriptor(BaseType(Integer)),
+                    },
+                    instructions: vec![PhoronDirective(AnnotationDefault(
+                        PhoronElementValue::Integer(1),
+                    ))],
+                },
+            ],
+        },
+    };
+
+    let actual_ast = parse("samples/Greeting.pho")?;
+    assert_eq!(expected_ast, actual_ast);
+
+    Ok(())
+}
+
+#[test]
+fn test_parse_annotation_names() -> Result<(), Box<dyn Error>> {
+    let print_stream = || ObjectType {
+        class_name: "java/io/PrintStream".to_string(),
+    };
+
+    let expected_ast = PhoronProgram {
+        header: PhoronHeader {
+            version_def: PhoronVersionDef::default(),
+            sourcefile_def: PhoronSourceFileDef {
+                source_file: "samples/AnnotationNames.pho".to_string(),
+            },
+            class_or_interface_def: Class(PhoronClassDef {
+                name: "AnnotationNames".to_string(),
+                access_flags: vec![
+                    PhoronClassOrInterfaceAccessFlag::AccSuper,
+                    PhoronClassOrInterfaceAccessFlag::AccPublic,
+                ],
+            }),
+            super_def: PhoronSuperDef {
+                super_class_name: "java/lang/Object".to_string(),
+            },
+            implements_defs: vec![],
+            class_directives: vec![],
+        },
+        body: PhoronBody {
+            field_defs: vec![PhoronFieldDef {
+                name: "annotation".to_string(),
+                access_flags: vec![
+                    PhoronFieldAccessFlag::AccPublic,
+                    PhoronFieldAccessFlag::AccStatic,
+                ],
+                field_descriptor: BaseType(Integer),
+                signature: None,
+                init_val: None,
+                annotations: vec![],
+                type_annotations: vec![],
+                attributes: vec![],
+                deprecated: false,
+                synthetic: false,
+            }],
+            method_defs: vec![
+                PhoronMethodDef {
+                    name: "annotation".to_string(),
+                    access_flags: vec![
+                        PhoronMethodAccessFlag::AccPublic,
+                        PhoronMethodAccessFlag::AccStatic,
+                    ],
+                    method_descriptor: PhoronMethodDescriptor {
+                        param_descriptor: vec![],
+                        return_descriptor: VoidDescriptor,
+                    },
+                    instructions: vec![
+                        PhoronDirective(LimitStack(2)),
+                        JvmInstruction(Getstatic {
+                            class_name: "java/lang/System".to_string(),
+                            field_name: "out".to_string(),
+                            field_descriptor: print_stream(),
+                        }),
+                        JvmInstruction(Getstatic {
+                            class_name: "AnnotationNames".to_string(),
+                            field_name: "annotation".to_string(),
+                            field_descriptor: BaseType(Integer),
+                        }),
+                        JvmInstruction(Invokevirtual {
+                            class_name: "java/io/PrintStream".to_string(),
+                            method_name: "println".to_string(),
+                            method_descriptor: PhoronMethodDescriptor {
+                                param_descriptor: vec![BaseType(Integer)],
+                                return_descriptor: VoidDescriptor,
+                            },
+                        }),
+                        JvmInstruction(Return),
+                    ],
+                },
+                PhoronMethodDef {
+                    name: "main".to_string(),
+                    access_flags: vec![
+                        PhoronMethodAccessFlag::AccPublic,
+                        PhoronMethodAccessFlag::AccStatic,
+                    ],
+                    method_descriptor: PhoronMethodDescriptor {
+                        param_descriptor: vec![ArrayType {
+                            component_type: Box::new(ObjectType {
+                                class_name: "java/lang/String".to_string(),
+                            }),
+                        }],
+                        return_descriptor: VoidDescriptor,
+                    },
+                    instructions: vec![
+                        PhoronDirective(LimitStack(1)),
+                        JvmInstruction(Bipush(42)),
+                        JvmInstruction(Putstatic {
+                            class_name: "AnnotationNames".to_string(),
+                            field_name: "annotation".to_string(),
+                            field_descriptor: BaseType(Integer),
+                        }),
+                        JvmInstruction(Invokestatic {
+                            class_name: "AnnotationNames".to_string(),
+                            method_name: "annotation".to_string(),
+                            method_descriptor: PhoronMethodDescriptor {
+                                param_descriptor: vec![],
+                                return_descriptor: VoidDescriptor,
+                            },
+                        }),
+                        JvmInstruction(Return),
+                    ],
+                },
+            ],
+        },
+    };
+
+    let actual_ast = parse("samples/AnnotationNames.pho")?;
+    assert_eq!(expected_ast, actual_ast);
+
+    Ok(())
+}
+
+#[test]
+fn test_parse_default_outside_annotation_interface() -> Result<(), Box<dyn Error>> {
+    assert!(!parse_errored("samples/Greeting.pho")?);
+    assert!(parse_errored("samples/invalid/DefaultInClass.pho")?);
+
+    Ok(())
+}
+
+#[test]
+fn test_parse_default_with_parameters() -> Result<(), Box<dyn Error>> {
+    assert!(parse_errored("samples/invalid/DefaultWithParameters.pho")?);
+
+    Ok(())
+}
+
+fn nested_class_init() -> PhoronMethodDef {
+    PhoronMethodDef {
+        name: "<init>".to_string(),
+        access_flags: vec![],
+        method_descriptor: PhoronMethodDescriptor {
+            param_descriptor: vec![],
+            return_descriptor: VoidDescriptor,
+        },
+        instructions: vec![
+            JvmInstruction(Aload0),
+            JvmInstruction(Invokespecial {
+                class_name: "java/lang/Object".to_string(),
+                method_name: "<init>".to_string(),
+                method_descriptor: PhoronMethodDescriptor {
+                    param_descriptor: vec![],
+                    return_descriptor: VoidDescriptor,
+                },
+            }),
+            JvmInstruction(Return),
+        ],
+    }
+}
+
+#[test]
+fn test_parse_nested_member_class() -> Result<(), Box<dyn Error>> {
+    let mut init = nested_class_init();
+    init.access_flags = vec![PhoronMethodAccessFlag::AccPublic];
+
+    let expected_ast = PhoronProgram {
+        header: PhoronHeader {
+            version_def: PhoronVersionDef {
+                major_version: 49,
+                minor_version: 0,
+            },
+            sourcefile_def: PhoronSourceFileDef {
+                source_file: "samples/nested/Outer$Member.pho".to_string(),
+            },
+            class_or_interface_def: Class(PhoronClassDef {
+                name: "Outer$Member".to_string(),
+                access_flags: vec![
+                    PhoronClassOrInterfaceAccessFlag::AccSuper,
+                    PhoronClassOrInterfaceAccessFlag::AccPublic,
+                ],
+            }),
+            super_def: PhoronSuperDef {
+                super_class_name: "java/lang/Object".to_string(),
+            },
+            implements_defs: vec![],
+            class_directives: vec![PhoronClassDirective::InnerClass(PhoronInnerClassDef {
+                inner_class_name: "Outer$Member".to_string(),
+                outer_class_name: Some("Outer".to_string()),
+                inner_name: Some("Member".to_string()),
+                access_flags: vec![
+                    PhoronInnerClassAccessFlag::AccPublic,
+                    PhoronInnerClassAccessFlag::AccStatic,
+                ],
+            })],
+        },
+        body: PhoronBody {
+            field_defs: vec![],
+            method_defs: vec![init],
+        },
+    };
+
+    let actual_ast = parse("samples/nested/Outer$Member.pho")?;
+    assert_eq!(expected_ast, actual_ast);
+
+    Ok(())
+}
+
+#[test]
+fn test_parse_nested_anonymous_class() -> Result<(), Box<dyn Error>> {
+    let expected_ast = PhoronProgram {
+        header: PhoronHeader {
+            version_def: PhoronVersionDef {
+                major_version: 49,
+                minor_version: 0,
+            },
+            sourcefile_def: PhoronSourceFileDef {
+                source_file: "samples/nested/Outer$1.pho".to_string(),
+            },
+            class_or_interface_def: Class(PhoronClassDef {
+                name: "Outer$1".to_string(),
+                access_flags: vec![
+                    PhoronClassOrInterfaceAccessFlag::AccSuper,
+                    PhoronClassOrInterfaceAccessFlag::AccFinal,
+                ],
+            }),
+            super_def: PhoronSuperDef {
+                super_class_name: "java/lang/Object".to_string(),
+            },
+            implements_defs: vec![],
+            class_directives: vec![
+                PhoronClassDirective::InnerClass(PhoronInnerClassDef {
+                    inner_class_name: "Outer$1".to_string(),
+                    outer_class_name: None,
+                    inner_name: None,
+                    access_flags: vec![],
+                }),
+                PhoronClassDirective::EnclosingMethod(PhoronEnclosingMethodDef {
+                    class_name: "Outer".to_string(),
+                    method: Some((
+                        "main".to_string(),
+                        PhoronMethodDescriptor {
+                            param_descriptor: vec![ArrayType {
+                                component_type: Box::new(ObjectType {
+                                    class_name: "java/lang/String".to_string(),
+                                }),
+                            }],
+                            return_descriptor: VoidDescriptor,
+                        },
+                    )),
+                }),
+            ],
+        },
+        body: PhoronBody {
+            field_defs: vec![],
+            method_defs: vec![nested_class_init()],
+        },
+    };
+
+    let actual_ast = parse("samples/nested/Outer$1.pho")?;
+    assert_eq!(expected_ast, actual_ast);
+
+    Ok(())
+}
+
+#[test]
+fn test_parse_nest_host() -> Result<(), Box<dyn Error>> {
+    let string_type = || ObjectType {
+        class_name: "java/lang/String".to_string(),
+    };
+
+    let expected_ast = PhoronProgram {
+        header: PhoronHeader {
+            version_def: PhoronVersionDef {
+                major_version: 55,
+                minor_version: 0,
+            },
+            sourcefile_def: PhoronSourceFileDef {
+                source_file: "samples/nest/Host.pho".to_string(),
+            },
+            class_or_interface_def: Class(PhoronClassDef {
+                name: "Host".to_string(),
+                access_flags: vec![
+                    PhoronClassOrInterfaceAccessFlag::AccSuper,
+                    PhoronClassOrInterfaceAccessFlag::AccPublic,
+                ],
+            }),
+            super_def: PhoronSuperDef {
+                super_class_name: "java/lang/Object".to_string(),
+            },
+            implements_defs: vec![],
+            class_directives: vec![PhoronClassDirective::NestMember("Host$Helper".to_string())],
+        },
+        body: PhoronBody {
+            field_defs: vec![],
+            method_defs: vec![
+                PhoronMethodDef {
+                    name: "<init>".to_string(),
+                    access_flags: vec![PhoronMethodAccessFlag::AccPublic],
+                    method_descriptor: PhoronMethodDescriptor {
+                        param_descriptor: vec![],
+                        return_descriptor: VoidDescriptor,
+                    },
+                    instructions: vec![
+                        JvmInstruction(Aload0),
+                        JvmInstruction(Invokespecial {
+                            class_name: "java/lang/Object".to_string(),
+                            method_name: "<init>".to_string(),
+                            method_descriptor: PhoronMethodDescriptor {
+                                param_descriptor: vec![],
+                                return_descriptor: VoidDescriptor,
+                            },
+                        }),
+                        JvmInstruction(Return),
+                    ],
+                },
+                PhoronMethodDef {
+                    name: "secret".to_string(),
+                    access_flags: vec![
+                        PhoronMethodAccessFlag::AccPrivate,
+                        PhoronMethodAccessFlag::AccStatic,
+                    ],
+                    method_descriptor: PhoronMethodDescriptor {
+                        param_descriptor: vec![],
+                        return_descriptor: FieldDescriptor(string_type()),
+                    },
+                    instructions: vec![
+                        JvmInstruction(Ldc(LdcValue::QuotedString(
+                            "Hello from a private method".to_string(),
+                        ))),
+                        JvmInstruction(Areturn),
+                    ],
+                },
+                PhoronMethodDef {
+                    name: "main".to_string(),
+                    access_flags: vec![
+                        PhoronMethodAccessFlag::AccPublic,
+                        PhoronMethodAccessFlag::AccStatic,
+                    ],
+                    method_descriptor: PhoronMethodDescriptor {
+                        param_descriptor: vec![ArrayType {
+                            component_type: Box::new(string_type()),
+                        }],
+                        return_descriptor: VoidDescriptor,
+                    },
+                    instructions: vec![
+                        JvmInstruction(Getstatic {
+                            class_name: "java/lang/System".to_string(),
+                            field_name: "out".to_string(),
+                            field_descriptor: ObjectType {
+                                class_name: "java/io/PrintStream".to_string(),
+                            },
+                        }),
+                        JvmInstruction(Invokestatic {
+                            class_name: "Host$Helper".to_string(),
+                            method_name: "reveal".to_string(),
+                            method_descriptor: PhoronMethodDescriptor {
+                                param_descriptor: vec![],
+                                return_descriptor: FieldDescriptor(string_type()),
+                            },
+                        }),
+                        JvmInstruction(Invokevirtual {
+                            class_name: "java/io/PrintStream".to_string(),
+                            method_name: "println".to_string(),
+                            method_descriptor: PhoronMethodDescriptor {
+                                param_descriptor: vec![string_type()],
+                                return_descriptor: VoidDescriptor,
+                            },
+                        }),
+                        JvmInstruction(Return),
+                    ],
+                },
+            ],
+        },
+    };
+
+    let actual_ast = parse("samples/nest/Host.pho")?;
+    assert_eq!(expected_ast, actual_ast);
+
+    Ok(())
+}
+
+#[test]
+fn test_parse_record_demo() -> Result<(), Box<dyn Error>> {
+    let object_type = |class_name: &str| ObjectType {
+        class_name: class_name.to_string(),
+    };
+
+    let field = |get: bool, field_name: &str, field_descriptor| {
+        let class_name = "RecordDemo".to_string();
+        let field_name = field_name.to_string();
+
+        if get {
+            Getfield {
+                class_name,
+                field_name,
+                field_descriptor,
+            }
+        } else {
+            Putfield {
+                class_name,
+                field_name,
+                field_descriptor,
+            }
+        }
+    };
+
+    let method_descriptor = |param_descriptor, return_descriptor| PhoronMethodDescriptor {
+        param_descriptor,
+        return_descriptor,
+    };
+
+    let method = |name: &str, access_flags, method_descriptor, instructions| PhoronMethodDef {
+        name: name.to_string(),
+        access_flags,
+        method_descriptor,
+        instructions,
+    };
+
+    let object_method = |name: &str, params: Vec<PhoronFieldDescriptor>, return_descriptor| {
+        let mut bootstrap_params = vec![object_type("RecordDemo")];
+        bootstrap_params.extend(params.iter().map(|_| object_type("java/lang/Object")));
+
+        let mut instructions = vec![JvmInstruction(Aload0)];
+        if !params.is_empty() {
+            instructions.push(JvmInstruction(Aload1));
+        }
+        instructions.push(JvmInstruction(Invokedynamic {
+            method_name: name.to_string(),
+            method_descriptor: method_descriptor(bootstrap_params, return_descriptor),
+            bootstrap_method: "objectMethods".to_string(),
+        }));
+
+        (
+            method_descriptor(
+                params,
+                match name {
+                    "toString" => FieldDescriptor(object_type("java/lang/String")),
+                    "hashCode" => FieldDescriptor(BaseType(Integer)),
+                    _ => FieldDescriptor(BaseType(Boolean)),
+                },
+            ),
+            instructions,
+        )
+    };
+
+    let public_final = || {
+        vec![
+            PhoronMethodAccessFlag::AccPublic,
+            PhoronMethodAccessFlag::AccFinal,
+        ]
+    };
+
+    let private_final = || {
+        vec![
+            PhoronFieldAccessFlag::AccPrivate,
+            PhoronFieldAccessFlag::AccFinal,
+        ]
+    };
+
+    let (to_string_descriptor, mut to_string_instructions) = object_method(
+        "toString",
+        vec![],
+        FieldDescriptor(object_type("java/lang/String")),
+    );
+    to_string_instructions.push(JvmInstruction(Areturn));
+
+    let (hash_code_descriptor, mut hash_code_instructions) =
+        object_method("hashCode", vec![], FieldDescriptor(BaseType(Integer)));
+    hash_code_instructions.push(JvmInstruction(Ireturn));
+
+    let (equals_descriptor, mut equals_instructions) = object_method(
+        "equals",
+        vec![object_type("java/lang/Object")],
+        FieldDescriptor(BaseType(Boolean)),
+    );
+    equals_instructions.push(JvmInstruction(Ireturn));
+
+    let get_out = || {
+        JvmInstruction(Getstatic {
+            class_name: "java/lang/System".to_string(),
+            field_name: "out".to_string(),
+            field_descriptor: object_type("java/io/PrintStream"),
+        })
+    };
+
+    let expected_ast = PhoronProgram {
+        header: PhoronHeader {
+            version_def: PhoronVersionDef {
+                major_version: 61,
+                minor_version: 0,
+            },
+            sourcefile_def: PhoronSourceFileDef {
+                source_file: "samples/RecordDemo.pho".to_string(),
+            },
+            class_or_interface_def: Class(PhoronClassDef {
+                name: "RecordDemo".to_string(),
+                access_flags: vec![
+                    PhoronClassOrInterfaceAccessFlag::AccSuper,
+                    PhoronClassOrInterfaceAccessFlag::AccPublic,
+                    PhoronClassOrInterfaceAccessFlag::AccFinal,
+                ],
+            }),
+            super_def: PhoronSuperDef {
+                super_class_name: "java/lang/Record".to_string(),
+            },
+            implements_defs: vec![],
+            class_directives: vec![
+                PhoronClassDirective::RecordComponent(PhoronRecordComponentDef {
+                    name: "x".to_string(),
+                    field_descriptor: BaseType(Integer),
+                    signature: None,
+                    annotations: vec![],
+                    type_annotations: vec![],
+                }),
+                PhoronClassDirective::RecordComponent(PhoronRecordComponentDef {
+                    name: "name".to_string(),
+                    field_descriptor: object_type("java/lang/String"),
+                    signature: None,
+                    annotations: vec![PhoronAnnotationDef {
+                        visibility: PhoronAnnotationVisibility::Visible,
+                        annotation: PhoronAnnotation {
+                            annotation_type: object_type("java/lang/Deprecated"),
+                            element_value_pairs: vec![],
+                        },
+                    }],
+                    type_annotations: vec![],
+                }),
+                PhoronClassDirective::Bootstrap {
+                    name: "objectMethods".to_string(),
+                    method_handle: PhoronMethodHandle::Invokestatic {
+                        class_name: "java/lang/runtime/ObjectMethods".to_string(),
+                        method_name: "bootstrap".to_string(),
+                        method_descriptor: method_descriptor(
+                            vec![
+                                object_type("java/lang/invoke/MethodHandles$Lookup"),
+                                object_type("java/lang/String"),
+                                object_type("java/lang/invoke/TypeDescriptor"),
+                                object_type("java/lang/Class"),
+                                object_type("java/lang/String"),
+                                ArrayType {
+                                    component_type: Box::new(object_type(
+                                        "java/lang/invoke/MethodHandle",
+                                    )),
+                                },
+                            ],
+                            FieldDescriptor(object_type("java/lang/Object")),
+                        ),
+                        is_interface: false,
+                    },
+                    arguments: vec![
+                        BootstrapArgument::Class("RecordDemo".to_string()),
+                        BootstrapArgument::QuotedString("x;name".to_string()),
+                        BootstrapArgument::MethodHandle(PhoronMethodHandle::Getfield {
+                            class_name: "RecordDemo".to_string(),
+                            field_name: "x".to_string(),
+                            field_descriptor: BaseType(Integer),
+                        }),
+                        BootstrapArgument::MethodHandle(PhoronMethodHandle::Getfield {
+                            class_name: "RecordDemo".to_string(),
+                            field_name: "name".to_string(),
+                            field_descriptor: object_type("java/lang/String"),
+                        }),
+                    ],
+                },
+            ],
+        },
+        body: PhoronBody {
+            field_defs: vec![
+                PhoronFieldDef {
+                    name: "x".to_string(),
+                    access_flags: private_final(),
+                    field_descriptor: BaseType(Integer),
+                    signature: None,
+                    init_val: None,
+                    annotations: vec![],
+                    type_annotations: vec![],
+                    attributes: vec![],
+                    deprecated: false,
+                    synthetic: false,
+                },
+                PhoronFieldDef {
+                    name: "name".to_string(),
+                    access_flags: private_final(),
+                    field_descriptor: object_type("java/lang/String"),
+                    signature: None,
+                    init_val: None,
+                    annotations: vec![],
+                    type_annotations: vec![],
+                    attributes: vec![],
+                    deprecated: false,
+                    synthetic: false,
+                },
+            ],
+            method_defs: vec![
+                method(
+                    "<init>",
+                    vec![PhoronMethodAccessFlag::AccPublic],
+                    method_descriptor(
+                        vec![BaseType(Integer), object_type("java/lang/String")],
+                        VoidDescriptor,
+                    ),
+                    vec![
+                        JvmInstruction(Aload0),
+                        JvmInstruction(Invokespecial {
+                            class_name: "java/lang/Record".to_string(),
+                            method_name: "<init>".to_string(),
+                            method_descriptor: method_descriptor(vec![], VoidDescriptor),
+                        }),
+                        JvmInstruction(Aload0),
+                        JvmInstruction(Iload1),
+                        JvmInstruction(field(false, "x", BaseType(Integer))),
+                        JvmInstruction(Aload0),
+                        JvmInstruction(Aload2),
+                        JvmInstruction(field(false, "name", object_type("java/lang/String"))),
+                        JvmInstruction(Return),
+                    ],
+                ),
+                method(
+                    "x",
+                    vec![PhoronMethodAccessFlag::AccPublic],
+                    method_descriptor(vec![], FieldDescriptor(BaseType(Integer))),
+                    vec![
+                        JvmInstruction(Aload0),
+                        JvmInstruction(field(true, "x", BaseType(Integer))),
+                        JvmInstruction(Ireturn),
+                    ],
+                ),
+                method(
+                    "name",
+                    vec![PhoronMethodAccessFlag::AccPublic],
+                    method_descriptor(vec![], FieldDescriptor(object_type("java/lang/String"))),
+                    vec![
+                        JvmInstruction(Aload0),
+                        JvmInstruction(field(true, "name", object_type("java/lang/String"))),
+                        JvmInstruction(Areturn),
+                    ],
+                ),
+                method(
+                    "toString",
+                    public_final(),
+                    to_string_descriptor,
+                    to_string_instructions,
+                ),
+                method(
+                    "hashCode",
+                    public_final(),
+                    hash_code_descriptor,
+                    hash_code_instructions,
+                ),
+                method(
+                    "equals",
+                    public_final(),
+                    equals_descriptor,
+                    equals_instructions,
+                ),
+                method(
+                    "main",
+                    vec![
+                        PhoronMethodAccessFlag::AccPublic,
+                        PhoronMethodAccessFlag::AccStatic,
+                    ],
+                    method_descriptor(
+                        vec![ArrayType {
+                            component_type: Box::new(object_type("java/lang/String")),
+                        }],
+                        VoidDescriptor,
+                    ),
+                    vec![
+                        get_out(),
+                        JvmInstruction(New {
+                            class_name: "RecordDemo".to_string(),
+                        }),
+                        JvmInstruction(Dup),
+                        JvmInstruction(Iconst3),
+                        JvmInstruction(Ldc(LdcValue::QuotedString("origin".to_string()))),
+                        JvmInstruction(Invokespecial {
+                            class_name: "RecordDemo".to_string(),
+                            method_name: "<init>".to_string(),
+                            method_descriptor: method_descriptor(
+                                vec![BaseType(Integer), object_type("java/lang/String")],
+                                VoidDescriptor,
+                            ),
+                        }),
+                        JvmInstruction(Invokevirtual {
+                            class_name: "java/io/PrintStream".to_string(),
+                            method_name: "println".to_string(),
+                            method_descriptor: method_descriptor(
+                                vec![object_type("java/lang/Object")],
+                                VoidDescriptor,
+                            ),
+                        }),
+                        get_out(),
+                        JvmInstruction(Ldc(LdcValue::Class("RecordDemo".to_string()))),
+                        JvmInstruction(Invokevirtual {
+                            class_name: "java/lang/Class".to_string(),
+                            method_name: "getRecordComponents".to_string(),
+                            method_descriptor: method_descriptor(
+                                vec![],
+                                FieldDescriptor(ArrayType {
+                                    component_type: Box::new(object_type(
+                                        "java/lang/reflect/RecordComponent",
+                                    )),
+                                }),
+                            ),
+                        }),
+                        JvmInstruction(Invokestatic {
+                            class_name: "java/util/Arrays".to_string(),
+                            method_name: "toString".to_string(),
+                            method_descriptor: method_descriptor(
+                                vec![ArrayType {
+                                    component_type: Box::new(object_type("java/lang/Object")),
+                                }],
+                                FieldDescriptor(object_type("java/lang/String")),
+                            ),
+                        }),
+                        JvmInstruction(Invokevirtual {
+                            class_name: "java/io/PrintStream".to_string(),
+                            method_name: "println".to_string(),
+                            method_descriptor: method_descriptor(
+                                vec![object_type("java/lang/String")],
+                                VoidDescriptor,
+                            ),
+                        }),
+                        JvmInstruction(Return),
+                    ],
+                ),
+            ],
+        },
+    };
+
+    let actual_ast = parse("samples/RecordDemo.pho")?;
+    assert_eq!(expected_ast, actual_ast);
+
+    Ok(())
+}
+
+#[test]
+fn test_parse_sealed_class() -> Result<(), Box<dyn Error>> {
+    let object_type = |class_name: &str| ObjectType {
+        class_name: class_name.to_string(),
+    };
+
+    let class_method = |method_name: &str, return_descriptor| {
+        JvmInstruction(Invokevirtual {
+            class_name: "java/lang/Class".to_string(),
+            method_name: method_name.to_string(),
+            method_descriptor: PhoronMethodDescriptor {
+                param_descriptor: vec![],
+                return_descriptor,
+            },
+        })
+    };
+
+    let getstatic_out = || {
+        JvmInstruction(Getstatic {
+            class_name: "java/lang/System".to_string(),
+            field_name: "out".to_string(),
+            field_descriptor: object_type("java/io/PrintStream"),
+        })
+    };
+
+    let println = |param_descriptor| {
+        JvmInstruction(Invokevirtual {
+            class_name: "java/io/PrintStream".to_string(),
+            method_name: "println".to_string(),
+            method_descriptor: PhoronMethodDescriptor {
+                param_descriptor: vec![param_descriptor],
+                return_descriptor: VoidDescriptor,
+            },
+        })
+    };
+
+    let print_subclass_name = |index| {
+        vec![
+            getstatic_out(),
+            JvmInstruction(Aload0),
+            JvmInstruction(index),
+            JvmInstruction(Aaload),
+            class_method("getName", FieldDescriptor(object_type("java/lang/String"))),
+            println(object_type("java/lang/String")),
+        ]
+    };
+
+    let mut init = nested_class_init();
+    init.access_flags = vec![PhoronMethodAccessFlag::AccPublic];
+
+    let mut instructions = vec![
+        PhoronDirective(LimitStack(3)),
+        PhoronDirective(LimitLocals(1)),
+        JvmInstruction(Ldc(LdcValue::Class("Shape".to_string()))),
+        class_method(
+            "getPermittedSubclasses",
+            FieldDescriptor(ArrayType {
+                component_type: Box::new(object_type("java/lang/Class")),
+            }),
+        ),
+        JvmInstruction(Astore0),
+        getstatic_out(),
+        JvmInstruction(Ldc(LdcValue::Class("Shape".to_string()))),
+        class_method("isSealed", FieldDescriptor(BaseType(Boolean))),
+        println(BaseType(Boolean)),
+    ];
+    instructions.extend(print_subclass_name(Iconst0));
+    instructions.extend(print_subclass_name(Iconst1));
+    instructions.push(JvmInstruction(Return));
+
+    let expected_ast = PhoronProgram {
+        header: PhoronHeader {
+            version_def: PhoronVersionDef {
+                major_version: 61,
+                minor_version: 0,
+            },
+            sourcefile_def: PhoronSourceFileDef {
+                source_file: "samples/sealed/Shape.pho".to_string(),
+            },
+            class_or_interface_def: Class(PhoronClassDef {
+                name: "Shape".to_string(),
+                access_flags: vec![
+                    PhoronClassOrInterfaceAccessFlag::AccSuper,
+                    PhoronClassOrInterfaceAccessFlag::AccPublic,
+                    PhoronClassOrInterfaceAccessFlag::AccAbstract,
+                ],
+            }),
+            super_def: PhoronSuperDef {
+                super_class_name: "java/lang/Object".to_string(),
+            },
+            implements_defs: vec![],
+            class_directives: vec![
+                PhoronClassDirective::Permits("Circle".to_string()),
+                PhoronClassDirective::Permits("Square".to_string()),
+            ],
+        },
+        body: PhoronBody {
+            field_defs: vec![],
+            method_defs: vec![
+                init,
+                PhoronMethodDef {
+                    name: "main".to_string(),
+                    access_flags: vec![
+                        PhoronMethodAccessFlag::AccPublic,
+                        PhoronMethodAccessFlag::AccStatic,
+                    ],
+                    method_descriptor: PhoronMethodDescriptor {
+                        param_descriptor: vec![ArrayType {
+                            component_type: Box::new(object_type("java/lang/String")),
+                        }],
+                        return_descriptor: VoidDescriptor,
+                    },
+                    instructions,
+                },
+            ],
+        },
+    };
+
+    let actual_ast = parse("samples/sealed/Shape.pho")?;
+    assert_eq!(expected_ast, actual_ast);
+
+    Ok(())
+}
+
+#[test]
+fn test_parse_module_declaration() -> Result<(), Box<dyn Error>> {
+    let expected_ast = PhoronProgram {
+        header: PhoronHeader {
+            version_def: PhoronVersionDef {
+                major_version: 61,
+                minor_version: 0,
+            },
+            sourcefile_def: PhoronSourceFileDef {
+                source_file: "samples/module/module-info.pho".to_string(),
+            },
+            class_or_interface_def: Module(PhoronModuleDef {
+                name: "com.example.app".to_string(),
+                access_flags: vec![],
+                version: Some("1.0".to_string()),
+                requires: vec![PhoronRequiresDef {
+                    module_name: "java.base".to_string(),
+                    access_flags: vec![PhoronRequiresAccessFlag::AccMandated],
+                    version: None,
+                }],
+                exports: vec![PhoronExportsDef {
+                    package_name: "com/example/app".to_string(),
+                    access_flags: vec![],
+                    to_modules: vec![],
+                }],
+                opens: vec![PhoronExportsDef {
+                    package_name: "com/example/app".to_string(),
+                    access_flags: vec![],
+                    to_modules: vec!["java.base".to_string()],
+                }],
+                uses: vec!["java/lang/Runnable".to_string()],
+                provides: vec![PhoronProvidesDef {
+                    service_name: "java/lang/Runnable".to_string(),
+                    with_classes: vec!["com/example/app/Greeter".to_string()],
+                }],
+                packages: vec!["com/example/app".to_string()],
+                main_class: Some("com/example/app/Main".to_string()),
+            }),
+            super_def: PhoronSuperDef::default(),
+            implements_defs: vec![],
+            class_directives: vec![],
+        },
+        body: PhoronBody {
+            field_defs: vec![],
+            method_defs: vec![],
+        },
+    };
+
+    let actual_ast = parse("samples/module/module-info.pho")?;
+    assert_eq!(expected_ast, actual_ast);
+
+    Ok(())
+}
+
+#[test]
+fn test_parse_parameter_demo() -> Result<(), Box<dyn Error>> {
+    let object_type = |class_name: &str| ObjectType {
+        class_name: class_name.to_string(),
+    };
+
+    let print_parameter = |index| {
+        vec![
+            JvmInstruction(Getstatic {
+                class_name: "java/lang/System".to_string(),
+                field_name: "out".to_string(),
+                field_descriptor: object_type("java/io/PrintStream"),
+            }),
+            JvmInstruction(Aload1),
+            JvmInstruction(index),
+            JvmInstruction(Aaload),
+            JvmInstruction(Invokevirtual {
+                class_name: "java/io/PrintStream".to_string(),
+                method_name: "println".to_string(),
+                method_descriptor: PhoronMethodDescriptor {
+                    param_descriptor: vec![object_type("java/lang/Object")],
+                    return_descriptor: VoidDescriptor,
+                },
+            }),
+        ]
+    };
+
+    let mut main_instructions = vec![
+        PhoronDirective(LimitStack(6)),
+        PhoronDirective(LimitLocals(2)),
+        JvmInstruction(Ldc(LdcValue::Class("ParameterDemo".to_string()))),
+        JvmInstruction(Ldc(LdcValue::QuotedString("greet".to_string()))),
+        JvmInstruction(Iconst2),
+        JvmInstruction(Anewarray {
+            component_type: object_type("java/lang/Class"),
+        }),
+        JvmInstruction(Dup),
+        JvmInstruction(Iconst0),
+        JvmInstruction(Ldc(LdcValue::Class("java/lang/String".to_string()))),
+        JvmInstruction(Aastore),
+        JvmInstruction(Dup),
+        JvmInstruction(Iconst1),
+        JvmInstruction(Getstatic {
+            class_name: "java/lang/Integer".to_string(),
+            field_name: "TYPE".to_string(),
+            field_descriptor: object_type("java/lang/Class"),
+        }),
+        JvmInstruction(Aastore),
+        JvmInstruction(Invokevirtual {
+            class_name: "java/lang/Class".to_string(),
+            method_name: "getDeclaredMethod".to_string(),
+            method_descriptor: PhoronMethodDescriptor {
+                param_descriptor: vec![
+                    object_type("java/lang/String"),
+                    ArrayType {
+                        component_type: Box::new(object_type("java/lang/Class")),
+                    },
+                ],
+                return_descriptor: FieldDescriptor(object_type("java/lang/reflect/Method")),
+            },
+        }),
+        JvmInstruction(Invokevirtual {
+            class_name: "java/lang/reflect/Method".to_string(),
+            method_name: "getParameters".to_string(),
+            method_descriptor: PhoronMethodDescriptor {
+                param_descriptor: vec![],
+                return_descriptor: FieldDescriptor(ArrayType {
+                    component_type: Box::new(object_type("java/lang/reflect/Parameter")),
+                }),
+            },
+        }),
+        JvmInstruction(Astore1),
+    ];
+    main_instructions.extend(print_parameter(Iconst0));
+    main_instructions.extend(print_parameter(Iconst1));
+    main_instructions.push(JvmInstruction(Return));
+
+    let expected_ast = PhoronProgram {
+        header: PhoronHeader {
+            version_def: PhoronVersionDef {
+                major_version: 52,
+                minor_version: 0,
+            },
+            sourcefile_def: PhoronSourceFileDef {
+                source_file: "samples/ParameterDemo.pho".to_string(),
+            },
+            class_or_interface_def: Class(PhoronClassDef {
+                name: "ParameterDemo".to_string(),
+                access_flags: vec![
+                    PhoronClassOrInterfaceAccessFlag::AccSuper,
+                    PhoronClassOrInterfaceAccessFlag::AccPublic,
+                ],
+            }),
+            super_def: PhoronSuperDef {
+                super_class_name: "java/lang/Object".to_string(),
+            },
+            implements_defs: vec![],
+            class_directives: vec![],
+        },
+        body: PhoronBody {
+            field_defs: vec![],
+            method_defs: vec![
+                PhoronMethodDef {
+                    name: "greet".to_string(),
+                    access_flags: vec![
+                        PhoronMethodAccessFlag::AccPublic,
+                        PhoronMethodAccessFlag::AccStatic,
+                    ],
+                    method_descriptor: PhoronMethodDescriptor {
+                        param_descriptor: vec![object_type("java/lang/String"), BaseType(Integer)],
+                        return_descriptor: VoidDescriptor,
+                    },
+                    instructions: vec![
+                        PhoronDirective(LimitStack(0)),
+                        PhoronDirective(LimitLocals(2)),
+                        PhoronDirective(Parameter {
+                            parameter_index: 0,
+                            name: "name".to_string(),
+                            access_flags: vec![PhoronParameterAccessFlag::AccFinal],
+                        }),
+                        PhoronDirective(Parameter {
+                            parameter_index: 1,
+                            name: "times".to_string(),
+                            access_flags: vec![],
+                        }),
+                        JvmInstruction(Return),
+                    ],
+                },
+                PhoronMethodDef {
+                    name: "main".to_string(),
+                    access_flags: vec![
+                        PhoronMethodAccessFlag::AccPublic,
+                        PhoronMethodAccessFlag::AccStatic,
+                    ],
+                    method_descriptor: PhoronMethodDescriptor {
+                        param_descriptor: vec![ArrayType {
+                            component_type: Box::new(object_type("java/lang/String")),
+                        }],
+                        return_descriptor: VoidDescriptor,
+                    },
+                    instructions: main_instructions,
+                },
+            ],
+        },
+    };
+
+    let actual_ast = parse("samples/ParameterDemo.pho")?;
+    assert_eq!(expected_ast, actual_ast);
+
+    Ok(())
+}
+
+#[test]
+fn test_parse_generic_local_var_demo() -> Result<(), Box<dyn Error>> {
+    let object_type = |class_name: &str| ObjectType {
+        class_name: class_name.to_string(),
+    };
+
+    let expected_ast = PhoronProgram {
+        header: PhoronHeader {
+            version_def: PhoronVersionDef {
+                major_version: 49,
+                minor_version: 0,
+            },
+            sourcefile_def: PhoronSourceFileDef {
+                source_file: "samples/GenericLocalVarDemo.pho".to_string(),
+            },
+            class_or_interface_def: Class(PhoronClassDef {
+                name: "GenericLocalVarDemo".to_string(),
+                access_flags: vec![
+                    PhoronClassOrInterfaceAccessFlag::AccSuper,
+                    PhoronClassOrInterfaceAccessFlag::AccPublic,
+                ],
+            }),
+            super_def: PhoronSuperDef {
+                super_class_name: "java/lang/Object".to_string(),
+            },
+            implements_defs: vec![],
+            class_directives: vec![],
+        },
+        body: PhoronBody {
+            field_defs: vec![],
+            method_defs: vec![
+                PhoronMethodDef {
+                    name: "<init>".to_string(),
+                    access_flags: vec![PhoronMethodAccessFlag::AccPublic],
+                    method_descriptor: PhoronMethodDescriptor {
+                        param_descriptor: vec![],
+                        return_descriptor: VoidDescriptor,
+                    },
+                    instructions: vec![
+                        JvmInstruction(Aload0),
+                        JvmInstruction(Invokespecial {
+                            class_name: "java/lang/Object".to_string(),
+                            method_name: "<init>".to_string(),
+                            method_descriptor: PhoronMethodDescriptor {
+                                param_descriptor: vec![],
+                                return_descriptor: VoidDescriptor,
+                            },
+                        }),
+                        JvmInstruction(Return),
+                    ],
+                },
+                PhoronMethodDef {
+                    name: "main".to_string(),
+                    access_flags: vec![
+                        PhoronMethodAccessFlag::AccPublic,
+                        PhoronMethodAccessFlag::AccStatic,
+                    ],
+                    method_descriptor: PhoronMethodDescriptor {
+                        param_descriptor: vec![ArrayType {
+                            component_type: Box::new(object_type("java/lang/String")),
+                        }],
+                        return_descriptor: VoidDescriptor,
+                    },
+                    instructions: vec![
+                        PhoronDirective(LimitStack(3)),
+                        PhoronDirective(LimitLocals(2)),
+                        PhoronDirective(Var {
+                            varnum: 0,
+                            name: "args".to_string(),
+                            field_descriptor: ArrayType {
+                                component_type: Box::new(object_type("java/lang/String")),
+                            },
+                            signature: None,
+                            from_label: "".to_string(),
+                            to_label: "".to_string(),
+                        }),
+                        PhoronDirective(Var {
+                            varnum: 1,
+                            name: "greetings".to_string(),
+                            field_descriptor: object_type("java/util/ArrayList"),
+                            signature: Some(
+                                "Ljava/util/ArrayList<Ljava/lang/String;>;".to_string(),
+                            ),
+                            from_label: "Start".to_string(),
+                            to_label: "End".to_string(),
+                        }),
+                        JvmInstruction(New {
+                            class_name: "java/util/ArrayList".to_string(),
+                        }),
+                        JvmInstruction(Dup),
+                        JvmInstruction(Invokespecial {
+                            class_name: "java/util/ArrayList".to_string(),
+                            method_name: "<init>".to_string(),
+                            method_descriptor: PhoronMethodDescriptor {
+                                param_descriptor: vec![],
+                                return_descriptor: VoidDescriptor,
+                            },
+                        }),
+                        JvmInstruction(Astore1),
+                        PhoronLabel("Start".to_string()),
+                        JvmInstruction(Aload1),
+                        JvmInstruction(Ldc(LdcValue::QuotedString("Hello, generics!".to_string()))),
+                        JvmInstruction(Invokevirtual {
+                            class_name: "java/util/ArrayList".to_string(),
+                            method_name: "add".to_string(),
+                            method_descriptor: PhoronMethodDescriptor {
+                                param_descriptor: vec![object_type("java/lang/Object")],
+                                return_descriptor: FieldDescriptor(BaseType(Boolean)),
+                            },
+                        }),
+                        JvmInstruction(Pop),
+                        JvmInstruction(Getstatic {
+                            class_name: "java/lang/System".to_string(),
+                            field_name: "out".to_string(),
+                            field_descriptor: object_type("java/io/PrintStream"),
+                        }),
+                        JvmInstruction(Aload1),
+                        JvmInstruction(Invokevirtual {
+                            class_name: "java/io/PrintStream".to_string(),
+                            method_name: "println".to_string(),
+                            method_descriptor: PhoronMethodDescriptor {
+                                param_descriptor: vec![object_type("java/lang/Object")],
+                                return_descriptor: VoidDescriptor,
+                            },
+                        }),
+                        PhoronLabel("End".to_string()),
+                        JvmInstruction(Return),
+                    ],
+                },
+            ],
+        },
+    };
+
+    let actual_ast = parse("samples/GenericLocalVarDemo.pho")?;
+    assert_eq!(expected_ast, actual_ast);
+
+    Ok(())
+}
+
+#[test]
+fn test_parse_source_debug_demo() -> Result<(), Box<dyn Error>> {
+    let object_type = |class_name: &str| ObjectType {
+        class_name: class_name.to_string(),
+    };
+
+    let expected_ast = PhoronProgram {
+        header: PhoronHeader {
+            version_def: PhoronVersionDef {
+                major_version: 49,
+                minor_version: 0,
+            },
+            sourcefile_def: PhoronSourceFileDef {
+                source_file: "SourceDebugDemo.pho".to_string(),
+            },
+            class_or_interface_def: Class(PhoronClassDef {
+                name: "SourceDebugDemo".to_string(),
+                access_flags: vec![
+                    PhoronClassOrInterfaceAccessFlag::AccSuper,
+                    PhoronClassOrInterfaceAccessFlag::AccPublic,
+                ],
+            }),
+            super_def: PhoronSuperDef {
+                super_class_name: "java/lang/Object".to_string(),
+            },
+            implements_defs: vec![],
+            class_directives: vec![
+                PhoronClassDirective::Debug(
+                    "SMAP\nSourceDebugDemo.pho\nKl\n*S Kl\n*F\n1 Greeting.kl\n".to_string(),
+                ),
+                PhoronClassDirective::Debug("*L\n1#1:15\n2#1:16\n*E\n".to_string()),
+            ],
+        },
+        body: PhoronBody {
+            field_defs: vec![],
+            method_defs: vec![
+                PhoronMethodDef {
+                    name: "<init>".to_string(),
+                    access_flags: vec![PhoronMethodAccessFlag::AccPublic],
+                    method_descriptor: PhoronMethodDescriptor {
+                        param_descriptor: vec![],
+                        return_descriptor: VoidDescriptor,
+                    },
+                    instructions: vec![
+                        JvmInstruction(Aload0),
+                        JvmInstruction(Invokespecial {
+                            class_name: "java/lang/Object".to_string(),
+                            method_name: "<init>".to_string(),
+                            method_descriptor: PhoronMethodDescriptor {
+                                param_descriptor: vec![],
+                                return_descriptor: VoidDescriptor,
+                            },
+                        }),
+                        JvmInstruction(Return),
+                    ],
+                },
+                PhoronMethodDef {
+                    name: "main".to_string(),
+                    access_flags: vec![
+                        PhoronMethodAccessFlag::AccPublic,
+                        PhoronMethodAccessFlag::AccStatic,
+                    ],
+                    method_descriptor: PhoronMethodDescriptor {
+                        param_descriptor: vec![ArrayType {
+                            component_type: Box::new(object_type("java/lang/String")),
+                        }],
+                        return_descriptor: VoidDescriptor,
+                    },
+                    instructions: vec![
+                        PhoronDirective(LimitStack(2)),
+                        PhoronDirective(LimitLocals(1)),
+                        PhoronDirective(LineNumber(15)),
+                        JvmInstruction(Getstatic {
+                            class_name: "java/lang/System".to_string(),
+                            field_name: "out".to_string(),
+                            field_descriptor: object_type("java/io/PrintStream"),
+                        }),
+                        JvmInstruction(Ldc(LdcValue::QuotedString("Hello from Kl!".to_string()))),
+                        JvmInstruction(Invokevirtual {
+                            class_name: "java/io/PrintStream".to_string(),
+                            method_name: "println".to_string(),
+                            method_descriptor: PhoronMethodDescriptor {
+                                param_descriptor: vec![object_type("java/lang/String")],
+                                return_descriptor: VoidDescriptor,
+                            },
+                        }),
+                        PhoronDirective(LineNumber(16)),
+                        JvmInstruction(Return),
+                    ],
+                },
+            ],
+        },
+    };
+
+    let actual_ast = parse("samples/SourceDebugDemo.pho")?;
+    assert_eq!(expected_ast, actual_ast);
+
+    Ok(())
+}
+
+#[test]
+fn test_parse_raw_attribute_demo() -> Result<(), Box<dyn Error>> {
+    let object_type = |class_name: &str| ObjectType {
+        class_name: class_name.to_string(),
+    };
+
+    let expected_ast = PhoronProgram {
+        header: PhoronHeader {
+            version_def: PhoronVersionDef {
+                major_version: 45,
+                minor_version: 3,
+            },
+            sourcefile_def: PhoronSourceFileDef {
+                source_file: "samples/RawAttributeDemo.pho".to_string(),
+            },
+            class_or_interface_def: Class(PhoronClassDef {
+                name: "RawAttributeDemo".to_string(),
+                access_flags: vec![
+                    PhoronClassOrInterfaceAccessFlag::AccSuper,
+                    PhoronClassOrInterfaceAccessFlag::AccPublic,
+                ],
+            }),
+            super_def: PhoronSuperDef {
+                super_class_name: "java/lang/Object".to_string(),
+            },
+            implements_defs: vec![],
+            class_directives: vec![PhoronClassDirective::Attribute(PhoronAttributeDef {
+                name: "CompilerInfo".to_string(),
+                info: b"phoron".to_vec(),
+            })],
+        },
+        body: PhoronBody {
+            field_defs: vec![PhoronFieldDef {
+                name: "count".to_string(),
+                access_flags: vec![
+                    PhoronFieldAccessFlag::AccPrivate,
+                    PhoronFieldAccessFlag::AccStatic,
+                ],
+                field_descriptor: BaseType(Integer),
+                signature: None,
+                init_val: None,
+                annotations: vec![],
+                type_annotations: vec![],
+                attributes: vec![PhoronAttributeDef {
+                    name: "FieldInfo".to_string(),
+                    info: vec![0xca, 0xfe, 0xba, 0xbe],
+                }],
+                deprecated: false,
+                synthetic: false,
+            }],
+            method_defs: vec![
+                PhoronMethodDef {
+                    name: "<init>".to_string(),
+                    access_flags: vec![PhoronMethodAccessFlag::AccPublic],
+                    method_descriptor: PhoronMethodDescriptor {
+                        param_descriptor: vec![],
+                        return_descriptor: VoidDescriptor,
+                    },
+                    instructions: vec![
+                        JvmInstruction(Aload0),
+                        JvmInstruction(Invokespecial {
+                            class_name: "java/lang/Object".to_string(),
+                            method_name: "<init>".to_string(),
+                            method_descriptor: PhoronMethodDescriptor {
+                                param_descriptor: vec![],
+                                return_descriptor: VoidDescriptor,
+                            },
+                        }),
+                        JvmInstruction(Return),
+                    ],
+                },
+                PhoronMethodDef {
+                    name: "main".to_string(),
+                    access_flags: vec![
+                        PhoronMethodAccessFlag::AccPublic,
+                        PhoronMethodAccessFlag::AccStatic,
+                    ],
+                    method_descriptor: PhoronMethodDescriptor {
+                        param_descriptor: vec![ArrayType {
+                            component_type: Box::new(object_type("java/lang/String")),
+                        }],
+                        return_descriptor: VoidDescriptor,
+                    },
+                    instructions: vec![
+                        PhoronDirective(LimitStack(2)),
+                        PhoronDirective(LimitLocals(1)),
+                        PhoronDirective(Attribute(PhoronAttributeDef {
+                            name: "MethodInfo".to_string(),
+                            info: vec![0x00, 0x01, 0x02, 0x03],
+                        })),
+                        PhoronDirective(CodeAttribute(PhoronAttributeDef {
+                            name: "CodeInfo".to_string(),
+                            info: vec![0xff],
+                        })),
+                        JvmInstruction(Getstatic {
+                            class_name: "java/lang/System".to_string(),
+                            field_name: "out".to_string(),
+                            field_descriptor: object_type("java/io/PrintStream"),
+                        }),
+                        JvmInstruction(Ldc(LdcValue::QuotedString(
+                            "Hello, raw attributes!".to_string(),
+                        ))),
+                        JvmInstruction(Invokevirtual {
+                            class_name: "java/io/PrintStream".to_string(),
+                            method_name: "println".to_string(),
+                            method_descriptor: PhoronMethodDescriptor {
+                                param_descriptor: vec![object_type("java/lang/String")],
+                                return_descriptor: VoidDescriptor,
+                            },
+                        }),
+                        JvmInstruction(Return),
+                    ],
+                },
+            ],
+        },
+    };
+
+    let actual_ast = parse("samples/RawAttributeDemo.pho")?;
+    assert_eq!(expected_ast, actual_ast);
+
+    Ok(())
+}
+
+#[test]
+fn test_parse_field_block_demo() -> Result<(), Box<dyn Error>> {
+    let object_type = |class_name: &str| ObjectType {
+        class_name: class_name.to_string(),
+    };
+
+    let expected_ast = PhoronProgram {
+        header: PhoronHeader {
+            version_def: PhoronVersionDef {
+                major_version: 49,
+                minor_version: 0,
+            },
+            sourcefile_def: PhoronSourceFileDef {
+                source_file: "samples/FieldBlockDemo.pho".to_string(),
+            },
+            class_or_interface_def: Class(PhoronClassDef {
+                name: "FieldBlockDemo".to_string(),
+                access_flags: vec![
+                    PhoronClassOrInterfaceAccessFlag::AccSuper,
+                    PhoronClassOrInterfaceAccessFlag::AccPublic,
+                ],
+            }),
+            super_def: PhoronSuperDef {
+                super_class_name: "java/lang/Object".to_string(),
+            },
+            implements_defs: vec![],
+            class_directives: vec![],
+        },
+        body: PhoronBody {
+            field_defs: vec![
+                PhoronFieldDef {
+                    name: "names".to_string(),
+                    access_flags: vec![
+                        PhoronFieldAccessFlag::AccPublic,
+                        PhoronFieldAccessFlag::AccStatic,
+                    ],
+                    field_descriptor: object_type("java/util/List"),
+                    signature: Some("Ljava/util/List<Ljava/lang/String;>;".to_string()),
+                    init_val: None,
+                    annotations: vec![PhoronAnnotationDef {
+                        visibility: PhoronAnnotationVisibility::Visible,
+                        annotation: PhoronAnnotation {
+                            annotation_type: object_type("java/lang/Deprecated"),
+                            element_value_pairs: vec![],
+                        },
+                    }],
+                    type_annotations: vec![],
+                    attributes: vec![],
+                    deprecated: false,
+                    synthetic: false,
+                },
+                PhoronFieldDef {
+                    name: "count".to_string(),
+                    access_flags: vec![
+                        PhoronFieldAccessFlag::AccPublic,
+                        PhoronFieldAccessFlag::AccStatic,
+                    ],
+                    field_descriptor: BaseType(Integer),
+                    signature: None,
+                    init_val: None,
+                    annotations: vec![],
+                    type_annotations: vec![],
+                    attributes: vec![],
+                    deprecated: true,
+                    synthetic: false,
+                },
+                PhoronFieldDef {
+                    name: "helper".to_string(),
+                    access_flags: vec![PhoronFieldAccessFlag::AccStatic],
+                    field_descriptor: BaseType(Integer),
+                    signature: None,
+                    init_val: None,
+                    annotations: vec![],
+                    type_annotations: vec![],
+                    attributes: vec![],
+                    deprecated: false,
+                    synthetic: true,
+                },
+            ],
+            method_defs: vec![
+                PhoronMethodDef {
+                    name: "<init>".to_string(),
+                    access_flags: vec![PhoronMethodAccessFlag::AccPublic],
+                    method_descriptor: PhoronMethodDescriptor {
+                        param_descriptor: vec![],
+                        return_descriptor: VoidDescriptor,
+                    },
+                    instructions: vec![
+                        JvmInstruction(Aload0),
+                        JvmInstruction(Invokespecial {
+                            class_name: "java/lang/Object".to_string(),
+                            method_name: "<init>".to_string(),
+                            method_descriptor: PhoronMethodDescriptor {
+                                param_descriptor: vec![],
+                                return_descriptor: VoidDescriptor,
+                            },
+                        }),
+                        JvmInstruction(Return),
+                    ],
+                },
+                PhoronMethodDef {
+                    name: "main".to_string(),
+                    access_flags: vec![
+                        PhoronMethodAccessFlag::AccPublic,
+                        PhoronMethodAccessFlag::AccStatic,
+                    ],
+                    method_descriptor: PhoronMethodDescriptor {
+                        param_descriptor: vec![ArrayType {
+                            component_type: Box::new(object_type("java/lang/String")),
+                        }],
+                        return_descriptor: VoidDescriptor,
+                    },
+                    instructions: vec![
+                        PhoronDirective(LimitStack(3)),
+                        PhoronDirective(LimitLocals(1)),
+                        JvmInstruction(Getstatic {
+                            class_name: "java/lang/System".to_string(),
+                            field_name: "out".to_string(),
+                            field_descriptor: object_type("java/io/PrintStream"),
+                        }),
+                        JvmInstruction(Ldc(LdcValue::Class("FieldBlockDemo".to_string()))),
+                        JvmInstruction(Ldc(LdcValue::QuotedString("names".to_string()))),
+                        JvmInstruction(Invokevirtual {
+                            class_name: "java/lang/Class".to_string(),
+                            method_name: "getField".to_string(),
+                            method_descriptor: PhoronMethodDescriptor {
+                                param_descriptor: vec![object_type("java/lang/String")],
+                                return_descriptor: FieldDescriptor(object_type(
+                                    "java/lang/reflect/Field",
+                                )),
+                            },
+                        }),
+                        JvmInstruction(Invokevirtual {
+                            class_name: "java/lang/reflect/Field".to_string(),
+                            method_name: "getGenericType".to_string(),
+                            method_descriptor: PhoronMethodDescriptor {
+                                param_descriptor: vec![],
+                                return_descriptor: FieldDescriptor(object_type(
+                                    "java/lang/reflect/Type",
+                                )),
+                            },
+                        }),
+                        JvmInstruction(Invokevirtual {
+                            class_name: "java/io/PrintStream".to_string(),
+                            method_name: "println".to_string(),
+                            method_descriptor: PhoronMethodDescriptor {
+                                param_descriptor: vec![object_type("java/lang/Object")],
+                                return_descriptor: VoidDescriptor,
+                            },
+                        }),
+                        JvmInstruction(Return),
+                    ],
+                },
+            ],
+        },
+    };
+
+    let actual_ast = parse("samples/FieldBlockDemo.pho")?;
+    assert_eq!(expected_ast, actual_ast);
+
+    Ok(())
+}
+
+#[test]
+fn test_parse_deprecated_synthetic_demo() -> Result<(), Box<dyn Error>> {
+    let object_type = |class_name: &str| ObjectType {
+        class_name: class_name.to_string(),
+    };
+
+    let expected_ast = PhoronProgram {
+        header: PhoronHeader {
+            version_def: PhoronVersionDef {
+                major_version: 49,
+                minor_version: 0,
+            },
+            sourcefile_def: PhoronSourceFileDef {
+                source_file: "samples/DeprecatedSyntheticDemo.pho".to_string(),
+            },
+            class_or_interface_def: Class(PhoronClassDef {
+                name: "DeprecatedSyntheticDemo".to_string(),
+                access_flags: vec![
+                    PhoronClassOrInterfaceAccessFlag::AccSuper,
+                    PhoronClassOrInterfaceAccessFlag::AccPublic,
+                ],
+            }),
+            super_def: PhoronSuperDef {
+                super_class_name: "java/lang/Object".to_string(),
+            },
+            implements_defs: vec![],
+            class_directives: vec![PhoronClassDirective::Deprecated],
+        },
+        body: PhoronBody {
+            field_defs: vec![],
+            method_defs: vec![
+                PhoronMethodDef {
+                    name: "<init>".to_string(),
+                    access_flags: vec![PhoronMethodAccessFlag::AccPublic],
+                    method_descriptor: PhoronMethodDescriptor {
+                        param_descriptor: vec![],
+                        return_descriptor: VoidDescriptor,
+                    },
+                    instructions: vec![
+                        JvmInstruction(Aload0),
+                        JvmInstruction(Invokespecial {
+                            class_name: "java/lang/Object".to_string(),
+                            method_name: "<init>".to_string(),
+                            method_descriptor: PhoronMethodDescriptor {
+                                param_descriptor: vec![],
+                                return_descriptor: VoidDescriptor,
+                            },
+                        }),
+                        JvmInstruction(Return),
+                    ],
+                },
+                PhoronMethodDef {
+                    name: "helper".to_string(),
+                    access_flags: vec![PhoronMethodAccessFlag::AccStatic],
+                    method_descriptor: PhoronMethodDescriptor {
+                        param_descriptor: vec![],
+                        return_descriptor: FieldDescriptor(BaseType(Integer)),
+                    },
+                    instructions: vec![
+                        PhoronDirective(Synthetic { flag: true }),
+                        PhoronDirective(Deprecated),
+                        PhoronDirective(LimitStack(1)),
+                        PhoronDirective(LimitLocals(0)),
+                        JvmInstruction(Bipush(42)),
+                        JvmInstruction(Ireturn),
+                    ],
+                },
+                PhoronMethodDef {
+                    name: "main".to_string(),
+                    access_flags: vec![
+                        PhoronMethodAccessFlag::AccPublic,
+                        PhoronMethodAccessFlag::AccStatic,
+                    ],
+                    method_descriptor: PhoronMethodDescriptor {
+                        param_descriptor: vec![ArrayType {
+                            component_type: Box::new(object_type("java/lang/String")),
+                        }],
+                        return_descriptor: VoidDescriptor,
+                    },
+                    instructions: vec![
+                        PhoronDirective(LimitStack(4)),
+                        PhoronDirective(LimitLocals(1)),
+                        JvmInstruction(Getstatic {
+                            class_name: "java/lang/System".to_string(),
+                            field_name: "out".to_string(),
+                            field_descriptor: object_type("java/io/PrintStream"),
+                        }),
+                        JvmInstruction(Ldc(LdcValue::Class("DeprecatedSyntheticDemo".to_string()))),
+                        JvmInstruction(Ldc(LdcValue::QuotedString("helper".to_string()))),
+                        JvmInstruction(Iconst0),
+                        JvmInstruction(Anewarray {
+                            component_type: object_type("java/lang/Class"),
+                        }),
+                        JvmInstruction(Invokevirtual {
+                            class_name: "java/lang/Class".to_string(),
+                            method_name: "getDeclaredMethod".to_string(),
+                            method_descriptor: PhoronMethodDescriptor {
+                                param_descriptor: vec![
+                                    object_type("java/lang/String"),
+                                    ArrayType {
+                                        component_type: Box::new(object_type("java/lang/Class")),
+                                    },
+                                ],
+                                return_descriptor: FieldDescriptor(object_type(
+                                    "java/lang/reflect/Method",
+                                )),
+                            },
+                        }),
+                        JvmInstruction(Invokevirtual {
+                            class_name: "java/lang/reflect/Method".to_string(),
+                            method_name: "isSynthetic".to_string(),
+                            method_descriptor: PhoronMethodDescriptor {
+                                param_descriptor: vec![],
+                                return_descriptor: FieldDescriptor(BaseType(Boolean)),
+                            },
+                        }),
+                        JvmInstruction(Invokevirtual {
+                            class_name: "java/io/PrintStream".to_string(),
+                            method_name: "println".to_string(),
+                            method_descriptor: PhoronMethodDescriptor {
+                                param_descriptor: vec![BaseType(Boolean)],
+                                return_descriptor: VoidDescriptor,
+                            },
+                        }),
+                        JvmInstruction(Return),
+                    ],
+                },
+            ],
+        },
+    };
+
+    let actual_ast = parse("samples/DeprecatedSyntheticDemo.pho")?;
+    assert_eq!(expected_ast, actual_ast);
+
+    Ok(())
+}
+
+#[test]
+fn test_parse_target_version() -> Result<(), Box<dyn Error>> {
+    let target_version = |major_version| PhoronVersionDef {
+        major_version,
+        minor_version: 0,
+    };
+
+    assert!(!parse_errored_with_target(
+        "samples/InvokedynamicDemo.pho",
+        target_version(52)
+    )?);
+
+    // bootstrap methods need 51.0, and sealed classes need 61.0
+    assert!(parse_errored_with_target(
+        "samples/InvokedynamicDemo.pho",
+        PhoronVersionDef::default()
+    )?);
+    assert!(parse_errored_with_target(
+        "samples/sealed/Shape.pho",
+        target_version(52)
+    )?);
+
+    Ok(())
+}
+
+#[test]
+fn test_parse_invalid_dynamic_constants() -> Result<(), Box<dyn Error>> {
+    assert!(parse_errored("samples/invalid/Ldc2wDynamicInt.pho")?);
+    assert!(parse_errored("samples/invalid/LdcDynamicLong.pho")?);
+    assert!(parse_errored(
+        "samples/invalid/DynamicConstantBeforeJava11.pho"
+    )?);
+
+    Ok(())
+}
+
+#[test]
+fn test_parse_conflicting_nest_directives() -> Result<(), Box<dyn Error>> {
+    assert!(parse_errored("samples/invalid/NestHostAndMember.pho")?);
+    assert!(parse_errored("samples/invalid/NestMemberAndHost.pho")?);
+    assert!(parse_errored("samples/invalid/DuplicateNestHost.pho")?);
+
+    Ok(())
+}
+
+#[test]
+fn test_parse_invalid_record_components() -> Result<(), Box<dyn Error>> {
+    assert!(parse_errored("samples/invalid/RecordWrongSuperclass.pho")?);
+    assert!(parse_errored("samples/invalid/RecordMissingField.pho")?);
+    assert!(parse_errored(
+        "samples/invalid/RecordFieldTypeMismatch.pho"
+    )?);
+    assert!(parse_errored(
+        "samples/invalid/RecordFieldNotPrivateFinal.pho"
+    )?);
+
+    Ok(())
+}
+
+#[test]
+fn test_parse_invalid_sealed_classes() -> Result<(), Box<dyn Error>> {
+    assert!(parse_errored("samples/invalid/FinalSealedClass.pho")?);
+    assert!(parse_errored(
+        "samples/invalid/SealedClassBeforeJava17.pho"
+    )?);
+
+    Ok(())
+}
+
+#[test]
+fn test_parse_parameter_index_out_of_range() -> Result<(), Box<dyn Error>> {
+    assert!(parse_errored(
+        "samples/invalid/ParameterIndexOutOfRange.pho"
+    )?);
+    assert!(parse_errored(
+        "samples/invalid/ParameterAnnotationIndexOutOfRange.pho"
+    )?);
+    assert!(parse_errored(
+        "samples/invalid/TypeAnnotationParameterIndexOutOfRange.pho"
+    )?);
+
+    Ok(())
+}